@@ -36,6 +36,10 @@ pub struct AclBinding {
     /// Is this a wildcard pattern?
     #[serde(rename = "wildcard")]
     pub wildcard: bool,
+
+    /// Resource pattern type (Literal, Prefixed)
+    #[serde(rename = "patternType")]
+    pub pattern_type: String,
 }
 
 /// ACL operation types
@@ -60,6 +64,8 @@ pub enum AclOperation {
     DescribeConfigs,
     /// Alter configs
     AlterConfigs,
+    /// Idempotent produce
+    IdempotentWrite,
     /// All operations
     All,
 }
@@ -119,6 +125,10 @@ pub struct CreateAclRequest {
     /// Is this a wildcard pattern?
     #[serde(rename = "wildcard")]
     pub wildcard: Option<bool>,
+
+    /// Resource pattern type (Literal, Prefixed); Literal when unset
+    #[serde(rename = "patternType", default)]
+    pub pattern_type: Option<String>,
 }
 
 impl CreateAclRequest {
@@ -150,8 +160,97 @@ impl CreateAclRequest {
             return Err(anyhow!("Invalid permission type: {}", self.permission_type));
         }
 
+        // Validate pattern type
+        if let Some(ref pattern_type) = self.pattern_type {
+            if pattern_type != "Literal" && pattern_type != "Prefixed" {
+                return Err(anyhow!("Invalid pattern type: {}", pattern_type));
+            }
+        }
+
         Ok(())
     }
+
+    /// Parse one CSV line:
+    /// `principal,resource_type,resource_name,operation,permission_type,host,pattern_type`
+    ///
+    /// Fields containing commas, such as `"User:CN=alice,OU=eng"`, must be double-quoted.
+    pub fn from_csv_line(line: &str) -> Result<Self> {
+        let fields = split_csv_line(line)?;
+        if fields.len() != 7 {
+            return Err(anyhow!("Expected 7 fields, found {}", fields.len()));
+        }
+
+        let optional = |value: &str| Some(value.to_string()).filter(|v| !v.is_empty());
+        Ok(Self {
+            principal: fields[0].clone(),
+            resource_type: fields[1].clone(),
+            resource_name: fields[2].clone(),
+            operation: fields[3].clone(),
+            permission_type: fields[4].clone(),
+            host: optional(&fields[5]),
+            wildcard: None,
+            pattern_type: optional(&fields[6]),
+        })
+    }
+}
+
+/// Split a CSV line into trimmed fields; double-quoted fields may contain commas and
+/// `""` for a literal quote
+fn split_csv_line(line: &str) -> Result<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if in_quotes => in_quotes = false,
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                in_quotes = true;
+            }
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err(anyhow!("Unterminated quoted field"));
+    }
+    fields.push(field);
+
+    Ok(fields.into_iter().map(|f| f.trim().to_string()).collect())
+}
+
+/// Result of creating ACLs in bulk
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchAclResult {
+    /// Number of ACLs created
+    pub created: usize,
+
+    /// Failed lines as (line number, error message)
+    pub failed: Vec<(usize, String)>,
+}
+
+/// Parse ACL CSV content into requests keyed by 1-based line number.
+///
+/// Blank lines and a leading `principal,...` header line are skipped.
+pub fn parse_acl_csv(csv_content: &str) -> Vec<(usize, Result<CreateAclRequest>)> {
+    csv_content
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty())
+        .filter(|(line_no, line)| !(*line_no == 1 && line.starts_with("principal,")))
+        .map(|(line_no, line)| {
+            let request = CreateAclRequest::from_csv_line(line)
+                .and_then(|request| request.validate().map(|_| request));
+            (line_no, request)
+        })
+        .collect()
 }
 
 /// ACL filter for listing
@@ -193,6 +292,7 @@ mod tests {
             permission_type: "Allow".to_string(),
             host: Some("*".to_string()),
             wildcard: Some(false),
+            pattern_type: None,
         };
 
         assert!(valid_request.validate().is_ok());
@@ -205,8 +305,54 @@ mod tests {
             permission_type: "Allow".to_string(),
             host: None,
             wildcard: None,
+            pattern_type: None,
         };
 
         assert!(invalid_request.validate().is_err());
     }
+
+    #[test]
+    fn test_parse_acl_csv() {
+        let csv = "principal,resource_type,resource_name,operation,permission_type,host,pattern_type
+User:alice,Topic,orders,Read,Allow,*,Literal
+
+User:bob,Group,billing-,Read,Allow,,Prefixed
+User:eve,Topic,orders,Steal,Allow,*,Literal
+User:mallory,Topic";
+
+        let parsed = parse_acl_csv(csv);
+        assert_eq!(parsed.len(), 4);
+
+        let (line_no, ref alice) = parsed[0];
+        assert_eq!(line_no, 2);
+        assert_eq!(alice.as_ref().unwrap().host.as_deref(), Some("*"));
+
+        let bob = parsed[1].1.as_ref().unwrap();
+        assert_eq!(bob.host, None);
+        assert_eq!(bob.pattern_type.as_deref(), Some("Prefixed"));
+
+        assert_eq!(parsed[2].0, 5);
+        assert!(parsed[2].1.is_err());
+        assert!(parsed[3].1.is_err());
+    }
+
+    #[test]
+    fn test_parse_acl_csv_quoted_dn_principal() {
+        let csv = r#""User:CN=alice,OU=eng,O=Example",Topic,orders,Read,Allow,*,Literal
+"User:CN=""bob"",OU=ops",Group,billing,Read,Allow,*,
+"User:CN=eve,Topic,orders,Read,Allow,*,Literal"#;
+
+        let parsed = parse_acl_csv(csv);
+        assert_eq!(parsed.len(), 3);
+
+        let alice = parsed[0].1.as_ref().unwrap();
+        assert_eq!(alice.principal, "User:CN=alice,OU=eng,O=Example");
+        assert_eq!(alice.resource_name, "orders");
+
+        let bob = parsed[1].1.as_ref().unwrap();
+        assert_eq!(bob.principal, r#"User:CN="bob",OU=ops"#);
+        assert_eq!(bob.pattern_type, None);
+
+        assert!(parsed[2].1.is_err());
+    }
 }
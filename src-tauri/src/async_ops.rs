@@ -4,17 +4,49 @@
 // Background task manager with progress tracking
 
 use anyhow::Result;
+use once_cell::sync::Lazy;
 use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
 use std::future::Future;
-use std::sync::{Arc, Mutex};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+use tokio::runtime::{Handle, Runtime};
 use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// How long a completed task and its result stay retrievable
+const RESULT_RETENTION: Duration = Duration::from_secs(60);
+/// Tasks run at once by `TaskManager::new`; periodic jobs such as lag tracking do not count
+pub const DEFAULT_MAX_CONCURRENT_TASKS: usize = 8;
+/// How often the sweeper collects finished tasks and drops expired ones
+const SWEEP_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Value a task finished with, or its error
+type TaskResult = Result<serde_json::Value>;
+
+/// Runtime for tasks spawned outside of an async context (e.g. sync Tauri commands)
+static BACKGROUND_RUNTIME: Lazy<Runtime> = Lazy::new(|| {
+    Runtime::new().expect("Failed to create background runtime")
+});
+
+/// Long-lived runtime for clients that keep background I/O tasks (e.g. ZooKeeper sessions)
+pub fn background_runtime() -> &'static Runtime {
+    &BACKGROUND_RUNTIME
+}
 
 /// Background task with progress tracking
 pub struct BackgroundTask {
     id: String,
     name: String,
     progress: Arc<Mutex<TaskProgress>>,
-    handle: Option<JoinHandle<Result<()>>>,
+    task_result: Arc<Mutex<Option<TaskResult>>>,
+    handle: Option<JoinHandle<()>>,
+    /// Runs until cancelled, outside the `max_concurrent` limit
+    periodic: bool,
+    /// Set by `cancel_task` so blocking work the task started can stop too
+    cancelled: Arc<AtomicBool>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -45,57 +77,610 @@ impl TaskProgress {
     }
 }
 
+/// Handle a running task uses to report its progress and notice cancellation
+#[derive(Clone)]
+pub struct TaskHandle {
+    progress: Arc<Mutex<TaskProgress>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl TaskHandle {
+    /// Set how many units of work the task has in total
+    pub fn set_total(&self, total: usize) {
+        self.progress.lock().unwrap().total = total;
+    }
+
+    /// Set how many units of work are done
+    pub fn set_current(&self, current: usize) {
+        self.progress.lock().unwrap().current = current;
+    }
+
+    /// Was the task cancelled? Aborting the task does not stop `spawn_blocking` work,
+    /// so blocking loops check this instead
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// Snapshot of a task for listing
+#[derive(Debug, Clone)]
+pub struct TaskSummary {
+    pub id: String,
+    pub name: String,
+    pub progress: TaskProgress,
+    /// Aborted by `cancel_task` before it completed
+    pub is_cancelled: bool,
+    /// The underlying future is no longer running
+    pub is_finished: bool,
+}
+
+/// Task waiting for a free slot; `task.handle` is `None` until it is started
+struct PendingTask {
+    task: BackgroundTask,
+    future: Pin<Box<dyn Future<Output = ()> + Send>>,
+}
+
+/// Final state of a finished or cancelled task, kept for `RESULT_RETENTION`
+struct CompletedTask {
+    summary: TaskSummary,
+    result: Option<TaskResult>,
+    completed_at: Instant,
+}
+
 /// Task manager for background operations
 pub struct TaskManager {
     tasks: Arc<Mutex<Vec<BackgroundTask>>>,
+    /// Tasks waiting for one of the `max_concurrent` slots, in submission order
+    pending: Arc<Mutex<VecDeque<PendingTask>>>,
+    completed_tasks: Arc<Mutex<HashMap<String, CompletedTask>>>,
+    max_concurrent: usize,
 }
 
 impl TaskManager {
     pub fn new() -> Self {
-        Self {
+        Self::new_with_limit(DEFAULT_MAX_CONCURRENT_TASKS)
+    }
+
+    /// Task manager running at most `max_concurrent` tasks at once (at least one);
+    /// further tasks are queued until a running one completes
+    pub fn new_with_limit(max_concurrent: usize) -> Self {
+        let manager = Self {
             tasks: Arc::new(Mutex::new(Vec::new())),
-        }
+            pending: Arc::new(Mutex::new(VecDeque::new())),
+            completed_tasks: Arc::new(Mutex::new(HashMap::new())),
+            max_concurrent: max_concurrent.max(1),
+        };
+        manager.start_sweeper();
+        manager
     }
 
+    pub fn max_concurrent(&self) -> usize {
+        self.max_concurrent
+    }
+
+    /// Number of tasks currently running, not counting periodic ones
+    pub fn running_count(&self) -> usize {
+        self.tasks.lock().unwrap().iter().filter(|t| t.is_active()).count()
+    }
+
+    /// Number of tasks waiting for a free slot
+    pub fn queue_depth(&self) -> usize {
+        self.pending.lock().unwrap().len()
+    }
+
+    /// Periodically reap finished tasks and drop results older than `RESULT_RETENTION`.
+    /// Also starts queued tasks whose slot was freed by a task that panicked.
+    /// The sweeper stops once the manager is dropped.
+    fn start_sweeper(&self) {
+        let tasks = Arc::downgrade(&self.tasks);
+        let pending = Arc::downgrade(&self.pending);
+        let completed_tasks = Arc::downgrade(&self.completed_tasks);
+        let max_concurrent = self.max_concurrent;
+        BACKGROUND_RUNTIME.spawn(async move {
+            loop {
+                tokio::time::sleep(SWEEP_INTERVAL).await;
+                let (Some(tasks), Some(pending), Some(completed_tasks)) =
+                    (Weak::upgrade(&tasks), Weak::upgrade(&pending), Weak::upgrade(&completed_tasks))
+                else {
+                    break;
+                };
+                start_pending(&tasks, &pending, max_concurrent);
+                let mut tasks = tasks.lock().unwrap();
+                let mut completed = completed_tasks.lock().unwrap();
+                reap_finished(&mut tasks, &mut completed);
+                let expired = expire_completed(&mut completed, Instant::now());
+                if expired > 0 {
+                    debug!("Dropped {} expired task results", expired);
+                }
+            }
+        });
+    }
+
+    /// Run `f` in the background, or queue it while `max_concurrent` tasks are running;
+    /// its value is available from `get_task_result` once it completes
     pub fn spawn_task<F, Fut>(&self, id: String, name: String, f: F) -> Result<()>
     where
         F: FnOnce() -> Fut + Send + 'static,
-        Fut: Future<Output = Result<()>> + Send + 'static,
+        Fut: Future<Output = TaskResult> + Send + 'static,
+    {
+        self.spawn_task_with_handle(id, name, move |_| f())
+    }
+
+    /// Like `spawn_task`, but `f` gets a `TaskHandle` to report its progress with
+    pub fn spawn_task_with_handle<F, Fut>(&self, id: String, name: String, f: F) -> Result<()>
+    where
+        F: FnOnce(TaskHandle) -> Fut + Send + 'static,
+        Fut: Future<Output = TaskResult> + Send + 'static,
+    {
+        self.spawn(id, name, false, f)
+    }
+
+    /// Start a job that runs until it is cancelled, such as lag tracking.
+    ///
+    /// Periodic tasks start right away and do not take one of the `max_concurrent` slots,
+    /// so they never hold up other tasks.
+    pub fn spawn_periodic_task<F, Fut>(&self, id: String, name: String, f: F) -> Result<()>
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = TaskResult> + Send + 'static,
+    {
+        self.spawn(id, name, true, move |_| f())
+    }
+
+    fn spawn<F, Fut>(&self, id: String, name: String, periodic: bool, f: F) -> Result<()>
+    where
+        F: FnOnce(TaskHandle) -> Fut + Send + 'static,
+        Fut: Future<Output = TaskResult> + Send + 'static,
     {
         let progress = Arc::new(Mutex::new(TaskProgress::new(100)));
-        let handle = tokio::spawn(async move {
-            f().await
-        });
+        let task_result = Arc::new(Mutex::new(None));
+        let (task_progress, result_slot) = (progress.clone(), task_result.clone());
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let handle = TaskHandle { progress: progress.clone(), cancelled: cancelled.clone() };
+        let (tasks, pending, max_concurrent) = (self.tasks.clone(), self.pending.clone(), self.max_concurrent);
+        let future = async move {
+            let result = f(handle).await;
+            {
+                let mut progress = task_progress.lock().unwrap();
+                progress.is_complete = true;
+                match result {
+                    Ok(_) => progress.current = progress.total,
+                    Err(ref e) => progress.error = Some(e.to_string()),
+                }
+                *result_slot.lock().unwrap() = Some(result);
+            }
+            start_pending(&tasks, &pending, max_concurrent);
+        };
 
         let task = BackgroundTask {
             id: id.clone(),
             name,
             progress,
-            handle: Some(handle),
+            task_result,
+            handle: None,
+            periodic,
+            cancelled,
         };
 
-        let mut tasks = self.tasks.lock()
-            .map_err(|e| anyhow::anyhow!("Task lock poisoned: {}", e))?;
-        tasks.push(task);
+        self.completed_tasks.lock().unwrap().remove(&id);
+        {
+            let mut tasks = self.tasks.lock()
+                .map_err(|e| anyhow::anyhow!("Task lock poisoned: {}", e))?;
+            let mut pending = self.pending.lock().unwrap();
+            let running = tasks.iter().filter(|t| t.is_active()).count();
+            if periodic || (pending.is_empty() && running < self.max_concurrent) {
+                tasks.push(PendingTask { task, future: Box::pin(future) }.start());
+                return Ok(());
+            }
+            pending.push_back(PendingTask { task, future: Box::pin(future) });
+        }
+        debug!("Queued task {} ({} waiting)", id, self.queue_depth());
         Ok(())
     }
 
     pub fn get_task_progress(&self, id: &str) -> Option<TaskProgress> {
+        self.reap_finished_tasks();
         let tasks = self.tasks.lock().ok()?;
-        tasks.iter().find(|t| t.id == id).map(|t| {
-            t.progress.lock().unwrap().clone()
-        })
+        if let Some(task) = tasks.iter().find(|t| t.id == id) {
+            return Some(task.progress.lock().unwrap().clone());
+        }
+        let pending = self.pending.lock().ok()?;
+        if let Some(queued) = pending.iter().find(|p| p.task.id == id) {
+            return Some(queued.task.progress.lock().unwrap().clone());
+        }
+        drop(pending);
+        let completed = self.completed_tasks.lock().ok()?;
+        completed.get(id).map(|t| t.summary.progress.clone())
+    }
+
+    /// Value or error a completed task finished with; `None` while it is running,
+    /// after it was cancelled, or once its result has expired
+    pub fn get_task_result(&self, id: &str) -> Option<TaskResult> {
+        self.reap_finished_tasks();
+        let completed = self.completed_tasks.lock().ok()?;
+        match completed.get(id)?.result.as_ref()? {
+            Ok(value) => Some(Ok(value.clone())),
+            Err(e) => Some(Err(anyhow::anyhow!("{}", e))),
+        }
+    }
+
+    /// List running tasks, then queued ones in the order they will start, then completed ones
+    pub fn list_tasks(&self) -> Vec<TaskSummary> {
+        self.reap_finished_tasks();
+        let mut summaries: Vec<TaskSummary> = self.tasks.lock().unwrap()
+            .iter()
+            .map(|t| t.summary())
+            .collect();
+        summaries.extend(self.pending.lock().unwrap().iter().map(|p| TaskSummary {
+            is_finished: false,
+            ..p.task.summary()
+        }));
+        let mut completed: Vec<TaskSummary> = self.completed_tasks.lock().unwrap()
+            .values()
+            .map(|t| t.summary.clone())
+            .collect();
+        completed.sort_by(|a, b| a.id.cmp(&b.id));
+        summaries.extend(completed);
+        summaries
+    }
+
+    fn reap_finished_tasks(&self) {
+        let mut tasks = self.tasks.lock().unwrap();
+        reap_finished(&mut tasks, &mut self.completed_tasks.lock().unwrap());
     }
 
     pub fn cancel_task(&self, id: &str) -> Result<()> {
         let mut tasks = self.tasks.lock()
             .map_err(|e| anyhow::anyhow!("Task lock poisoned: {}", e))?;
-        if let Some(pos) = tasks.iter().position(|t| t.id == id) {
-            let task = tasks.remove(pos);
-            if let Some(handle) = task.handle {
+        let task = match tasks.iter().position(|t| t.id == id) {
+            Some(pos) => Some(tasks.remove(pos)),
+            None => {
+                let mut pending = self.pending.lock().unwrap();
+                pending.iter().position(|p| p.task.id == id)
+                    .and_then(|pos| pending.remove(pos))
+                    .map(|p| p.task)
+            }
+        };
+        drop(tasks);
+
+        if let Some(task) = task {
+            task.cancelled.store(true, Ordering::SeqCst);
+            if let Some(ref handle) = task.handle {
                 handle.abort();
             }
+            let mut completed = task.into_completed();
+            completed.summary.is_finished = true;
+            if !completed.summary.progress.is_complete {
+                completed.summary.progress.is_complete = true;
+                completed.summary.progress.error = Some("Cancelled".to_string());
+                completed.summary.is_cancelled = true;
+            }
+            self.completed_tasks.lock().unwrap().insert(completed.summary.id.clone(), completed);
+            start_pending(&self.tasks, &self.pending, self.max_concurrent);
         }
         Ok(())
     }
+
+    /// Cancel every queued and running task, returning how many were cancelled
+    pub fn cancel_all(&self) -> usize {
+        // Queued tasks go first so cancelling running ones does not start them
+        let mut ids: Vec<String> = self.pending.lock().unwrap()
+            .iter()
+            .map(|p| p.task.id.clone())
+            .collect();
+        ids.extend(self.tasks.lock().unwrap().iter().map(|t| t.id.clone()));
+        for id in &ids {
+            if let Err(e) = self.cancel_task(id) {
+                warn!("Failed to cancel task {}: {}", id, e);
+            }
+        }
+        ids.len()
+    }
+}
+
+impl BackgroundTask {
+    fn summary(&self) -> TaskSummary {
+        TaskSummary {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            progress: self.progress.lock().unwrap().clone(),
+            is_cancelled: false,
+            is_finished: self.is_finished(),
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.handle.as_ref().is_none_or(|h| h.is_finished())
+    }
+
+    /// Holds a concurrency slot: not periodic, started and has not stored its result yet
+    fn is_active(&self) -> bool {
+        !self.periodic && !self.is_finished() && self.task_result.lock().unwrap().is_none()
+    }
+
+    fn into_completed(self) -> CompletedTask {
+        CompletedTask {
+            summary: self.summary(),
+            result: self.task_result.lock().unwrap().take(),
+            completed_at: Instant::now(),
+        }
+    }
+}
+
+impl PendingTask {
+    fn start(mut self) -> BackgroundTask {
+        let handle = match Handle::try_current() {
+            Ok(handle) => handle.spawn(self.future),
+            Err(_) => BACKGROUND_RUNTIME.spawn(self.future),
+        };
+        self.task.handle = Some(handle);
+        self.task
+    }
+}
+
+/// Start queued tasks while fewer than `max_concurrent` are running
+fn start_pending(tasks: &Mutex<Vec<BackgroundTask>>, pending: &Mutex<VecDeque<PendingTask>>, max_concurrent: usize) {
+    let mut tasks = tasks.lock().unwrap();
+    let mut pending = pending.lock().unwrap();
+    let mut running = tasks.iter().filter(|t| t.is_active()).count();
+    while running < max_concurrent {
+        let Some(next) = pending.pop_front() else {
+            break;
+        };
+        debug!("Starting queued task {}", next.task.id);
+        tasks.push(next.start());
+        running += 1;
+    }
+}
+
+/// Move tasks whose future has finished into `completed`
+fn reap_finished(tasks: &mut Vec<BackgroundTask>, completed: &mut HashMap<String, CompletedTask>) {
+    let (finished, running): (Vec<_>, Vec<_>) = tasks.drain(..).partition(BackgroundTask::is_finished);
+    *tasks = running;
+    for task in finished {
+        completed.insert(task.id.clone(), task.into_completed());
+    }
+}
+
+/// Drop completed tasks older than `RESULT_RETENTION`, returning how many were dropped
+fn expire_completed(completed: &mut HashMap<String, CompletedTask>, now: Instant) -> usize {
+    let before = completed.len();
+    completed.retain(|_, t| now.saturating_duration_since(t.completed_at) < RESULT_RETENTION);
+    before - completed.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_tasks_running_then_completed() {
+        let manager = TaskManager::new();
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        manager.spawn_task("task-1".to_string(), "Test task".to_string(), move || async move {
+            let _ = rx.await;
+            Ok(serde_json::Value::Null)
+        }).unwrap();
+
+        let tasks = manager.list_tasks();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].id, "task-1");
+        assert_eq!(tasks[0].name, "Test task");
+        assert!(!tasks[0].progress.is_complete);
+        assert!(!tasks[0].is_finished);
+        assert!(!tasks[0].is_cancelled);
+
+        tx.send(()).unwrap();
+        for _ in 0..100 {
+            if manager.list_tasks().iter().all(|t| t.is_finished) {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        let progress = manager.get_task_progress("task-1").unwrap();
+        assert!(progress.is_complete);
+        assert!(progress.error.is_none());
+
+        let tasks = manager.list_tasks();
+        assert_eq!(tasks.len(), 1);
+        assert!(tasks[0].is_finished);
+        assert!(!tasks[0].is_cancelled);
+    }
+
+    #[test]
+    fn test_cancel_all() {
+        let manager = TaskManager::new();
+        for i in 0..2 {
+            manager.spawn_task(format!("task-{}", i), "Sleeper".to_string(), || async {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                Ok(serde_json::Value::Null)
+            }).unwrap();
+        }
+
+        assert_eq!(manager.cancel_all(), 2);
+        let progress = manager.get_task_progress("task-0").unwrap();
+        assert!(progress.is_complete);
+        assert_eq!(progress.error.as_deref(), Some("Cancelled"));
+        assert_eq!(manager.cancel_all(), 0);
+
+        let tasks = manager.list_tasks();
+        assert_eq!(tasks.iter().map(|t| t.id.as_str()).collect::<Vec<_>>(), ["task-0", "task-1"]);
+        assert!(tasks.iter().all(|t| t.is_cancelled && t.is_finished));
+        assert!(manager.get_task_result("task-0").is_none());
+    }
+
+    fn wait_for_result(manager: &TaskManager, id: &str) -> TaskResult {
+        for _ in 0..100 {
+            if let Some(result) = manager.get_task_result(id) {
+                return result;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        panic!("Task {} did not complete", id);
+    }
+
+    #[test]
+    fn test_task_result_success() {
+        let manager = TaskManager::new();
+        manager.spawn_task("count".to_string(), "Count".to_string(), || async {
+            Ok(serde_json::json!({ "count": 3 }))
+        }).unwrap();
+
+        let value = wait_for_result(&manager, "count").unwrap();
+        assert_eq!(value["count"], 3);
+        // Results can be read more than once
+        assert!(manager.get_task_result("count").unwrap().is_ok());
+        assert!(manager.get_task_result("missing").is_none());
+    }
+
+    #[test]
+    fn test_task_result_error() {
+        let manager = TaskManager::new();
+        manager.spawn_task("fail".to_string(), "Fail".to_string(), || async {
+            Err(anyhow::anyhow!("broker unavailable"))
+        }).unwrap();
+
+        let err = wait_for_result(&manager, "fail").unwrap_err();
+        assert_eq!(err.to_string(), "broker unavailable");
+        let progress = manager.get_task_progress("fail").unwrap();
+        assert_eq!(progress.error.as_deref(), Some("broker unavailable"));
+    }
+
+    /// Spawn `count` tasks that each run until their sender fires
+    fn spawn_gated(manager: &TaskManager, count: usize) -> Vec<tokio::sync::oneshot::Sender<()>> {
+        (0..count).map(|i| {
+            let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+            manager.spawn_task(format!("task-{}", i), "Gated".to_string(), move || async move {
+                let _ = rx.await;
+                Ok(serde_json::json!(i))
+            }).unwrap();
+            tx
+        }).collect()
+    }
+
+    #[test]
+    fn test_fifth_task_is_queued_at_limit_of_four() {
+        let manager = TaskManager::new_with_limit(4);
+        let mut gates = spawn_gated(&manager, 5);
+
+        assert_eq!(manager.running_count(), 4);
+        assert_eq!(manager.queue_depth(), 1);
+        let tasks = manager.list_tasks();
+        assert_eq!(tasks.len(), 5);
+        assert_eq!(tasks[4].id, "task-4");
+        assert!(!tasks[4].is_finished);
+        assert!(!manager.get_task_progress("task-4").unwrap().is_complete);
+
+        gates.remove(0).send(()).unwrap();
+        assert_eq!(wait_for_result(&manager, "task-0").unwrap(), serde_json::json!(0));
+        for _ in 0..100 {
+            if manager.queue_depth() == 0 {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(manager.queue_depth(), 0);
+        assert_eq!(manager.running_count(), 4);
+
+        for gate in gates {
+            gate.send(()).unwrap();
+        }
+        assert_eq!(wait_for_result(&manager, "task-4").unwrap(), serde_json::json!(4));
+    }
+
+    #[test]
+    fn test_cancel_queued_task() {
+        let manager = TaskManager::new_with_limit(1);
+        let _gates = spawn_gated(&manager, 3);
+        assert_eq!(manager.queue_depth(), 2);
+
+        manager.cancel_task("task-2").unwrap();
+        assert_eq!(manager.queue_depth(), 1);
+        let progress = manager.get_task_progress("task-2").unwrap();
+        assert_eq!(progress.error.as_deref(), Some("Cancelled"));
+
+        // Cancelling the running task starts the next queued one
+        manager.cancel_task("task-0").unwrap();
+        assert_eq!(manager.queue_depth(), 0);
+        assert_eq!(manager.running_count(), 1);
+        assert_eq!(manager.cancel_all(), 1);
+    }
+
+    #[test]
+    fn test_expire_completed() {
+        let manager = TaskManager::new();
+        manager.spawn_task("done".to_string(), "Done".to_string(), || async {
+            Ok(serde_json::Value::Bool(true))
+        }).unwrap();
+        wait_for_result(&manager, "done").unwrap();
+
+        let mut completed = manager.completed_tasks.lock().unwrap();
+        assert_eq!(expire_completed(&mut completed, Instant::now()), 0);
+        assert_eq!(expire_completed(&mut completed, Instant::now() + RESULT_RETENTION), 1);
+        assert!(completed.is_empty());
+    }
+
+    #[test]
+    fn test_task_handle_reports_progress() {
+        let manager = TaskManager::new();
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        manager.spawn_task_with_handle("export".to_string(), "Export".to_string(), move |handle| async move {
+            handle.set_total(250);
+            handle.set_current(40);
+            let _ = rx.await;
+            Ok(serde_json::Value::Null)
+        }).unwrap();
+
+        let mut progress = manager.get_task_progress("export").unwrap();
+        for _ in 0..100 {
+            if progress.current == 40 {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+            progress = manager.get_task_progress("export").unwrap();
+        }
+        assert_eq!((progress.current, progress.total), (40, 250));
+
+        tx.send(()).unwrap();
+        wait_for_result(&manager, "export").unwrap();
+        let progress = manager.get_task_progress("export").unwrap();
+        assert_eq!((progress.current, progress.total), (250, 250));
+    }
+
+    #[test]
+    fn test_periodic_tasks_do_not_take_slots() {
+        let manager = TaskManager::new();
+        for i in 0..DEFAULT_MAX_CONCURRENT_TASKS {
+            manager.spawn_periodic_task(format!("periodic-{}", i), "Periodic".to_string(), || async {
+                loop {
+                    tokio::time::sleep(Duration::from_secs(60)).await;
+                }
+            }).unwrap();
+        }
+        manager.spawn_task("normal".to_string(), "Normal".to_string(), || async {
+            Ok(serde_json::json!("done"))
+        }).unwrap();
+
+        assert_eq!(manager.queue_depth(), 0);
+        assert_eq!(wait_for_result(&manager, "normal").unwrap(), serde_json::json!("done"));
+        assert_eq!(manager.running_count(), 0);
+        assert_eq!(manager.cancel_all(), DEFAULT_MAX_CONCURRENT_TASKS);
+    }
+
+    #[test]
+    fn test_cancel_task_sets_handle_flag() {
+        let manager = TaskManager::new();
+        let (tx, rx) = std::sync::mpsc::channel();
+        manager.spawn_task_with_handle("blocking".to_string(), "Blocking".to_string(), move |handle| async move {
+            tx.send(handle).unwrap();
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok(serde_json::Value::Null)
+        }).unwrap();
+
+        let handle = rx.recv_timeout(Duration::from_secs(1)).unwrap();
+        assert!(!handle.is_cancelled());
+        manager.cancel_task("blocking").unwrap();
+        assert!(handle.is_cancelled());
+    }
 }
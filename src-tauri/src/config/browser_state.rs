@@ -0,0 +1,97 @@
+// Copyright 2024 Offset Explorer Rust Team
+// Licensed under Proprietary License
+//
+// Browser state - last opened location, restored on startup
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File name of the browser state inside the configuration directory
+const BROWSER_STATE_FILE: &str = "browserstate.json";
+
+/// Where the user was browsing when the application closed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BrowserState {
+    #[serde(rename = "lastServerId")]
+    pub last_server_id: Option<i64>,
+    #[serde(rename = "lastTopic")]
+    pub last_topic: Option<String>,
+    #[serde(rename = "lastPartition")]
+    pub last_partition: Option<i32>,
+    #[serde(rename = "lastOffset")]
+    pub last_offset: Option<i64>,
+    #[serde(rename = "expandedTopics")]
+    pub expanded_topics: Vec<String>,
+}
+
+impl BrowserState {
+    /// Default location: `~/.offsetexplorer3/browserstate.json`
+    pub fn default_path() -> Result<PathBuf> {
+        let home = dirs::home_dir().ok_or_else(|| anyhow!("Could not determine home directory"))?;
+        Ok(home.join(".offsetexplorer3").join(BROWSER_STATE_FILE))
+    }
+
+    /// Load the state, returning an empty state if the file does not exist yet
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let json = fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+        serde_json::from_str(&json)
+            .map_err(|e| anyhow!("Failed to parse {}: {}", path.display(), e))
+    }
+
+    /// Write the state, creating the configuration directory if needed
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| anyhow!("Failed to create {}: {}", dir.display(), e))?;
+        }
+
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| anyhow!("Failed to serialize browser state: {}", e))?;
+        fs::write(path, json)
+            .map_err(|e| anyhow!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Remember a topic/partition the user navigated to
+    pub fn navigate(&mut self, server_id: i64, topic: &str, partition: Option<i32>, offset: Option<i64>) {
+        self.last_server_id = Some(server_id);
+        self.last_topic = Some(topic.to_string());
+        self.last_partition = partition;
+        self.last_offset = offset;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_browser_state_round_trip() {
+        let path = std::env::temp_dir()
+            .join(format!("offset-explorer-browserstate-{}", std::process::id()))
+            .join(BROWSER_STATE_FILE);
+        assert_eq!(BrowserState::load_from(&path).unwrap(), BrowserState::default());
+
+        let mut state = BrowserState::default();
+        state.navigate(3, "orders", Some(1), Some(42));
+        state.expanded_topics.push("orders".to_string());
+        state.save_to(&path).unwrap();
+        assert_eq!(BrowserState::load_from(&path).unwrap(), state);
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_browser_state_partial_json() {
+        let state: BrowserState = serde_json::from_str(r#"{"lastTopic":"audit"}"#).unwrap();
+        assert_eq!(state.last_topic.as_deref(), Some("audit"));
+        assert!(state.expanded_topics.is_empty());
+    }
+}
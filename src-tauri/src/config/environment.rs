@@ -1,21 +1,17 @@
 // Copyright 2024 Offset Explorer Rust Team
 // Licensed under Proprietary License
 //
-// Environment singleton - manages global application state and configuration
+// Environment - application configuration files and the settings loaded from them
 // Compatible with Java Environment class
 
-use crate::config::{settings::UserSettings, server_connection_clean::{ServerConnectionSettings as ServerConnectionSettings, ServerGroupManager}, ui_events::EventBus};
-use dirs;
-pub use server_connection_clean::ServerConnection as Connection;
-use crate::ui_events::EventBus;
+use crate::config::{ServerConnectionSettings, ServerGroupManager, UserSettings};
+use anyhow::{Result, anyhow};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
 
-pub use server_connection_clean::ServerConnection as Connection;
-
-pub const fn get_user_home() -> Option<PathBuf> {
-    dirs::home_dir()
-}
-
-impl ConfigPaths {
+/// Configuration directory paths
+#[derive(Debug, Clone)]
+pub struct ConfigPaths {
     pub user_home: PathBuf,
     pub config_dir: PathBuf,
     pub settings_file: PathBuf,
@@ -24,15 +20,242 @@ impl ConfigPaths {
     pub browser_history_file: PathBuf,
     pub license_file: PathBuf,
 }
-// Copyright 2024 Offset Explorer Rust Team
-// Licensed under Proprietary License
 
-use crate::config::{settings::UserSettings, server_connection_clean::{ServerConnectionSettings as ServerConnectionSettings, ServerGroupManager}, ui_events::EventBus};
-use dirs;
-pub use server_connection_clean::ServerConnection as Connection;
+impl ConfigPaths {
+    /// Paths under `<user_home>/.offsetexplorer3`
+    pub fn new(user_home: PathBuf) -> Self {
+        let config_dir = user_home.join(".offsetexplorer3");
+        Self {
+            settings_file: config_dir.join("settings.xml"),
+            server_groups_file: config_dir.join("servergroups.xml"),
+            connections_file: config_dir.join("connections.xml"),
+            browser_history_file: config_dir.join("browserhistory.xml"),
+            license_file: config_dir.join("license.ktl"),
+            config_dir,
+            user_home,
+        }
+    }
+
+    /// Paths under the current user's home directory
+    pub fn from_home_dir() -> Result<Self> {
+        let home = dirs::home_dir().ok_or_else(|| anyhow!("Could not determine home directory"))?;
+        Ok(Self::new(home))
+    }
+}
+
+/// Application configuration loaded from the configuration directory
+pub struct Environment {
+    pub paths: ConfigPaths,
+    pub settings: UserSettings,
+    pub connection_settings: ServerConnectionSettings,
+    pub server_group_manager: ServerGroupManager,
+    /// Problems found by the last `load_settings`, for display to the user
+    pub load_errors: Vec<String>,
+    /// Set when a damaged file could not be backed up, so saving would destroy it
+    pub read_only: bool,
+}
+
+impl Environment {
+    /// Create an environment with default settings; call `load_settings` to read the files
+    pub fn new(paths: ConfigPaths) -> Self {
+        Self {
+            paths,
+            settings: UserSettings::new(),
+            connection_settings: ServerConnectionSettings::new(),
+            server_group_manager: ServerGroupManager::new(),
+            load_errors: Vec::new(),
+            read_only: false,
+        }
+    }
+
+    /// Load settings from XML files
+    ///
+    /// A file that cannot be read is moved to `<file>.bak` and replaced by defaults; a file
+    /// with skipped entries is copied to `<file>.bak` before the next save can drop them.
+    /// Problems are collected in `load_errors`.
+    pub fn load_settings(&mut self) {
+        info!("Loading application settings from {}", self.paths.config_dir.display());
+        self.load_errors.clear();
+        self.read_only = false;
+
+        let settings_file = self.paths.settings_file.clone();
+        self.settings = UserSettings::load_from_file(&settings_file).unwrap_or_else(|e| {
+            self.set_aside(&settings_file, e);
+            UserSettings::new()
+        });
+
+        let connections_file = self.paths.connections_file.clone();
+        self.connection_settings = match ServerConnectionSettings::load_from_file(&connections_file, &self.settings) {
+            Ok(connection_settings) => {
+                self.back_up_partial(&connections_file, connection_settings.load_warnings());
+                connection_settings
+            }
+            Err(e) => {
+                self.set_aside(&connections_file, e);
+                ServerConnectionSettings::new()
+            }
+        };
+        info!("Loaded {} server connections", self.connection_settings.get_connections().len());
+
+        let server_groups_file = self.paths.server_groups_file.clone();
+        self.server_group_manager = match ServerGroupManager::load_from_file(&server_groups_file) {
+            Ok(server_group_manager) => {
+                self.back_up_partial(&server_groups_file, server_group_manager.load_warnings());
+                server_group_manager
+            }
+            Err(e) => {
+                self.set_aside(&server_groups_file, e);
+                ServerGroupManager::new()
+            }
+        };
+    }
+
+    /// Move a file that failed to load out of the way so defaults can be saved over it
+    fn set_aside(&mut self, path: &Path, error: anyhow::Error) {
+        let backup = backup_path(path);
+        let message = match std::fs::rename(path, &backup) {
+            Ok(()) => format!("{}; moved it to {} and started with defaults", error, backup.display()),
+            Err(e) => {
+                self.read_only = true;
+                format!(
+                    "{}; could not move it to {} ({}), so changes will not be saved",
+                    error,
+                    backup.display(),
+                    e
+                )
+            }
+        };
+        warn!("{}", message);
+        self.load_errors.push(message);
+    }
+
+    /// Keep a copy of a file whose skipped entries would be lost on the next save
+    fn back_up_partial(&mut self, path: &Path, warnings: &[String]) {
+        if warnings.is_empty() {
+            return;
+        }
+
+        let backup = backup_path(path);
+        let kept = match std::fs::copy(path, &backup) {
+            Ok(_) => format!("the original is kept in {}", backup.display()),
+            Err(e) => {
+                self.read_only = true;
+                format!("could not copy it to {} ({}), so changes will not be saved", backup.display(), e)
+            }
+        };
+        for warning in warnings {
+            self.load_errors.push(format!("{}: {}; {}", path.display(), warning, kept));
+        }
+    }
+
+    fn ensure_writable(&self) -> Result<()> {
+        if self.read_only {
+            return Err(anyhow!(
+                "Configuration in {} could not be loaded or backed up, so changes are not saved",
+                self.paths.config_dir.display()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Save the server connections to `connections.xml`
+    pub fn save_connections(&self) -> Result<()> {
+        self.ensure_writable()?;
+        self.connection_settings.save_to_file(&self.paths.connections_file)
+    }
+
+    /// Save all settings to XML files
+    pub fn save_settings(&self) -> Result<()> {
+        self.ensure_writable()?;
+        info!("Saving application settings to {}", self.paths.config_dir.display());
+        self.settings.save_to_file(&self.paths.settings_file)?;
+        self.connection_settings.save_to_file(&self.paths.connections_file)?;
+        self.server_group_manager.save_to_file(&self.paths.server_groups_file)
+    }
+}
+
+/// `<file>.bak` next to a configuration file
+fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ServerConnection, SettingDataType, SettingValue};
+
+    #[test]
+    fn test_environment_save_and_load() {
+        let home = std::env::temp_dir().join(format!("offset-explorer-env-{}", std::process::id()));
+        let paths = ConfigPaths::new(home.clone());
+        assert_eq!(paths.connections_file, home.join(".offsetexplorer3").join("connections.xml"));
+
+        let mut env = Environment::new(paths.clone());
+        env.load_settings();
+        assert!(env.connection_settings.get_connections().is_empty());
+
+        let mut conn = ServerConnection::new(3, "staging".to_string());
+        conn.bootstrap_servers = "staging:9092".to_string();
+        env.connection_settings.add_connection(conn).unwrap();
+        let group = env.server_group_manager.add_group(0, "Staging".to_string()).unwrap();
+        env.settings.set("broker_read_timeout", SettingValue::Integer(2500), true, SettingDataType::Integer, true);
+        env.save_settings().unwrap();
+
+        let mut reloaded = Environment::new(paths);
+        reloaded.load_settings();
+        let _ = std::fs::remove_dir_all(&home);
+        let conn = reloaded.connection_settings.find_connection(3).unwrap();
+        assert_eq!(conn.get_name(), "staging");
+        assert_eq!(conn.bootstrap_servers, "staging:9092");
+        assert_eq!(reloaded.settings.get_int("broker_read_timeout").unwrap(), 2500);
+        assert_eq!(reloaded.server_group_manager.get_group(group).unwrap().get_name(), "Staging");
+    }
+
+    #[test]
+    fn test_unreadable_connections_file_is_moved_aside() {
+        let home = std::env::temp_dir().join(format!("offset-explorer-env-bad-{}", std::process::id()));
+        let paths = ConfigPaths::new(home.clone());
+        std::fs::create_dir_all(&paths.config_dir).unwrap();
+        std::fs::write(&paths.connections_file, "<connections></servergroups>").unwrap();
+
+        let mut env = Environment::new(paths.clone());
+        env.load_settings();
+        assert_eq!(env.load_errors.len(), 1);
+        assert!(!env.read_only);
+        let backup = paths.config_dir.join("connections.xml.bak");
+        let saved = std::fs::read_to_string(&backup).unwrap();
+        assert!(!paths.connections_file.exists());
+
+        env.connection_settings.add_connection(ServerConnection::new(1, "new".to_string())).unwrap();
+        env.save_connections().unwrap();
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), saved);
+
+        env.read_only = true;
+        assert!(env.save_connections().is_err());
+        let _ = std::fs::remove_dir_all(&home);
+        assert_eq!(saved, "<connections></servergroups>");
+    }
 
-pub use server_connection_clean::ServerConnection as Connection;
+    #[test]
+    fn test_skipped_connections_are_backed_up() {
+        let home = std::env::temp_dir().join(format!("offset-explorer-env-partial-{}", std::process::id()));
+        let paths = ConfigPaths::new(home.clone());
+        std::fs::create_dir_all(&paths.config_dir).unwrap();
+        let xml = r#"<connections>
+  <connection name="broken" id="1" broker_security_type="KERBEROS"></connection>
+  <connection name="good" id="2"></connection>
+</connections>"#;
+        std::fs::write(&paths.connections_file, xml).unwrap();
 
-pub const fn get_user_home() -> Option<PathBuf> {
-    dirs::home_dir()
+        let mut env = Environment::new(paths.clone());
+        env.load_settings();
+        let backup = std::fs::read_to_string(paths.config_dir.join("connections.xml.bak"));
+        let _ = std::fs::remove_dir_all(&home);
+        assert_eq!(backup.unwrap(), xml);
+        assert_eq!(env.connection_settings.get_connections().len(), 1);
+        assert_eq!(env.load_errors.len(), 1);
+        assert!(env.load_errors[0].contains("connections.xml.bak"));
+    }
 }
@@ -3,12 +3,38 @@
 
 // Configuration modules
 
+pub mod browser_state;
 pub mod crypto;
+pub mod environment;
 pub mod server_group;
 pub mod settings_complete;
 pub mod server_connection;
 
 // Re-export commonly used types
 pub use settings_complete::{Setting, SettingDataType, SettingValue, UserSettings};
+pub use browser_state::BrowserState;
+pub use environment::{ConfigPaths, Environment};
 pub use server_group::{ServerGroup, ServerGroupManager};
-pub use server_connection::{ServerConnection, ServerConnectionSettings, BrokerSecurityType, ClusterVersion};
+pub use server_connection::{ServerConnection, ServerConnectionSettings, BrokerSecurityType, ClusterVersion, ConnectionEnvironment, ConnectionSummary, SaslMechanism};
+
+use anyhow::{Result, anyhow};
+use std::path::Path;
+
+/// Write a configuration file next to its target and rename it over the target, creating
+/// the configuration directory if needed, so an interrupted save never leaves a truncated file
+pub fn write_file_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| anyhow!("Failed to create {}: {}", dir.display(), e))?;
+    }
+
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+    std::fs::write(&tmp_path, contents)
+        .map_err(|e| anyhow!("Failed to write {}: {}", tmp_path.display(), e))?;
+    std::fs::rename(&tmp_path, path).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp_path);
+        anyhow!("Failed to replace {}: {}", path.display(), e)
+    })
+}
@@ -4,13 +4,20 @@
 // Server connection configuration
 // Compatible with Java ServerConnection and ServerConnectionSettings classes
 
-use crate::config::{crypto, settings_complete::UserSettings};
+use crate::config::{crypto, settings_complete::UserSettings, write_file_atomic};
+use crate::decoders::{
+    Base64Decoder, ByteArrayDecoder, ByteArrayFormat, ChainedDecoder, Decoder, DecoderFactory,
+    JsonDecoder, StringDecoder,
+};
 use anyhow::{Result, anyhow};
 use serde::{Serialize, Deserialize};
 use quick_xml::events::{Event, BytesStart};
 use quick_xml::Writer;
 use std::collections::HashMap;
+use std::fmt;
 use std::io::Cursor;
+use std::path::Path;
+use tracing::warn;
 
 /// Broker security types (compatible with Java BrokerSecurityType)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -22,8 +29,19 @@ pub enum BrokerSecurityType {
 }
 
 impl BrokerSecurityType {
+    /// All security types, in the order shown to the user
+    pub fn all_variants() -> &'static [BrokerSecurityType] {
+        &[
+            BrokerSecurityType::PLAINTEXT,
+            BrokerSecurityType::SSL,
+            BrokerSecurityType::SASL_PLAINTEXT,
+            BrokerSecurityType::SASL_SSL,
+        ]
+    }
+
+    /// Parse a security type name, ignoring case (older files use e.g. "Sasl_Ssl")
     pub fn from_str(s: &str) -> Result<Self> {
-        match s {
+        match s.to_ascii_uppercase().as_str() {
             "PLAINTEXT" => Ok(BrokerSecurityType::PLAINTEXT),
             "SSL" => Ok(BrokerSecurityType::SSL),
             "SASL_PLAINTEXT" => Ok(BrokerSecurityType::SASL_PLAINTEXT),
@@ -32,6 +50,17 @@ impl BrokerSecurityType {
         }
     }
 
+    /// Map the numeric codes stored by older Java versions
+    pub fn from_legacy_int(n: i32) -> Result<Self> {
+        match n {
+            0 => Ok(BrokerSecurityType::PLAINTEXT),
+            1 => Ok(BrokerSecurityType::SSL),
+            2 => Ok(BrokerSecurityType::SASL_PLAINTEXT),
+            3 => Ok(BrokerSecurityType::SASL_SSL),
+            _ => Err(anyhow!("Unknown legacy security type: {}", n)),
+        }
+    }
+
     pub fn as_str(&self) -> &'static str {
         match self {
             BrokerSecurityType::PLAINTEXT => "PLAINTEXT",
@@ -48,10 +77,110 @@ impl BrokerSecurityType {
     pub fn is_ssl(&self) -> bool {
         matches!(self, BrokerSecurityType::SSL | BrokerSecurityType::SASL_SSL)
     }
+
+    /// Is traffic to the broker encrypted with TLS?
+    pub fn is_encrypted(&self) -> bool {
+        matches!(self, BrokerSecurityType::SSL | BrokerSecurityType::SASL_SSL)
+    }
+
+    /// Does connecting need SASL credentials?
+    pub fn requires_credentials(&self) -> bool {
+        matches!(self, BrokerSecurityType::SASL_PLAINTEXT | BrokerSecurityType::SASL_SSL)
+    }
 }
 
-/// Cluster version (compatible with Java ClusterVersion)
+impl fmt::Display for BrokerSecurityType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl TryFrom<String> for BrokerSecurityType {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        Self::from_str(&value)
+    }
+}
+
+/// SASL mechanisms supported for broker authentication
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SaslMechanism {
+    PLAIN,
+    #[serde(rename = "SCRAM-SHA-256")]
+    SCRAM_SHA_256,
+    #[serde(rename = "SCRAM-SHA-512")]
+    SCRAM_SHA_512,
+    GSSAPI,
+    OAUTHBEARER,
+}
+
+impl SaslMechanism {
+    /// All mechanisms, in the order shown to the user
+    pub fn all_variants() -> &'static [SaslMechanism] {
+        &[
+            SaslMechanism::PLAIN,
+            SaslMechanism::SCRAM_SHA_256,
+            SaslMechanism::SCRAM_SHA_512,
+            SaslMechanism::GSSAPI,
+            SaslMechanism::OAUTHBEARER,
+        ]
+    }
+
+    /// Parse a mechanism name, ignoring case and accepting `_` for `-`
+    pub fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_uppercase().replace('_', "-").as_str() {
+            "PLAIN" => Ok(SaslMechanism::PLAIN),
+            "SCRAM-SHA-256" => Ok(SaslMechanism::SCRAM_SHA_256),
+            "SCRAM-SHA-512" => Ok(SaslMechanism::SCRAM_SHA_512),
+            "GSSAPI" => Ok(SaslMechanism::GSSAPI),
+            "OAUTHBEARER" => Ok(SaslMechanism::OAUTHBEARER),
+            _ => Err(anyhow!("Unknown SASL mechanism: {}", s)),
+        }
+    }
+
+    /// Parse a stored mechanism, falling back to the default for unknown values so one
+    /// bad entry does not fail loading the whole connections file
+    pub fn from_str_or_default(s: &str) -> Self {
+        Self::from_str(s).unwrap_or_else(|e| {
+            warn!("{}, using {}", e, SaslMechanism::default());
+            SaslMechanism::default()
+        })
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SaslMechanism::PLAIN => "PLAIN",
+            SaslMechanism::SCRAM_SHA_256 => "SCRAM-SHA-256",
+            SaslMechanism::SCRAM_SHA_512 => "SCRAM-SHA-512",
+            SaslMechanism::GSSAPI => "GSSAPI",
+            SaslMechanism::OAUTHBEARER => "OAUTHBEARER",
+        }
+    }
+
+    /// Does the mechanism authenticate with a username and password?
+    pub fn is_password_based(&self) -> bool {
+        matches!(
+            self,
+            SaslMechanism::PLAIN | SaslMechanism::SCRAM_SHA_256 | SaslMechanism::SCRAM_SHA_512
+        )
+    }
+}
+
+impl Default for SaslMechanism {
+    fn default() -> Self {
+        SaslMechanism::PLAIN
+    }
+}
+
+impl fmt::Display for SaslMechanism {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Cluster version (compatible with Java ClusterVersion), ordered oldest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum ClusterVersion {
     VERSION_0_8_0,
     VERSION_0_8_1,
@@ -102,6 +231,248 @@ impl ClusterVersion {
             _ => "VERSION_1_0_0",
         }
     }
+
+    /// Release number shown to the user, e.g. "3.5"
+    pub fn display_version(&self) -> &'static str {
+        match self {
+            ClusterVersion::VERSION_0_8_0 => "0.8.0",
+            ClusterVersion::VERSION_0_8_1 => "0.8.1",
+            ClusterVersion::VERSION_0_8_2 => "0.8.2",
+            ClusterVersion::VERSION_0_8_2_0 => "0.8.2.0",
+            ClusterVersion::VERSION_0_8_2_1 => "0.8.2.1",
+            ClusterVersion::VERSION_0_8_2_2 => "0.8.2.2",
+            ClusterVersion::VERSION_0_9 => "0.9",
+            ClusterVersion::VERSION_0_10 => "0.10",
+            ClusterVersion::VERSION_0_10_1 => "0.10.1",
+            ClusterVersion::VERSION_0_10_2 => "0.10.2",
+            ClusterVersion::VERSION_0_11 => "0.11",
+            ClusterVersion::VERSION_1_0 => "1.0",
+            ClusterVersion::VERSION_1_1 => "1.1",
+            ClusterVersion::VERSION_2_0 => "2.0",
+            ClusterVersion::VERSION_2_1 => "2.1",
+            ClusterVersion::VERSION_2_2 => "2.2",
+            ClusterVersion::VERSION_2_3 => "2.3",
+            ClusterVersion::VERSION_2_4 => "2.4",
+            ClusterVersion::VERSION_2_5 => "2.5",
+            ClusterVersion::VERSION_2_6 => "2.6",
+            ClusterVersion::VERSION_2_7 => "2.7",
+            ClusterVersion::VERSION_2_8 => "2.8",
+            ClusterVersion::VERSION_3_0 => "3.0",
+            ClusterVersion::VERSION_3_1 => "3.1",
+            ClusterVersion::VERSION_3_2 => "3.2",
+            ClusterVersion::VERSION_3_3 => "3.3",
+            ClusterVersion::VERSION_3_4 => "3.4",
+            ClusterVersion::VERSION_3_5 => "3.5",
+            ClusterVersion::VERSION_3_6 => "3.6",
+            ClusterVersion::VERSION_3_7 => "3.7",
+            ClusterVersion::LATEST => "latest",
+        }
+    }
+
+    /// Highest version of a Kafka API the cluster supports, or `None` if the API
+    /// is not available (or the version predates the table)
+    pub fn max_api_version(&self, api_key: i16) -> Option<i16> {
+        let (_, versions) = API_VERSION_TABLE
+            .iter()
+            .rev()
+            .find(|(release, _)| release <= self)?;
+        versions
+            .iter()
+            .find(|(key, _)| *key == api_key)
+            .map(|(_, max)| *max)
+    }
+}
+
+// Kafka API keys
+pub const API_PRODUCE: i16 = 0;
+pub const API_FETCH: i16 = 1;
+pub const API_LIST_OFFSETS: i16 = 2;
+pub const API_METADATA: i16 = 3;
+pub const API_OFFSET_COMMIT: i16 = 8;
+pub const API_OFFSET_FETCH: i16 = 9;
+pub const API_FIND_COORDINATOR: i16 = 10;
+pub const API_DESCRIBE_GROUPS: i16 = 15;
+pub const API_LIST_GROUPS: i16 = 16;
+pub const API_API_VERSIONS: i16 = 18;
+pub const API_CREATE_TOPICS: i16 = 19;
+pub const API_DELETE_TOPICS: i16 = 20;
+pub const API_DESCRIBE_CONFIGS: i16 = 32;
+pub const API_ALTER_CONFIGS: i16 = 33;
+pub const API_CREATE_PARTITIONS: i16 = 37;
+pub const API_INCREMENTAL_ALTER_CONFIGS: i16 = 44;
+
+/// Maximum API versions, as (api_key, max_version) pairs, for each release that changed them
+pub type ApiVersionTable = &'static [(ClusterVersion, &'static [(i16, i16)])];
+
+/// Broker API support by release; releases not listed use the closest earlier entry
+pub const API_VERSION_TABLE: ApiVersionTable = &[
+    (ClusterVersion::VERSION_0_10, &[
+        (API_PRODUCE, 2), (API_FETCH, 2), (API_LIST_OFFSETS, 0), (API_METADATA, 1),
+        (API_OFFSET_COMMIT, 2), (API_OFFSET_FETCH, 1), (API_FIND_COORDINATOR, 0),
+        (API_DESCRIBE_GROUPS, 0), (API_LIST_GROUPS, 0), (API_API_VERSIONS, 0),
+    ]),
+    (ClusterVersion::VERSION_0_10_1, &[
+        (API_PRODUCE, 2), (API_FETCH, 3), (API_LIST_OFFSETS, 1), (API_METADATA, 2),
+        (API_OFFSET_COMMIT, 2), (API_OFFSET_FETCH, 2), (API_FIND_COORDINATOR, 0),
+        (API_DESCRIBE_GROUPS, 0), (API_LIST_GROUPS, 0), (API_API_VERSIONS, 0),
+        (API_CREATE_TOPICS, 0), (API_DELETE_TOPICS, 0),
+    ]),
+    (ClusterVersion::VERSION_0_10_2, &[
+        (API_PRODUCE, 2), (API_FETCH, 3), (API_LIST_OFFSETS, 1), (API_METADATA, 2),
+        (API_OFFSET_COMMIT, 2), (API_OFFSET_FETCH, 2), (API_FIND_COORDINATOR, 0),
+        (API_DESCRIBE_GROUPS, 0), (API_LIST_GROUPS, 0), (API_API_VERSIONS, 0),
+        (API_CREATE_TOPICS, 1), (API_DELETE_TOPICS, 0),
+    ]),
+    (ClusterVersion::VERSION_0_11, &[
+        (API_PRODUCE, 3), (API_FETCH, 5), (API_LIST_OFFSETS, 2), (API_METADATA, 4),
+        (API_OFFSET_COMMIT, 3), (API_OFFSET_FETCH, 3), (API_FIND_COORDINATOR, 1),
+        (API_DESCRIBE_GROUPS, 1), (API_LIST_GROUPS, 1), (API_API_VERSIONS, 1),
+        (API_CREATE_TOPICS, 2), (API_DELETE_TOPICS, 1), (API_DESCRIBE_CONFIGS, 0),
+        (API_ALTER_CONFIGS, 0),
+    ]),
+    (ClusterVersion::VERSION_1_0, &[
+        (API_PRODUCE, 5), (API_FETCH, 6), (API_LIST_OFFSETS, 2), (API_METADATA, 5),
+        (API_OFFSET_COMMIT, 3), (API_OFFSET_FETCH, 3), (API_FIND_COORDINATOR, 1),
+        (API_DESCRIBE_GROUPS, 1), (API_LIST_GROUPS, 1), (API_API_VERSIONS, 1),
+        (API_CREATE_TOPICS, 2), (API_DELETE_TOPICS, 1), (API_DESCRIBE_CONFIGS, 0),
+        (API_ALTER_CONFIGS, 0), (API_CREATE_PARTITIONS, 0),
+    ]),
+    (ClusterVersion::VERSION_1_1, &[
+        (API_PRODUCE, 5), (API_FETCH, 7), (API_LIST_OFFSETS, 2), (API_METADATA, 5),
+        (API_OFFSET_COMMIT, 3), (API_OFFSET_FETCH, 3), (API_FIND_COORDINATOR, 1),
+        (API_DESCRIBE_GROUPS, 1), (API_LIST_GROUPS, 1), (API_API_VERSIONS, 1),
+        (API_CREATE_TOPICS, 2), (API_DELETE_TOPICS, 1), (API_DESCRIBE_CONFIGS, 1),
+        (API_ALTER_CONFIGS, 0), (API_CREATE_PARTITIONS, 0),
+    ]),
+    (ClusterVersion::VERSION_2_0, &[
+        (API_PRODUCE, 6), (API_FETCH, 8), (API_LIST_OFFSETS, 3), (API_METADATA, 6),
+        (API_OFFSET_COMMIT, 4), (API_OFFSET_FETCH, 4), (API_FIND_COORDINATOR, 2),
+        (API_DESCRIBE_GROUPS, 2), (API_LIST_GROUPS, 2), (API_API_VERSIONS, 2),
+        (API_CREATE_TOPICS, 3), (API_DELETE_TOPICS, 2), (API_DESCRIBE_CONFIGS, 2),
+        (API_ALTER_CONFIGS, 1), (API_CREATE_PARTITIONS, 1),
+    ]),
+    (ClusterVersion::VERSION_2_1, &[
+        (API_PRODUCE, 7), (API_FETCH, 10), (API_LIST_OFFSETS, 4), (API_METADATA, 7),
+        (API_OFFSET_COMMIT, 6), (API_OFFSET_FETCH, 5), (API_FIND_COORDINATOR, 2),
+        (API_DESCRIBE_GROUPS, 2), (API_LIST_GROUPS, 2), (API_API_VERSIONS, 2),
+        (API_CREATE_TOPICS, 3), (API_DELETE_TOPICS, 3), (API_DESCRIBE_CONFIGS, 2),
+        (API_ALTER_CONFIGS, 1), (API_CREATE_PARTITIONS, 1),
+    ]),
+    (ClusterVersion::VERSION_2_2, &[
+        (API_PRODUCE, 7), (API_FETCH, 10), (API_LIST_OFFSETS, 5), (API_METADATA, 7),
+        (API_OFFSET_COMMIT, 6), (API_OFFSET_FETCH, 5), (API_FIND_COORDINATOR, 2),
+        (API_DESCRIBE_GROUPS, 3), (API_LIST_GROUPS, 2), (API_API_VERSIONS, 2),
+        (API_CREATE_TOPICS, 3), (API_DELETE_TOPICS, 3), (API_DESCRIBE_CONFIGS, 2),
+        (API_ALTER_CONFIGS, 1), (API_CREATE_PARTITIONS, 1),
+    ]),
+    (ClusterVersion::VERSION_2_3, &[
+        (API_PRODUCE, 7), (API_FETCH, 11), (API_LIST_OFFSETS, 5), (API_METADATA, 8),
+        (API_OFFSET_COMMIT, 7), (API_OFFSET_FETCH, 5), (API_FIND_COORDINATOR, 2),
+        (API_DESCRIBE_GROUPS, 4), (API_LIST_GROUPS, 2), (API_API_VERSIONS, 2),
+        (API_CREATE_TOPICS, 3), (API_DELETE_TOPICS, 3), (API_DESCRIBE_CONFIGS, 2),
+        (API_ALTER_CONFIGS, 1), (API_INCREMENTAL_ALTER_CONFIGS, 0), (API_CREATE_PARTITIONS, 1),
+    ]),
+    (ClusterVersion::VERSION_2_4, &[
+        (API_PRODUCE, 8), (API_FETCH, 11), (API_LIST_OFFSETS, 5), (API_METADATA, 9),
+        (API_OFFSET_COMMIT, 8), (API_OFFSET_FETCH, 6), (API_FIND_COORDINATOR, 3),
+        (API_DESCRIBE_GROUPS, 5), (API_LIST_GROUPS, 3), (API_API_VERSIONS, 3),
+        (API_CREATE_TOPICS, 5), (API_DELETE_TOPICS, 4), (API_DESCRIBE_CONFIGS, 2),
+        (API_ALTER_CONFIGS, 1), (API_INCREMENTAL_ALTER_CONFIGS, 1), (API_CREATE_PARTITIONS, 2),
+    ]),
+    (ClusterVersion::VERSION_2_5, &[
+        (API_PRODUCE, 8), (API_FETCH, 11), (API_LIST_OFFSETS, 5), (API_METADATA, 9),
+        (API_OFFSET_COMMIT, 8), (API_OFFSET_FETCH, 7), (API_FIND_COORDINATOR, 3),
+        (API_DESCRIBE_GROUPS, 5), (API_LIST_GROUPS, 3), (API_API_VERSIONS, 3),
+        (API_CREATE_TOPICS, 5), (API_DELETE_TOPICS, 4), (API_DESCRIBE_CONFIGS, 2),
+        (API_ALTER_CONFIGS, 1), (API_INCREMENTAL_ALTER_CONFIGS, 1), (API_CREATE_PARTITIONS, 2),
+    ]),
+    (ClusterVersion::VERSION_2_6, &[
+        (API_PRODUCE, 8), (API_FETCH, 11), (API_LIST_OFFSETS, 5), (API_METADATA, 9),
+        (API_OFFSET_COMMIT, 8), (API_OFFSET_FETCH, 7), (API_FIND_COORDINATOR, 3),
+        (API_DESCRIBE_GROUPS, 5), (API_LIST_GROUPS, 4), (API_API_VERSIONS, 3),
+        (API_CREATE_TOPICS, 5), (API_DELETE_TOPICS, 4), (API_DESCRIBE_CONFIGS, 3),
+        (API_ALTER_CONFIGS, 1), (API_INCREMENTAL_ALTER_CONFIGS, 1), (API_CREATE_PARTITIONS, 2),
+    ]),
+    (ClusterVersion::VERSION_2_7, &[
+        (API_PRODUCE, 8), (API_FETCH, 12), (API_LIST_OFFSETS, 6), (API_METADATA, 10),
+        (API_OFFSET_COMMIT, 8), (API_OFFSET_FETCH, 7), (API_FIND_COORDINATOR, 3),
+        (API_DESCRIBE_GROUPS, 5), (API_LIST_GROUPS, 4), (API_API_VERSIONS, 3),
+        (API_CREATE_TOPICS, 6), (API_DELETE_TOPICS, 5), (API_DESCRIBE_CONFIGS, 3),
+        (API_ALTER_CONFIGS, 1), (API_INCREMENTAL_ALTER_CONFIGS, 1), (API_CREATE_PARTITIONS, 2),
+    ]),
+    (ClusterVersion::VERSION_2_8, &[
+        (API_PRODUCE, 9), (API_FETCH, 12), (API_LIST_OFFSETS, 6), (API_METADATA, 11),
+        (API_OFFSET_COMMIT, 8), (API_OFFSET_FETCH, 7), (API_FIND_COORDINATOR, 3),
+        (API_DESCRIBE_GROUPS, 5), (API_LIST_GROUPS, 4), (API_API_VERSIONS, 3),
+        (API_CREATE_TOPICS, 7), (API_DELETE_TOPICS, 6), (API_DESCRIBE_CONFIGS, 4),
+        (API_ALTER_CONFIGS, 2), (API_INCREMENTAL_ALTER_CONFIGS, 1), (API_CREATE_PARTITIONS, 3),
+    ]),
+    (ClusterVersion::VERSION_3_0, &[
+        (API_PRODUCE, 9), (API_FETCH, 13), (API_LIST_OFFSETS, 7), (API_METADATA, 12),
+        (API_OFFSET_COMMIT, 8), (API_OFFSET_FETCH, 8), (API_FIND_COORDINATOR, 4),
+        (API_DESCRIBE_GROUPS, 5), (API_LIST_GROUPS, 4), (API_API_VERSIONS, 3),
+        (API_CREATE_TOPICS, 7), (API_DELETE_TOPICS, 6), (API_DESCRIBE_CONFIGS, 4),
+        (API_ALTER_CONFIGS, 2), (API_INCREMENTAL_ALTER_CONFIGS, 1), (API_CREATE_PARTITIONS, 3),
+    ]),
+    (ClusterVersion::VERSION_3_5, &[
+        (API_PRODUCE, 9), (API_FETCH, 15), (API_LIST_OFFSETS, 8), (API_METADATA, 12),
+        (API_OFFSET_COMMIT, 8), (API_OFFSET_FETCH, 8), (API_FIND_COORDINATOR, 4),
+        (API_DESCRIBE_GROUPS, 5), (API_LIST_GROUPS, 4), (API_API_VERSIONS, 3),
+        (API_CREATE_TOPICS, 7), (API_DELETE_TOPICS, 6), (API_DESCRIBE_CONFIGS, 4),
+        (API_ALTER_CONFIGS, 2), (API_INCREMENTAL_ALTER_CONFIGS, 1), (API_CREATE_PARTITIONS, 3),
+    ]),
+    (ClusterVersion::VERSION_3_7, &[
+        (API_PRODUCE, 10), (API_FETCH, 16), (API_LIST_OFFSETS, 8), (API_METADATA, 12),
+        (API_OFFSET_COMMIT, 9), (API_OFFSET_FETCH, 9), (API_FIND_COORDINATOR, 4),
+        (API_DESCRIBE_GROUPS, 5), (API_LIST_GROUPS, 5), (API_API_VERSIONS, 3),
+        (API_CREATE_TOPICS, 7), (API_DELETE_TOPICS, 6), (API_DESCRIBE_CONFIGS, 4),
+        (API_ALTER_CONFIGS, 2), (API_INCREMENTAL_ALTER_CONFIGS, 1), (API_CREATE_PARTITIONS, 3),
+    ]),
+];
+
+/// Deployment environment a connection points at
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum ConnectionEnvironment {
+    Dev,
+    Staging,
+    Production,
+    Custom(String),
+}
+
+impl ConnectionEnvironment {
+    /// Parse an environment name; unknown names become `Custom`
+    pub fn from_str(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "dev" => ConnectionEnvironment::Dev,
+            "staging" => ConnectionEnvironment::Staging,
+            "production" => ConnectionEnvironment::Production,
+            _ => ConnectionEnvironment::Custom(s.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for ConnectionEnvironment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConnectionEnvironment::Dev => f.write_str("dev"),
+            ConnectionEnvironment::Staging => f.write_str("staging"),
+            ConnectionEnvironment::Production => f.write_str("production"),
+            ConnectionEnvironment::Custom(name) => f.write_str(name),
+        }
+    }
+}
+
+impl From<String> for ConnectionEnvironment {
+    fn from(value: String) -> Self {
+        Self::from_str(&value)
+    }
+}
+
+impl From<ConnectionEnvironment> for String {
+    fn from(value: ConnectionEnvironment) -> Self {
+        value.to_string()
+    }
 }
 
 /// Topic folder (for organizing topics)
@@ -124,13 +495,70 @@ impl TopicFolder {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TopicDecoderConfig {
     pub key_decoder: Option<String>,
+    /// Decoder name resolved by `decoders::decoder_by_name`: string, bytearray, hex, avro,
+    /// json, integer, long, short, uint8/16/32/64, varint, zigzag, float, double, base64, protobuf,
+    /// msgpack, gzip, snappy, lz4 or zstd
     pub message_decoder: Option<String>,
+    /// Comma separated decoder names applied in order (e.g. `base64,json`); overrides `message_decoder`
+    #[serde(default)]
+    pub decoder_chain: Option<String>,
+    /// JSONPath (e.g. `$.user.id`) selecting the part of JSON values to display
+    #[serde(default)]
+    pub json_path: Option<String>,
     pub string_type: Option<String>,
     pub header_type: Option<String>,
     pub offset_request_type: Option<String>,
     pub parent_folder: Option<String>,
 }
 
+impl TopicDecoderConfig {
+    /// Build the decoder for header values; unknown types fall back to UTF-8 strings
+    pub fn build_header_decoder(header_type: &str) -> Box<dyn Decoder + Send + Sync> {
+        match header_type.to_lowercase().as_str() {
+            "base64" => Box::new(Base64Decoder::new()),
+            "hex" => Box::new(ByteArrayDecoder::with_format(ByteArrayFormat::Hex)),
+            "json" => Box::new(JsonDecoder::new()),
+            _ => Box::new(StringDecoder::new()),
+        }
+    }
+
+    /// Build the configured message decoder chain, if any
+    pub fn build_decoder_chain(&self) -> anyhow::Result<Option<ChainedDecoder>> {
+        self.decoder_chain
+            .as_deref()
+            .filter(|spec| !spec.trim().is_empty())
+            .map(DecoderFactory::parse_chain)
+            .transpose()
+    }
+}
+
+/// Connection details for the frontend connection list
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectionSummary {
+    pub name: String,
+    #[serde(rename = "bootstrapServers")]
+    pub bootstrap_servers: String,
+    #[serde(rename = "securityType")]
+    pub security_type: BrokerSecurityType,
+    pub version: String,
+    #[serde(rename = "hasSchemaRegistry")]
+    pub has_schema_registry: bool,
+    #[serde(rename = "hasZookeeper")]
+    pub has_zookeeper: bool,
+}
+
+/// Number of entries in a comma-separated broker list
+fn broker_count(bootstrap_servers: &str) -> usize {
+    bootstrap_servers.split(',').filter(|s| !s.trim().is_empty()).count()
+}
+
+/// Value of a `key="value"` option in a JAAS login module line
+fn jaas_option(jaas: &str, key: &str) -> Option<String> {
+    let pattern = format!(r#"(?:^|\s){}\s*=\s*"((?:[^"\\]|\\.)*)""#, regex::escape(key));
+    let captures = regex::Regex::new(&pattern).ok()?.captures(jaas)?;
+    Some(captures[1].replace(r#"\""#, "\"").replace(r"\\", r"\"))
+}
+
 /// Server connection configuration
 /// Compatible with Java ServerConnection class
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -146,6 +574,7 @@ pub struct ServerConnection {
     pub bootstrap_servers: String,
     pub validate_ssl_endpoint_hostname: bool,
     pub enable_poller: bool,
+    pub environment: Option<ConnectionEnvironment>,
 
     // SSL settings
     pub truststore_location: Option<String>,
@@ -155,11 +584,31 @@ pub struct ServerConnection {
     pub keystore_privatekey: Option<String>,
 
     // SASL settings
-    pub sasl_mechanism: Option<String>,
+    pub sasl_mechanism: SaslMechanism,
     pub sasl_callback: Option<String>,
     pub sasl_endpoint_token: Option<String>,
     pub jaas_config: Option<String>,
 
+    // Kerberos settings (SASL GSSAPI)
+    #[serde(default)]
+    pub kerberos_service_name: Option<String>,
+    #[serde(default)]
+    pub kerberos_keytab: Option<String>,
+    #[serde(default)]
+    pub kerberos_principal: Option<String>,
+
+    // OAuth settings (SASL OAUTHBEARER)
+    #[serde(default)]
+    pub oauth_token: Option<String>,
+    #[serde(default)]
+    pub oauth_token_endpoint_url: Option<String>,
+
+    // Producer settings
+    #[serde(default)]
+    pub enable_idempotence: bool,
+    #[serde(default)]
+    pub transactional_id: Option<String>,
+
     // Schema Registry settings
     pub schema_registry_endpoint: Option<String>,
     pub schema_registry_basic_auth: Option<String>,
@@ -188,15 +637,23 @@ impl ServerConnection {
             bootstrap_servers: String::new(),
             validate_ssl_endpoint_hostname: true,
             enable_poller: true,
+            environment: None,
             truststore_location: None,
             truststore_password: None,
             keystore_location: None,
             keystore_password: None,
             keystore_privatekey: None,
-            sasl_mechanism: None,
+            sasl_mechanism: SaslMechanism::default(),
             sasl_callback: None,
             sasl_endpoint_token: None,
             jaas_config: None,
+            kerberos_service_name: None,
+            kerberos_keytab: None,
+            kerberos_principal: None,
+            oauth_token: None,
+            oauth_token_endpoint_url: None,
+            enable_idempotence: false,
+            transactional_id: None,
             schema_registry_endpoint: None,
             schema_registry_basic_auth: None,
             schema_registry_ssl_truststore_location: None,
@@ -209,6 +666,34 @@ impl ServerConnection {
         }
     }
 
+    /// Copy the decoder config of `source_topic` to each of `dest_topics`,
+    /// overwriting existing entries. Returns the number of topics updated.
+    pub fn clone_topic_config(&mut self, source_topic: &str, dest_topics: &[String]) -> Result<usize> {
+        let config = self.topic_configs.get(source_topic)
+            .cloned()
+            .ok_or_else(|| anyhow!("Topic '{}' has no decoder config", source_topic))?;
+
+        let mut updated = 0;
+        for dest in dest_topics.iter().filter(|t| t.as_str() != source_topic) {
+            self.topic_configs.insert(dest.clone(), config.clone());
+            updated += 1;
+        }
+        Ok(updated)
+    }
+
+    /// Remove passwords, private keys, tokens and JAAS config (which embeds credentials)
+    pub fn clear_secrets(&mut self) {
+        self.truststore_password = None;
+        self.keystore_password = None;
+        self.keystore_privatekey = None;
+        self.jaas_config = None;
+        self.oauth_token = None;
+        self.schema_registry_basic_auth = None;
+        self.schema_registry_ssl_truststore_password = None;
+        self.schema_registry_ssl_keystore_password = None;
+        self.schema_registry_ssl_keystore_private_key = None;
+    }
+
     pub fn get_id(&self) -> i64 {
         self.id
     }
@@ -241,6 +726,14 @@ impl ServerConnection {
         self.host.is_empty()
     }
 
+    /// ZooKeeper connect string built from host and port
+    pub fn get_zookeeper_hosts(&self) -> String {
+        match self.port {
+            Some(port) => format!("{}:{}", self.host, port),
+            None => self.host.clone(),
+        }
+    }
+
     pub fn get_bootstrap_servers(&self) -> String {
         if self.bootstrap_servers.is_empty() {
             if let Some(port) = self.port {
@@ -253,6 +746,31 @@ impl ServerConnection {
         }
     }
 
+    /// Summary for the connection list, e.g. "MyCluster (3 brokers, SASL_SSL, v3.5)"
+    pub fn to_display_string(&self) -> String {
+        let brokers = broker_count(&self.get_bootstrap_servers());
+        format!(
+            "{} ({} broker{}, {}, v{})",
+            self.name,
+            brokers,
+            if brokers == 1 { "" } else { "s" },
+            self.broker_security_type,
+            self.version.display_version()
+        )
+    }
+
+    /// Details shown on the connection list card
+    pub fn get_connection_summary(&self) -> ConnectionSummary {
+        ConnectionSummary {
+            name: self.name.clone(),
+            bootstrap_servers: self.get_bootstrap_servers(),
+            security_type: self.broker_security_type,
+            version: self.version.display_version().to_string(),
+            has_schema_registry: self.schema_registry_endpoint.as_deref().is_some_and(|e| !e.is_empty()),
+            has_zookeeper: !self.no_zookeeper_mode(),
+        }
+    }
+
     /// Add sasl_username and sasl_password fields for compatibility
     /// These map to sasl_callback and sasl_endpoint_token
     pub fn get_sasl_username(&self) -> Option<&String> {
@@ -263,16 +781,37 @@ impl ServerConnection {
         self.sasl_endpoint_token.as_ref()
     }
 
+    /// SASL username and password. Options of a non-empty JAAS config win over the individual
+    /// fields, which are used for whatever the JAAS config leaves out; librdkafka has no
+    /// `sasl.jaas.config`, so the credentials are read out of the login module options.
+    pub fn sasl_credentials(&self) -> Option<(String, String)> {
+        let jaas = self.jaas_config.as_deref().filter(|j| !j.trim().is_empty());
+        let username = jaas
+            .and_then(|jaas| jaas_option(jaas, "username"))
+            .or_else(|| self.sasl_callback.clone())?;
+        let password = jaas
+            .and_then(|jaas| jaas_option(jaas, "password"))
+            .or_else(|| self.sasl_endpoint_token.clone())?;
+        Some((username, password))
+    }
+
     pub fn get_security_type(&self) -> &BrokerSecurityType {
         &self.broker_security_type
     }
+
+    pub fn is_production(&self) -> bool {
+        self.environment == Some(ConnectionEnvironment::Production)
+    }
 }
 
 /// Server connection settings manager
 /// Compatible with Java ServerConnectionSettings class
+#[derive(Clone)]
 pub struct ServerConnectionSettings {
     connections: Vec<ServerConnection>,
     next_id: i64,
+    /// Connections skipped by the last `from_xml` because they could not be parsed
+    load_warnings: Vec<String>,
 }
 
 impl ServerConnectionSettings {
@@ -280,9 +819,15 @@ impl ServerConnectionSettings {
         Self {
             connections: Vec::new(),
             next_id: chrono::Utc::now().timestamp_millis(),
+            load_warnings: Vec::new(),
         }
     }
 
+    /// Problems found by the last `from_xml`, one per skipped connection
+    pub fn load_warnings(&self) -> &[String] {
+        &self.load_warnings
+    }
+
     pub fn get_connections(&self) -> &[ServerConnection] {
         &self.connections
     }
@@ -295,7 +840,12 @@ impl ServerConnectionSettings {
         self.connections.iter().find(|c| c.name.eq_ignore_ascii_case(name)).cloned()
     }
 
-    pub fn add_connection(&mut self, mut connection: ServerConnection) -> Result<()> {
+    pub fn find_connections_by_environment(&self, env: &ConnectionEnvironment) -> Vec<&ServerConnection> {
+        self.connections.iter().filter(|c| c.environment.as_ref() == Some(env)).collect()
+    }
+
+    /// Add a connection, returning its ID (generated when the connection has ID 0)
+    pub fn add_connection(&mut self, mut connection: ServerConnection) -> Result<i64> {
         // Check for duplicate name
         if self.find_connection_by_name(&connection.name).is_some() {
             return Err(anyhow!("Server connection with name '{}' already exists", connection.name));
@@ -306,7 +856,20 @@ impl ServerConnectionSettings {
             connection.id = self.generate_id();
         }
 
+        let id = connection.id;
         self.connections.push(connection);
+        Ok(id)
+    }
+
+    /// Replace the connection with the same ID
+    pub fn update_connection(&mut self, connection: ServerConnection) -> Result<()> {
+        if self.connections.iter().any(|c| c.id != connection.id && c.name.eq_ignore_ascii_case(&connection.name)) {
+            return Err(anyhow!("Server connection with name '{}' already exists", connection.name));
+        }
+
+        let existing = self.connections.iter_mut().find(|c| c.id == connection.id)
+            .ok_or_else(|| anyhow!("Connection not found: {}", connection.id))?;
+        *existing = connection;
         Ok(())
     }
 
@@ -317,11 +880,76 @@ impl ServerConnectionSettings {
         Ok(())
     }
 
+    /// Check all connections for incomplete configuration, returning a warning per problem found
+    pub fn validate_all(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        for conn in &self.connections {
+            if conn.get_security_type().requires_credentials()
+                && (conn.sasl_callback.is_none() || conn.sasl_endpoint_token.is_none())
+            {
+                warnings.push(format!(
+                    "Connection '{}' uses {} but has no SASL callback or endpoint token configured",
+                    conn.get_name(), conn.get_security_type()
+                ));
+            }
+        }
+        warnings
+    }
+
+    /// Serialize all connections to JSON, with passwords and secrets set to `null`
+    pub fn to_json(&self) -> Result<String> {
+        let connections: Vec<ServerConnection> = self.connections
+            .iter()
+            .cloned()
+            .map(|mut conn| {
+                conn.clear_secrets();
+                conn
+            })
+            .collect();
+
+        serde_json::to_string_pretty(&connections)
+            .map_err(|e| anyhow!("Failed to serialize connections: {}", e))
+    }
+
+    /// Parse connections exported by `to_json`; secrets stay unset
+    pub fn from_json(json: &str) -> Result<Self> {
+        let connections: Vec<ServerConnection> = serde_json::from_str(json)
+            .map_err(|e| anyhow!("Invalid connections JSON: {}", e))?;
+
+        let mut settings = Self::new();
+        for mut conn in connections {
+            conn.clear_secrets();
+            settings.next_id = settings.next_id.max(conn.id);
+            settings.add_connection(conn)?;
+        }
+        Ok(settings)
+    }
+
     fn generate_id(&mut self) -> i64 {
         self.next_id += 1;
         self.next_id
     }
 
+    /// Load `connections.xml`, returning empty settings if the file does not exist yet
+    pub fn load_from_file(path: &Path, settings: &UserSettings) -> Result<Self> {
+        let mut connection_settings = Self::new();
+        if !path.exists() {
+            return Ok(connection_settings);
+        }
+
+        let xml = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+        connection_settings.from_xml(&xml, settings)
+            .map_err(|e| anyhow!("Failed to parse {}: {}", path.display(), e))?;
+        Ok(connection_settings)
+    }
+
+    /// Write `connections.xml` with encrypted passwords, replacing the file atomically
+    pub fn save_to_file(&self, path: &Path) -> Result<()> {
+        let xml = self.to_xml()?;
+        write_file_atomic(path, xml.as_bytes())
+    }
+
     /// Serialize to XML (compatible with Java ServerConnectionSettings.toXml())
     pub fn to_xml(&self) -> Result<String> {
         let mut writer = Writer::new_with_indent(Cursor::new(Vec::new()), b' ', 2);
@@ -350,6 +978,9 @@ impl ServerConnectionSettings {
         elem.push_attribute(("bootstrap_servers", conn.bootstrap_servers.as_str()));
         elem.push_attribute(("validate_ssl_endpoint_hostname", if conn.validate_ssl_endpoint_hostname { "true" } else { "false" }));
         elem.push_attribute(("enable_poller", if conn.enable_poller { "true" } else { "false" }));
+        if let Some(ref env) = conn.environment {
+            elem.push_attribute(("environment", env.to_string().as_str()));
+        }
 
         // SSL settings
         if let Some(ref loc) = conn.truststore_location {
@@ -372,9 +1003,7 @@ impl ServerConnectionSettings {
         }
 
         // SASL settings
-        if let Some(ref mech) = conn.sasl_mechanism {
-            elem.push_attribute(("sasl_mechanism", mech.as_str()));
-        }
+        elem.push_attribute(("sasl_mechanism", conn.sasl_mechanism.as_str()));
         if let Some(ref cb) = conn.sasl_callback {
             elem.push_attribute(("sasl_callback", cb.as_str()));
         }
@@ -385,6 +1014,32 @@ impl ServerConnectionSettings {
             elem.push_attribute(("jaas_config", jaas.as_str()));
         }
 
+        // Kerberos settings
+        if let Some(ref service_name) = conn.kerberos_service_name {
+            elem.push_attribute(("kerberos_service_name", service_name.as_str()));
+        }
+        if let Some(ref keytab) = conn.kerberos_keytab {
+            elem.push_attribute(("kerberos_keytab", keytab.as_str()));
+        }
+        if let Some(ref principal) = conn.kerberos_principal {
+            elem.push_attribute(("kerberos_principal", principal.as_str()));
+        }
+
+        // OAuth settings
+        if let Some(ref token) = conn.oauth_token {
+            let encrypted = crypto::encrypt_password(token)?;
+            elem.push_attribute(("oauth_token", encrypted.as_str()));
+        }
+        if let Some(ref url) = conn.oauth_token_endpoint_url {
+            elem.push_attribute(("oauth_token_endpoint_url", url.as_str()));
+        }
+
+        // Producer settings
+        elem.push_attribute(("enable_idempotence", if conn.enable_idempotence { "true" } else { "false" }));
+        if let Some(ref id) = conn.transactional_id {
+            elem.push_attribute(("transactional_id", id.as_str()));
+        }
+
         // Schema Registry settings
         if let Some(ref endpoint) = conn.schema_registry_endpoint {
             elem.push_attribute(("schema_registry_endpoint", endpoint.as_str()));
@@ -436,6 +1091,12 @@ impl ServerConnectionSettings {
                 if let Some(ref decoder) = config.message_decoder {
                     topic_elem.push_attribute(("message_decoder", decoder.as_str()));
                 }
+                if let Some(ref chain) = config.decoder_chain {
+                    topic_elem.push_attribute(("decoder_chain", chain.as_str()));
+                }
+                if let Some(ref path) = config.json_path {
+                    topic_elem.push_attribute(("json_path", path.as_str()));
+                }
                 if let Some(ref st) = config.string_type {
                     topic_elem.push_attribute(("string_type", st.as_str()));
                 }
@@ -458,7 +1119,10 @@ impl ServerConnectionSettings {
     }
 
     /// Deserialize from XML (compatible with Java ServerConnectionSettings.fromXml())
+    ///
+    /// Connections that cannot be parsed are skipped and listed in `load_warnings`.
     pub fn from_xml(&mut self, xml: &str, _settings: &UserSettings) -> Result<()> {
+        self.load_warnings.clear();
         let mut reader = quick_xml::Reader::from_str(xml);
         reader.trim_text(true);
 
@@ -476,7 +1140,19 @@ impl ServerConnectionSettings {
                         b"connections" => in_connections = true,
                         b"connection" if in_connections => {
                             in_connection = true;
-                            current_connection = Some(self.parse_connection_start(e)?);
+                            current_connection = match self.parse_connection_start(e) {
+                                Ok(conn) => Some(conn),
+                                Err(err) => {
+                                    let name = e.try_get_attribute("name").ok().flatten()
+                                        .and_then(|a| a.decode_and_unescape_value(&reader).ok())
+                                        .map(|name| name.into_owned())
+                                        .unwrap_or_default();
+                                    let warning = format!("Skipped connection '{}': {}", name, err);
+                                    warn!("{}", warning);
+                                    self.load_warnings.push(warning);
+                                    None
+                                }
+                            };
                         }
                         b"folders" if in_connection => in_folders = true,
                         b"topics" if in_connection => in_topics = true,
@@ -494,6 +1170,8 @@ impl ServerConnectionSettings {
                                     let config = TopicDecoderConfig {
                                         key_decoder: e.try_get_attribute("key_decoder")?.and_then(|a| a.decode_and_unescape_value(&reader).ok()).map(|s| s.into_owned()),
                                         message_decoder: e.try_get_attribute("message_decoder")?.and_then(|a| a.decode_and_unescape_value(&reader).ok()).map(|s| s.into_owned()),
+                                        decoder_chain: e.try_get_attribute("decoder_chain")?.and_then(|a| a.decode_and_unescape_value(&reader).ok()).map(|s| s.into_owned()),
+                                        json_path: e.try_get_attribute("json_path")?.and_then(|a| a.decode_and_unescape_value(&reader).ok()).map(|s| s.into_owned()),
                                         string_type: e.try_get_attribute("string_type")?.and_then(|a| a.decode_and_unescape_value(&reader).ok()).map(|s| s.into_owned()),
                                         header_type: e.try_get_attribute("header_type")?.and_then(|a| a.decode_and_unescape_value(&reader).ok()).map(|s| s.into_owned()),
                                         offset_request_type: e.try_get_attribute("offset_request_type")?.and_then(|a| a.decode_and_unescape_value(&reader).ok()).map(|s| s.into_owned()),
@@ -558,10 +1236,14 @@ impl ServerConnectionSettings {
             conn.port = port.parse().ok();
         }
         if let Some(version) = e.try_get_attribute("version")?.and_then(|a| a.decode_and_unescape_value(&reader).ok()) {
-            conn.version = ClusterVersion::from_str(&version.into_owned())?;
+            conn.version = ClusterVersion::from_str(&version)?;
         }
         if let Some(security) = e.try_get_attribute("broker_security_type")?.and_then(|a| a.decode_and_unescape_value(&reader).ok()) {
-            conn.broker_security_type = BrokerSecurityType::from_str(&security.into_owned())?;
+            // Old connection files store the security type as a number
+            conn.broker_security_type = match security.parse::<i32>() {
+                Ok(n) => BrokerSecurityType::from_legacy_int(n)?,
+                Err(_) => BrokerSecurityType::from_str(&security)?,
+            };
         }
         if let Some(bootstrap) = e.try_get_attribute("bootstrap_servers")?.and_then(|a| a.decode_and_unescape_value(&reader).ok()) {
             conn.bootstrap_servers = bootstrap.into_owned();
@@ -572,27 +1254,30 @@ impl ServerConnectionSettings {
         if let Some(poller) = e.try_get_attribute("enable_poller")?.and_then(|a| a.decode_and_unescape_value(&reader).ok()) {
             conn.enable_poller = poller.parse().unwrap_or(true);
         }
+        if let Some(env) = e.try_get_attribute("environment")?.and_then(|a| a.decode_and_unescape_value(&reader).ok()) {
+            conn.environment = Some(ConnectionEnvironment::from_str(&env));
+        }
 
         // Parse SSL settings
         if let Some(loc) = e.try_get_attribute("truststore_location")?.and_then(|a| a.decode_and_unescape_value(&reader).ok()) {
             conn.truststore_location = Some(loc.into_owned());
         }
         if let Some(pwd) = e.try_get_attribute("truststore_password")?.and_then(|a| a.decode_and_unescape_value(&reader).ok()) {
-            conn.truststore_password = Some(crypto::decrypt_password(&pwd.into_owned())?);
+            conn.truststore_password = Some(crypto::decrypt_password(&pwd)?);
         }
         if let Some(loc) = e.try_get_attribute("keystore_location")?.and_then(|a| a.decode_and_unescape_value(&reader).ok()) {
             conn.keystore_location = Some(loc.into_owned());
         }
         if let Some(pwd) = e.try_get_attribute("keystore_password")?.and_then(|a| a.decode_and_unescape_value(&reader).ok()) {
-            conn.keystore_password = Some(crypto::decrypt_password(&pwd.into_owned())?);
+            conn.keystore_password = Some(crypto::decrypt_password(&pwd)?);
         }
         if let Some(key) = e.try_get_attribute("keystore_privatekey")?.and_then(|a| a.decode_and_unescape_value(&reader).ok()) {
-            conn.keystore_privatekey = Some(crypto::decrypt_password(&key.into_owned())?);
+            conn.keystore_privatekey = Some(crypto::decrypt_password(&key)?);
         }
 
         // Parse SASL settings
         if let Some(mech) = e.try_get_attribute("sasl_mechanism")?.and_then(|a| a.decode_and_unescape_value(&reader).ok()) {
-            conn.sasl_mechanism = Some(mech.into_owned());
+            conn.sasl_mechanism = SaslMechanism::from_str_or_default(&mech);
         }
         if let Some(cb) = e.try_get_attribute("sasl_callback")?.and_then(|a| a.decode_and_unescape_value(&reader).ok()) {
             conn.sasl_callback = Some(cb.into_owned());
@@ -604,6 +1289,33 @@ impl ServerConnectionSettings {
             conn.jaas_config = Some(jaas.into_owned());
         }
 
+        // Parse Kerberos settings
+        if let Some(service_name) = e.try_get_attribute("kerberos_service_name")?.and_then(|a| a.decode_and_unescape_value(&reader).ok()) {
+            conn.kerberos_service_name = Some(service_name.into_owned());
+        }
+        if let Some(keytab) = e.try_get_attribute("kerberos_keytab")?.and_then(|a| a.decode_and_unescape_value(&reader).ok()) {
+            conn.kerberos_keytab = Some(keytab.into_owned());
+        }
+        if let Some(principal) = e.try_get_attribute("kerberos_principal")?.and_then(|a| a.decode_and_unescape_value(&reader).ok()) {
+            conn.kerberos_principal = Some(principal.into_owned());
+        }
+
+        // Parse OAuth settings
+        if let Some(token) = e.try_get_attribute("oauth_token")?.and_then(|a| a.decode_and_unescape_value(&reader).ok()) {
+            conn.oauth_token = Some(crypto::decrypt_password(&token)?);
+        }
+        if let Some(url) = e.try_get_attribute("oauth_token_endpoint_url")?.and_then(|a| a.decode_and_unescape_value(&reader).ok()) {
+            conn.oauth_token_endpoint_url = Some(url.into_owned());
+        }
+
+        // Parse producer settings
+        if let Some(idempotence) = e.try_get_attribute("enable_idempotence")?.and_then(|a| a.decode_and_unescape_value(&reader).ok()) {
+            conn.enable_idempotence = idempotence.parse().unwrap_or(false);
+        }
+        if let Some(id) = e.try_get_attribute("transactional_id")?.and_then(|a| a.decode_and_unescape_value(&reader).ok()) {
+            conn.transactional_id = Some(id.into_owned());
+        }
+
         // Parse Schema Registry settings
         if let Some(endpoint) = e.try_get_attribute("schema_registry_endpoint")?.and_then(|a| a.decode_and_unescape_value(&reader).ok()) {
             conn.schema_registry_endpoint = Some(endpoint.into_owned());
@@ -615,16 +1327,16 @@ impl ServerConnectionSettings {
             conn.schema_registry_ssl_truststore_location = Some(loc.into_owned());
         }
         if let Some(pwd) = e.try_get_attribute("schema_registry_ssl_truststore_password")?.and_then(|a| a.decode_and_unescape_value(&reader).ok()) {
-            conn.schema_registry_ssl_truststore_password = Some(crypto::decrypt_password(&pwd.into_owned())?);
+            conn.schema_registry_ssl_truststore_password = Some(crypto::decrypt_password(&pwd)?);
         }
         if let Some(loc) = e.try_get_attribute("schema_registry_ssl_keystore_location")?.and_then(|a| a.decode_and_unescape_value(&reader).ok()) {
             conn.schema_registry_ssl_keystore_location = Some(loc.into_owned());
         }
         if let Some(pwd) = e.try_get_attribute("schema_registry_ssl_keystore_password")?.and_then(|a| a.decode_and_unescape_value(&reader).ok()) {
-            conn.schema_registry_ssl_keystore_password = Some(crypto::decrypt_password(&pwd.into_owned())?);
+            conn.schema_registry_ssl_keystore_password = Some(crypto::decrypt_password(&pwd)?);
         }
         if let Some(key) = e.try_get_attribute("schema_registry_ssl_keystore_private_key")?.and_then(|a| a.decode_and_unescape_value(&reader).ok()) {
-            conn.schema_registry_ssl_keystore_private_key = Some(crypto::decrypt_password(&key.into_owned())?);
+            conn.schema_registry_ssl_keystore_private_key = Some(crypto::decrypt_password(&key)?);
         }
 
         Ok(conn)
@@ -634,3 +1346,297 @@ impl ServerConnectionSettings {
         self.connections.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sasl_credentials_prefers_jaas() {
+        let mut conn = ServerConnection::new(1, "sasl".to_string());
+        assert_eq!(conn.sasl_credentials(), None);
+
+        conn.sasl_callback = Some("alice".to_string());
+        conn.sasl_endpoint_token = Some("pw".to_string());
+        assert_eq!(conn.sasl_credentials(), Some(("alice".to_string(), "pw".to_string())));
+
+        conn.jaas_config = Some(
+            r#"org.apache.kafka.common.security.scram.ScramLoginModule required username="svc" password="p\"w";"#
+                .to_string(),
+        );
+        assert_eq!(conn.sasl_credentials(), Some(("svc".to_string(), "p\"w".to_string())));
+    }
+
+    #[test]
+    fn test_sasl_credentials_fall_back_when_jaas_has_none() {
+        let mut conn = ServerConnection::new(1, "sasl".to_string());
+        conn.sasl_callback = Some("alice".to_string());
+        conn.sasl_endpoint_token = Some("pw".to_string());
+        conn.jaas_config = Some("org.apache.kafka.common.security.plain.PlainLoginModule required;".to_string());
+        assert_eq!(conn.sasl_credentials(), Some(("alice".to_string(), "pw".to_string())));
+
+        conn.jaas_config = Some(r#"org.apache.kafka.common.security.plain.PlainLoginModule required username="svc";"#.to_string());
+        assert_eq!(conn.sasl_credentials(), Some(("svc".to_string(), "pw".to_string())));
+
+        conn.sasl_endpoint_token = None;
+        assert_eq!(conn.sasl_credentials(), None);
+    }
+
+    #[test]
+    fn test_security_type_round_trip() {
+        for variant in BrokerSecurityType::all_variants() {
+            assert_eq!(BrokerSecurityType::from_str(variant.as_str()).unwrap(), *variant);
+            assert_eq!(BrokerSecurityType::try_from(variant.to_string()).unwrap(), *variant);
+        }
+        assert!(BrokerSecurityType::from_str("KERBEROS").is_err());
+    }
+
+    #[test]
+    fn test_legacy_security_types() {
+        assert_eq!(BrokerSecurityType::from_legacy_int(0).unwrap(), BrokerSecurityType::PLAINTEXT);
+        assert_eq!(BrokerSecurityType::from_legacy_int(3).unwrap(), BrokerSecurityType::SASL_SSL);
+        assert!(BrokerSecurityType::from_legacy_int(4).is_err());
+        assert_eq!(BrokerSecurityType::from_str("Sasl_Ssl").unwrap(), BrokerSecurityType::SASL_SSL);
+    }
+
+    #[test]
+    fn test_security_type_predicates() {
+        use BrokerSecurityType::*;
+        // (variant, is_sasl, is_ssl, is_encrypted, requires_credentials)
+        let cases = [
+            (PLAINTEXT, false, false, false, false),
+            (SSL, false, true, true, false),
+            (SASL_PLAINTEXT, true, false, false, true),
+            (SASL_SSL, true, true, true, true),
+        ];
+        for (variant, sasl, ssl, encrypted, credentials) in cases {
+            assert_eq!(variant.is_sasl(), sasl, "{}", variant);
+            assert_eq!(variant.is_ssl(), ssl, "{}", variant);
+            assert_eq!(variant.is_encrypted(), encrypted, "{}", variant);
+            assert_eq!(variant.requires_credentials(), credentials, "{}", variant);
+        }
+    }
+
+    #[test]
+    fn test_validate_all_sasl_credentials() {
+        let mut settings = ServerConnectionSettings::new();
+        let mut conn = ServerConnection::new(1, "secure".to_string());
+        conn.broker_security_type = BrokerSecurityType::SASL_SSL;
+        settings.connections.push(conn);
+        assert_eq!(settings.validate_all().len(), 1);
+
+        settings.connections[0].sasl_callback = Some("callback".to_string());
+        settings.connections[0].sasl_endpoint_token = Some("https://idp/token".to_string());
+        assert!(settings.validate_all().is_empty());
+    }
+
+    #[test]
+    fn test_kerberos_xml_round_trip() {
+        let mut settings = ServerConnectionSettings::new();
+        let mut conn = ServerConnection::new(1, "kerberized".to_string());
+        conn.sasl_mechanism = SaslMechanism::GSSAPI;
+        conn.kerberos_service_name = Some("kafka".to_string());
+        conn.kerberos_keytab = Some("/etc/security/kafka.keytab".to_string());
+        conn.kerberos_principal = Some("explorer@EXAMPLE.COM".to_string());
+        settings.connections.push(conn);
+
+        let mut loaded = ServerConnectionSettings::new();
+        loaded.from_xml(&settings.to_xml().unwrap(), &UserSettings::new()).unwrap();
+        let conn = &loaded.connections[0];
+        assert_eq!(conn.kerberos_service_name.as_deref(), Some("kafka"));
+        assert_eq!(conn.kerberos_keytab.as_deref(), Some("/etc/security/kafka.keytab"));
+        assert_eq!(conn.kerberos_principal.as_deref(), Some("explorer@EXAMPLE.COM"));
+    }
+
+    #[test]
+    fn test_connections_file_round_trip() {
+        let path = std::env::temp_dir()
+            .join(format!("offset-explorer-connections-{}", std::process::id()))
+            .join("connections.xml");
+        let loaded = ServerConnectionSettings::load_from_file(&path, &UserSettings::new()).unwrap();
+        assert!(loaded.get_connections().is_empty());
+
+        let mut settings = ServerConnectionSettings::new();
+        let mut conn = ServerConnection::new(7, "production".to_string());
+        conn.bootstrap_servers = "kafka1:9092,kafka2:9092".to_string();
+        conn.broker_security_type = BrokerSecurityType::SASL_SSL;
+        conn.sasl_mechanism = SaslMechanism::SCRAM_SHA_256;
+        conn.schema_registry_endpoint = Some("http://registry:8081".to_string());
+        settings.add_connection(conn).unwrap();
+        settings.save_to_file(&path).unwrap();
+        assert!(!path.with_extension("xml.tmp").exists());
+
+        let loaded = ServerConnectionSettings::load_from_file(&path, &UserSettings::new()).unwrap();
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+        let conn = loaded.find_connection(7).unwrap();
+        assert_eq!(conn.get_name(), "production");
+        assert_eq!(conn.bootstrap_servers, "kafka1:9092,kafka2:9092");
+        assert_eq!(conn.broker_security_type, BrokerSecurityType::SASL_SSL);
+        assert_eq!(conn.sasl_mechanism, SaslMechanism::SCRAM_SHA_256);
+        assert_eq!(conn.schema_registry_endpoint.as_deref(), Some("http://registry:8081"));
+    }
+
+    #[test]
+    fn test_max_api_version() {
+        assert_eq!(ClusterVersion::VERSION_0_9.max_api_version(API_METADATA), None);
+        assert_eq!(ClusterVersion::VERSION_0_10.max_api_version(API_CREATE_TOPICS), None);
+        assert_eq!(ClusterVersion::VERSION_0_10_1.max_api_version(API_CREATE_TOPICS), Some(0));
+        assert_eq!(ClusterVersion::VERSION_1_0.max_api_version(API_METADATA), Some(5));
+        // Releases without their own entry use the closest earlier one
+        assert_eq!(ClusterVersion::VERSION_3_2.max_api_version(API_FETCH), Some(13));
+        assert_eq!(ClusterVersion::LATEST.max_api_version(API_PRODUCE), Some(10));
+        assert_eq!(ClusterVersion::LATEST.max_api_version(-1), None);
+    }
+
+    #[test]
+    fn test_sasl_mechanism_round_trip() {
+        for variant in SaslMechanism::all_variants() {
+            assert_eq!(SaslMechanism::from_str(variant.as_str()).unwrap(), *variant);
+        }
+        assert!(SaslMechanism::SCRAM_SHA_512.is_password_based());
+        assert!(!SaslMechanism::OAUTHBEARER.is_password_based());
+        assert!(SaslMechanism::from_str("scram").is_err());
+        assert_eq!(SaslMechanism::from_str("scram-sha-512").unwrap(), SaslMechanism::SCRAM_SHA_512);
+        assert_eq!(SaslMechanism::from_str("Scram_Sha_256").unwrap(), SaslMechanism::SCRAM_SHA_256);
+        assert_eq!(SaslMechanism::from_str("oauthbearer").unwrap(), SaslMechanism::OAUTHBEARER);
+        assert_eq!(SaslMechanism::from_str_or_default("KERBEROS"), SaslMechanism::PLAIN);
+        assert_eq!(serde_json::to_string(&SaslMechanism::SCRAM_SHA_256).unwrap(), "\"SCRAM-SHA-256\"");
+    }
+
+    #[test]
+    fn test_build_header_decoder() {
+        assert_eq!(TopicDecoderConfig::build_header_decoder("base64").name(), "Base64");
+        assert_eq!(TopicDecoderConfig::build_header_decoder("HEX").name(), "ByteArray");
+        assert_eq!(TopicDecoderConfig::build_header_decoder("json").name(), "JSON");
+        assert_eq!(TopicDecoderConfig::build_header_decoder("string").name(), "String");
+        assert_eq!(TopicDecoderConfig::build_header_decoder("unknown").name(), "String");
+    }
+
+    #[test]
+    fn test_clone_topic_config() {
+        let mut conn = ServerConnection::new(1, "local".to_string());
+        conn.topic_configs.insert("orders".to_string(), TopicDecoderConfig {
+            key_decoder: Some("string".to_string()),
+            message_decoder: Some("json".to_string()),
+            decoder_chain: None,
+            json_path: None,
+            string_type: None,
+            header_type: None,
+            offset_request_type: None,
+            parent_folder: None,
+        });
+
+        let dests = vec!["orders".to_string(), "payments".to_string(), "refunds".to_string()];
+        assert_eq!(conn.clone_topic_config("orders", &dests).unwrap(), 2);
+        assert_eq!(conn.topic_configs["refunds"].message_decoder.as_deref(), Some("json"));
+        assert!(conn.clone_topic_config("missing", &dests).is_err());
+    }
+
+    #[test]
+    fn test_to_display_string() {
+        let mut conn = ServerConnection::new(1, "MyCluster".to_string());
+        conn.bootstrap_servers = "b1:9092, b2:9092,b3:9092".to_string();
+        conn.broker_security_type = BrokerSecurityType::SASL_SSL;
+        conn.version = ClusterVersion::VERSION_3_5;
+        assert_eq!(conn.to_display_string(), "MyCluster (3 brokers, SASL_SSL, v3.5)");
+
+        conn.bootstrap_servers = "b1:9092".to_string();
+        let summary = conn.get_connection_summary();
+        assert_eq!(summary.version, "3.5");
+        assert!(!summary.has_schema_registry);
+        assert!(conn.to_display_string().contains("(1 broker,"));
+    }
+
+    #[test]
+    fn test_zookeeper_hosts() {
+        let mut conn = ServerConnection::new(1, "local".to_string());
+        conn.host = "zk1".to_string();
+        assert_eq!(conn.get_zookeeper_hosts(), "zk1");
+        conn.port = Some(2181);
+        assert_eq!(conn.get_zookeeper_hosts(), "zk1:2181");
+    }
+
+    #[test]
+    fn test_find_connections_by_environment() {
+        let mut settings = ServerConnectionSettings::new();
+        let mut prod = ServerConnection::new(1, "prod-cluster".to_string());
+        prod.environment = Some(ConnectionEnvironment::from_str("Production"));
+        let mut qa = ServerConnection::new(2, "qa-cluster".to_string());
+        qa.environment = Some(ConnectionEnvironment::from_str("qa"));
+        settings.add_connection(prod).unwrap();
+        settings.add_connection(qa).unwrap();
+        settings.add_connection(ServerConnection::new(3, "local".to_string())).unwrap();
+
+        let found = settings.find_connections_by_environment(&ConnectionEnvironment::Production);
+        assert_eq!(found.len(), 1);
+        assert!(found[0].is_production());
+
+        let custom = ConnectionEnvironment::Custom("qa".to_string());
+        assert_eq!(settings.find_connections_by_environment(&custom)[0].name, "qa-cluster");
+        assert_eq!(custom.to_string(), "qa");
+    }
+
+    #[test]
+    fn test_unknown_sasl_mechanism_does_not_fail_load() {
+        let xml = r#"<connections>
+  <connection name="legacy" id="5" broker_security_type="SASL_SSL" sasl_mechanism="scram-sha-256"></connection>
+  <connection name="odd" id="6" broker_security_type="SASL_SSL" sasl_mechanism="NTLM"></connection>
+</connections>"#;
+        let mut settings = ServerConnectionSettings::new();
+        settings.from_xml(xml, &UserSettings::new()).unwrap();
+        assert_eq!(settings.find_connection(5).unwrap().sasl_mechanism, SaslMechanism::SCRAM_SHA_256);
+        assert_eq!(settings.find_connection(6).unwrap().sasl_mechanism, SaslMechanism::PLAIN);
+    }
+
+    #[test]
+    fn test_invalid_connection_is_skipped() {
+        let xml = r#"<connections>
+  <connection name="broken" id="5" broker_security_type="KERBEROS"><topics><topic name="t"/></topics></connection>
+  <connection name="good" id="6" bootstrap_servers="kafka:9092"></connection>
+</connections>"#;
+        let mut settings = ServerConnectionSettings::new();
+        settings.from_xml(xml, &UserSettings::new()).unwrap();
+        assert_eq!(settings.get_connections().len(), 1);
+        assert_eq!(settings.find_connection(6).unwrap().bootstrap_servers, "kafka:9092");
+        assert_eq!(settings.load_warnings().len(), 1);
+        assert!(settings.load_warnings()[0].contains("'broken'"));
+    }
+
+    #[test]
+    fn test_update_connection() {
+        let mut settings = ServerConnectionSettings::new();
+        let id = settings.add_connection(ServerConnection::new(0, "local".to_string())).unwrap();
+        settings.add_connection(ServerConnection::new(0, "staging".to_string())).unwrap();
+
+        let mut conn = settings.find_connection(id).unwrap();
+        conn.bootstrap_servers = "localhost:9093".to_string();
+        settings.update_connection(conn.clone()).unwrap();
+        assert_eq!(settings.find_connection(id).unwrap().bootstrap_servers, "localhost:9093");
+
+        conn.name = "Staging".to_string();
+        assert!(settings.update_connection(conn).is_err());
+        assert!(settings.update_connection(ServerConnection::new(-1, "other".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_json_round_trip_omits_secrets() {
+        let mut settings = ServerConnectionSettings::new();
+        let mut conn = ServerConnection::new(7, "secure".to_string());
+        conn.broker_security_type = BrokerSecurityType::SASL_SSL;
+        conn.truststore_location = Some("/etc/kafka/truststore.jks".to_string());
+        conn.truststore_password = Some("changeit".to_string());
+        conn.jaas_config = Some("username=\"u\" password=\"p\";".to_string());
+        settings.add_connection(conn).unwrap();
+
+        let json = settings.to_json().unwrap();
+        assert!(!json.contains("changeit"));
+        assert!(json.contains("\"truststore_password\": null"));
+
+        let imported = ServerConnectionSettings::from_json(&json).unwrap();
+        let conn = imported.find_connection(7).unwrap();
+        assert_eq!(conn.broker_security_type, BrokerSecurityType::SASL_SSL);
+        assert_eq!(conn.truststore_location.as_deref(), Some("/etc/kafka/truststore.jks"));
+        assert!(conn.truststore_password.is_none());
+        assert!(conn.jaas_config.is_none());
+    }
+}
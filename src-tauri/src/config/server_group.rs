@@ -9,6 +9,9 @@ use serde::{Serialize, Deserialize};
 use std::io::{Cursor, Write};
 use quick_xml::events::{Event, BytesStart};
 use quick_xml::Writer;
+use tracing::warn;
+
+use crate::config::write_file_atomic;
 
 /// Server group for organizing server connections
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -60,6 +63,8 @@ impl ServerGroup {
 pub struct ServerGroupManager {
     root: ServerGroup,
     next_id: i64,
+    /// Connection references skipped by the last `from_xml`
+    load_warnings: Vec<String>,
 }
 
 impl ServerGroupManager {
@@ -68,9 +73,15 @@ impl ServerGroupManager {
         Self {
             root,
             next_id: 1,
+            load_warnings: Vec::new(),
         }
     }
 
+    /// Problems found by the last `from_xml`, one per skipped connection reference
+    pub fn load_warnings(&self) -> &[String] {
+        &self.load_warnings
+    }
+
     pub fn get_root(&self) -> &ServerGroup {
         &self.root
     }
@@ -93,7 +104,7 @@ impl ServerGroupManager {
         Self::find_group_recursive_mut(&mut self.root, id)
     }
 
-    fn find_group_recursive<'a>(group: &'a ServerGroup, id: i64) -> Option<&'a ServerGroup> {
+    fn find_group_recursive(group: &ServerGroup, id: i64) -> Option<&ServerGroup> {
         if group.id == id {
             return Some(group);
         }
@@ -107,7 +118,7 @@ impl ServerGroupManager {
         None
     }
 
-    fn find_group_recursive_mut<'a>(group: &'a mut ServerGroup, id: i64) -> Option<&'a mut ServerGroup> {
+    fn find_group_recursive_mut(group: &mut ServerGroup, id: i64) -> Option<&mut ServerGroup> {
         if group.id == id {
             return Some(group);
         }
@@ -181,15 +192,207 @@ impl ServerGroupManager {
         Ok(())
     }
 
-    /// Deserialize from XML
-    pub fn from_xml(&mut self, _xml: &str) -> Result<()> {
-        // TODO: Implement XML parsing
+    /// Deserialize from XML, replacing the current tree
+    ///
+    /// Connection references with a bad id are skipped and listed in `load_warnings`;
+    /// a broken group structure still fails the whole file.
+    pub fn from_xml(&mut self, xml: &str) -> Result<()> {
+        let mut warnings = Vec::new();
+        let mut reader = quick_xml::Reader::from_str(xml);
+        reader.trim_text(true);
+
+        // Groups whose end tag has not been read yet, outermost first
+        let mut open_groups: Vec<ServerGroup> = Vec::new();
+        let mut root = None;
+
+        let mut buf = Vec::new();
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(ref e)) if e.name().as_ref() == b"servergroup" => {
+                    open_groups.push(Self::read_group(e, &reader)?);
+                }
+                Ok(Event::Empty(ref e)) if e.name().as_ref() == b"servergroup" => {
+                    let group = Self::read_group(e, &reader)?;
+                    Self::close_group(&mut open_groups, &mut root, group)?;
+                }
+                Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e)) if e.name().as_ref() == b"connection" => {
+                    let added = Self::read_id(e, &reader).and_then(|id| {
+                        open_groups.last_mut()
+                            .ok_or_else(|| anyhow!("Connection {} is outside of a server group", id))?
+                            .add_child(ServerGroupChild::Connection(id));
+                        Ok(())
+                    });
+                    if let Err(err) = added {
+                        let warning = format!("Skipped server group connection: {}", err);
+                        warn!("{}", warning);
+                        warnings.push(warning);
+                    }
+                }
+                Ok(Event::End(ref e)) if e.name().as_ref() == b"servergroup" => {
+                    let group = open_groups.pop()
+                        .ok_or_else(|| anyhow!("Unexpected </servergroup>"))?;
+                    Self::close_group(&mut open_groups, &mut root, group)?;
+                }
+                Ok(Event::Eof) => break,
+                Err(e) => return Err(anyhow!("XML parse error: {}", e)),
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        if !open_groups.is_empty() {
+            return Err(anyhow!("Unclosed server group '{}'", open_groups[0].name));
+        }
+        self.root = root.unwrap_or_else(|| ServerGroup::new(0, "Root".to_string()));
+        self.next_id = Self::max_group_id(&self.root) + 1;
+        self.load_warnings = warnings;
+        Ok(())
+    }
+
+    fn read_group(e: &BytesStart, reader: &quick_xml::Reader<&[u8]>) -> Result<ServerGroup> {
+        let name = e.try_get_attribute("name")?
+            .map(|a| a.decode_and_unescape_value(reader).map(|v| v.into_owned()))
+            .transpose()?
+            .unwrap_or_default();
+        Ok(ServerGroup::new(Self::read_id(e, reader)?, name))
+    }
+
+    fn read_id(e: &BytesStart, reader: &quick_xml::Reader<&[u8]>) -> Result<i64> {
+        let value = e.try_get_attribute("id")?
+            .ok_or_else(|| anyhow!("Missing id attribute on <{}>", String::from_utf8_lossy(e.name().as_ref())))?
+            .decode_and_unescape_value(reader)?;
+        value.parse().map_err(|_| anyhow!("Invalid id: {}", value))
+    }
+
+    /// Attach a completed group to its parent, or make it the root
+    fn close_group(open_groups: &mut [ServerGroup], root: &mut Option<ServerGroup>, group: ServerGroup) -> Result<()> {
+        match open_groups.last_mut() {
+            Some(parent) => parent.add_child(ServerGroupChild::Group(Box::new(group))),
+            None if root.is_none() => *root = Some(group),
+            None => return Err(anyhow!("More than one root server group")),
+        }
         Ok(())
     }
 
+    fn max_group_id(group: &ServerGroup) -> i64 {
+        group.children.iter()
+            .filter_map(|child| match child {
+                ServerGroupChild::Group(subgroup) => Some(Self::max_group_id(subgroup)),
+                ServerGroupChild::Connection(_) => None,
+            })
+            .fold(group.id, i64::max)
+    }
+
+    /// Load `servergroups.xml`, returning just the root group if the file does not exist yet
+    pub fn load_from_file(path: &std::path::Path) -> Result<Self> {
+        let mut manager = Self::new();
+        if !path.exists() {
+            return Ok(manager);
+        }
+
+        let xml = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+        manager.from_xml(&xml)
+            .map_err(|e| anyhow!("Failed to parse {}: {}", path.display(), e))?;
+        Ok(manager)
+    }
+
     pub fn save_to_file(&self, path: &std::path::Path) -> Result<()> {
         let xml = self.to_xml()?;
-        std::fs::write(path, xml)?;
-        Ok(())
+        write_file_atomic(path, xml.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn child_ids(group: &ServerGroup) -> Vec<String> {
+        group.children.iter()
+            .map(|child| match child {
+                ServerGroupChild::Group(g) => format!("group:{}", g.id),
+                ServerGroupChild::Connection(id) => format!("connection:{}", id),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_from_xml_nested_groups() {
+        let xml = r#"<servergroups>
+  <servergroup name="Root" id="0">
+    <connection id="100"/>
+    <servergroup name="Production" id="1">
+      <servergroup name="EU" id="4">
+        <connection id="101"/>
+      </servergroup>
+      <connection id="102"/>
+    </servergroup>
+    <servergroup name="Empty" id="2"/>
+  </servergroup>
+</servergroups>"#;
+
+        let mut manager = ServerGroupManager::new();
+        manager.from_xml(xml).unwrap();
+
+        assert_eq!(child_ids(manager.get_root()), ["connection:100", "group:1", "group:2"]);
+        assert_eq!(child_ids(manager.get_group(1).unwrap()), ["group:4", "connection:102"]);
+        assert_eq!(child_ids(manager.get_group(4).unwrap()), ["connection:101"]);
+        assert_eq!(manager.get_group(4).unwrap().get_name(), "EU");
+        assert!(manager.get_group(2).unwrap().children.is_empty());
+        assert_eq!(manager.add_group(0, "Staging".to_string()).unwrap(), 5);
+    }
+
+    #[test]
+    fn test_server_groups_round_trip() {
+        let mut manager = ServerGroupManager::new();
+        let dev = manager.add_group(0, "Dev & Test".to_string()).unwrap();
+        let nested = manager.add_group(dev, "Nested".to_string()).unwrap();
+        manager.get_group_mut(nested).unwrap().add_child(ServerGroupChild::Connection(42));
+        manager.get_root_mut().add_child(ServerGroupChild::Connection(7));
+
+        let mut loaded = ServerGroupManager::new();
+        loaded.from_xml(&manager.to_xml().unwrap()).unwrap();
+        assert_eq!(loaded.to_xml().unwrap(), manager.to_xml().unwrap());
+        assert_eq!(loaded.get_group(dev).unwrap().get_name(), "Dev & Test");
+        assert_eq!(child_ids(loaded.get_group(nested).unwrap()), ["connection:42"]);
+    }
+
+    #[test]
+    fn test_from_xml_skips_bad_connection_ids() {
+        let xml = r#"<servergroups>
+  <connection id="7"/>
+  <servergroup name="Root" id="0">
+    <connection id="abc"/>
+    <connection id="100"/>
+  </servergroup>
+</servergroups>"#;
+
+        let mut manager = ServerGroupManager::new();
+        manager.from_xml(xml).unwrap();
+
+        assert_eq!(child_ids(manager.get_root()), ["connection:100"]);
+        assert_eq!(manager.load_warnings().len(), 2);
+    }
+
+    #[test]
+    fn test_from_xml_rejects_unclosed_group() {
+        let mut manager = ServerGroupManager::new();
+        assert!(manager.from_xml(r#"<servergroups><servergroup name="Root" id="0">"#).is_err());
+    }
+
+    #[test]
+    fn test_save_replaces_file_without_leftovers() {
+        let dir = std::env::temp_dir().join(format!("offset-explorer-groups-{}", std::process::id()));
+        let path = dir.join("servergroups.xml");
+        let mut manager = ServerGroupManager::new();
+        manager.save_to_file(&path).unwrap();
+        manager.add_group(0, "Prod".to_string()).unwrap();
+        manager.save_to_file(&path).unwrap();
+
+        let loaded = ServerGroupManager::load_from_file(&path).unwrap();
+        let leftovers: Vec<_> = std::fs::read_dir(&dir).unwrap().flatten().map(|e| e.file_name()).collect();
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(loaded.to_xml().unwrap(), manager.to_xml().unwrap());
+        assert_eq!(leftovers, ["servergroups.xml"]);
     }
 }
@@ -149,7 +149,7 @@ impl UserSettings {
         self.set("confirm_exit", SettingValue::Boolean(true), true, SettingDataType::Boolean, true);
 
         // Default decoders
-        self.set("key_type", SettingValue::String("byte_array".to_string()), true, SettingDataType::String, true);
+        self.set("default_key_decoder", SettingValue::String("byte_array".to_string()), true, SettingDataType::String, true);
         self.set("message_type", SettingValue::String("byte_array".to_string()), true, SettingDataType::String, true);
 
         // Export settings
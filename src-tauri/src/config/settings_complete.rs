@@ -7,9 +7,68 @@ use anyhow::{Result, anyhow};
 use serde::{Serialize, Deserialize};
 use std::collections::HashMap;
 use std::io::Cursor;
+use std::path::Path;
 use quick_xml::events::{Event, BytesStart};
 use quick_xml::Writer;
-use tracing::info;
+use tracing::{info, warn};
+
+use crate::config::write_file_atomic;
+
+/// Current version of the settings file format
+pub const CURRENT_SETTINGS_VERSION: u32 = 2;
+
+/// Keys from older versions that are renamed by `UserSettings::migrate_from`
+const LEGACY_SETTING_KEYS: &[&str] = &["key_type"];
+
+/// Result of loading a settings file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SettingsLoadSummary {
+    pub loaded: usize,
+    pub skipped: usize,
+}
+
+/// Attributes of a `<setting>` element before type conversion
+struct RawSetting {
+    key: String,
+    value: String,
+    dynamic: String,
+    data_type: String,
+    persisted: String,
+}
+
+impl RawSetting {
+    fn from_element(e: &BytesStart, reader: &quick_xml::Reader<&[u8]>) -> Result<Self> {
+        let attr = |name: &str| -> Result<String> {
+            Ok(e.try_get_attribute(name)?
+                .ok_or_else(|| anyhow!("Missing {} attribute", name))?
+                .decode_and_unescape_value(reader)?
+                .into_owned())
+        };
+
+        Ok(Self {
+            key: attr("name")?,
+            value: attr("value")?,
+            dynamic: attr("dynamic")?,
+            data_type: attr("data_type")?,
+            persisted: attr("persisted")?,
+        })
+    }
+
+    fn into_setting(self) -> Result<Setting> {
+        let data_type = SettingDataType::from_str(&self.data_type)
+            .map_err(|e| anyhow!("Setting '{}': {}", self.key, e))?;
+        let value = SettingValue::from_string(&self.value, data_type)
+            .map_err(|e| anyhow!("Setting '{}': {}", self.key, e))?;
+
+        Ok(Setting {
+            key: self.key,
+            value,
+            dynamic: self.dynamic.parse::<bool>().unwrap_or(false),
+            data_type,
+            persisted: self.persisted.parse::<bool>().unwrap_or(true),
+        })
+    }
+}
 
 /// Data type enumeration (compatible with Java SettingDataType)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -35,6 +94,22 @@ impl SettingDataType {
         }
     }
 
+    /// Parse a data type stored either as its number ("4") or its name ("String")
+    pub fn from_str(s: &str) -> Result<Self> {
+        if let Ok(value) = s.trim().parse::<i32>() {
+            return Self::from_i32(value);
+        }
+        match s.trim() {
+            "Integer" => Ok(SettingDataType::Integer),
+            "Long" => Ok(SettingDataType::Long),
+            "Double" => Ok(SettingDataType::Double),
+            "String" => Ok(SettingDataType::String),
+            "Boolean" => Ok(SettingDataType::Boolean),
+            "Color" => Ok(SettingDataType::Color),
+            _ => Err(anyhow!("Unknown data type: {}", s)),
+        }
+    }
+
     pub fn as_i32(&self) -> i32 {
         *self as i32
     }
@@ -115,6 +190,7 @@ impl SettingValue {
 }
 
 /// User settings manager (compatible with Java UserSettings class)
+#[derive(Clone)]
 pub struct UserSettings {
     settings: HashMap<String, Setting>,
 }
@@ -168,6 +244,27 @@ impl UserSettings {
             data_type: SettingDataType::Integer,
             persisted: true,
         });
+        settings.insert("auto_refresh_interval_ms".to_string(), Setting {
+            key: "auto_refresh_interval_ms".to_string(),
+            value: SettingValue::Integer(30000),
+            dynamic: true,
+            data_type: SettingDataType::Integer,
+            persisted: true,
+        });
+        settings.insert("lag_tracking_groups_per_tick".to_string(), Setting {
+            key: "lag_tracking_groups_per_tick".to_string(),
+            value: SettingValue::Integer(10),
+            dynamic: true,
+            data_type: SettingDataType::Integer,
+            persisted: true,
+        });
+        settings.insert("offset_diff_max_interval_secs".to_string(), Setting {
+            key: "offset_diff_max_interval_secs".to_string(),
+            value: SettingValue::Integer(60),
+            dynamic: true,
+            data_type: SettingDataType::Integer,
+            persisted: true,
+        });
 
         // Message settings
         settings.insert("max.messages.bytes".to_string(), Setting {
@@ -230,10 +327,17 @@ impl UserSettings {
             data_type: SettingDataType::Boolean,
             persisted: true,
         });
+        settings.insert("warn_on_produce_in_production".to_string(), Setting {
+            key: "warn_on_produce_in_production".to_string(),
+            value: SettingValue::Boolean(true),
+            dynamic: true,
+            data_type: SettingDataType::Boolean,
+            persisted: true,
+        });
 
         // Default decoders
-        settings.insert("key_type".to_string(), Setting {
-            key: "key_type".to_string(),
+        settings.insert("default_key_decoder".to_string(), Setting {
+            key: "default_key_decoder".to_string(),
             value: SettingValue::String("byte_array".to_string()),
             dynamic: true,
             data_type: SettingDataType::String,
@@ -485,7 +589,9 @@ impl UserSettings {
     /// Serialize settings to XML format (compatible with Java UserSettings.toXml())
     pub fn to_xml(&self) -> Result<String> {
         let mut writer = Writer::new_with_indent(Cursor::new(Vec::new()), b' ', 2);
-        writer.write_event(Event::Start(BytesStart::new("settings")))?;
+        let mut root = BytesStart::new("settings");
+        root.push_attribute(("version", CURRENT_SETTINGS_VERSION.to_string().as_str()));
+        writer.write_event(Event::Start(root))?;
 
         // Write all persisted settings in sorted order
         let mut keys: Vec<&String> = self.settings.keys().collect();
@@ -514,50 +620,100 @@ impl UserSettings {
     }
 
     /// Deserialize settings from XML format (compatible with Java UserSettings.fromXML())
-    pub fn from_xml(&mut self, xml: &str) -> Result<()> {
+    ///
+    /// Settings with an invalid data type or value are skipped rather than failing the load.
+    pub fn from_xml(&mut self, xml: &str) -> Result<SettingsLoadSummary> {
+        for warning in Self::validate_xml(xml)? {
+            warn!("Settings file: {}", warning);
+        }
+
+        let (version, raw_settings) = Self::read_xml_settings(xml)?;
+
+        let mut summary = SettingsLoadSummary::default();
+        for raw in raw_settings {
+            match raw.and_then(|raw| raw.into_setting()) {
+                Ok(setting) => {
+                    self.settings.insert(setting.key.clone(), setting);
+                    summary.loaded += 1;
+                }
+                Err(_) => summary.skipped += 1,
+            }
+        }
+
+        if version < CURRENT_SETTINGS_VERSION {
+            Self::migrate_from(version, self);
+        }
+
+        info!("Loaded {} settings from XML ({} skipped)", summary.loaded, summary.skipped);
+        Ok(summary)
+    }
+
+    /// Load `settings.xml` on top of the defaults, so settings missing from the file keep their default
+    pub fn load_from_file(path: &Path) -> Result<Self> {
+        let mut settings = Self::new();
+        if !path.exists() {
+            return Ok(settings);
+        }
+
+        let xml = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+        settings.from_xml(&xml)
+            .map_err(|e| anyhow!("Failed to parse {}: {}", path.display(), e))?;
+        Ok(settings)
+    }
+
+    /// Write `settings.xml`, creating the configuration directory if needed
+    pub fn save_to_file(&self, path: &Path) -> Result<()> {
+        let xml = self.to_xml()?;
+        write_file_atomic(path, xml.as_bytes())
+    }
+
+    /// Check settings XML without applying it, returning a warning per problem found
+    pub fn validate_xml(xml: &str) -> Result<Vec<String>> {
+        let known = Self::new();
+        let (_, raw_settings) = Self::read_xml_settings(xml)?;
+
+        let mut warnings = Vec::new();
+        for raw in raw_settings {
+            let raw = match raw {
+                Ok(raw) => raw,
+                Err(e) => {
+                    warnings.push(e.to_string());
+                    continue;
+                }
+            };
+            if known.get(&raw.key).is_none() && !LEGACY_SETTING_KEYS.contains(&raw.key.as_str()) {
+                warnings.push(format!("Unknown setting '{}'", raw.key));
+            }
+            if let Err(e) = raw.into_setting() {
+                warnings.push(e.to_string());
+            }
+        }
+        Ok(warnings)
+    }
+
+    /// Read the file version and the attributes of every `<setting>` element
+    fn read_xml_settings(xml: &str) -> Result<(u32, Vec<Result<RawSetting>>)> {
         let mut reader = quick_xml::Reader::from_str(xml);
         reader.trim_text(true);
 
-        let mut current_setting: Option<(String, String, String, String, String)> = None;
+        // Files written before versioning was introduced have no version attribute
+        let mut version = 1;
+        let mut raw_settings = Vec::new();
 
         let mut buf = Vec::new();
         loop {
             match reader.read_event_into(&mut buf) {
-                Ok(Event::Start(ref e)) if e.name().as_ref() == b"setting" => {
-                    let key = e.try_get_attribute("name")?
-                        .ok_or_else(|| anyhow!("Missing name attribute"))?
-                        .decode_and_unescape_value(&reader)?;
-                    let value = e.try_get_attribute("value")?
-                        .ok_or_else(|| anyhow!("Missing value attribute"))?
-                        .decode_and_unescape_value(&reader)?;
-                    let dynamic = e.try_get_attribute("dynamic")?
-                        .ok_or_else(|| anyhow!("Missing dynamic attribute"))?
-                        .decode_and_unescape_value(&reader)?;
-                    let data_type = e.try_get_attribute("data_type")?
-                        .ok_or_else(|| anyhow!("Missing data_type attribute"))?
-                        .decode_and_unescape_value(&reader)?;
-                    let persisted = e.try_get_attribute("persisted")?
-                        .ok_or_else(|| anyhow!("Missing persisted attribute"))?
-                        .decode_and_unescape_value(&reader)?;
-
-                    current_setting = Some((key.into_owned(), value.into_owned(), dynamic.into_owned(), data_type.into_owned(), persisted.into_owned()));
-                }
-                Ok(Event::End(ref e)) if e.name().as_ref() == b"setting" => {
-                    if let Some((key, value, dynamic, data_type_str, persisted)) = current_setting.take() {
-                        let data_type = SettingDataType::from_i32(data_type_str.parse()?)?;
-                        let setting_value = SettingValue::from_string(&value, data_type)?;
-                        let dynamic_bool = dynamic.parse::<bool>().unwrap_or(false);
-                        let persisted_bool = persisted.parse::<bool>().unwrap_or(true);
-
-                        self.settings.insert(key.clone(), Setting {
-                            key,
-                            value: setting_value,
-                            dynamic: dynamic_bool,
-                            data_type,
-                            persisted: persisted_bool,
-                        });
+                Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e)) if e.name().as_ref() == b"settings" => {
+                    if let Some(attr) = e.try_get_attribute("version")? {
+                        version = attr.decode_and_unescape_value(&reader)?.parse()
+                            .map_err(|_| anyhow!("Invalid settings version"))?;
                     }
                 }
+                // `to_xml` writes self-closing elements; older files use start/end pairs
+                Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e)) if e.name().as_ref() == b"setting" => {
+                    raw_settings.push(RawSetting::from_element(e, &reader));
+                }
                 Ok(Event::Eof) => break,
                 Err(e) => return Err(anyhow!("XML parse error: {}", e)),
                 _ => {}
@@ -565,7 +721,129 @@ impl UserSettings {
             buf.clear();
         }
 
-        info!("Loaded {} settings from XML", self.settings.len());
-        Ok(())
+        Ok((version, raw_settings))
+    }
+
+    /// Apply migrations for settings saved by an older version
+    pub fn migrate_from(old_version: u32, settings: &mut UserSettings) {
+        if old_version < 2 {
+            settings.rename("key_type", "default_key_decoder");
+        }
+        info!("Migrated settings from version {} to {}", old_version, CURRENT_SETTINGS_VERSION);
+    }
+
+    /// Move a setting to a new key, replacing any existing value
+    fn rename(&mut self, old_key: &str, new_key: &str) {
+        if let Some(mut setting) = self.settings.remove(old_key) {
+            setting.key = new_key.to_string();
+            self.settings.insert(new_key.to_string(), setting);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrate_renames_key_type() {
+        let mut settings = UserSettings::new();
+        settings.set("key_type", SettingValue::String("string".to_string()), true, SettingDataType::String, true);
+
+        UserSettings::migrate_from(1, &mut settings);
+
+        assert!(settings.get("key_type").is_none());
+        assert_eq!(settings.get_string("default_key_decoder").unwrap(), "string");
+    }
+
+    #[test]
+    fn test_from_xml_migrates_unversioned_file() {
+        let xml = r#"<settings>
+  <setting name="key_type" value="string" dynamic="true" data_type="4" persisted="true"></setting>
+</settings>"#;
+        let mut settings = UserSettings::new();
+        settings.from_xml(xml).unwrap();
+
+        assert!(settings.get("key_type").is_none());
+        assert_eq!(settings.get_string("default_key_decoder").unwrap(), "string");
+    }
+
+    #[test]
+    fn test_data_type_from_str() {
+        assert_eq!(SettingDataType::from_str("4").unwrap(), SettingDataType::String);
+        assert_eq!(SettingDataType::from_str("Boolean").unwrap(), SettingDataType::Boolean);
+        assert!(SettingDataType::from_str("Text").is_err());
+
+        let xml = r#"<settings version="2">
+  <setting name="broker_read_timeout" value="5000" dynamic="true" data_type="Integer" persisted="true"></setting>
+</settings>"#;
+        let mut settings = UserSettings::new();
+        settings.from_xml(xml).unwrap();
+        assert_eq!(settings.get_int("broker_read_timeout").unwrap(), 5000);
+    }
+
+    #[test]
+    fn test_corrupt_settings_are_skipped() {
+        let xml = r#"<settings version="2">
+  <setting name="broker_read_timeout" value="5000" dynamic="true" data_type="1" persisted="true"></setting>
+  <setting name="zookeeper_timeout" value="soon" dynamic="true" data_type="1" persisted="true"></setting>
+  <setting name="mystery" value="x" dynamic="true" data_type="Text" persisted="true"></setting>
+</settings>"#;
+
+        let warnings = UserSettings::validate_xml(xml).unwrap();
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings.iter().any(|w| w.contains("Unknown setting 'mystery'")));
+
+        let mut settings = UserSettings::new();
+        let summary = settings.from_xml(xml).unwrap();
+        assert_eq!(summary, SettingsLoadSummary { loaded: 1, skipped: 2 });
+        assert_eq!(settings.get_int("broker_read_timeout").unwrap(), 5000);
+        assert_eq!(settings.get_int("zookeeper_timeout").unwrap(), 10000);
+    }
+
+    #[test]
+    fn test_settings_file_round_trip() {
+        let path = std::env::temp_dir()
+            .join(format!("offset-explorer-settings-{}", std::process::id()))
+            .join("settings.xml");
+        assert_eq!(UserSettings::load_from_file(&path).unwrap().to_xml().unwrap(), UserSettings::new().to_xml().unwrap());
+
+        let mut settings = UserSettings::new();
+        settings.set("broker_read_timeout", SettingValue::Integer(4321), true, SettingDataType::Integer, true);
+        settings.set("test_long", SettingValue::Long(9_876_543_210), true, SettingDataType::Long, true);
+        settings.set("test_double", SettingValue::Double(0.25), true, SettingDataType::Double, true);
+        settings.set("test_string", SettingValue::String("a \"quoted\" <value>".to_string()), true, SettingDataType::String, true);
+        settings.set("test_boolean", SettingValue::Boolean(true), true, SettingDataType::Boolean, true);
+        settings.set("test_color", SettingValue::Color { r: 12, g: 34, b: 56 }, true, SettingDataType::Color, true);
+        settings.set("test_transient", SettingValue::Boolean(true), true, SettingDataType::Boolean, false);
+        settings.save_to_file(&path).unwrap();
+
+        let loaded = UserSettings::load_from_file(&path).unwrap();
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+        assert_eq!(loaded.to_xml().unwrap(), settings.to_xml().unwrap());
+        assert_eq!(loaded.get_int("broker_read_timeout").unwrap(), 4321);
+        assert_eq!(loaded.get_long("test_long").unwrap(), 9_876_543_210);
+        assert!(matches!(loaded.get("test_double").unwrap().value, SettingValue::Double(d) if d == 0.25));
+        assert_eq!(loaded.get_string("test_string").unwrap(), "a \"quoted\" <value>");
+        assert!(loaded.get_bool("test_boolean").unwrap());
+        assert!(matches!(loaded.get("test_color").unwrap().value, SettingValue::Color { r: 12, g: 34, b: 56 }));
+        assert!(loaded.get("test_transient").is_none());
+    }
+
+    #[test]
+    fn test_from_xml_keeps_defaults_for_missing_settings() {
+        let xml = r#"<settings version="2">
+  <setting name="broker_read_timeout" value="5000" dynamic="true" data_type="1" persisted="true"/>
+</settings>"#;
+        let mut settings = UserSettings::new();
+        settings.from_xml(xml).unwrap();
+        assert_eq!(settings.get_int("broker_read_timeout").unwrap(), 5000);
+        assert_eq!(settings.get_int("zookeeper_timeout").unwrap(), 10000);
+    }
+
+    #[test]
+    fn test_to_xml_writes_current_version() {
+        let xml = UserSettings::new().to_xml().unwrap();
+        assert!(xml.contains(&format!("<settings version=\"{}\">", CURRENT_SETTINGS_VERSION)));
     }
 }
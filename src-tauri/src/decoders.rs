@@ -10,6 +10,11 @@ pub mod no_key_decoder;
 pub mod json_decoder;
 pub mod numeric_decoders;
 pub mod base64_decoder;
+pub mod protobuf_decoder;
+pub mod msgpack_decoder;
+pub mod chained_decoder;
+pub mod compression_decoder;
+pub mod auto_detect;
 
 // Re-export decoders
 pub use string_decoder::{StringDecoder, Encoding};
@@ -19,10 +24,20 @@ pub use no_key_decoder::NoKeyDecoder;
 pub use json_decoder::JsonDecoder;
 pub use numeric_decoders::{
     IntegerDecoder, LongDecoder, ShortDecoder,
-    FloatDecoder, DoubleDecoder, Endianness
+    UnsignedIntegerDecoder, UnsignedLongDecoder, UnsignedShortDecoder, ByteDecoder,
+    VarIntDecoder, ZigZagDecoder,
+    FloatDecoder, DoubleDecoder
 };
 
 pub use base64_decoder::Base64Decoder;
+pub use protobuf_decoder::ProtobufDecoder;
+pub use msgpack_decoder::MsgPackDecoder;
+pub use chained_decoder::{ChainedDecoder, DecoderFactory};
+pub use compression_decoder::CompressionDecoder;
+pub use auto_detect::auto_detect;
+
+use std::sync::Arc;
+use crate::schema_registry::client::SchemaRegistryClient;
 
 /// Decoder trait for pluggable decoder system
 pub trait Decoder {
@@ -116,6 +131,66 @@ impl Decoder for ShortDecoder {
     }
 }
 
+impl Decoder for UnsignedIntegerDecoder {
+    fn decode(&self, data: &[u8]) -> anyhow::Result<String> {
+        self.decode(data)
+    }
+
+    fn name(&self) -> &str {
+        "UInt32"
+    }
+}
+
+impl Decoder for UnsignedLongDecoder {
+    fn decode(&self, data: &[u8]) -> anyhow::Result<String> {
+        self.decode(data)
+    }
+
+    fn name(&self) -> &str {
+        "UInt64"
+    }
+}
+
+impl Decoder for UnsignedShortDecoder {
+    fn decode(&self, data: &[u8]) -> anyhow::Result<String> {
+        self.decode(data)
+    }
+
+    fn name(&self) -> &str {
+        "UInt16"
+    }
+}
+
+impl Decoder for ByteDecoder {
+    fn decode(&self, data: &[u8]) -> anyhow::Result<String> {
+        self.decode(data)
+    }
+
+    fn name(&self) -> &str {
+        "UInt8"
+    }
+}
+
+impl Decoder for VarIntDecoder {
+    fn decode(&self, data: &[u8]) -> anyhow::Result<String> {
+        self.decode(data)
+    }
+
+    fn name(&self) -> &str {
+        "VarInt"
+    }
+}
+
+impl Decoder for ZigZagDecoder {
+    fn decode(&self, data: &[u8]) -> anyhow::Result<String> {
+        self.decode(data)
+    }
+
+    fn name(&self) -> &str {
+        "ZigZag"
+    }
+}
+
 impl Decoder for FloatDecoder {
     fn decode(&self, data: &[u8]) -> anyhow::Result<String> {
         self.decode(data)
@@ -145,3 +220,93 @@ impl Decoder for Base64Decoder {
         "Base64"
     }
 }
+
+impl Decoder for ProtobufDecoder {
+    fn decode(&self, data: &[u8]) -> anyhow::Result<String> {
+        self.decode(data)
+    }
+
+    fn name(&self) -> &str {
+        "Protobuf"
+    }
+}
+
+impl Decoder for MsgPackDecoder {
+    fn decode(&self, data: &[u8]) -> anyhow::Result<String> {
+        self.decode(data)
+    }
+
+    fn name(&self) -> &str {
+        "MessagePack"
+    }
+}
+
+impl Decoder for ChainedDecoder {
+    fn decode(&self, data: &[u8]) -> anyhow::Result<String> {
+        self.decode(data)
+    }
+
+    fn name(&self) -> &str {
+        "Chained"
+    }
+}
+
+impl Decoder for CompressionDecoder {
+    fn decode(&self, data: &[u8]) -> anyhow::Result<String> {
+        self.decode(data)
+    }
+
+    fn name(&self) -> &str {
+        CompressionDecoder::name(self)
+    }
+}
+
+/// Build a decoder from its display name (case-insensitive). `schema` is the Avro schema,
+/// or `<descriptor set path>#<message type>` for Protobuf. Avro resolves Confluent wire
+/// format messages through `registry` when the connection has one.
+pub fn decoder_by_name(
+    name: &str,
+    schema: Option<String>,
+    registry: Option<Arc<SchemaRegistryClient>>,
+) -> anyhow::Result<Box<dyn Decoder + Send + Sync>> {
+    let decoder: Box<dyn Decoder + Send + Sync> = match name.to_lowercase().as_str() {
+        "string" => Box::new(StringDecoder::new()),
+        "bytearray" => Box::new(ByteArrayDecoder::new()),
+        "hex" => Box::new(ByteArrayDecoder::with_format(ByteArrayFormat::Hex)),
+        "avro" => {
+            let decoder = match schema {
+                Some(schema) => AvroDecoder::with_schema(schema),
+                None => AvroDecoder::new(),
+            };
+            match registry {
+                Some(registry) => Box::new(decoder.with_registry(registry)),
+                None => Box::new(decoder),
+            }
+        }
+        "nokey" => Box::new(NoKeyDecoder),
+        "json" => Box::new(JsonDecoder::new()),
+        "integer" => Box::new(IntegerDecoder::new()),
+        "long" => Box::new(LongDecoder::new()),
+        "short" => Box::new(ShortDecoder::new()),
+        "uint32" => Box::new(UnsignedIntegerDecoder::new()),
+        "uint64" => Box::new(UnsignedLongDecoder::new()),
+        "uint16" => Box::new(UnsignedShortDecoder::new()),
+        "uint8" => Box::new(ByteDecoder::new()),
+        "varint" => Box::new(VarIntDecoder::new()),
+        "zigzag" => Box::new(ZigZagDecoder::new()),
+        "float" => Box::new(FloatDecoder::new()),
+        "double" => Box::new(DoubleDecoder::new()),
+        "base64" => Box::new(Base64Decoder::new()),
+        "protobuf" => match schema {
+            Some(spec) => Box::new(ProtobufDecoder::from_schema_spec(&spec)?),
+            None => Box::new(ProtobufDecoder::new()),
+        },
+        "msgpack" | "messagepack" => Box::new(MsgPackDecoder::new()),
+        "gzip" => Box::new(CompressionDecoder::Gzip),
+        "snappy" => Box::new(CompressionDecoder::Snappy),
+        "lz4" => Box::new(CompressionDecoder::Lz4),
+        "zstd" => Box::new(CompressionDecoder::Zstd),
+        _ => return Err(anyhow::anyhow!("Unknown decoder: {}", name)),
+    };
+    Ok(decoder)
+}
@@ -0,0 +1,64 @@
+// Copyright 2024 Offset Explorer Rust Team
+// Licensed under Proprietary License
+//
+// Message format auto-detection
+
+use anyhow::Result;
+use std::sync::Arc;
+use tracing::debug;
+
+use super::avro_decoder::{confluent_schema_id, CONFLUENT_HEADER_LEN};
+use super::{AvroDecoder, ByteArrayDecoder, JsonDecoder};
+use crate::schema_registry::client::SchemaRegistryClient;
+
+/// Decode a message by trying Avro, JSON, UTF-8 string and byte array in order.
+///
+/// Returns the decoded text and the name of the format that matched. Avro is
+/// only attempted when a schema registry client is available.
+pub async fn auto_detect(
+    data: &[u8],
+    schema_registry: Option<Arc<SchemaRegistryClient>>,
+) -> Result<(String, &'static str)> {
+    if let (Some(schema_id), Some(registry)) = (confluent_schema_id(data), schema_registry) {
+        match registry.get_schema_by_id(schema_id).await {
+            Ok(schema) => {
+                let decoder = AvroDecoder::with_schema(schema.schema);
+                match decoder.decode(&data[CONFLUENT_HEADER_LEN..]) {
+                    Ok(decoded) => return Ok((decoded, "Avro")),
+                    Err(e) => debug!("Avro decoding with schema {} failed: {}", schema_id, e),
+                }
+            }
+            Err(e) => debug!("Schema lookup for ID {} failed: {}", schema_id, e),
+        }
+    }
+
+    Ok(detect_without_registry(data))
+}
+
+/// Detect JSON, UTF-8 string or byte array
+fn detect_without_registry(data: &[u8]) -> (String, &'static str) {
+    if let Ok(decoded) = JsonDecoder::new().decode(data) {
+        return (decoded, "JSON");
+    }
+
+    if let Ok(text) = std::str::from_utf8(data) {
+        return (text.to_string(), "String");
+    }
+
+    let decoded = ByteArrayDecoder::new()
+        .decode(data)
+        .unwrap_or_else(|e| format!("<Decode error: {}>", e));
+    (decoded, "ByteArray")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_without_registry() {
+        assert_eq!(detect_without_registry(br#"{"a":1}"#).1, "JSON");
+        assert_eq!(detect_without_registry(b"hello world").1, "String");
+        assert_eq!(detect_without_registry(&[0xff, 0xfe, 0x00]).1, "ByteArray");
+    }
+}
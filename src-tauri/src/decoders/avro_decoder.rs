@@ -6,11 +6,33 @@
 use anyhow::{Result, anyhow};
 use apache_avro::{from_avro_datum, types::Value};
 use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use crate::schema_registry::client::{SchemaRegistryClient, SchemaType};
+
+/// Magic byte prefixing Confluent wire format messages
+const CONFLUENT_MAGIC_BYTE: u8 = 0x0;
+
+/// Magic byte plus 4-byte schema ID
+pub(crate) const CONFLUENT_HEADER_LEN: usize = 5;
+
+/// Schema ID of a Confluent wire format message, if the data looks like one
+pub(crate) fn confluent_schema_id(data: &[u8]) -> Option<i32> {
+    if data.len() <= CONFLUENT_HEADER_LEN || data[0] != CONFLUENT_MAGIC_BYTE {
+        return None;
+    }
+    Some(i32::from_be_bytes([data[1], data[2], data[3], data[4]]))
+}
 
 /// Avro decoder configuration
 pub struct AvroDecoder {
     /// Optional schema for decoding (in Avro JSON format)
     schema: Option<String>,
+    /// Registry resolving the schema IDs of Confluent wire format messages
+    registry: Option<Arc<SchemaRegistryClient>>,
+    /// Parsed registry schemas by ID
+    registry_cache: Arc<RwLock<HashMap<i32, apache_avro::Schema>>>,
 }
 
 impl AvroDecoder {
@@ -18,6 +40,8 @@ impl AvroDecoder {
     pub fn new() -> Self {
         Self {
             schema: None,
+            registry: None,
+            registry_cache: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -25,6 +49,19 @@ impl AvroDecoder {
     pub fn with_schema(schema: String) -> Self {
         Self {
             schema: Some(schema),
+            ..Self::new()
+        }
+    }
+
+    /// Look up the schemas of Confluent wire format messages in a Schema Registry;
+    /// other messages still use the configured schema.
+    ///
+    /// Lookups block on the shared background runtime, so `decode` must not be
+    /// called from async code.
+    pub fn with_registry(self, registry: Arc<SchemaRegistryClient>) -> Self {
+        Self {
+            registry: Some(registry),
+            ..self
         }
     }
 
@@ -34,6 +71,12 @@ impl AvroDecoder {
             return Ok(String::new());
         }
 
+        // Confluent wire format: magic byte, schema ID, then the datum
+        if let (Some(registry), Some(schema_id)) = (&self.registry, confluent_schema_id(data)) {
+            let schema = self.registry_schema(registry, schema_id)?;
+            return self.decode_datum(&data[CONFLUENT_HEADER_LEN..], &schema);
+        }
+
         // Try to decode with schema if provided
         if let Some(ref schema_str) = self.schema {
             return self.decode_with_schema(data, schema_str);
@@ -48,10 +91,31 @@ impl AvroDecoder {
         // Parse schema from JSON string
         let schema = apache_avro::Schema::parse_str(schema_str)
             .map_err(|e| anyhow!("Failed to parse Avro schema: {}", e))?;
+        self.decode_datum(data, &schema)
+    }
+
+    /// Fetch and parse a registry schema, caching it for later messages
+    fn registry_schema(&self, registry: &SchemaRegistryClient, schema_id: i32) -> Result<apache_avro::Schema> {
+        if let Some(schema) = self.registry_cache.read().unwrap().get(&schema_id) {
+            return Ok(schema.clone());
+        }
 
-        // Decode the Avro data
+        let info = crate::async_ops::background_runtime()
+            .block_on(registry.get_schema_by_id(schema_id))?;
+        if info.schema_type != SchemaType::AVRO {
+            return Err(anyhow!("Schema {} is {:?}, not Avro", schema_id, info.schema_type));
+        }
+        let schema = apache_avro::Schema::parse_str(&info.schema)
+            .map_err(|e| anyhow!("Failed to parse Avro schema {}: {}", schema_id, e))?;
+
+        self.registry_cache.write().unwrap().insert(schema_id, schema.clone());
+        Ok(schema)
+    }
+
+    /// Decode an Avro datum with a parsed schema
+    fn decode_datum(&self, data: &[u8], schema: &apache_avro::Schema) -> Result<String> {
         let mut cursor = std::io::Cursor::new(data);
-        let datum = from_avro_datum(&schema, &mut cursor, None)
+        let datum = from_avro_datum(schema, &mut cursor, None)
             .map_err(|e| anyhow!("Failed to decode Avro datum: {}", e))?;
 
         // Convert to JSON for display
@@ -148,3 +212,61 @@ impl Default for AvroDecoder {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Serve `body` for every request on a local port, counting the requests
+    fn mock_registry(body: &'static str) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut request = [0u8; 4096];
+                let _ = stream.read(&mut request);
+                counter.fetch_add(1, Ordering::SeqCst);
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+            }
+        });
+        (url, hits)
+    }
+
+    #[test]
+    fn test_confluent_schema_id() {
+        assert_eq!(confluent_schema_id(&[0, 0, 0, 1, 42, 2]), Some(298));
+        assert_eq!(confluent_schema_id(&[1, 0, 0, 1, 42, 2]), None);
+        assert_eq!(confluent_schema_id(&[0, 0, 0, 1]), None);
+    }
+
+    #[test]
+    fn test_registry_schema_lookup_is_cached() {
+        let (url, hits) = mock_registry(r#"{"schema":"\"string\""}"#);
+        let decoder = AvroDecoder::new().with_registry(Arc::new(SchemaRegistryClient::new(url).unwrap()));
+
+        // Schema ID 7 followed by the Avro string "hello"
+        let data = [0, 0, 0, 0, 7, 0x0A, b'h', b'e', b'l', b'l', b'o'];
+        assert_eq!(decoder.decode(&data).unwrap(), "\"hello\"");
+        assert_eq!(decoder.decode(&data).unwrap(), "\"hello\"");
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+        assert!(decoder.registry_cache.read().unwrap().contains_key(&7));
+    }
+
+    #[test]
+    fn test_registry_rejects_non_avro_schema() {
+        let (url, _) = mock_registry(r#"{"schema":"syntax = \"proto3\";","schemaType":"PROTOBUF"}"#);
+        let decoder = AvroDecoder::new().with_registry(Arc::new(SchemaRegistryClient::new(url).unwrap()));
+        let err = decoder.decode(&[0, 0, 0, 0, 3, 0x02]).unwrap_err();
+        assert!(err.to_string().contains("not Avro"), "{}", err);
+    }
+}
@@ -0,0 +1,92 @@
+// Copyright 2024 Offset Explorer Rust Team
+// Licensed under Proprietary License
+//
+// Chained (pipeline) message decoder
+
+use anyhow::{Result, anyhow};
+
+use super::{Decoder, decoder_by_name};
+
+/// Decoder applying several decoders in order, feeding each stage's output to the next
+pub struct ChainedDecoder {
+    stages: Vec<Box<dyn Decoder + Send + Sync>>,
+}
+
+impl ChainedDecoder {
+    /// Create a chain from already built stages
+    pub fn new(stages: Vec<Box<dyn Decoder + Send + Sync>>) -> Self {
+        Self { stages }
+    }
+
+    /// Run every stage in order
+    pub fn decode(&self, data: &[u8]) -> Result<String> {
+        let (first, rest) = self.stages.split_first()
+            .ok_or_else(|| anyhow!("Decoder chain is empty"))?;
+
+        let mut output = first.decode(data)
+            .map_err(|e| anyhow!("Stage 1 ({}) failed: {}", first.name(), e))?;
+        for (index, stage) in rest.iter().enumerate() {
+            output = stage.decode(output.as_bytes())
+                .map_err(|e| anyhow!("Stage {} ({}) failed: {}", index + 2, stage.name(), e))?;
+        }
+        Ok(output)
+    }
+}
+
+/// Builds decoders from their registered names
+pub struct DecoderFactory;
+
+impl DecoderFactory {
+    /// Build a chain from decoder names, e.g. `["base64", "json"]`
+    pub fn build_chain(names: &[&str]) -> Result<ChainedDecoder> {
+        if names.is_empty() {
+            return Err(anyhow!("Decoder chain must name at least one decoder"));
+        }
+
+        let stages = names.iter()
+            .map(|name| decoder_by_name(name.trim(), None, None))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(ChainedDecoder::new(stages))
+    }
+
+    /// Build a chain from a comma separated spec such as `"base64,json"`
+    pub fn parse_chain(spec: &str) -> Result<ChainedDecoder> {
+        let names: Vec<&str> = spec.split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .collect();
+        Self::build_chain(&names)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine;
+
+    fn encode(data: &str) -> String {
+        base64::engine::general_purpose::STANDARD.encode(data)
+    }
+
+    #[test]
+    fn test_two_stage_chain() {
+        let chain = DecoderFactory::build_chain(&["base64", "json"]).unwrap();
+        let output = chain.decode(encode(r#"{"id":1}"#).as_bytes()).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(value, serde_json::json!({"id": 1}));
+    }
+
+    #[test]
+    fn test_three_stage_chain() {
+        let chain = DecoderFactory::parse_chain("base64, base64, json").unwrap();
+        let payload = encode(&encode(r#"{"name":"order"}"#));
+        let output = chain.decode(payload.as_bytes()).unwrap();
+        assert!(output.contains("\"name\": \"order\""), "{}", output);
+    }
+
+    #[test]
+    fn test_build_chain_rejects_unknown_and_empty() {
+        assert!(DecoderFactory::build_chain(&["base64", "rot13"]).is_err());
+        assert!(DecoderFactory::parse_chain(" , ").is_err());
+    }
+}
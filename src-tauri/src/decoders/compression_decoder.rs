@@ -0,0 +1,116 @@
+// Copyright 2024 Offset Explorer Rust Team
+// Licensed under Proprietary License
+//
+// Decompression decoders for application-level compressed payloads
+
+use std::io::Read;
+
+use anyhow::{Result, anyhow};
+
+/// Upper bound on decompressed output, guarding against decompression bombs
+const MAX_DECOMPRESSED_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Stream identifier chunk opening the Snappy framing format
+const SNAPPY_FRAME_MAGIC: &[u8] = b"\xff\x06\x00\x00sNaPpY";
+
+/// Magic number opening an LZ4 frame
+const LZ4_FRAME_MAGIC: &[u8] = &[0x04, 0x22, 0x4d, 0x18];
+
+/// Decompresses payloads that producers compressed before handing them to Kafka.
+///
+/// The output is the decompressed bytes as UTF-8 (lossy), meant to feed the next
+/// stage of a `ChainedDecoder` such as `gzip,json`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionDecoder {
+    Gzip,
+    /// Raw Snappy blocks or the Snappy framing format
+    Snappy,
+    /// LZ4 frames or size-prepended LZ4 blocks
+    Lz4,
+    Zstd,
+}
+
+impl CompressionDecoder {
+    /// Decompress bytes
+    pub fn decompress(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let result = match self {
+            Self::Gzip => read_limited(flate2::read::GzDecoder::new(data)),
+            Self::Snappy if data.starts_with(SNAPPY_FRAME_MAGIC) => {
+                read_limited(snap::read::FrameDecoder::new(data))
+            }
+            Self::Snappy => snap::raw::Decoder::new().decompress_vec(data).map_err(Into::into),
+            Self::Lz4 if data.starts_with(LZ4_FRAME_MAGIC) => {
+                read_limited(lz4_flex::frame::FrameDecoder::new(data))
+            }
+            Self::Lz4 => lz4_flex::block::decompress_size_prepended(data).map_err(Into::into),
+            Self::Zstd => zstd::stream::read::Decoder::new(data)
+                .map_err(Into::into)
+                .and_then(read_limited),
+        };
+        result.map_err(|e| anyhow!("{} decompression failed: {}", self.name(), e))
+    }
+
+    /// Decompress bytes to a UTF-8 (lossy) string
+    pub fn decode(&self, data: &[u8]) -> Result<String> {
+        Ok(String::from_utf8_lossy(&self.decompress(data)?).into_owned())
+    }
+
+    /// Display name of the codec
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Gzip => "Gzip",
+            Self::Snappy => "Snappy",
+            Self::Lz4 => "LZ4",
+            Self::Zstd => "Zstd",
+        }
+    }
+}
+
+fn read_limited(reader: impl Read) -> Result<Vec<u8>> {
+    let mut output = Vec::new();
+    reader.take(MAX_DECOMPRESSED_BYTES + 1).read_to_end(&mut output)?;
+    if output.len() as u64 > MAX_DECOMPRESSED_BYTES {
+        return Err(anyhow!("output exceeds {} bytes", MAX_DECOMPRESSED_BYTES));
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const PAYLOAD: &str = r#"{"order":42,"items":["a","b","c"],"note":"compressed payload"}"#;
+
+    #[test]
+    fn test_gzip_round_trip() {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(PAYLOAD.as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+        assert_eq!(CompressionDecoder::Gzip.decode(&compressed).unwrap(), PAYLOAD);
+    }
+
+    #[test]
+    fn test_snappy_round_trip() {
+        let compressed = snap::raw::Encoder::new().compress_vec(PAYLOAD.as_bytes()).unwrap();
+        assert_eq!(CompressionDecoder::Snappy.decode(&compressed).unwrap(), PAYLOAD);
+    }
+
+    #[test]
+    fn test_lz4_round_trip() {
+        let compressed = lz4_flex::compress_prepend_size(PAYLOAD.as_bytes());
+        assert_eq!(CompressionDecoder::Lz4.decode(&compressed).unwrap(), PAYLOAD);
+    }
+
+    #[test]
+    fn test_zstd_round_trip() {
+        let compressed = zstd::stream::encode_all(PAYLOAD.as_bytes(), 3).unwrap();
+        assert_eq!(CompressionDecoder::Zstd.decode(&compressed).unwrap(), PAYLOAD);
+    }
+
+    #[test]
+    fn test_invalid_gzip() {
+        let err = CompressionDecoder::Gzip.decode(b"not gzip").unwrap_err();
+        assert!(err.to_string().starts_with("Gzip decompression failed"));
+    }
+}
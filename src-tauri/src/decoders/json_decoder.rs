@@ -3,13 +3,14 @@
 //
 // JSON message decoder
 
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use serde_json::Value;
 
 /// JSON decoder with pretty printing
 pub struct JsonDecoder {
     pretty: bool,
     color: bool,
+    json_path: Option<String>,
 }
 
 impl JsonDecoder {
@@ -18,9 +19,21 @@ impl JsonDecoder {
         Self {
             pretty: true,
             color: false,
+            json_path: None,
         }
     }
 
+    /// Create a JSON decoder showing only what `path` (e.g. `$.user.id`) selects,
+    /// failing if the path is not valid JSONPath
+    pub fn with_path(path: &str) -> Result<Self> {
+        jsonpath_lib::select(&Value::Null, path)
+            .map_err(|e| anyhow!("Invalid JSON path '{}': {}", path, e))?;
+        Ok(Self {
+            json_path: Some(path.to_string()),
+            ..Self::new()
+        })
+    }
+
     /// Set pretty printing
     pub fn set_pretty(mut self, pretty: bool) -> Self {
         self.pretty = pretty;
@@ -33,7 +46,17 @@ impl JsonDecoder {
         let text = String::from_utf8_lossy(data);
 
         // Try to parse as JSON
-        let value: Value = serde_json::from_str(&text)?;
+        let mut value: Value = serde_json::from_str(&text)?;
+
+        if let Some(ref path) = self.json_path {
+            let mut matches = jsonpath_lib::select(&value, path)
+                .map_err(|e| anyhow!("Invalid JSON path '{}': {}", path, e))?;
+            value = match matches.len() {
+                0 => return Ok(format!("<no match for path: {}>", path)),
+                1 => matches.remove(0).clone(),
+                _ => Value::Array(matches.into_iter().cloned().collect()),
+            };
+        }
 
         if self.pretty {
             // Pretty print with 2-space indentation
@@ -78,4 +101,31 @@ mod tests {
         // Pretty printed JSON should have newlines
         assert!(result.contains('\n'));
     }
+
+    const EVENT: &[u8] = br#"{"user":{"id":42,"name":"ann"},"items":[{"sku":"a"},{"sku":"b"}]}"#;
+
+    #[test]
+    fn test_json_path_single_value() {
+        let decoder = JsonDecoder::with_path("$.user.id").unwrap();
+        assert_eq!(decoder.decode(EVENT).unwrap(), "42");
+    }
+
+    #[test]
+    fn test_json_path_array() {
+        let decoder = JsonDecoder::with_path("$.items[*].sku").unwrap();
+        let result: Value = serde_json::from_str(&decoder.decode(EVENT).unwrap()).unwrap();
+        assert_eq!(result, serde_json::json!(["a", "b"]));
+    }
+
+    #[test]
+    fn test_json_path_no_match() {
+        let decoder = JsonDecoder::with_path("$.user.email").unwrap();
+        assert_eq!(decoder.decode(EVENT).unwrap(), "<no match for path: $.user.email>");
+    }
+
+    #[test]
+    fn test_json_path_is_validated_up_front() {
+        let err = JsonDecoder::with_path("user.id").err().unwrap();
+        assert!(err.to_string().contains("Invalid JSON path 'user.id'"), "{}", err);
+    }
 }
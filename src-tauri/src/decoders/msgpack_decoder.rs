@@ -0,0 +1,212 @@
+// Copyright 2024 Offset Explorer Rust Team
+// Licensed under Proprietary License
+//
+// MessagePack message decoder
+
+use anyhow::{Result, anyhow};
+use base64::Engine;
+use serde_json::{Map, Value};
+
+/// Bytes shown in the hex preview of undecodable payloads
+const HEX_PREVIEW_BYTES: usize = 32;
+
+/// Containers nested deeper than this are rejected
+const MAX_DEPTH: usize = 128;
+
+/// MessagePack decoder rendering payloads as pretty-printed JSON.
+///
+/// Binary values are shown as base64 and extension types as `{"type", "data"}` objects.
+pub struct MsgPackDecoder;
+
+impl MsgPackDecoder {
+    /// Create a new MessagePack decoder
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Decode bytes to pretty-printed JSON
+    pub fn decode(&self, data: &[u8]) -> Result<String> {
+        let value = self.decode_value(data).map_err(|e| {
+            let preview = hex::encode(&data[..data.len().min(HEX_PREVIEW_BYTES)]);
+            let ellipsis = if data.len() > HEX_PREVIEW_BYTES { "..." } else { "" };
+            anyhow!("Not valid MessagePack ({}): {}{}", e, preview, ellipsis)
+        })?;
+        Ok(serde_json::to_string_pretty(&value)?)
+    }
+
+    /// Decode a single MessagePack value, rejecting trailing bytes
+    pub fn decode_value(&self, data: &[u8]) -> Result<Value> {
+        let mut reader = Reader { data, pos: 0 };
+        let value = reader.read_value(0)?;
+        if reader.pos != data.len() {
+            return Err(anyhow!("{} trailing bytes after value", data.len() - reader.pos));
+        }
+        Ok(value)
+    }
+}
+
+impl Default for MsgPackDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self.pos.checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| anyhow!("unexpected end of data at byte {}", self.pos))?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.take(N)?.try_into()?)
+    }
+
+    fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn read_len(&mut self, width: usize) -> Result<usize> {
+        Ok(match width {
+            1 => self.read_u8()? as usize,
+            2 => u16::from_be_bytes(self.take_array()?) as usize,
+            _ => u32::from_be_bytes(self.take_array()?) as usize,
+        })
+    }
+
+    fn read_value(&mut self, depth: usize) -> Result<Value> {
+        if depth > MAX_DEPTH {
+            return Err(anyhow!("nesting deeper than {} levels", MAX_DEPTH));
+        }
+
+        let marker = self.read_u8()?;
+        Ok(match marker {
+            0x00..=0x7f => Value::from(marker),
+            0x80..=0x8f => self.read_map((marker & 0x0f) as usize, depth)?,
+            0x90..=0x9f => self.read_array((marker & 0x0f) as usize, depth)?,
+            0xa0..=0xbf => self.read_str((marker & 0x1f) as usize)?,
+            0xc0 => Value::Null,
+            0xc2 => Value::Bool(false),
+            0xc3 => Value::Bool(true),
+            0xc4..=0xc6 => {
+                let len = self.read_len(1 << (marker - 0xc4))?;
+                Value::String(base64::engine::general_purpose::STANDARD.encode(self.take(len)?))
+            }
+            0xc7..=0xc9 => {
+                let len = self.read_len(1 << (marker - 0xc7))?;
+                self.read_ext(len)?
+            }
+            0xca => serde_json::json!(f32::from_be_bytes(self.take_array()?)),
+            0xcb => serde_json::json!(f64::from_be_bytes(self.take_array()?)),
+            0xcc => Value::from(self.read_u8()?),
+            0xcd => Value::from(u16::from_be_bytes(self.take_array()?)),
+            0xce => Value::from(u32::from_be_bytes(self.take_array()?)),
+            0xcf => Value::from(u64::from_be_bytes(self.take_array()?)),
+            0xd0 => Value::from(self.read_u8()? as i8),
+            0xd1 => Value::from(i16::from_be_bytes(self.take_array()?)),
+            0xd2 => Value::from(i32::from_be_bytes(self.take_array()?)),
+            0xd3 => Value::from(i64::from_be_bytes(self.take_array()?)),
+            0xd4..=0xd8 => self.read_ext(1 << (marker - 0xd4))?,
+            0xd9..=0xdb => {
+                let len = self.read_len(1 << (marker - 0xd9))?;
+                self.read_str(len)?
+            }
+            0xdc | 0xdd => {
+                let len = self.read_len(if marker == 0xdc { 2 } else { 4 })?;
+                self.read_array(len, depth)?
+            }
+            0xde | 0xdf => {
+                let len = self.read_len(if marker == 0xde { 2 } else { 4 })?;
+                self.read_map(len, depth)?
+            }
+            0xe0..=0xff => Value::from(marker as i8),
+            0xc1 => return Err(anyhow!("reserved marker 0xc1 at byte {}", self.pos - 1)),
+        })
+    }
+
+    fn read_str(&mut self, len: usize) -> Result<Value> {
+        let bytes = self.take(len)?;
+        let text = std::str::from_utf8(bytes)
+            .map_err(|e| anyhow!("invalid UTF-8 string: {}", e))?;
+        Ok(Value::String(text.to_string()))
+    }
+
+    fn read_ext(&mut self, len: usize) -> Result<Value> {
+        let ext_type = self.read_u8()? as i8;
+        let data = self.take(len)?;
+        Ok(serde_json::json!({
+            "type": ext_type,
+            "data": base64::engine::general_purpose::STANDARD.encode(data),
+        }))
+    }
+
+    fn read_array(&mut self, len: usize, depth: usize) -> Result<Value> {
+        // Every element takes at least one byte
+        let mut items = Vec::with_capacity(len.min(self.data.len() - self.pos));
+        for _ in 0..len {
+            items.push(self.read_value(depth + 1)?);
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn read_map(&mut self, len: usize, depth: usize) -> Result<Value> {
+        let mut map = Map::new();
+        for _ in 0..len {
+            let key = match self.read_value(depth + 1)? {
+                Value::String(key) => key,
+                other => other.to_string(),
+            };
+            let value = self.read_value(depth + 1)?;
+            map.insert(key, value);
+        }
+        Ok(Value::Object(map))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_scalars() {
+        let decoder = MsgPackDecoder::new();
+        assert_eq!(decoder.decode_value(&[0xc0]).unwrap(), Value::Null);
+        assert_eq!(decoder.decode_value(&[0x2a]).unwrap(), Value::from(42));
+        assert_eq!(decoder.decode_value(&[0xff]).unwrap(), Value::from(-1));
+        assert_eq!(decoder.decode_value(&[0xd1, 0xfc, 0x18]).unwrap(), Value::from(-1000));
+        assert_eq!(decoder.decode_value(&[0xce, 0x00, 0x01, 0x00, 0x00]).unwrap(), Value::from(65536));
+        assert_eq!(decoder.decode_value(&[0xa3, b'a', b'b', b'c']).unwrap(), Value::from("abc"));
+        assert_eq!(decoder.decode_value(&[0xcb, 0x3f, 0xf8, 0, 0, 0, 0, 0, 0]).unwrap(), Value::from(1.5));
+    }
+
+    #[test]
+    fn test_decode_map_and_array() {
+        // {"id": 7, "tags": ["a", nil], "ok": true}
+        let data = [
+            0x83,
+            0xa2, b'i', b'd', 0x07,
+            0xa4, b't', b'a', b'g', b's', 0x92, 0xa1, b'a', 0xc0,
+            0xa2, b'o', b'k', 0xc3,
+        ];
+        let decoder = MsgPackDecoder::new();
+        let value: Value = serde_json::from_str(&decoder.decode(&data).unwrap()).unwrap();
+        assert_eq!(value, serde_json::json!({"id": 7, "tags": ["a", null], "ok": true}));
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        let decoder = MsgPackDecoder::new();
+        let err = decoder.decode(&[0xa5, b'a']).unwrap_err().to_string();
+        assert!(err.contains("a561"), "{}", err);
+        assert!(decoder.decode(&[0xc1]).is_err());
+        assert!(decoder.decode(&[0x01, 0x02]).is_err());
+    }
+}
@@ -3,7 +3,7 @@
 //
 // Numeric message decoders
 
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use byteorder::{BigEndian, LittleEndian, ByteOrder, ReadBytesExt};
 use std::io::Cursor;
 
@@ -26,10 +26,6 @@ impl IntegerDecoder {
         }
     }
 
-    pub fn with_endianness(endianness: Endianness) -> Self {
-        Self { endianness }
-    }
-
     pub fn decode(&self, data: &[u8]) -> Result<String> {
         if data.len() < 4 {
             return Ok(format!("<Insufficient data: {} bytes, need 4>", data.len()));
@@ -62,10 +58,6 @@ impl LongDecoder {
         }
     }
 
-    pub fn with_endianness(endianness: Endianness) -> Self {
-        Self { endianness }
-    }
-
     pub fn decode(&self, data: &[u8]) -> Result<String> {
         if data.len() < 8 {
             return Ok(format!("<Insufficient data: {} bytes, need 8>", data.len()));
@@ -108,10 +100,6 @@ impl ShortDecoder {
         }
     }
 
-    pub fn with_endianness(endianness: Endianness) -> Self {
-        Self { endianness }
-    }
-
     pub fn decode(&self, data: &[u8]) -> Result<String> {
         if data.len() < 2 {
             return Ok(format!("<Insufficient data: {} bytes, need 2>", data.len()));
@@ -132,6 +120,103 @@ impl Default for ShortDecoder {
     }
 }
 
+/// Unsigned integer decoder (32-bit), big-endian
+pub struct UnsignedIntegerDecoder;
+
+impl UnsignedIntegerDecoder {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn decode(&self, data: &[u8]) -> Result<String> {
+        if data.len() < 4 {
+            return Ok(format!("<Insufficient data: {} bytes, need 4>", data.len()));
+        }
+
+        let value = u32::from_be_bytes([data[0], data[1], data[2], data[3]]);
+        Ok(value.to_string())
+    }
+}
+
+impl Default for UnsignedIntegerDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Unsigned long decoder (64-bit), big-endian
+pub struct UnsignedLongDecoder;
+
+impl UnsignedLongDecoder {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn decode(&self, data: &[u8]) -> Result<String> {
+        if data.len() < 8 {
+            return Ok(format!("<Insufficient data: {} bytes, need 8>", data.len()));
+        }
+
+        let value = u64::from_be_bytes([
+            data[0], data[1], data[2], data[3],
+            data[4], data[5], data[6], data[7],
+        ]);
+        Ok(value.to_string())
+    }
+}
+
+impl Default for UnsignedLongDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Unsigned short decoder (16-bit), big-endian
+pub struct UnsignedShortDecoder;
+
+impl UnsignedShortDecoder {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn decode(&self, data: &[u8]) -> Result<String> {
+        if data.len() < 2 {
+            return Ok(format!("<Insufficient data: {} bytes, need 2>", data.len()));
+        }
+
+        let value = u16::from_be_bytes([data[0], data[1]]);
+        Ok(value.to_string())
+    }
+}
+
+impl Default for UnsignedShortDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Byte decoder (unsigned 8-bit); endianness does not apply to a single byte
+pub struct ByteDecoder;
+
+impl ByteDecoder {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn decode(&self, data: &[u8]) -> Result<String> {
+        match data.first() {
+            Some(value) => Ok(value.to_string()),
+            None => Ok("<Insufficient data: 0 bytes, need 1>".to_string()),
+        }
+    }
+}
+
+impl Default for ByteDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Float decoder (32-bit IEEE 754)
 pub struct FloatDecoder {
     endianness: Endianness,
@@ -144,10 +229,6 @@ impl FloatDecoder {
         }
     }
 
-    pub fn with_endianness(endianness: Endianness) -> Self {
-        Self { endianness }
-    }
-
     pub fn decode(&self, data: &[u8]) -> Result<String> {
         if data.len() < 4 {
             return Ok(format!("<Insufficient data: {} bytes, need 4>", data.len()));
@@ -181,10 +262,6 @@ impl DoubleDecoder {
         }
     }
 
-    pub fn with_endianness(endianness: Endianness) -> Self {
-        Self { endianness }
-    }
-
     pub fn decode(&self, data: &[u8]) -> Result<String> {
         if data.len() < 8 {
             return Ok(format!("<Insufficient data: {} bytes, need 8>", data.len()));
@@ -216,6 +293,82 @@ impl Default for DoubleDecoder {
     }
 }
 
+/// Read a base-128 varint, advancing `pos`
+pub(crate) fn read_varint(data: &[u8], pos: &mut usize) -> Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *data.get(*pos).ok_or_else(|| anyhow!("Truncated varint at byte {}", *pos))?;
+        *pos += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(anyhow!("Varint longer than 10 bytes at byte {}", *pos))
+}
+
+/// Undo zigzag encoding, mapping 0, 1, 2, 3... back to 0, -1, 1, -2...
+pub(crate) fn zigzag(v: u64) -> i64 {
+    ((v >> 1) as i64) ^ -((v & 1) as i64)
+}
+
+/// VarInt decoder (unsigned base-128, Protobuf wire format)
+pub struct VarIntDecoder;
+
+impl VarIntDecoder {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Read the varint at the start of `data`, returning it with the number of bytes consumed
+    pub fn decode_with_len(&self, data: &[u8]) -> Result<(u64, usize)> {
+        let mut pos = 0;
+        let value = read_varint(data, &mut pos)?;
+        Ok((value, pos))
+    }
+
+    pub fn decode(&self, data: &[u8]) -> Result<String> {
+        match self.decode_with_len(data) {
+            Ok((value, len)) => Ok(format!("{} ({} bytes)", value, len)),
+            Err(e) => Ok(format!("<Invalid VarInt: {}>", e)),
+        }
+    }
+}
+
+impl Default for VarIntDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// ZigZag decoder (signed varint as used by Protobuf sint32/sint64)
+pub struct ZigZagDecoder;
+
+impl ZigZagDecoder {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Read the zigzag varint at the start of `data`, returning it with the number of bytes consumed
+    pub fn decode_with_len(&self, data: &[u8]) -> Result<(i64, usize)> {
+        let (value, len) = VarIntDecoder.decode_with_len(data)?;
+        Ok((zigzag(value), len))
+    }
+
+    pub fn decode(&self, data: &[u8]) -> Result<String> {
+        match self.decode_with_len(data) {
+            Ok((value, len)) => Ok(format!("{} ({} bytes)", value, len)),
+            Err(e) => Ok(format!("<Invalid VarInt: {}>", e)),
+        }
+    }
+}
+
+impl Default for ZigZagDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -237,4 +390,43 @@ mod tests {
         let result = decoder.decode(data).unwrap();
         assert!(result.starts_with("3.14"));
     }
+
+    #[test]
+    fn test_unsigned_max_values() {
+        assert_eq!(UnsignedShortDecoder::new().decode(&[0xFF; 2]).unwrap(), u16::MAX.to_string());
+        assert_eq!(UnsignedIntegerDecoder::new().decode(&[0xFF; 4]).unwrap(), u32::MAX.to_string());
+        assert_eq!(UnsignedLongDecoder::new().decode(&[0xFF; 8]).unwrap(), u64::MAX.to_string());
+        assert_eq!(ByteDecoder::new().decode(&[0xFF]).unwrap(), "255");
+    }
+
+    #[test]
+    fn test_unsigned_big_endian() {
+        let data = &[0x00, 0x00, 0x00, 0x80];
+        assert_eq!(UnsignedIntegerDecoder::new().decode(data).unwrap(), "128");
+        assert_eq!(UnsignedLongDecoder::new().decode(&[0, 0, 0, 0, 0, 0, 0x01, 0x00]).unwrap(), "256");
+        assert!(ByteDecoder::new().decode(&[]).unwrap().starts_with("<Insufficient data"));
+    }
+
+    #[test]
+    fn test_varint_decode() {
+        let decoder = VarIntDecoder::new();
+        assert_eq!(decoder.decode_with_len(&[0x01]).unwrap(), (1, 1));
+        assert_eq!(decoder.decode_with_len(&[0xAC, 0x02, 0xFF]).unwrap(), (300, 2));
+        let max = [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01];
+        assert_eq!(decoder.decode_with_len(&max).unwrap(), (u64::MAX, 10));
+        assert_eq!(decoder.decode(&[0xAC, 0x02]).unwrap(), "300 (2 bytes)");
+        assert!(decoder.decode(&[0x80]).unwrap().starts_with("<Invalid VarInt"));
+    }
+
+    #[test]
+    fn test_zigzag_decode() {
+        let decoder = ZigZagDecoder::new();
+        assert_eq!(decoder.decode_with_len(&[0x00]).unwrap(), (0, 1));
+        assert_eq!(decoder.decode_with_len(&[0x03]).unwrap(), (-2, 1));
+        assert_eq!(decoder.decode_with_len(&[0xAC, 0x02]).unwrap(), (150, 2));
+        let max = [0xFE, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01];
+        assert_eq!(decoder.decode_with_len(&max).unwrap(), (i64::MAX, 10));
+        let min = [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01];
+        assert_eq!(decoder.decode(&min).unwrap(), format!("{} (10 bytes)", i64::MIN));
+    }
 }
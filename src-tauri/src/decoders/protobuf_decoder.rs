@@ -0,0 +1,513 @@
+// Copyright 2024 Offset Explorer Rust Team
+// Licensed under Proprietary License
+//
+// Protobuf message decoder
+//
+// Schemas are read from a compiled FileDescriptorSet
+// (`protoc --include_imports --descriptor_set_out=schema.desc schema.proto`).
+// Without a schema the wire format is shown with field numbers and wire types.
+
+use anyhow::{Result, anyhow};
+use base64::Engine;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::numeric_decoders::{read_varint, zigzag};
+
+const WIRE_VARINT: u8 = 0;
+const WIRE_FIXED64: u8 = 1;
+const WIRE_LENGTH_DELIMITED: u8 = 2;
+const WIRE_START_GROUP: u8 = 3;
+const WIRE_END_GROUP: u8 = 4;
+const WIRE_FIXED32: u8 = 5;
+
+/// Nested messages deeper than this are shown as bytes when decoding without a schema
+const MAX_GUESS_DEPTH: usize = 8;
+
+/// Field value as read from the wire
+#[derive(Debug, Clone, PartialEq)]
+enum WireValue<'a> {
+    Varint(u64),
+    Fixed64(u64),
+    LengthDelimited(&'a [u8]),
+    Fixed32(u32),
+}
+
+impl WireValue<'_> {
+    fn wire_type_name(&self) -> &'static str {
+        match self {
+            WireValue::Varint(_) => "varint",
+            WireValue::Fixed64(_) => "fixed64",
+            WireValue::LengthDelimited(_) => "length-delimited",
+            WireValue::Fixed32(_) => "fixed32",
+        }
+    }
+}
+
+fn read_bytes<'a>(data: &'a [u8], pos: &mut usize, len: usize) -> Result<&'a [u8]> {
+    let end = pos.checked_add(len)
+        .filter(|end| *end <= data.len())
+        .ok_or_else(|| anyhow!("Field of {} bytes at byte {} exceeds the message", len, *pos))?;
+    let bytes = &data[*pos..end];
+    *pos = end;
+    Ok(bytes)
+}
+
+/// Split a message into `(field number, value)` pairs
+fn parse_fields(data: &[u8]) -> Result<Vec<(u32, WireValue<'_>)>> {
+    let mut fields = Vec::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        let key = read_varint(data, &mut pos)?;
+        let number = (key >> 3) as u32;
+        if number == 0 {
+            return Err(anyhow!("Invalid field number 0 at byte {}", pos));
+        }
+        let value = match (key & 0x7) as u8 {
+            WIRE_VARINT => WireValue::Varint(read_varint(data, &mut pos)?),
+            WIRE_FIXED64 => WireValue::Fixed64(u64::from_le_bytes(read_bytes(data, &mut pos, 8)?.try_into()?)),
+            WIRE_LENGTH_DELIMITED => {
+                let len = read_varint(data, &mut pos)? as usize;
+                WireValue::LengthDelimited(read_bytes(data, &mut pos, len)?)
+            }
+            WIRE_FIXED32 => WireValue::Fixed32(u32::from_le_bytes(read_bytes(data, &mut pos, 4)?.try_into()?)),
+            WIRE_START_GROUP | WIRE_END_GROUP => return Err(anyhow!("Groups are not supported")),
+            other => return Err(anyhow!("Invalid wire type {} at byte {}", other, pos)),
+        };
+        fields.push((number, value));
+    }
+    Ok(fields)
+}
+
+fn bytes_to_json(bytes: &[u8]) -> Value {
+    Value::String(base64::engine::general_purpose::STANDARD.encode(bytes))
+}
+
+/// Field of a message type from the descriptor set
+#[derive(Debug, Clone)]
+struct FieldDescriptor {
+    name: String,
+    number: u32,
+    repeated: bool,
+    field_type: u64,
+    /// Fully qualified name of the message or enum type, e.g. `.shop.Order`
+    type_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct MessageDescriptor {
+    fields: Vec<FieldDescriptor>,
+}
+
+// FieldDescriptorProto.Type values
+const TYPE_DOUBLE: u64 = 1;
+const TYPE_FLOAT: u64 = 2;
+const TYPE_INT64: u64 = 3;
+const TYPE_UINT64: u64 = 4;
+const TYPE_INT32: u64 = 5;
+const TYPE_FIXED64: u64 = 6;
+const TYPE_FIXED32: u64 = 7;
+const TYPE_BOOL: u64 = 8;
+const TYPE_STRING: u64 = 9;
+const TYPE_MESSAGE: u64 = 11;
+const TYPE_BYTES: u64 = 12;
+const TYPE_UINT32: u64 = 13;
+const TYPE_ENUM: u64 = 14;
+const TYPE_SFIXED32: u64 = 15;
+const TYPE_SFIXED64: u64 = 16;
+const TYPE_SINT32: u64 = 17;
+const TYPE_SINT64: u64 = 18;
+
+/// Label value for repeated fields
+const LABEL_REPEATED: u64 = 3;
+
+/// Message and enum types of a FileDescriptorSet, keyed by fully qualified name
+#[derive(Debug, Clone, Default)]
+struct DescriptorPool {
+    messages: HashMap<String, MessageDescriptor>,
+    enums: HashMap<String, HashMap<i32, String>>,
+}
+
+impl DescriptorPool {
+    fn from_descriptor_set(data: &[u8]) -> Result<Self> {
+        let mut pool = Self::default();
+        for (number, value) in parse_fields(data)? {
+            if let (1, WireValue::LengthDelimited(file)) = (number, value) {
+                pool.add_file(file)?;
+            }
+        }
+        if pool.messages.is_empty() {
+            return Err(anyhow!("Descriptor set contains no message types"));
+        }
+        Ok(pool)
+    }
+
+    fn add_file(&mut self, file: &[u8]) -> Result<()> {
+        let fields = parse_fields(file)?;
+        let package = fields.iter()
+            .find_map(|(n, v)| match (n, v) {
+                (2, WireValue::LengthDelimited(p)) => Some(String::from_utf8_lossy(p).into_owned()),
+                _ => None,
+            })
+            .unwrap_or_default();
+        let scope = if package.is_empty() { String::new() } else { format!(".{}", package) };
+
+        for (number, value) in fields {
+            match (number, value) {
+                (4, WireValue::LengthDelimited(message)) => self.add_message(&scope, message)?,
+                (5, WireValue::LengthDelimited(enum_type)) => self.add_enum(&scope, enum_type)?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn add_message(&mut self, scope: &str, data: &[u8]) -> Result<()> {
+        let fields = parse_fields(data)?;
+        let full_name = format!("{}.{}", scope, string_field(&fields, 1));
+
+        let mut message = MessageDescriptor::default();
+        for (number, value) in fields {
+            match (number, value) {
+                (2, WireValue::LengthDelimited(field)) => message.fields.push(parse_field_descriptor(field)?),
+                (3, WireValue::LengthDelimited(nested)) => self.add_message(&full_name, nested)?,
+                (4, WireValue::LengthDelimited(enum_type)) => self.add_enum(&full_name, enum_type)?,
+                _ => {}
+            }
+        }
+        self.messages.insert(full_name, message);
+        Ok(())
+    }
+
+    fn add_enum(&mut self, scope: &str, data: &[u8]) -> Result<()> {
+        let fields = parse_fields(data)?;
+        let full_name = format!("{}.{}", scope, string_field(&fields, 1));
+
+        let mut values = HashMap::new();
+        for (number, value) in fields {
+            if let (2, WireValue::LengthDelimited(value)) = (number, value) {
+                let value_fields = parse_fields(value)?;
+                let name = string_field(&value_fields, 1);
+                let number = varint_field(&value_fields, 2).unwrap_or(0) as i32;
+                values.insert(number, name);
+            }
+        }
+        self.enums.insert(full_name, values);
+        Ok(())
+    }
+
+    /// Look up a message type by name, with or without package or leading dot
+    fn resolve(&self, message_type: &str) -> Option<&str> {
+        let qualified = format!(".{}", message_type.trim_start_matches('.'));
+        if let Some((name, _)) = self.messages.get_key_value(&qualified) {
+            return Some(name);
+        }
+        // Unqualified name: accept it when exactly one type matches
+        let mut matches = self.messages.keys().filter(|name| name.ends_with(&qualified));
+        match (matches.next(), matches.next()) {
+            (Some(name), None) => Some(name),
+            _ => None,
+        }
+    }
+
+    fn decode_message(&self, type_name: &str, data: &[u8]) -> Result<Value> {
+        let descriptor = self.messages.get(type_name)
+            .ok_or_else(|| anyhow!("Unknown message type {}", type_name))?;
+        let by_number: HashMap<u32, &FieldDescriptor> =
+            descriptor.fields.iter().map(|f| (f.number, f)).collect();
+
+        let mut object = Map::new();
+        for (number, value) in parse_fields(data)? {
+            let Some(field) = by_number.get(&number) else {
+                object.insert(number.to_string(), guess_value(&value, 0));
+                continue;
+            };
+
+            let values = match value {
+                // Packed repeated scalars
+                WireValue::LengthDelimited(bytes) if field.repeated && is_packable(field.field_type) => {
+                    self.decode_packed(field, bytes)?
+                }
+                value => vec![self.decode_value(field, &value)?],
+            };
+
+            if field.repeated {
+                let entry = object.entry(field.name.clone()).or_insert_with(|| Value::Array(Vec::new()));
+                if let Value::Array(items) = entry {
+                    items.extend(values);
+                }
+            } else if let Some(value) = values.into_iter().last() {
+                object.insert(field.name.clone(), value);
+            }
+        }
+        Ok(Value::Object(object))
+    }
+
+    fn decode_packed(&self, field: &FieldDescriptor, bytes: &[u8]) -> Result<Vec<Value>> {
+        let mut values = Vec::new();
+        let mut pos = 0;
+        while pos < bytes.len() {
+            let value = match field.field_type {
+                TYPE_DOUBLE | TYPE_FIXED64 | TYPE_SFIXED64 => {
+                    WireValue::Fixed64(u64::from_le_bytes(read_bytes(bytes, &mut pos, 8)?.try_into()?))
+                }
+                TYPE_FLOAT | TYPE_FIXED32 | TYPE_SFIXED32 => {
+                    WireValue::Fixed32(u32::from_le_bytes(read_bytes(bytes, &mut pos, 4)?.try_into()?))
+                }
+                _ => WireValue::Varint(read_varint(bytes, &mut pos)?),
+            };
+            values.push(self.decode_value(field, &value)?);
+        }
+        Ok(values)
+    }
+
+    fn decode_value(&self, field: &FieldDescriptor, value: &WireValue) -> Result<Value> {
+        let mismatch = || anyhow!("Field '{}' has unexpected wire type {}", field.name, value.wire_type_name());
+        Ok(match (field.field_type, value) {
+            (TYPE_DOUBLE, WireValue::Fixed64(v)) => serde_json::json!(f64::from_bits(*v)),
+            (TYPE_FLOAT, WireValue::Fixed32(v)) => serde_json::json!(f32::from_bits(*v)),
+            (TYPE_INT64, WireValue::Varint(v)) => Value::from(*v as i64),
+            (TYPE_UINT64, WireValue::Varint(v)) => Value::from(*v),
+            (TYPE_INT32, WireValue::Varint(v)) => Value::from(*v as i32),
+            (TYPE_UINT32, WireValue::Varint(v)) => Value::from(*v as u32),
+            (TYPE_BOOL, WireValue::Varint(v)) => Value::Bool(*v != 0),
+            (TYPE_SINT32, WireValue::Varint(v)) => Value::from(zigzag(*v) as i32),
+            (TYPE_SINT64, WireValue::Varint(v)) => Value::from(zigzag(*v)),
+            (TYPE_FIXED64, WireValue::Fixed64(v)) => Value::from(*v),
+            (TYPE_SFIXED64, WireValue::Fixed64(v)) => Value::from(*v as i64),
+            (TYPE_FIXED32, WireValue::Fixed32(v)) => Value::from(*v),
+            (TYPE_SFIXED32, WireValue::Fixed32(v)) => Value::from(*v as i32),
+            (TYPE_ENUM, WireValue::Varint(v)) => {
+                let number = *v as i32;
+                field.type_name.as_ref()
+                    .and_then(|t| self.enums.get(t))
+                    .and_then(|values| values.get(&number))
+                    .map(|name| Value::String(name.clone()))
+                    .unwrap_or_else(|| Value::from(number))
+            }
+            (TYPE_STRING, WireValue::LengthDelimited(b)) => Value::String(String::from_utf8_lossy(b).into_owned()),
+            (TYPE_BYTES, WireValue::LengthDelimited(b)) => bytes_to_json(b),
+            (TYPE_MESSAGE, WireValue::LengthDelimited(b)) => {
+                let type_name = field.type_name.as_deref().ok_or_else(mismatch)?;
+                self.decode_message(type_name, b)?
+            }
+            _ => return Err(mismatch()),
+        })
+    }
+}
+
+fn is_packable(field_type: u64) -> bool {
+    !matches!(field_type, TYPE_STRING | TYPE_BYTES | TYPE_MESSAGE)
+}
+
+fn string_field(fields: &[(u32, WireValue)], number: u32) -> String {
+    fields.iter()
+        .find_map(|(n, v)| match v {
+            WireValue::LengthDelimited(b) if *n == number => Some(String::from_utf8_lossy(b).into_owned()),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+fn varint_field(fields: &[(u32, WireValue)], number: u32) -> Option<u64> {
+    fields.iter().find_map(|(n, v)| match v {
+        WireValue::Varint(v) if *n == number => Some(*v),
+        _ => None,
+    })
+}
+
+fn parse_field_descriptor(data: &[u8]) -> Result<FieldDescriptor> {
+    let fields = parse_fields(data)?;
+    let type_name = string_field(&fields, 6);
+    Ok(FieldDescriptor {
+        name: string_field(&fields, 1),
+        number: varint_field(&fields, 3).unwrap_or(0) as u32,
+        repeated: varint_field(&fields, 4) == Some(LABEL_REPEATED),
+        field_type: varint_field(&fields, 5).unwrap_or(0),
+        type_name: Some(type_name).filter(|t| !t.is_empty()),
+    })
+}
+
+/// Best-effort rendering of a field without a schema
+fn guess_value(value: &WireValue, depth: usize) -> Value {
+    match value {
+        WireValue::Varint(v) => Value::from(*v),
+        WireValue::Fixed64(v) => Value::from(*v),
+        WireValue::Fixed32(v) => Value::from(*v),
+        WireValue::LengthDelimited(bytes) => {
+            if let Ok(text) = std::str::from_utf8(bytes) {
+                if !text.chars().any(|c| c.is_control() && !c.is_whitespace()) {
+                    return Value::String(text.to_string());
+                }
+            }
+            if depth < MAX_GUESS_DEPTH {
+                if let Ok(fields) = parse_fields(bytes) {
+                    return Value::Array(describe_fields(&fields, depth + 1));
+                }
+            }
+            bytes_to_json(bytes)
+        }
+    }
+}
+
+fn describe_fields(fields: &[(u32, WireValue)], depth: usize) -> Vec<Value> {
+    fields.iter()
+        .map(|(number, value)| serde_json::json!({
+            "field": number,
+            "wireType": value.wire_type_name(),
+            "value": guess_value(value, depth),
+        }))
+        .collect()
+}
+
+/// Protobuf decoder
+pub struct ProtobufDecoder {
+    /// Descriptors and the fully qualified name of the payload message type
+    schema: Option<(DescriptorPool, String)>,
+}
+
+impl ProtobufDecoder {
+    /// Decoder without a schema, showing field numbers and wire types
+    pub fn new() -> Self {
+        Self { schema: None }
+    }
+
+    /// Decode payloads as `message_type` using a serialized FileDescriptorSet
+    pub fn with_descriptor_set(descriptor_set: &[u8], message_type: &str) -> Result<Self> {
+        let pool = DescriptorPool::from_descriptor_set(descriptor_set)
+            .map_err(|e| anyhow!("Invalid descriptor set: {}", e))?;
+        let type_name = pool.resolve(message_type)
+            .ok_or_else(|| anyhow!("Message type '{}' not found in descriptor set", message_type))?
+            .to_string();
+        Ok(Self { schema: Some((pool, type_name)) })
+    }
+
+    /// Load the descriptor set from a file. `.proto` sources must be compiled first.
+    pub fn from_file(path: &Path, message_type: &str) -> Result<Self> {
+        if path.extension().is_some_and(|ext| ext == "proto") {
+            return Err(anyhow!(
+                "{} is a .proto source; compile it with `protoc --include_imports --descriptor_set_out=<file>`",
+                path.display()
+            ));
+        }
+        let data = std::fs::read(path)
+            .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+        Self::with_descriptor_set(&data, message_type)
+    }
+
+    /// Build from a `<descriptor set path>#<message type>` schema string
+    pub fn from_schema_spec(spec: &str) -> Result<Self> {
+        let (path, message_type) = spec.rsplit_once('#')
+            .ok_or_else(|| anyhow!("Protobuf schema must be '<descriptor set path>#<message type>'"))?;
+        Self::from_file(Path::new(path), message_type)
+    }
+
+    /// Decode bytes to pretty-printed JSON
+    pub fn decode(&self, data: &[u8]) -> Result<String> {
+        let value = match self.schema {
+            Some((ref pool, ref type_name)) => pool.decode_message(type_name, data)?,
+            None => Value::Array(describe_fields(&parse_fields(data)?, 0)),
+        };
+        Ok(serde_json::to_string_pretty(&value)?)
+    }
+}
+
+impl Default for ProtobufDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn length_delimited(number: u32, bytes: &[u8]) -> Vec<u8> {
+        let mut out = vec![((number << 3) | WIRE_LENGTH_DELIMITED as u32) as u8];
+        let mut len = bytes.len();
+        while len >= 0x80 {
+            out.push((len as u8 & 0x7f) | 0x80);
+            len >>= 7;
+        }
+        out.push(len as u8);
+        out.extend_from_slice(bytes);
+        out
+    }
+
+    fn varint(number: u32, value: u8) -> Vec<u8> {
+        vec![((number << 3) | WIRE_VARINT as u32) as u8, value]
+    }
+
+    /// Descriptor set for:
+    /// `package shop; message Order { int32 id = 1; string item = 2; repeated sint32 deltas = 3; Status status = 4; }`
+    /// with `enum Status { NEW = 0; SHIPPED = 1; }` nested in Order
+    fn order_descriptor_set() -> Vec<u8> {
+        let field = |name: &str, number: u8, label: u8, field_type: u8, type_name: &str| {
+            let mut f = length_delimited(1, name.as_bytes());
+            f.extend(varint(3, number));
+            f.extend(varint(4, label));
+            f.extend(varint(5, field_type));
+            if !type_name.is_empty() {
+                f.extend(length_delimited(6, type_name.as_bytes()));
+            }
+            length_delimited(2, &f)
+        };
+        let enum_value = |name: &str, number: u8| {
+            let mut v = length_delimited(1, name.as_bytes());
+            v.extend(varint(2, number));
+            length_delimited(2, &v)
+        };
+
+        let mut status = length_delimited(1, b"Status");
+        status.extend(enum_value("NEW", 0));
+        status.extend(enum_value("SHIPPED", 1));
+
+        let mut order = length_delimited(1, b"Order");
+        order.extend(field("id", 1, 1, TYPE_INT32 as u8, ""));
+        order.extend(field("item", 2, 1, TYPE_STRING as u8, ""));
+        order.extend(field("deltas", 3, 3, TYPE_SINT32 as u8, ""));
+        order.extend(field("status", 4, 1, TYPE_ENUM as u8, ".shop.Order.Status"));
+        order.extend(length_delimited(4, &status));
+
+        let mut file = length_delimited(1, b"order.proto");
+        file.extend(length_delimited(2, b"shop"));
+        file.extend(length_delimited(4, &order));
+        length_delimited(1, &file)
+    }
+
+    /// Order { id: 150, item: "book", deltas: [1, -1] (packed), status: SHIPPED }
+    const ORDER: &[u8] = &[0x08, 0x96, 0x01, 0x12, 0x04, b'b', b'o', b'o', b'k', 0x1a, 0x02, 0x02, 0x01, 0x20, 0x01];
+
+    #[test]
+    fn test_decode_with_descriptor_set() {
+        let decoder = ProtobufDecoder::with_descriptor_set(&order_descriptor_set(), "Order").unwrap();
+        let value: Value = serde_json::from_str(&decoder.decode(ORDER).unwrap()).unwrap();
+        assert_eq!(value, serde_json::json!({
+            "id": 150,
+            "item": "book",
+            "deltas": [1, -1],
+            "status": "SHIPPED",
+        }));
+
+        assert!(ProtobufDecoder::with_descriptor_set(&order_descriptor_set(), "shop.Invoice").is_err());
+    }
+
+    #[test]
+    fn test_decode_without_schema() {
+        let decoder = ProtobufDecoder::new();
+        let value: Value = serde_json::from_str(&decoder.decode(ORDER).unwrap()).unwrap();
+        assert_eq!(value[0], serde_json::json!({"field": 1, "wireType": "varint", "value": 150}));
+        assert_eq!(value[1]["wireType"], "length-delimited");
+        assert_eq!(value[1]["value"], "book");
+        assert_eq!(value[3]["field"], 4);
+
+        assert!(decoder.decode(&[0x08, 0x96]).is_err());
+    }
+
+    #[test]
+    fn test_proto_source_rejected() {
+        assert!(ProtobufDecoder::from_schema_spec("schemas/order.proto#shop.Order").is_err());
+        assert!(ProtobufDecoder::from_schema_spec("schemas/order.desc").is_err());
+    }
+}
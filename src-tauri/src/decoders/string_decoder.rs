@@ -11,7 +11,11 @@ pub enum Encoding {
     Utf8,
     Utf16Le,
     Utf16Be,
+    Utf32Le,
+    Utf32Be,
     Gbk,
+    /// Superset of GBK, covering all of Unicode with four-byte sequences
+    Gb18030,
     Iso88591,
 }
 
@@ -57,10 +61,10 @@ impl StringDecoder {
                 }
                 Ok(String::from_utf16_lossy(&u16_data).to_string())
             }
-            Encoding::Gbk => {
-                // GBK encoding - would need encoding_rs, fallback to lossy UTF-8
-                Ok(format!("<GBK encoding not yet implemented, {} bytes>", data.len()))
-            }
+            Encoding::Utf32Le => Ok(decode_utf32(data, u32::from_le_bytes)),
+            Encoding::Utf32Be => Ok(decode_utf32(data, u32::from_be_bytes)),
+            Encoding::Gbk => Ok(decode_with(encoding_rs::GBK, data)),
+            Encoding::Gb18030 => Ok(decode_with(encoding_rs::GB18030, data)),
             Encoding::Iso88591 => {
                 // ISO-8859-1 is a single-byte encoding, 1:1 mapping to Unicode codepoints
                 let decoded: String = data.iter().map(|&b| b as char).collect();
@@ -69,8 +73,30 @@ impl StringDecoder {
         }
     }
 
-    /// Try to detect encoding and decode
+    /// Detect a byte order mark, returning its encoding and length
+    pub fn detect_bom(data: &[u8]) -> Option<(Encoding, usize)> {
+        // UTF-32 LE must be checked before UTF-16 LE, whose BOM it starts with
+        if data.starts_with(&[0xFF, 0xFE, 0x00, 0x00]) {
+            Some((Encoding::Utf32Le, 4))
+        } else if data.starts_with(&[0x00, 0x00, 0xFE, 0xFF]) {
+            Some((Encoding::Utf32Be, 4))
+        } else if data.starts_with(&[0xEF, 0xBB, 0xBF]) {
+            Some((Encoding::Utf8, 3))
+        } else if data.starts_with(&[0xFF, 0xFE]) {
+            Some((Encoding::Utf16Le, 2))
+        } else if data.starts_with(&[0xFE, 0xFF]) {
+            Some((Encoding::Utf16Be, 2))
+        } else {
+            None
+        }
+    }
+
+    /// Try to detect encoding and decode, honouring and stripping any BOM
     pub fn decode_auto(&self, data: &[u8]) -> Result<String> {
+        if let Some((encoding, bom_len)) = Self::detect_bom(data) {
+            return Self::with_encoding(encoding).decode(&data[bom_len..]);
+        }
+
         // Try UTF-8 first
         if std::str::from_utf8(data).is_ok() {
             return Ok(String::from_utf8_lossy(data).to_string());
@@ -103,6 +129,26 @@ impl StringDecoder {
     }
 }
 
+/// Decode UTF-32 code units, replacing invalid code points with U+FFFD
+fn decode_utf32(data: &[u8], read: fn([u8; 4]) -> u32) -> String {
+    data.chunks_exact(4)
+        .map(|chunk| {
+            char::from_u32(read([chunk[0], chunk[1], chunk[2], chunk[3]]))
+                .unwrap_or(char::REPLACEMENT_CHARACTER)
+        })
+        .collect()
+}
+
+/// Decode with an `encoding_rs` codec, noting when unmappable bytes were replaced
+fn decode_with(encoding: &'static encoding_rs::Encoding, data: &[u8]) -> String {
+    let (text, had_errors) = encoding.decode_without_bom_handling(data);
+    if had_errors {
+        format!("{} <invalid {} sequences replaced with U+FFFD>", text, encoding.name())
+    } else {
+        text.into_owned()
+    }
+}
+
 impl Default for StringDecoder {
     fn default() -> Self {
         Self::new()
@@ -137,4 +183,51 @@ mod tests {
         assert!(result.is_ok());
         assert!(result.unwrap().contains("�"));
     }
+
+    #[test]
+    fn test_auto_decode_bom() {
+        let decoder = StringDecoder::new();
+        let cases: [&[u8]; 5] = [
+            &[0xEF, 0xBB, 0xBF, b'h', b'i'],
+            &[0xFF, 0xFE, b'h', 0x00, b'i', 0x00],
+            &[0xFE, 0xFF, 0x00, b'h', 0x00, b'i'],
+            &[0xFF, 0xFE, 0x00, 0x00, b'h', 0x00, 0x00, 0x00, b'i', 0x00, 0x00, 0x00],
+            &[0x00, 0x00, 0xFE, 0xFF, 0x00, 0x00, 0x00, b'h', 0x00, 0x00, 0x00, b'i'],
+        ];
+        for data in cases {
+            assert_eq!(decoder.decode_auto(data).unwrap(), "hi", "{:02X?}", data);
+        }
+    }
+
+    #[test]
+    fn test_utf32_decode() {
+        let decoder = StringDecoder::with_encoding(Encoding::Utf32Be);
+        assert_eq!(decoder.decode(&[0x00, 0x01, 0xF6, 0x00]).unwrap(), "\u{1F600}");
+        let decoder = StringDecoder::with_encoding(Encoding::Utf32Le);
+        assert_eq!(decoder.decode(&[0x00, 0xD8, 0x00, 0x00]).unwrap(), "\u{FFFD}");
+    }
+
+    #[test]
+    fn test_gbk_decode() {
+        let decoder = StringDecoder::with_encoding(Encoding::Gbk);
+        // "你好, 中文" in GBK
+        let data = &[0xC4, 0xE3, 0xBA, 0xC3, b',', b' ', 0xD6, 0xD0, 0xCE, 0xC4];
+        assert_eq!(decoder.decode(data).unwrap(), "你好, 中文");
+    }
+
+    #[test]
+    fn test_gbk_invalid_sequence() {
+        let decoder = StringDecoder::with_encoding(Encoding::Gbk);
+        let result = decoder.decode(&[0xD6, 0xD0, 0xFF]).unwrap();
+        assert!(result.starts_with("中\u{FFFD}"), "{}", result);
+        assert!(result.contains("invalid GBK sequences"));
+    }
+
+    #[test]
+    fn test_gb18030_four_byte_decode() {
+        let decoder = StringDecoder::with_encoding(Encoding::Gb18030);
+        // GB18030 four-byte sequence for U+0080, followed by "中"
+        let data = &[0x81, 0x30, 0x81, 0x30, 0xD6, 0xD0];
+        assert_eq!(decoder.decode(data).unwrap(), "\u{80}中");
+    }
 }
@@ -1,8 +1,13 @@
 // Copyright 2024 Offset Explorer Rust Team
 // Licensed under Proprietary License
 
+pub mod acl_admin;
+pub mod config_admin;
 pub mod mapper;
+mod native_admin;
 pub mod client;
+pub mod oauth;
+pub mod size_stats;
 
 pub use mapper::KafkaMapper;
 pub use client::KafkaClient;
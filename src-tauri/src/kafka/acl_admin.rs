@@ -0,0 +1,523 @@
+// Copyright 2024 Offset Explorer Rust Team
+// Licensed under Proprietary License
+//
+// ACL management through the librdkafka Admin API.
+// rdkafka's AdminClient has no ACL support, so the C API is called directly
+// through the helpers in `native_admin`.
+
+use anyhow::{Result, anyhow};
+use rdkafka::bindings as rdsys;
+use rdkafka::bindings::{
+    rd_kafka_AclOperation_t as RDAclOperation, rd_kafka_AclPermissionType_t as RDAclPermission,
+    rd_kafka_ResourcePatternType_t as RDPatternType, rd_kafka_ResourceType_t as RDResourceType,
+};
+use std::ffi::CString;
+use std::os::raw::c_char;
+use std::ptr;
+use std::time::Duration;
+use crate::acls::{AclBinding, AclFilter, AclOperation, AclPermission, CreateAclRequest};
+use crate::kafka::native_admin::{c_string, run_admin_op};
+
+/// ACL binding or binding filter expressed in librdkafka wire values.
+///
+/// In a filter, `None` strings and the `ANY` enum values match anything.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AclSpec {
+    pub resource_type: RDResourceType,
+    pub resource_name: Option<String>,
+    pub pattern_type: RDPatternType,
+    pub principal: Option<String>,
+    pub host: Option<String>,
+    pub operation: RDAclOperation,
+    pub permission_type: RDAclPermission,
+}
+
+impl AclSpec {
+    /// Filter matching every ACL
+    pub fn match_all() -> Self {
+        Self {
+            resource_type: RDResourceType::RD_KAFKA_RESOURCE_ANY,
+            resource_name: None,
+            pattern_type: RDPatternType::RD_KAFKA_RESOURCE_PATTERN_ANY,
+            principal: None,
+            host: None,
+            operation: RDAclOperation::RD_KAFKA_ACL_OPERATION_ANY,
+            permission_type: RDAclPermission::RD_KAFKA_ACL_PERMISSION_TYPE_ANY,
+        }
+    }
+
+    /// Resource pattern and entry filter for a user-supplied `AclFilter`
+    pub fn from_filter(filter: &AclFilter) -> Result<Self> {
+        let mut spec = Self::match_all();
+        if let Some(ref resource_type) = filter.resource_type {
+            spec.resource_type = resource_type_from_name(resource_type)?;
+        }
+        if let Some(ref operation) = filter.operation {
+            spec.operation = operation_from_name(operation)?;
+        }
+        if let Some(ref permission_type) = filter.permission_type {
+            spec.permission_type = permission_from_name(permission_type)?;
+        }
+        spec.resource_name = filter.resource_name.clone().filter(|n| !n.is_empty());
+        spec.principal = filter.principal.clone().filter(|p| !p.is_empty());
+        spec.host = filter.host.clone().filter(|h| !h.is_empty());
+        Ok(spec)
+    }
+
+    /// Binding to create for a validated `CreateAclRequest`
+    pub fn from_request(request: &CreateAclRequest) -> Result<Self> {
+        request.validate()?;
+
+        let resource_name = if request.wildcard == Some(true) {
+            "*".to_string()
+        } else {
+            request.resource_name.clone()
+        };
+
+        Ok(Self {
+            resource_type: resource_type_from_name(&request.resource_type)?,
+            resource_name: Some(resource_name),
+            pattern_type: pattern_type_from_name(request.pattern_type.as_deref())?,
+            principal: Some(request.principal.clone()),
+            host: Some(request.host.clone().filter(|h| !h.is_empty()).unwrap_or_else(|| "*".to_string())),
+            operation: operation_from_name(&request.operation)?,
+            permission_type: permission_from_name(&request.permission_type)?,
+        })
+    }
+
+    /// Filter selecting one ACL entry for deletion. The pattern type is the binding's own
+    /// (Literal when unset), so a prefixed ACL does not take a literal one with it.
+    /// An empty host matches every host.
+    pub fn for_deletion(
+        principal: &str,
+        resource_type: &str,
+        resource_name: &str,
+        operation: &str,
+        permission_type: &str,
+        host: &str,
+        pattern_type: Option<&str>,
+    ) -> Result<Self> {
+        if principal.is_empty() {
+            return Err(anyhow!("Principal cannot be empty"));
+        }
+        if resource_name.is_empty() {
+            return Err(anyhow!("Resource name cannot be empty"));
+        }
+
+        Ok(Self {
+            resource_type: resource_type_from_name(resource_type)?,
+            resource_name: Some(resource_name.to_string()),
+            pattern_type: pattern_type_from_name(pattern_type)?,
+            principal: Some(principal.to_string()),
+            host: Some(host.to_string()).filter(|h| !h.is_empty()),
+            operation: operation_from_name(operation)?,
+            permission_type: permission_from_name(permission_type)?,
+        })
+    }
+
+    /// Allocate the librdkafka binding; the caller owns the returned pointer
+    fn to_native_binding(&self) -> Result<*mut rdsys::rd_kafka_AclBinding_t> {
+        self.to_native(rdsys::rd_kafka_AclBinding_new)
+    }
+
+    /// Allocate the librdkafka filter; the caller owns the returned pointer
+    fn to_native_filter(&self) -> Result<*mut rdsys::rd_kafka_AclBindingFilter_t> {
+        self.to_native(rdsys::rd_kafka_AclBindingFilter_new)
+    }
+
+    fn to_native(
+        &self,
+        new: unsafe extern "C" fn(
+            RDResourceType,
+            *const c_char,
+            RDPatternType,
+            *const c_char,
+            *const c_char,
+            RDAclOperation,
+            RDAclPermission,
+            *mut c_char,
+            usize,
+        ) -> *mut rdsys::rd_kafka_AclBinding_t,
+    ) -> Result<*mut rdsys::rd_kafka_AclBinding_t> {
+        let resource_name = optional_cstring(self.resource_name.as_deref())?;
+        let principal = optional_cstring(self.principal.as_deref())?;
+        let host = optional_cstring(self.host.as_deref())?;
+        let mut err_buf = [0 as c_char; 512];
+
+        let binding = unsafe {
+            new(
+                self.resource_type,
+                resource_name.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
+                self.pattern_type,
+                principal.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
+                host.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
+                self.operation,
+                self.permission_type,
+                err_buf.as_mut_ptr(),
+                err_buf.len(),
+            )
+        };
+        if binding.is_null() {
+            return Err(anyhow!("Invalid ACL: {}", unsafe { c_string(err_buf.as_ptr()) }));
+        }
+        Ok(binding)
+    }
+}
+
+/// Map a resource type name ("Topic", "TOPIC", ...) to its wire value
+pub fn resource_type_from_name(name: &str) -> Result<RDResourceType> {
+    match normalize(name).as_str() {
+        "any" => Ok(RDResourceType::RD_KAFKA_RESOURCE_ANY),
+        "topic" => Ok(RDResourceType::RD_KAFKA_RESOURCE_TOPIC),
+        "group" => Ok(RDResourceType::RD_KAFKA_RESOURCE_GROUP),
+        "cluster" | "broker" => Ok(RDResourceType::RD_KAFKA_RESOURCE_BROKER),
+        "transactionalid" => Ok(RDResourceType::RD_KAFKA_RESOURCE_TRANSACTIONAL_ID),
+        "delegationtoken" => Err(anyhow!("DelegationToken ACLs are not supported by librdkafka")),
+        _ => Err(anyhow!("Invalid resource type: {}", name)),
+    }
+}
+
+/// Resource type name as used by `AclBinding` and `CreateAclRequest`
+pub fn resource_type_name(resource_type: RDResourceType) -> &'static str {
+    match resource_type {
+        RDResourceType::RD_KAFKA_RESOURCE_ANY => "Any",
+        RDResourceType::RD_KAFKA_RESOURCE_TOPIC => "Topic",
+        RDResourceType::RD_KAFKA_RESOURCE_GROUP => "Group",
+        RDResourceType::RD_KAFKA_RESOURCE_BROKER => "Cluster",
+        RDResourceType::RD_KAFKA_RESOURCE_TRANSACTIONAL_ID => "TransactionalId",
+        _ => "Unknown",
+    }
+}
+
+/// Map a pattern type name ("Literal", "PREFIXED", ...) to its wire value; Literal when unset
+pub fn pattern_type_from_name(name: Option<&str>) -> Result<RDPatternType> {
+    match name.map(normalize).as_deref() {
+        None | Some("") | Some("literal") => Ok(RDPatternType::RD_KAFKA_RESOURCE_PATTERN_LITERAL),
+        Some("prefixed") => Ok(RDPatternType::RD_KAFKA_RESOURCE_PATTERN_PREFIXED),
+        _ => Err(anyhow!("Invalid pattern type: {}", name.unwrap_or_default())),
+    }
+}
+
+/// Pattern type name as used by `AclBinding` and `CreateAclRequest`
+pub fn pattern_type_name(pattern_type: RDPatternType) -> &'static str {
+    match pattern_type {
+        RDPatternType::RD_KAFKA_RESOURCE_PATTERN_LITERAL => "Literal",
+        RDPatternType::RD_KAFKA_RESOURCE_PATTERN_PREFIXED => "Prefixed",
+        RDPatternType::RD_KAFKA_RESOURCE_PATTERN_MATCH => "Match",
+        RDPatternType::RD_KAFKA_RESOURCE_PATTERN_ANY => "Any",
+        _ => "Unknown",
+    }
+}
+
+/// Map an operation name ("Read", "CLUSTER_ACTION", ...) to its wire value
+pub fn operation_from_name(name: &str) -> Result<RDAclOperation> {
+    match normalize(name).as_str() {
+        "any" => Ok(RDAclOperation::RD_KAFKA_ACL_OPERATION_ANY),
+        "all" => Ok(RDAclOperation::RD_KAFKA_ACL_OPERATION_ALL),
+        "read" => Ok(RDAclOperation::RD_KAFKA_ACL_OPERATION_READ),
+        "write" => Ok(RDAclOperation::RD_KAFKA_ACL_OPERATION_WRITE),
+        "create" => Ok(RDAclOperation::RD_KAFKA_ACL_OPERATION_CREATE),
+        "delete" => Ok(RDAclOperation::RD_KAFKA_ACL_OPERATION_DELETE),
+        "alter" => Ok(RDAclOperation::RD_KAFKA_ACL_OPERATION_ALTER),
+        "describe" => Ok(RDAclOperation::RD_KAFKA_ACL_OPERATION_DESCRIBE),
+        "clusteraction" => Ok(RDAclOperation::RD_KAFKA_ACL_OPERATION_CLUSTER_ACTION),
+        "describeconfigs" => Ok(RDAclOperation::RD_KAFKA_ACL_OPERATION_DESCRIBE_CONFIGS),
+        "alterconfigs" => Ok(RDAclOperation::RD_KAFKA_ACL_OPERATION_ALTER_CONFIGS),
+        "idempotentwrite" => Ok(RDAclOperation::RD_KAFKA_ACL_OPERATION_IDEMPOTENT_WRITE),
+        _ => Err(anyhow!("Invalid operation: {}", name)),
+    }
+}
+
+fn operation_from_native(operation: RDAclOperation) -> Option<AclOperation> {
+    match operation {
+        RDAclOperation::RD_KAFKA_ACL_OPERATION_ALL => Some(AclOperation::All),
+        RDAclOperation::RD_KAFKA_ACL_OPERATION_READ => Some(AclOperation::Read),
+        RDAclOperation::RD_KAFKA_ACL_OPERATION_WRITE => Some(AclOperation::Write),
+        RDAclOperation::RD_KAFKA_ACL_OPERATION_CREATE => Some(AclOperation::Create),
+        RDAclOperation::RD_KAFKA_ACL_OPERATION_DELETE => Some(AclOperation::Delete),
+        RDAclOperation::RD_KAFKA_ACL_OPERATION_ALTER => Some(AclOperation::Alter),
+        RDAclOperation::RD_KAFKA_ACL_OPERATION_DESCRIBE => Some(AclOperation::Describe),
+        RDAclOperation::RD_KAFKA_ACL_OPERATION_CLUSTER_ACTION => Some(AclOperation::ClusterAction),
+        RDAclOperation::RD_KAFKA_ACL_OPERATION_DESCRIBE_CONFIGS => Some(AclOperation::DescribeConfigs),
+        RDAclOperation::RD_KAFKA_ACL_OPERATION_ALTER_CONFIGS => Some(AclOperation::AlterConfigs),
+        RDAclOperation::RD_KAFKA_ACL_OPERATION_IDEMPOTENT_WRITE => Some(AclOperation::IdempotentWrite),
+        _ => None,
+    }
+}
+
+/// Map a permission type name ("Allow", "DENY", ...) to its wire value
+pub fn permission_from_name(name: &str) -> Result<RDAclPermission> {
+    match normalize(name).as_str() {
+        "any" => Ok(RDAclPermission::RD_KAFKA_ACL_PERMISSION_TYPE_ANY),
+        "allow" => Ok(RDAclPermission::RD_KAFKA_ACL_PERMISSION_TYPE_ALLOW),
+        "deny" => Ok(RDAclPermission::RD_KAFKA_ACL_PERMISSION_TYPE_DENY),
+        _ => Err(anyhow!("Invalid permission type: {}", name)),
+    }
+}
+
+fn permission_from_native(permission: RDAclPermission) -> Option<AclPermission> {
+    match permission {
+        RDAclPermission::RD_KAFKA_ACL_PERMISSION_TYPE_ALLOW => Some(AclPermission::Allow),
+        RDAclPermission::RD_KAFKA_ACL_PERMISSION_TYPE_DENY => Some(AclPermission::Deny),
+        _ => None,
+    }
+}
+
+/// Lower-case a name and drop separators so "ClusterAction" matches "CLUSTER_ACTION"
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+/// Describe the ACLs matching `filter`
+pub fn describe_acls(
+    client: *mut rdsys::rd_kafka_t,
+    filter: &AclSpec,
+    timeout: Duration,
+) -> Result<Vec<AclBinding>> {
+    let native_filter = NativeBindings(vec![filter.to_native_filter()?]);
+
+    let event = run_admin_op(client, rdsys::rd_kafka_admin_op_t::RD_KAFKA_ADMIN_OP_DESCRIBEACLS, timeout, |options, queue| unsafe {
+        rdsys::rd_kafka_DescribeAcls(client, native_filter.0[0], options, queue)
+    })?;
+
+    let mut count = 0;
+    let bindings = unsafe {
+        let result = rdsys::rd_kafka_event_DescribeAcls_result(event.0);
+        if result.is_null() {
+            return Err(anyhow!("Unexpected admin result for DescribeAcls"));
+        }
+        rdsys::rd_kafka_DescribeAcls_result_acls(result, &mut count)
+    };
+
+    Ok((0..count)
+        .filter_map(|i| unsafe { binding_from_native(*bindings.add(i)) })
+        .collect())
+}
+
+/// Create ACL bindings, failing with the broker's reason for the first rejected one
+pub fn create_acls(client: *mut rdsys::rd_kafka_t, acls: &[AclSpec], timeout: Duration) -> Result<()> {
+    let mut native = NativeBindings(Vec::with_capacity(acls.len()));
+    for acl in acls {
+        native.0.push(acl.to_native_binding()?);
+    }
+
+    let event = run_admin_op(client, rdsys::rd_kafka_admin_op_t::RD_KAFKA_ADMIN_OP_CREATEACLS, timeout, |options, queue| unsafe {
+        rdsys::rd_kafka_CreateAcls(client, native.0.as_mut_ptr(), native.0.len(), options, queue)
+    })?;
+
+    let mut count = 0;
+    let results = unsafe {
+        let result = rdsys::rd_kafka_event_CreateAcls_result(event.0);
+        if result.is_null() {
+            return Err(anyhow!("Unexpected admin result for CreateAcls"));
+        }
+        rdsys::rd_kafka_CreateAcls_result_acls(result, &mut count)
+    };
+
+    for i in 0..count {
+        let error = unsafe { rdsys::rd_kafka_acl_result_error(*results.add(i)) };
+        if !error.is_null() {
+            return Err(anyhow!("Failed to create ACL: {}", unsafe {
+                c_string(rdsys::rd_kafka_error_string(error))
+            }));
+        }
+    }
+    Ok(())
+}
+
+/// Delete the ACLs matching each filter, returning how many were deleted
+pub fn delete_acls(client: *mut rdsys::rd_kafka_t, filters: &[AclSpec], timeout: Duration) -> Result<usize> {
+    let mut native = NativeBindings(Vec::with_capacity(filters.len()));
+    for filter in filters {
+        native.0.push(filter.to_native_filter()?);
+    }
+
+    let event = run_admin_op(client, rdsys::rd_kafka_admin_op_t::RD_KAFKA_ADMIN_OP_DELETEACLS, timeout, |options, queue| unsafe {
+        rdsys::rd_kafka_DeleteAcls(client, native.0.as_mut_ptr(), native.0.len(), options, queue)
+    })?;
+
+    let mut count = 0;
+    let responses = unsafe {
+        let result = rdsys::rd_kafka_event_DeleteAcls_result(event.0);
+        if result.is_null() {
+            return Err(anyhow!("Unexpected admin result for DeleteAcls"));
+        }
+        rdsys::rd_kafka_DeleteAcls_result_responses(result, &mut count)
+    };
+
+    let mut deleted = 0;
+    for i in 0..count {
+        let response = unsafe { *responses.add(i) };
+        let error = unsafe { rdsys::rd_kafka_DeleteAcls_result_response_error(response) };
+        if !error.is_null() {
+            return Err(anyhow!("Failed to delete ACL: {}", unsafe {
+                c_string(rdsys::rd_kafka_error_string(error))
+            }));
+        }
+        let mut matching = 0;
+        unsafe { rdsys::rd_kafka_DeleteAcls_result_response_matching_acls(response, &mut matching) };
+        deleted += matching;
+    }
+    Ok(deleted)
+}
+
+/// Convert a binding owned by librdkafka, skipping operations this app does not model
+unsafe fn binding_from_native(acl: *const rdsys::rd_kafka_AclBinding_t) -> Option<AclBinding> {
+    let resource_name = c_string(rdsys::rd_kafka_AclBinding_name(acl));
+    Some(AclBinding {
+        principal: c_string(rdsys::rd_kafka_AclBinding_principal(acl)),
+        resource_type: resource_type_name(rdsys::rd_kafka_AclBinding_restype(acl)).to_string(),
+        wildcard: resource_name == "*",
+        resource_name,
+        operation: operation_from_native(rdsys::rd_kafka_AclBinding_operation(acl))?,
+        permission_type: permission_from_native(rdsys::rd_kafka_AclBinding_permission_type(acl))?,
+        host: c_string(rdsys::rd_kafka_AclBinding_host(acl)),
+        pattern_type: pattern_type_name(rdsys::rd_kafka_AclBinding_resource_pattern_type(acl)).to_string(),
+    })
+}
+
+fn optional_cstring(value: Option<&str>) -> Result<Option<CString>> {
+    value
+        .map(|v| CString::new(v).map_err(|e| anyhow!("Invalid ACL field '{}': {}", v, e)))
+        .transpose()
+}
+
+/// Bindings or binding filters allocated by us; librdkafka copies them on submit
+struct NativeBindings(Vec<*mut rdsys::rd_kafka_AclBinding_t>);
+
+impl Drop for NativeBindings {
+    fn drop(&mut self) {
+        for binding in &self.0 {
+            unsafe { rdsys::rd_kafka_AclBinding_destroy(*binding) }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_to_spec() {
+        assert_eq!(AclSpec::from_filter(&AclFilter {
+            resource_type: None,
+            resource_name: None,
+            principal: None,
+            operation: None,
+            permission_type: None,
+            host: None,
+        }).unwrap(), AclSpec::match_all());
+
+        let spec = AclSpec::from_filter(&AclFilter {
+            resource_type: Some("Topic".to_string()),
+            resource_name: Some("orders".to_string()),
+            principal: Some("User:alice".to_string()),
+            operation: Some("CLUSTER_ACTION".to_string()),
+            permission_type: Some("deny".to_string()),
+            host: Some(String::new()),
+        }).unwrap();
+        assert_eq!(spec.resource_type, RDResourceType::RD_KAFKA_RESOURCE_TOPIC);
+        assert_eq!(spec.resource_name.as_deref(), Some("orders"));
+        assert_eq!(spec.pattern_type, RDPatternType::RD_KAFKA_RESOURCE_PATTERN_ANY);
+        assert_eq!(spec.principal.as_deref(), Some("User:alice"));
+        assert_eq!(spec.host, None);
+        assert_eq!(spec.operation, RDAclOperation::RD_KAFKA_ACL_OPERATION_CLUSTER_ACTION);
+        assert_eq!(spec.permission_type, RDAclPermission::RD_KAFKA_ACL_PERMISSION_TYPE_DENY);
+
+        let invalid = AclFilter {
+            resource_type: Some("Queue".to_string()),
+            resource_name: None,
+            principal: None,
+            operation: None,
+            permission_type: None,
+            host: None,
+        };
+        assert!(AclSpec::from_filter(&invalid).is_err());
+    }
+
+    fn create_request(resource_type: &str) -> CreateAclRequest {
+        CreateAclRequest {
+            principal: "User:alice".to_string(),
+            resource_type: resource_type.to_string(),
+            resource_name: "orders".to_string(),
+            operation: "Write".to_string(),
+            permission_type: "Allow".to_string(),
+            host: None,
+            wildcard: None,
+            pattern_type: Some("Prefixed".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_request_to_spec() {
+        let spec = AclSpec::from_request(&create_request("Cluster")).unwrap();
+        assert_eq!(spec.resource_type, RDResourceType::RD_KAFKA_RESOURCE_BROKER);
+        assert_eq!(spec.resource_type as u32, 4);
+        assert_eq!(spec.pattern_type, RDPatternType::RD_KAFKA_RESOURCE_PATTERN_PREFIXED);
+        assert_eq!(spec.host.as_deref(), Some("*"));
+        assert_eq!(spec.operation, RDAclOperation::RD_KAFKA_ACL_OPERATION_WRITE);
+
+        assert_eq!(AclSpec::from_request(&create_request("Topic")).unwrap().resource_type as u32, 2);
+        assert_eq!(AclSpec::from_request(&create_request("Group")).unwrap().resource_type as u32, 3);
+        assert_eq!(AclSpec::from_request(&create_request("TransactionalId")).unwrap().resource_type as u32, 5);
+
+        let mut wildcard = create_request("Topic");
+        wildcard.wildcard = Some(true);
+        assert_eq!(AclSpec::from_request(&wildcard).unwrap().resource_name.as_deref(), Some("*"));
+
+        assert!(AclSpec::from_request(&create_request("Queue")).is_err());
+        assert!(AclSpec::from_request(&create_request("DelegationToken")).is_err());
+        let mut no_principal = create_request("Topic");
+        no_principal.principal.clear();
+        assert!(AclSpec::from_request(&no_principal).is_err());
+    }
+
+    #[test]
+    fn test_deletion_filter_wire_types() {
+        let spec = AclSpec::for_deletion("User:alice", "TransactionalId", "tx-1", "IdempotentWrite", "Deny", "", None).unwrap();
+        assert_eq!(spec.resource_type as u32, 5);
+        assert_eq!(spec.pattern_type, RDPatternType::RD_KAFKA_RESOURCE_PATTERN_LITERAL);
+        assert_eq!(spec.operation as u32, 12);
+        assert_eq!(spec.permission_type, RDAclPermission::RD_KAFKA_ACL_PERMISSION_TYPE_DENY);
+        assert_eq!(spec.host, None);
+
+        let spec = AclSpec::for_deletion("User:alice", "Topic", "orders", "READ", "ALLOW", "10.0.0.1", Some("Prefixed")).unwrap();
+        assert_eq!(spec.resource_type as u32, 2);
+        assert_eq!(spec.pattern_type, RDPatternType::RD_KAFKA_RESOURCE_PATTERN_PREFIXED);
+        assert_eq!(spec.operation as u32, 3);
+        assert_eq!(spec.permission_type as u32, 3);
+        assert_eq!(spec.host.as_deref(), Some("10.0.0.1"));
+
+        assert!(AclSpec::for_deletion("User:alice", "Topic", "orders", "Steal", "Allow", "*", None).is_err());
+        assert!(AclSpec::for_deletion("User:alice", "Topic", "orders", "Read", "Maybe", "*", None).is_err());
+        assert!(AclSpec::for_deletion("User:alice", "Topic", "orders", "Read", "Allow", "*", Some("Any")).is_err());
+        assert!(AclSpec::for_deletion("", "Topic", "orders", "Read", "Allow", "*", None).is_err());
+    }
+
+    #[test]
+    fn test_native_binding_conversion() {
+        let spec = AclSpec {
+            resource_type: RDResourceType::RD_KAFKA_RESOURCE_GROUP,
+            resource_name: Some("*".to_string()),
+            pattern_type: RDPatternType::RD_KAFKA_RESOURCE_PATTERN_PREFIXED,
+            principal: Some("User:bob".to_string()),
+            host: Some("10.0.0.1".to_string()),
+            operation: RDAclOperation::RD_KAFKA_ACL_OPERATION_READ,
+            permission_type: RDAclPermission::RD_KAFKA_ACL_PERMISSION_TYPE_ALLOW,
+        };
+        let native = NativeBindings(vec![spec.to_native_binding().unwrap()]);
+
+        let binding = unsafe { binding_from_native(native.0[0]) }.unwrap();
+        assert_eq!(binding.principal, "User:bob");
+        assert_eq!(binding.resource_type, "Group");
+        assert_eq!(binding.resource_name, "*");
+        assert!(binding.wildcard);
+        assert_eq!(binding.operation, AclOperation::Read);
+        assert_eq!(binding.permission_type, AclPermission::Allow);
+        assert_eq!(binding.host, "10.0.0.1");
+        assert_eq!(binding.pattern_type, "Prefixed");
+    }
+}
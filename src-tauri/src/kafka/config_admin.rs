@@ -0,0 +1,416 @@
+// Copyright 2024 Offset Explorer Rust Team
+// Licensed under Proprietary License
+//
+// Topic config changes through the librdkafka IncrementalAlterConfigs API.
+// rdkafka's AdminClient only wraps the legacy AlterConfigs request.
+
+use anyhow::{Result, anyhow};
+use rdkafka::bindings as rdsys;
+use rdkafka::bindings::rd_kafka_AlterConfigOpType_t as RDAlterOp;
+use std::collections::{HashMap, HashSet};
+use std::ffi::CString;
+use std::ptr;
+use std::time::Duration;
+use crate::kafka::native_admin::{c_string, run_admin_op};
+
+/// Operation applied to one config entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigAlterOp {
+    /// Set the value, overriding the default
+    Set,
+    /// Remove the override, reverting to the default
+    Delete,
+    /// Add an item to a list config
+    Append,
+    /// Remove an item from a list config
+    Subtract,
+}
+
+impl ConfigAlterOp {
+    /// Parse an operation name ("SET", "delete", ...)
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name.trim().to_ascii_uppercase().as_str() {
+            "SET" => Ok(Self::Set),
+            "DELETE" => Ok(Self::Delete),
+            "APPEND" => Ok(Self::Append),
+            "SUBTRACT" => Ok(Self::Subtract),
+            _ => Err(anyhow!("Invalid config operation: {}", name)),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Set => "SET",
+            Self::Delete => "DELETE",
+            Self::Append => "APPEND",
+            Self::Subtract => "SUBTRACT",
+        }
+    }
+
+    fn to_native(self) -> RDAlterOp {
+        match self {
+            Self::Set => RDAlterOp::RD_KAFKA_ALTER_CONFIG_OP_TYPE_SET,
+            Self::Delete => RDAlterOp::RD_KAFKA_ALTER_CONFIG_OP_TYPE_DELETE,
+            Self::Append => RDAlterOp::RD_KAFKA_ALTER_CONFIG_OP_TYPE_APPEND,
+            Self::Subtract => RDAlterOp::RD_KAFKA_ALTER_CONFIG_OP_TYPE_SUBTRACT,
+        }
+    }
+}
+
+/// A validated change to one config entry
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigAlteration {
+    pub name: String,
+    pub op: ConfigAlterOp,
+    /// Always present except for `Delete`
+    pub value: Option<String>,
+}
+
+impl ConfigAlteration {
+    /// Validate a change as requested by the user
+    pub fn parse(name: &str, operation: &str, value: Option<&str>) -> Result<Self> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(anyhow!("Config name cannot be empty"));
+        }
+        if name.contains('\0') {
+            return Err(anyhow!("Config name contains a NUL character"));
+        }
+        let op = ConfigAlterOp::from_name(operation)?;
+        let value = match (op, value) {
+            (ConfigAlterOp::Delete, _) => None,
+            (_, Some(value)) if value.contains('\0') => {
+                return Err(anyhow!("Value of {} contains a NUL character", name));
+            }
+            (ConfigAlterOp::Set, Some(value)) => Some(value.to_string()),
+            (_, Some(value)) if value.trim().is_empty() => {
+                return Err(anyhow!("{} requires a list item to {}", name, op.as_str().to_lowercase()));
+            }
+            (_, Some(value)) => Some(value.trim().to_string()),
+            (_, None) => return Err(anyhow!("{} of {} requires a value", op.as_str(), name)),
+        };
+        Ok(Self { name: name.to_string(), op, value })
+    }
+
+    /// Outcome of this change; `error` is `None` when it was applied
+    pub fn result(&self, error: Option<String>) -> ConfigAlterResult {
+        ConfigAlterResult {
+            name: self.name.clone(),
+            operation: self.op.as_str().to_string(),
+            error,
+        }
+    }
+}
+
+/// Outcome of one requested config change
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigAlterResult {
+    pub name: String,
+    pub operation: String,
+    /// Why the change was rejected, `None` when it was applied
+    pub error: Option<String>,
+}
+
+impl ConfigAlterResult {
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Validate `(name, operation, value)` requests, returning the valid changes and a failed
+/// result for every rejected one. A name requested more than once is rejected everywhere
+/// since the intended order is ambiguous.
+pub fn validate_alterations<'a>(
+    requests: impl IntoIterator<Item = (&'a str, &'a str, Option<&'a str>)>,
+) -> (Vec<ConfigAlteration>, Vec<ConfigAlterResult>) {
+    let parsed: Vec<_> = requests.into_iter()
+        .map(|(name, operation, value)| (name, operation, ConfigAlteration::parse(name, operation, value)))
+        .collect();
+
+    let mut seen = HashSet::new();
+    let duplicates: HashSet<&str> = parsed.iter()
+        .filter_map(|(_, _, parsed)| parsed.as_ref().ok())
+        .filter(|alteration| !seen.insert(alteration.name.as_str()))
+        .map(|alteration| alteration.name.as_str())
+        .collect();
+
+    let mut valid = Vec::new();
+    let mut rejected = Vec::new();
+    for (name, operation, parsed) in &parsed {
+        match parsed {
+            Ok(alteration) if duplicates.contains(alteration.name.as_str()) => {
+                rejected.push(alteration.result(Some(format!("{} is listed more than once", alteration.name))));
+            }
+            Ok(alteration) => valid.push(alteration.clone()),
+            Err(e) => rejected.push(ConfigAlterResult {
+                name: name.trim().to_string(),
+                operation: operation.to_string(),
+                error: Some(e.to_string()),
+            }),
+        }
+    }
+    (valid, rejected)
+}
+
+/// Apply changes to a topic's override configs, for brokers without IncrementalAlterConfigs.
+/// List configs are comma separated.
+pub fn apply_alterations(configs: &mut HashMap<String, String>, alterations: &[ConfigAlteration]) {
+    for alteration in alterations {
+        let value = alteration.value.as_deref().unwrap_or_default();
+        match alteration.op {
+            ConfigAlterOp::Set => {
+                configs.insert(alteration.name.clone(), value.to_string());
+            }
+            ConfigAlterOp::Delete => {
+                configs.remove(&alteration.name);
+            }
+            ConfigAlterOp::Append => {
+                let items = configs.entry(alteration.name.clone()).or_default();
+                if !items.split(',').any(|item| item.trim() == value) {
+                    if !items.is_empty() {
+                        items.push(',');
+                    }
+                    items.push_str(value);
+                }
+            }
+            ConfigAlterOp::Subtract => {
+                if let Some(items) = configs.get_mut(&alteration.name) {
+                    *items = items.split(',')
+                        .filter(|item| item.trim() != value)
+                        .collect::<Vec<_>>()
+                        .join(",");
+                }
+            }
+        }
+    }
+}
+
+/// Outcome of each change after the broker rejected the whole topic resource with `error`.
+///
+/// Changes named in the error get it; the others were not applied since a resource is
+/// altered atomically. When the error names none of them, every change gets it.
+pub fn rejected_alterations(alterations: &[ConfigAlteration], error: &str) -> Vec<ConfigAlterResult> {
+    let named: HashSet<&str> = alterations.iter()
+        .map(|a| a.name.as_str())
+        .filter(|name| mentions_config(error, name))
+        .collect();
+
+    alterations.iter()
+        .map(|a| {
+            if named.is_empty() || named.contains(a.name.as_str()) {
+                a.result(Some(error.to_string()))
+            } else {
+                a.result(Some(format!("Not applied: {}", error)))
+            }
+        })
+        .collect()
+}
+
+/// Whether `message` refers to config `name` as a whole word, so `retention.ms`
+/// does not match `local.retention.ms`
+fn mentions_config(message: &str, name: &str) -> bool {
+    let is_name_char = |c: char| c.is_ascii_alphanumeric() || c == '.' || c == '_' || c == '-';
+    message.match_indices(name).any(|(start, _)| {
+        let before = message[..start].chars().next_back();
+        let after = message[start + name.len()..].chars().next();
+        !before.is_some_and(is_name_char) && !after.is_some_and(|c| is_name_char(c) && c != '.')
+    })
+}
+
+/// Apply changes to a topic in one IncrementalAlterConfigs request.
+/// The broker applies all of them or none; its reason for rejecting the topic resource is
+/// returned as `Ok(Some(..))`, while failures to complete the request are errors.
+pub fn incremental_alter_topic_config(
+    client: *mut rdsys::rd_kafka_t,
+    topic: &str,
+    alterations: &[ConfigAlteration],
+    timeout: Duration,
+) -> Result<Option<String>> {
+    let resource = NativeConfigResource::for_topic(topic)?;
+    for alteration in alterations {
+        resource.add(alteration)?;
+    }
+
+    let mut resources = [resource.0];
+    let event = run_admin_op(client, rdsys::rd_kafka_admin_op_t::RD_KAFKA_ADMIN_OP_INCREMENTALALTERCONFIGS, timeout, |options, queue| unsafe {
+        rdsys::rd_kafka_IncrementalAlterConfigs(client, resources.as_mut_ptr(), resources.len(), options, queue)
+    })?;
+
+    let mut count = 0;
+    let results = unsafe {
+        let result = rdsys::rd_kafka_event_IncrementalAlterConfigs_result(event.0);
+        if result.is_null() {
+            return Err(anyhow!("Unexpected admin result for IncrementalAlterConfigs"));
+        }
+        rdsys::rd_kafka_IncrementalAlterConfigs_result_resources(result, &mut count)
+    };
+
+    for i in 0..count {
+        let result = unsafe { *results.add(i) };
+        let code = unsafe { rdsys::rd_kafka_ConfigResource_error(result) };
+        if code != rdkafka::types::RDKafkaRespErr::RD_KAFKA_RESP_ERR_NO_ERROR {
+            return Ok(Some(unsafe { c_string(rdsys::rd_kafka_ConfigResource_error_string(result)) }));
+        }
+    }
+    Ok(None)
+}
+
+/// Config resource allocated by us; librdkafka copies it on submit
+struct NativeConfigResource(*mut rdsys::rd_kafka_ConfigResource_t);
+
+impl NativeConfigResource {
+    fn for_topic(topic: &str) -> Result<Self> {
+        let name = CString::new(topic).map_err(|e| anyhow!("Invalid topic name '{}': {}", topic, e))?;
+        Ok(Self(unsafe {
+            rdsys::rd_kafka_ConfigResource_new(rdsys::rd_kafka_ResourceType_t::RD_KAFKA_RESOURCE_TOPIC, name.as_ptr())
+        }))
+    }
+
+    fn add(&self, alteration: &ConfigAlteration) -> Result<()> {
+        let name = CString::new(alteration.name.as_str())?;
+        let value = alteration.value.as_deref().map(CString::new).transpose()?;
+        let error = unsafe {
+            rdsys::rd_kafka_ConfigResource_add_incremental_config(
+                self.0,
+                name.as_ptr(),
+                alteration.op.to_native(),
+                value.as_ref().map_or(ptr::null(), |v| v.as_ptr()),
+            )
+        };
+        if error.is_null() {
+            return Ok(());
+        }
+        let message = unsafe { c_string(rdsys::rd_kafka_error_string(error)) };
+        unsafe { rdsys::rd_kafka_error_destroy(error) };
+        Err(anyhow!("Invalid change to {}: {}", alteration.name, message))
+    }
+}
+
+impl Drop for NativeConfigResource {
+    fn drop(&mut self) {
+        unsafe { rdsys::rd_kafka_ConfigResource_destroy(self.0) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(name: &str, value: &str) -> ConfigAlteration {
+        ConfigAlteration::parse(name, "SET", Some(value)).unwrap()
+    }
+
+    #[test]
+    fn test_parse_operations() {
+        let parsed = ConfigAlteration::parse(" retention.ms ", "set", Some("86400000")).unwrap();
+        assert_eq!(parsed, set("retention.ms", "86400000"));
+
+        let delete = ConfigAlteration::parse("retention.ms", "DELETE", Some("ignored")).unwrap();
+        assert_eq!(delete.op, ConfigAlterOp::Delete);
+        assert_eq!(delete.value, None);
+
+        let append = ConfigAlteration::parse("cleanup.policy", "Append", Some(" compact ")).unwrap();
+        assert_eq!(append.op, ConfigAlterOp::Append);
+        assert_eq!(append.value.as_deref(), Some("compact"));
+
+        assert_eq!(ConfigAlteration::parse("x", "SUBTRACT", Some("a")).unwrap().op, ConfigAlterOp::Subtract);
+        assert_eq!(ConfigAlteration::parse("x", "SET", Some("")).unwrap().value.as_deref(), Some(""));
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_entries() {
+        assert!(ConfigAlteration::parse("", "SET", Some("1")).is_err());
+        assert!(ConfigAlteration::parse("retention.ms", "REPLACE", Some("1")).is_err());
+        assert!(ConfigAlteration::parse("retention.ms", "SET", None).is_err());
+        assert!(ConfigAlteration::parse("cleanup.policy", "APPEND", Some(" ")).is_err());
+        assert!(ConfigAlteration::parse("cleanup.policy", "SUBTRACT", None).is_err());
+        assert!(ConfigAlteration::parse("retention.ms", "SET", Some("1\0")).is_err());
+    }
+
+    #[test]
+    fn test_validate_reports_per_entry_errors() {
+        let (valid, rejected) = validate_alterations(vec![
+            ("retention.ms", "SET", Some("1000")),
+            ("segment.ms", "MERGE", Some("1")),
+            ("cleanup.policy", "APPEND", Some("compact")),
+            ("max.message.bytes", "SET", None),
+        ]);
+
+        assert_eq!(valid.len(), 2);
+        assert_eq!(valid[0].name, "retention.ms");
+        assert_eq!(valid[1].name, "cleanup.policy");
+
+        assert_eq!(rejected.len(), 2);
+        assert_eq!(rejected[0].name, "segment.ms");
+        assert_eq!(rejected[0].operation, "MERGE");
+        assert!(rejected[0].error.as_deref().unwrap().contains("Invalid config operation"));
+        assert_eq!(rejected[1].name, "max.message.bytes");
+        assert!(!rejected[1].is_success());
+    }
+
+    #[test]
+    fn test_validate_rejects_duplicate_names() {
+        let (valid, rejected) = validate_alterations(vec![
+            ("retention.ms", "SET", Some("1000")),
+            ("segment.ms", "DELETE", None),
+            ("retention.ms", "DELETE", None),
+        ]);
+
+        assert_eq!(valid, vec![ConfigAlteration::parse("segment.ms", "DELETE", None).unwrap()]);
+        assert_eq!(rejected.len(), 2);
+        assert!(rejected.iter().all(|r| r.name == "retention.ms"));
+        assert!(rejected[0].error.as_deref().unwrap().contains("more than once"));
+    }
+
+    #[test]
+    fn test_apply_alterations() {
+        let mut configs = HashMap::from([
+            ("retention.ms".to_string(), "1000".to_string()),
+            ("cleanup.policy".to_string(), "delete".to_string()),
+            ("segment.ms".to_string(), "60000".to_string()),
+        ]);
+        let alterations = [
+            set("retention.ms", "2000"),
+            ConfigAlteration::parse("segment.ms", "DELETE", None).unwrap(),
+            ConfigAlteration::parse("cleanup.policy", "APPEND", Some("compact")).unwrap(),
+            ConfigAlteration::parse("cleanup.policy", "APPEND", Some("compact")).unwrap(),
+            ConfigAlteration::parse("cleanup.policy", "SUBTRACT", Some("delete")).unwrap(),
+            ConfigAlteration::parse("follower.replication.throttled.replicas", "APPEND", Some("0:1")).unwrap(),
+        ];
+        apply_alterations(&mut configs, &alterations);
+
+        assert_eq!(configs["retention.ms"], "2000");
+        assert!(!configs.contains_key("segment.ms"));
+        assert_eq!(configs["cleanup.policy"], "compact");
+        assert_eq!(configs["follower.replication.throttled.replicas"], "0:1");
+    }
+
+    #[test]
+    fn test_rejected_alterations() {
+        let alterations = [
+            set("retention.ms", "abc"),
+            set("local.retention.ms", "1000"),
+            ConfigAlteration::parse("segment.ms", "DELETE", None).unwrap(),
+        ];
+
+        let results = rejected_alterations(
+            &alterations,
+            "Invalid value abc for configuration retention.ms: Not a number of type LONG",
+        );
+        assert_eq!(results.len(), 3);
+        assert!(results[0].error.as_deref().unwrap().starts_with("Invalid value abc"));
+        assert!(results[1].error.as_deref().unwrap().starts_with("Not applied: "));
+        assert!(results[2].error.as_deref().unwrap().starts_with("Not applied: "));
+
+        let results = rejected_alterations(&alterations, "Topic authorization failed");
+        assert!(results.iter().all(|r| r.error.as_deref() == Some("Topic authorization failed")));
+    }
+
+    #[test]
+    fn test_native_resource_accepts_all_operations() {
+        let resource = NativeConfigResource::for_topic("orders").unwrap();
+        resource.add(&set("retention.ms", "1000")).unwrap();
+        resource.add(&ConfigAlteration::parse("segment.ms", "DELETE", None).unwrap()).unwrap();
+        resource.add(&ConfigAlteration::parse("cleanup.policy", "APPEND", Some("compact")).unwrap()).unwrap();
+    }
+}
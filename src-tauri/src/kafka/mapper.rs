@@ -5,82 +5,236 @@
 // Basic implementation for PLAINTEXT connections
 
 use anyhow::{Result, anyhow};
-use rdkafka::admin::{AdminClient, AdminOptions, NewTopic, TopicReplication};
+use rdkafka::admin::{
+    AdminClient, AdminOptions, AlterConfig, ConfigEntry, ConfigSource, NewPartitions, NewTopic, OwnedResourceSpecifier, ResourceSpecifier,
+    TopicReplication,
+};
 use rdkafka::config::ClientConfig;
 use rdkafka::consumer::{BaseConsumer, Consumer};
-use rdkafka::message::Message;
+use rdkafka::message::{Header, Headers, Message, OwnedHeaders};
 use rdkafka::metadata::Metadata;
-use rdkafka::producer::{BaseProducer, BaseRecord};
+use rdkafka::client::ClientContext;
+use rdkafka::producer::{BaseProducer, BaseRecord, DeliveryResult, Producer, ProducerContext};
 use rdkafka::topic_partition_list::TopicPartitionList;
-use rdkafka::error::KafkaError;
+use rdkafka::error::{KafkaError, RDKafkaErrorCode};
 use rdkafka::Offset;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::ffi::CString;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Weak};
 use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 use tracing::{info, debug, warn, error};
-use crate::config::ServerConnection;
+use crate::config::{SaslMechanism, ServerConnection, UserSettings};
+use crate::config::server_connection::{
+    TopicDecoderConfig, API_ALTER_CONFIGS, API_CREATE_PARTITIONS, API_DESCRIBE_CONFIGS, API_INCREMENTAL_ALTER_CONFIGS,
+};
+use crate::decoders::Decoder;
+use crate::kafka::acl_admin::{self, AclSpec};
+use crate::kafka::config_admin::{self, ConfigAlteration, ConfigAlterResult};
+use crate::kafka::oauth::OAuthAccessToken;
+use crate::kafka::size_stats::{MessageSizeDistribution, SizeStats};
+
+/// Consumer groups checked by `list_consumer_groups_for_topic`
+const MAX_GROUPS_PER_TOPIC_SCAN: usize = 100;
+
+/// Offset fetches run concurrently by `list_consumer_groups_for_topic`
+const MAX_CONCURRENT_OFFSET_FETCHES: usize = 10;
+
+/// Kerberos principal name of the brokers when none is configured
+const DEFAULT_KERBEROS_SERVICE_NAME: &str = "kafka";
+
+/// OAUTHBEARER principal when the connection does not name one
+const DEFAULT_OAUTH_PRINCIPAL: &str = "offset-explorer";
+
+/// Consumer group used when browsing messages
+pub const BROWSE_CONSUMER_GROUP: &str = "offset-explorer-temp";
+
+/// How long `produce_messages_batch` waits for the batch to be delivered
+const BATCH_FLUSH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long a batch send waits for room in a full producer queue before retrying
+const QUEUE_FULL_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Client timeouts applied to every admin client, producer and consumer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KafkaClientTimeouts {
+    /// Used for request.timeout.ms, socket.timeout.ms and blocking metadata calls
+    pub request_timeout_ms: i32,
+    pub metadata_max_age_ms: i32,
+}
+
+impl KafkaClientTimeouts {
+    /// Read timeouts from `broker_read_timeout` and `auto_refresh_interval_ms`
+    pub fn from_settings(settings: &UserSettings) -> Self {
+        let defaults = Self::default();
+        let request_timeout_ms = settings
+            .get_int("broker_read_timeout")
+            .unwrap_or(defaults.request_timeout_ms);
+        // Refresh metadata twice per UI refresh so it is never stale in between
+        let metadata_max_age_ms = settings
+            .get_int("auto_refresh_interval_ms")
+            .map(|interval| interval / 2)
+            .unwrap_or(defaults.metadata_max_age_ms);
+
+        Self {
+            request_timeout_ms,
+            metadata_max_age_ms,
+        }
+    }
+
+    fn request_timeout(&self) -> Duration {
+        Duration::from_millis(self.request_timeout_ms.max(0) as u64)
+    }
+}
+
+impl Default for KafkaClientTimeouts {
+    fn default() -> Self {
+        Self {
+            request_timeout_ms: 10000,
+            metadata_max_age_ms: 15000,
+        }
+    }
+}
 
 /// Kafka mapper - wrapper around rdkafka for Kafka operations
 /// Compatible with Java KafkaMapper class
 pub struct KafkaMapper {
     connection: ServerConnection,
     admin: Option<AdminClient<rdkafka::client::DefaultClientContext>>,
-    producer: Option<BaseProducer<rdkafka::producer::DefaultProducerContext>>,
+    producer: Option<BaseProducer<DeliveryReportContext>>,
+    timeouts: KafkaClientTimeouts,
+    /// Latest OAUTHBEARER token and principal passed to `set_oauth_token`
+    oauth_token: Mutex<Option<(OAuthAccessToken, String)>>,
+    /// Consumers created for an OAUTHBEARER connection, refreshed by `set_oauth_token`
+    oauth_consumers: Mutex<Vec<Weak<BaseConsumer>>>,
 }
 
 impl KafkaMapper {
     /// Create a new Kafka mapper from server connection
-    pub fn new(connection: ServerConnection) -> Result<Self> {
+    pub fn new(connection: ServerConnection, timeouts: KafkaClientTimeouts) -> Result<Self> {
         info!("Creating Kafka mapper for: {}", connection.get_name());
 
         // Build bootstrap servers
         let bootstrap_servers = connection.get_bootstrap_servers();
 
-        // Check if security is enabled
-        let security_type = connection.get_security_type();
-        if security_type != &crate::config::BrokerSecurityType::PLAINTEXT {
-            warn!("Security type {:?} not yet fully supported, using basic configuration",
-                  security_type);
-            // TODO: Implement full SSL/SASL support when system libraries are available
-        }
-
         // Create admin client
-        let admin = Self::create_admin_client(&bootstrap_servers, &connection)?;
+        let admin = Self::create_admin_client(&bootstrap_servers, &connection, &timeouts)?;
 
         // Create producer
-        let producer = Self::create_producer(&bootstrap_servers, &connection)?;
+        let producer = Self::create_producer(&bootstrap_servers, &connection, &timeouts)?;
 
-        Ok(Self {
+        let mapper = Self {
             connection,
             admin: Some(admin),
             producer: Some(producer),
-        })
+            timeouts,
+            oauth_token: Mutex::new(None),
+            oauth_consumers: Mutex::new(Vec::new()),
+        };
+
+        if let Some(token) = mapper.static_oauth_token() {
+            mapper.set_oauth_token(&token.token, token.expires_at_ms, oauth_principal(&mapper.connection))?;
+        }
+
+        Ok(mapper)
     }
 
-    /// Create admin client
-    fn create_admin_client(
+    /// Connection configuration the clients were created with
+    pub fn connection(&self) -> &ServerConnection {
+        &self.connection
+    }
+
+    /// Build the client configuration shared by all client types
+    fn build_client_config(
         bootstrap_servers: &str,
-        connection: &ServerConnection
-    ) -> Result<AdminClient<rdkafka::client::DefaultClientContext>> {
+        connection: &ServerConnection,
+        timeouts: &KafkaClientTimeouts,
+    ) -> Result<ClientConfig> {
         let mut config = ClientConfig::new();
         config.set("bootstrap.servers", bootstrap_servers);
+        config.set("request.timeout.ms", timeouts.request_timeout_ms.to_string());
+        config.set("socket.timeout.ms", timeouts.request_timeout_ms.to_string());
+        config.set("metadata.max.age.ms", timeouts.metadata_max_age_ms.to_string());
 
         // Add security configuration based on connection type
-        match connection.get_security_type() {
-            crate::config::BrokerSecurityType::PLAINTEXT => {
-                // No additional config needed
+        let security_type = connection.get_security_type();
+        config.set("security.protocol", security_type.as_str().to_ascii_lowercase());
+        if security_type.is_ssl() {
+            Self::apply_ssl_config(&mut config, connection)?;
+        }
+        if security_type.is_sasl() {
+            Self::apply_sasl_config(&mut config, connection);
+        }
+
+        Ok(config)
+    }
+
+    /// TLS settings; the truststore and keystore are expected to be PEM files,
+    /// with the keystore holding both the client certificate and its private key
+    fn apply_ssl_config(config: &mut ClientConfig, connection: &ServerConnection) -> Result<()> {
+        if let Some(ref location) = connection.truststore_location {
+            reject_java_keystore(location, "Truststore")?;
+            config.set("ssl.ca.location", location);
+        }
+        if let Some(ref location) = connection.keystore_location {
+            reject_java_keystore(location, "Keystore")?;
+            config.set("ssl.certificate.location", location);
+            config.set("ssl.key.location", location);
+        }
+        if let Some(password) = connection.keystore_privatekey.as_ref()
+            .or(connection.keystore_password.as_ref())
+        {
+            config.set("ssl.key.password", password);
+        }
+        // librdkafka accepts "https" or "none"
+        config.set(
+            "ssl.endpoint.identification.algorithm",
+            if connection.validate_ssl_endpoint_hostname { "https" } else { "none" },
+        );
+        Ok(())
+    }
+
+    /// SASL mechanism and credentials; OAUTHBEARER tokens are set once the client exists
+    fn apply_sasl_config(config: &mut ClientConfig, connection: &ServerConnection) {
+        config.set("sasl.mechanism", connection.sasl_mechanism.as_str());
+        if connection.sasl_mechanism == SaslMechanism::OAUTHBEARER {
+            config.set("sasl.oauthbearer.config", format!("principal={}", oauth_principal(connection)));
+        }
+        match connection.sasl_credentials() {
+            Some((username, password)) => {
+                config.set("sasl.username", username);
+                config.set("sasl.password", password);
             }
-            crate::config::BrokerSecurityType::SSL => {
-                // TODO: Add SSL configuration when system libraries available
-                warn!("SSL configuration not yet implemented");
+            None if connection.sasl_mechanism.is_password_based() => {
+                warn!("No SASL credentials configured for {}", connection.get_name());
             }
-            crate::config::BrokerSecurityType::SASL_PLAINTEXT => {
-                // TODO: Add SASL configuration when system libraries available
-                warn!("SASL_PLAINTEXT configuration not yet implemented");
+            None => {}
+        }
+
+        if connection.sasl_mechanism == SaslMechanism::GSSAPI {
+            let service_name = connection.kerberos_service_name.as_deref()
+                .filter(|name| !name.is_empty())
+                .unwrap_or(DEFAULT_KERBEROS_SERVICE_NAME);
+            config.set("sasl.kerberos.service.name", service_name);
+            if let Some(ref principal) = connection.kerberos_principal {
+                config.set("sasl.kerberos.principal", principal);
             }
-            crate::config::BrokerSecurityType::SASL_SSL => {
-                // TODO: Add SASL_SSL configuration when system libraries available
-                warn!("SASL_SSL configuration not yet implemented");
+            if let Some(ref keytab) = connection.kerberos_keytab {
+                config.set("sasl.kerberos.keytab", keytab);
             }
         }
+    }
+
+    /// Create admin client
+    fn create_admin_client(
+        bootstrap_servers: &str,
+        connection: &ServerConnection,
+        timeouts: &KafkaClientTimeouts,
+    ) -> Result<AdminClient<rdkafka::client::DefaultClientContext>> {
+        let mut config = Self::build_client_config(bootstrap_servers, connection, timeouts)?;
 
         // Set client ID
         config.set("client.id", format!("offset-explorer-rust-{}", connection.get_name()));
@@ -95,31 +249,45 @@ impl KafkaMapper {
     /// Create producer
     fn create_producer(
         bootstrap_servers: &str,
-        connection: &ServerConnection
-    ) -> Result<BaseProducer<rdkafka::producer::DefaultProducerContext>> {
-        let mut config = ClientConfig::new();
-        config.set("bootstrap.servers", bootstrap_servers);
-
-        // Add security configuration
-        match connection.get_security_type() {
-            crate::config::BrokerSecurityType::PLAINTEXT => {
-                // No additional config needed
-            }
-            _ => {
-                warn!("Security type {:?} not yet supported for producer",
-                      connection.get_security_type());
-            }
-        }
+        connection: &ServerConnection,
+        timeouts: &KafkaClientTimeouts,
+    ) -> Result<BaseProducer<DeliveryReportContext>> {
+        let mut config = Self::build_client_config(bootstrap_servers, connection, timeouts)?;
 
         config.set("client.id", format!("offset-explorer-rust-producer-{}", connection.get_name()));
 
+        if connection.enable_idempotence {
+            config.set("enable.idempotence", "true");
+            config.set("acks", "all");
+            config.set("max.in.flight.requests.per.connection", "1");
+        }
+        if let Some(ref transactional_id) = connection.transactional_id {
+            config.set("transactional.id", transactional_id);
+        }
+
         let producer: BaseProducer<_> = config
-            .create()
+            .create_with_context(DeliveryReportContext)
             .map_err(|e| anyhow!("Failed to create producer: {}", e))?;
 
+        if connection.transactional_id.is_some() {
+            producer.init_transactions(timeouts.request_timeout())
+                .map_err(|e| anyhow!("Failed to initialize transactions: {}", e))?;
+        }
+
         Ok(producer)
     }
 
+    /// Fail early when the configured cluster version predates an API
+    fn require_api(&self, api_key: i16, api_name: &str) -> Result<()> {
+        match self.connection.version.max_api_version(api_key) {
+            Some(_) => Ok(()),
+            None => Err(anyhow!(
+                "{} is not supported by cluster version {:?}",
+                api_name, self.connection.version
+            )),
+        }
+    }
+
     /// List all topics
     pub fn list_topics(&self) -> Result<Vec<String>> {
         info!("Listing topics for: {}", self.connection.get_name());
@@ -129,7 +297,7 @@ impl KafkaMapper {
 
         // Get metadata with timeout
         let metadata = admin.inner()
-            .fetch_metadata(None, Duration::from_secs(10))
+            .fetch_metadata(None, self.timeouts.request_timeout())
             .map_err(|e| anyhow!("Failed to fetch metadata: {}", e))?;
 
         // Extract topic names
@@ -143,6 +311,213 @@ impl KafkaMapper {
         Ok(topics)
     }
 
+    /// List non-internal topics with the config values that differ from the defaults
+    pub fn get_topics_with_configs(&self) -> Result<Vec<TopicWithConfigs>> {
+        info!("Describing topic configs for: {}", self.connection.get_name());
+        self.require_api(API_DESCRIBE_CONFIGS, "DescribeConfigs")?;
+
+        let admin = self.admin.as_ref()
+            .ok_or_else(|| anyhow!("Admin client not initialized"))?;
+
+        let topics: Vec<String> = self.list_topics()?
+            .into_iter()
+            .filter(|t| !t.starts_with("__"))
+            .collect();
+        let resources: Vec<ResourceSpecifier> = topics.iter()
+            .map(|t| ResourceSpecifier::Topic(t))
+            .collect();
+
+        let admin_opts = AdminOptions::new()
+            .request_timeout(Some(self.timeouts.request_timeout()));
+
+        let rt = tokio::runtime::Runtime::new()
+            .map_err(|e| anyhow!("Failed to create runtime: {}", e))?;
+
+        // One request for all topics
+        let results = rt.block_on(async {
+            admin.describe_configs(&resources, &admin_opts).await
+        }).map_err(|e| anyhow!("Failed to describe topic configs: {}", e))?;
+
+        let mut topics_with_configs = Vec::new();
+        for result in results {
+            match result {
+                Ok(resource) => {
+                    if let OwnedResourceSpecifier::Topic(name) = resource.specifier {
+                        topics_with_configs.push(TopicWithConfigs {
+                            name,
+                            non_default_configs: non_default_configs(&resource.entries),
+                        });
+                    }
+                }
+                Err(code) => warn!("Failed to describe topic config: {}", code),
+            }
+        }
+
+        topics_with_configs.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(topics_with_configs)
+    }
+
+    /// Get compaction, tiered storage and retention flags from a topic's configs
+    pub fn get_topic_flags(&self, topic: &str) -> Result<TopicFlags> {
+        Ok(TopicFlags::from_configs(&self.get_topic_config(topic)?))
+    }
+
+    /// Get all config values of a topic, by name
+    pub fn get_topic_config(&self, topic: &str) -> Result<HashMap<String, String>> {
+        Ok(self.get_topic_config_entries(topic)?
+            .into_iter()
+            .filter_map(|e| e.value.map(|v| (e.name, v)))
+            .collect())
+    }
+
+    /// Get all config entries of a topic, with their default and read-only flags
+    pub fn get_topic_config_entries(&self, topic: &str) -> Result<Vec<TopicConfigInfo>> {
+        debug!("Describing configs of topic: {}", topic);
+        self.require_api(API_DESCRIBE_CONFIGS, "DescribeConfigs")?;
+
+        let admin = self.admin.as_ref()
+            .ok_or_else(|| anyhow!("Admin client not initialized"))?;
+
+        let admin_opts = AdminOptions::new()
+            .request_timeout(Some(self.timeouts.request_timeout()));
+
+        let rt = tokio::runtime::Runtime::new()
+            .map_err(|e| anyhow!("Failed to create runtime: {}", e))?;
+
+        let results = rt.block_on(async {
+            admin.describe_configs(&[ResourceSpecifier::Topic(topic)], &admin_opts).await
+        }).map_err(|e| anyhow!("Failed to describe topic config: {}", e))?;
+
+        let resource = results
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("No config returned for topic {}", topic))?
+            .map_err(|code| anyhow!("Failed to describe config of topic {}: {}", topic, code))?;
+
+        Ok(resource.entries.into_iter().map(TopicConfigInfo::from).collect())
+    }
+
+    /// Apply validated config changes to an existing topic, returning the outcome of each.
+    ///
+    /// Uses IncrementalAlterConfigs where the cluster supports it (Kafka 2.3+). Older
+    /// clusters get a full AlterConfigs request built from the topic's current overrides.
+    /// A rejection by the broker is reported on the entries; a failed request is an error.
+    pub fn alter_topic_config_entries(
+        &self,
+        topic: &str,
+        alterations: &[ConfigAlteration],
+    ) -> Result<Vec<ConfigAlterResult>> {
+        info!("Altering {} configs of topic '{}' on: {}", alterations.len(), topic, self.connection.get_name());
+        self.require_api(API_ALTER_CONFIGS, "AlterConfigs")?;
+
+        let admin = self.admin.as_ref()
+            .ok_or_else(|| anyhow!("Admin client not initialized"))?;
+
+        if !self.list_topics()?.iter().any(|t| t == topic) {
+            return Err(anyhow!("Topic '{}' does not exist", topic));
+        }
+        if alterations.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let rejection = if self.connection.version.max_api_version(API_INCREMENTAL_ALTER_CONFIGS).is_some() {
+            config_admin::incremental_alter_topic_config(
+                admin.inner().native_ptr(), topic, alterations, self.timeouts.request_timeout(),
+            )
+        } else {
+            self.replace_topic_config(admin, topic, alterations)
+        }.map_err(|e| anyhow!("Failed to alter config of topic '{}': {}", topic, e))?;
+
+        match rejection {
+            Some(error) => {
+                warn!("Broker rejected config changes to topic '{}': {}", topic, error);
+                Ok(config_admin::rejected_alterations(alterations, &error))
+            }
+            None => Ok(alterations.iter().map(|a| a.result(None)).collect()),
+        }
+    }
+
+    /// Legacy AlterConfigs replaces every override of the topic, so the current
+    /// overrides are read first and the changes applied on top of them.
+    /// Returns the broker's reason if it rejected the topic resource.
+    fn replace_topic_config(
+        &self,
+        admin: &AdminClient<rdkafka::client::DefaultClientContext>,
+        topic: &str,
+        alterations: &[ConfigAlteration],
+    ) -> Result<Option<String>> {
+        let admin_opts = AdminOptions::new()
+            .request_timeout(Some(self.timeouts.request_timeout()));
+
+        let rt = tokio::runtime::Runtime::new()
+            .map_err(|e| anyhow!("Failed to create runtime: {}", e))?;
+
+        let resource = rt.block_on(async {
+            admin.describe_configs(&[ResourceSpecifier::Topic(topic)], &admin_opts).await
+        }).map_err(|e| anyhow!("Failed to describe topic config: {}", e))?
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("No config returned for topic {}", topic))?
+            .map_err(|code| anyhow!("Failed to describe config of topic {}: {}", topic, code))?;
+
+        let mut configs = HashMap::new();
+        for entry in resource.entries.into_iter().filter(|e| e.source == ConfigSource::DynamicTopic) {
+            let value = entry.value.ok_or_else(|| anyhow!(
+                "Cannot preserve sensitive config {} with AlterConfigs", entry.name
+            ))?;
+            configs.insert(entry.name, value);
+        }
+        config_admin::apply_alterations(&mut configs, alterations);
+
+        let alter = configs.iter()
+            .fold(AlterConfig::new(ResourceSpecifier::Topic(topic)), |alter, (k, v)| alter.set(k, v));
+        let results = rt.block_on(async {
+            admin.alter_configs(&[alter], &admin_opts).await
+        }).map_err(|e| anyhow!("Failed to alter topic config: {}", e))?;
+
+        Ok(results.into_iter().find_map(|result| result.err().map(|(_, code)| code.to_string())))
+    }
+
+    /// Get the broker configs that are still at their hardcoded default
+    pub fn get_default_broker_configs(&self, broker_id: i32) -> Result<HashMap<String, String>> {
+        info!("Describing default configs of broker {} for: {}", broker_id, self.connection.get_name());
+        Ok(default_configs(&self.describe_broker_configs(broker_id)?))
+    }
+
+    /// Get all config entries of a broker, with where each value comes from
+    pub fn get_broker_config_entries(&self, broker_id: i32) -> Result<Vec<BrokerConfigInfo>> {
+        info!("Describing configs of broker {} for: {}", broker_id, self.connection.get_name());
+        Ok(self.describe_broker_configs(broker_id)?
+            .into_iter()
+            .map(BrokerConfigInfo::from)
+            .collect())
+    }
+
+    fn describe_broker_configs(&self, broker_id: i32) -> Result<Vec<ConfigEntry>> {
+        self.require_api(API_DESCRIBE_CONFIGS, "DescribeConfigs")?;
+
+        let admin = self.admin.as_ref()
+            .ok_or_else(|| anyhow!("Admin client not initialized"))?;
+
+        let admin_opts = AdminOptions::new()
+            .request_timeout(Some(self.timeouts.request_timeout()));
+
+        let rt = tokio::runtime::Runtime::new()
+            .map_err(|e| anyhow!("Failed to create runtime: {}", e))?;
+
+        let results = rt.block_on(async {
+            admin.describe_configs(&[ResourceSpecifier::Broker(broker_id)], &admin_opts).await
+        }).map_err(|e| anyhow!("Failed to describe broker configs: {}", e))?;
+
+        let resource = results
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("No config returned for broker {}", broker_id))?
+            .map_err(|code| anyhow!("Failed to describe broker {}: {}", broker_id, code))?;
+
+        Ok(resource.entries)
+    }
+
     /// Create a new topic
     pub fn create_topic(&self, name: &str, partitions: i32, replication_factor: i32) -> Result<()> {
         info!("Creating topic: {} with {} partitions and replication factor {}",
@@ -245,6 +620,40 @@ impl KafkaMapper {
         Ok(())
     }
 
+    /// Increase the partition count of a topic; Kafka cannot remove partitions
+    pub fn increase_partitions(&self, topic: &str, new_partition_count: i32) -> Result<()> {
+        info!("Increasing partitions of topic {} to {}", topic, new_partition_count);
+        self.require_api(API_CREATE_PARTITIONS, "CreatePartitions")?;
+
+        let admin = self.admin.as_ref()
+            .ok_or_else(|| anyhow!("Admin client not initialized"))?;
+
+        let current = self.get_topic_metadata(topic)?.partitions.len();
+        validate_partition_increase(topic, current, new_partition_count)?;
+
+        let admin_opts = AdminOptions::new()
+            .request_timeout(Some(self.timeouts.request_timeout()))
+            .operation_timeout(Some(self.timeouts.request_timeout()));
+
+        let rt = tokio::runtime::Runtime::new()
+            .map_err(|e| anyhow!("Failed to create runtime: {}", e))?;
+
+        let new_partitions = NewPartitions::new(topic, new_partition_count as usize);
+        let results = rt.block_on(async {
+            admin.create_partitions(&[new_partitions], &admin_opts).await
+        }).map_err(|e| anyhow!("Failed to create partitions: {}", e))?;
+
+        for result in results {
+            if let Err((topic, error)) = result {
+                error!("Failed to increase partitions of topic {}: {}", topic, error);
+                return Err(anyhow!("Failed to increase partitions of topic '{}': {}", topic, error));
+            }
+        }
+
+        info!("Topic {} now has {} partitions", topic, new_partition_count);
+        Ok(())
+    }
+
     /// Get topic metadata
     pub fn get_topic_metadata(&self, name: &str) -> Result<TopicMetadata> {
         info!("Getting metadata for topic: {}", name);
@@ -254,7 +663,7 @@ impl KafkaMapper {
 
         // Fetch metadata
         let metadata = admin.inner()
-            .fetch_metadata(None, Duration::from_secs(10))
+            .fetch_metadata(None, self.timeouts.request_timeout())
             .map_err(|e| anyhow!("Failed to fetch metadata: {}", e))?;
 
         // Find the topic
@@ -287,6 +696,15 @@ impl KafkaMapper {
         })
     }
 
+    /// Replica broker IDs per partition; the first replica is the preferred leader
+    pub fn get_replica_assignment(&self, topic: &str) -> Result<HashMap<i32, Vec<i32>>> {
+        let metadata = self.get_topic_metadata(topic)?;
+        Ok(metadata.partitions
+            .into_iter()
+            .map(|p| (p.id, p.replicas))
+            .collect())
+    }
+
     /// Get list of broker IDs
     pub fn list_brokers(&self) -> Result<Vec<i32>> {
         info!("Listing brokers for: {}", self.connection.get_name());
@@ -296,7 +714,7 @@ impl KafkaMapper {
 
         // Fetch metadata
         let metadata = admin.inner()
-            .fetch_metadata(None, Duration::from_secs(10))
+            .fetch_metadata(None, self.timeouts.request_timeout())
             .map_err(|e| anyhow!("Failed to fetch metadata: {}", e))?;
 
         // Extract broker IDs
@@ -310,196 +728,1351 @@ impl KafkaMapper {
         Ok(brokers)
     }
 
+    /// Get the brokers advertised in the cluster metadata
+    pub fn describe_cluster(&self) -> Result<Vec<BrokerDetail>> {
+        debug!("Describing cluster for: {}", self.connection.get_name());
+
+        let admin = self.admin.as_ref()
+            .ok_or_else(|| anyhow!("Admin client not initialized"))?;
+
+        let metadata = admin.inner()
+            .fetch_metadata(None, self.timeouts.request_timeout())
+            .map_err(|e| anyhow!("Failed to fetch metadata: {}", e))?;
+
+        Ok(metadata
+            .brokers()
+            .iter()
+            .map(|b| BrokerDetail {
+                id: b.id(),
+                host: b.host().to_string(),
+                port: b.port(),
+            })
+            .collect())
+    }
+
+    /// Describe cluster features.
+    ///
+    /// librdkafka does not expose the ApiVersions feature levels, so KRaft mode is
+    /// read from the configs of the broker that answered the metadata request and
+    /// `supported_features` is left empty.
+    pub fn describe_features(&self) -> Result<ClusterFeatures> {
+        info!("Describing cluster features for: {}", self.connection.get_name());
+
+        let admin = self.admin.as_ref()
+            .ok_or_else(|| anyhow!("Admin client not initialized"))?;
+
+        let metadata = admin.inner()
+            .fetch_metadata(None, self.timeouts.request_timeout())
+            .map_err(|e| anyhow!("Failed to fetch metadata: {}", e))?;
+
+        let broker_id = metadata.orig_broker_id();
+        let entries = self.describe_broker_configs(broker_id)?;
+        Ok(ClusterFeatures {
+            kraft_enabled: is_kraft_broker(&entries),
+            supported_features: HashMap::new(),
+        })
+    }
+
     /// Create a consumer for consuming messages
     pub fn create_consumer(&self, group_id: &str) -> Result<ConsumerWrapper> {
         info!("Creating consumer with group: {}", group_id);
 
         let bootstrap_servers = self.connection.get_bootstrap_servers();
 
-        let mut config = ClientConfig::new();
-        config.set("bootstrap.servers", &bootstrap_servers);
+        let mut config = Self::build_client_config(&bootstrap_servers, &self.connection, &self.timeouts)?;
         config.set("group.id", group_id);
         config.set("enable.auto.commit", "false");
         config.set("client.id", format!("offset-explorer-rust-consumer-{}",
                                         self.connection.get_name()));
 
-        // Add security configuration
-        match self.connection.get_security_type() {
-            crate::config::BrokerSecurityType::PLAINTEXT => {
-                // No additional config needed
-            }
-            _ => {
-                warn!("Security type {:?} not yet supported for consumer",
-                      self.connection.get_security_type());
-            }
-        }
-
         let consumer: BaseConsumer = config
             .create()
             .map_err(|e| anyhow!("Failed to create consumer: {}", e))?;
 
-        Ok(ConsumerWrapper::new(consumer))
-    }
-
-    /// Produce a message to a topic
-    pub fn produce_message(
-        &self,
-        topic: &str,
-        key: Option<Vec<u8>>,
-        payload: Option<Vec<u8>>
-    ) -> Result<()> {
-        debug!("Producing message to topic: {}", topic);
-
-        let producer = self.producer.as_ref()
-            .ok_or_else(|| anyhow!("Producer not initialized"))?;
+        if self.connection.sasl_mechanism == SaslMechanism::OAUTHBEARER {
+            let current = self.oauth_token.lock().unwrap().clone().or_else(|| {
+                self.static_oauth_token()
+                    .map(|token| (token, oauth_principal(&self.connection).to_string()))
+            });
+            if let Some((token, principal)) = current {
+                set_client_oauth_token(consumer.client().native_ptr(), &token.token, token.expires_at_ms, &principal)?;
+            }
+        }
 
-        // Create base record
-        let mut record = BaseRecord::to(topic);
+        let header_decoders = self.connection.topic_configs
+            .iter()
+            .filter_map(|(topic, cfg)| {
+                cfg.header_type.as_deref()
+                    .map(|t| (topic.clone(), TopicDecoderConfig::build_header_decoder(t)))
+            })
+            .collect();
 
-        // Set payload
-        if let Some(ref data) = payload {
-            record = record.payload(data);
+        let consumer = ConsumerWrapper::new(consumer, header_decoders);
+        if self.connection.sasl_mechanism == SaslMechanism::OAUTHBEARER {
+            let mut consumers = self.oauth_consumers.lock().unwrap();
+            consumers.retain(|client| client.strong_count() > 0);
+            consumers.push(Arc::downgrade(&consumer.consumer));
         }
+        Ok(consumer)
+    }
 
-        // Set key
-        if let Some(ref key_data) = key {
-            record = record.key(key_data);
+    /// Fetch the single message stored at `offset` in a partition
+    pub fn get_message_at_offset(&self, topic: &str, partition: i32, offset: i64) -> Result<KafkaMessage> {
+        debug!("Fetching message {}/{} at offset {}", topic, partition, offset);
+
+        let consumer = self.create_consumer(BROWSE_CONSUMER_GROUP)?;
+        consumer.assign(topic, partition, offset)?;
+
+        let timeout_ms = self.timeouts.request_timeout_ms.max(0) as u32;
+        match consumer.poll(timeout_ms)? {
+            Some(msg) if msg.offset == offset => Ok(msg),
+            Some(msg) => Err(anyhow!(
+                "No message at offset {} in {}/{} (next available is {})",
+                offset, topic, partition, msg.offset
+            )),
+            None => Err(anyhow!("No message at offset {} in {}/{}", offset, topic, partition)),
         }
-
-        // Send message
-        producer.send(record)
-            .map_err(|(e, _)| anyhow!("Failed to produce message: {}", e))?;
-
-        debug!("Message produced successfully to topic: {}", topic);
-        Ok(())
     }
 
-    /// Get consumer group offsets
-    pub fn list_consumer_groups(&self) -> Result<Vec<ConsumerGroupInfo>> {
-        info!("Listing consumer groups");
-
+    /// Low and high watermark offsets of a partition
+    pub fn get_watermarks(&self, topic: &str, partition: i32) -> Result<(i64, i64)> {
         let admin = self.admin.as_ref()
             .ok_or_else(|| anyhow!("Admin client not initialized"))?;
-
-        // List consumer groups - use metadata to get group info
-        let metadata = admin.inner()
-            .fetch_metadata(None, Duration::from_secs(10))
-            .map_err(|e| anyhow!("Failed to fetch metadata: {}", e))?;
-
-        // Note: rdkafka's AdminClient doesn't have a direct list_consumer_groups method
-        // This is a stub implementation that returns empty list
-        // TODO: Implement using Kafka Admin API directly or wait for rdkafka support
-
-        warn!("Consumer group listing not fully implemented - returning empty list");
-        Ok(Vec::new())
+        admin.inner()
+            .fetch_watermarks(topic, partition, self.timeouts.request_timeout())
+            .map_err(|e| anyhow!("Failed to fetch watermarks for {}-{}: {}", topic, partition, e))
     }
 
-    // ==================== ACL Management Methods ====================
+    /// Watermarks of every partition of a topic, by partition ID
+    pub fn get_all_watermarks(&self, topic: &str) -> Result<Vec<PartitionWatermark>> {
+        debug!("Fetching watermarks of all partitions of {}", topic);
 
-    /// List ACL bindings with optional filter
-    pub fn list_acls(&self, _filter: Option<crate::acls::AclFilter>) -> Result<Vec<crate::acls::AclBinding>> {
-        info!("Listing ACLs for: {}", self.connection.get_name());
+        let mut partitions: Vec<i32> = self.get_topic_metadata(topic)?
+            .partitions
+            .iter()
+            .map(|p| p.id)
+            .collect();
+        partitions.sort_unstable();
 
-        // Note: rdkafka doesn't have native ACL management in the basic AdminClient
-        // This is a stub implementation for now
-        // TODO: Implement using Kafka Admin API directly or wait for rdkafka support
-        warn!("ACL listing not fully implemented - returning empty list");
-        Ok(Vec::new())
+        partitions.into_iter()
+            .map(|partition| {
+                let (low, high) = self.get_watermarks(topic, partition)?;
+                Ok(PartitionWatermark::new(partition, low, high))
+            })
+            .collect()
     }
 
-    /// Create a new ACL
-    pub fn create_acl(&self, request: &crate::acls::CreateAclRequest) -> Result<()> {
-        info!("Creating ACL on: {}", self.connection.get_name());
+    /// Earliest offset per partition whose message timestamp is at or after `timestamp_ms`.
+    /// Partitions without such a message get their high watermark.
+    pub fn offsets_for_timestamp(&self, topic: &str, timestamp_ms: i64) -> Result<Vec<(i32, i64)>> {
+        debug!("Looking up offsets of {} at timestamp {}", topic, timestamp_ms);
 
-        // Note: rdkafka doesn't have native ACL management in the basic AdminClient
-        // TODO: Implement using Kafka Admin API directly
-        warn!("ACL creation not fully implemented");
-        Ok(())
+        let partitions: Vec<i32> = self.get_topic_metadata(topic)?
+            .partitions
+            .iter()
+            .map(|p| p.id)
+            .collect();
+        let tpl = timestamp_partition_list(topic, &partitions, timestamp_ms)?;
+
+        let consumer = self.create_consumer(BROWSE_CONSUMER_GROUP)?;
+        let offsets = consumer.consumer
+            .offsets_for_times(tpl, self.timeouts.request_timeout())
+            .map_err(|e| anyhow!("Failed to look up offsets for timestamp {}: {}", timestamp_ms, e))?;
+
+        let mut result = Vec::with_capacity(partitions.len());
+        for elem in offsets.elements() {
+            elem.error()
+                .map_err(|e| anyhow!("Failed to look up offset of {}-{}: {}", topic, elem.partition(), e))?;
+            let offset = match elem.offset() {
+                Offset::Offset(offset) => offset,
+                _ => self.get_watermarks(topic, elem.partition())?.1,
+            };
+            result.push((elem.partition(), offset));
+        }
+        result.sort_unstable();
+        Ok(result)
     }
 
-    /// Delete an ACL
-    pub fn delete_acl(
+    /// Measure key + value sizes of the last `sample_size` messages of a partition
+    pub fn sample_message_sizes(
         &self,
-        _principal: &str,
-        _resource_type: &str,
-        _resource_name: &str,
-        _operation: &str,
-        _permission_type: &str,
-        _host: &str,
-    ) -> Result<()> {
-        info!("Deleting ACL on: {}", self.connection.get_name());
+        topic: &str,
+        partition: i32,
+        sample_size: usize,
+    ) -> Result<MessageSizeDistribution> {
+        debug!("Sampling {} message sizes from {}/{}", sample_size, topic, partition);
 
-        // Note: rdkafka doesn't have native ACL management in the basic AdminClient
-        // TODO: Implement using Kafka Admin API directly
-        warn!("ACL deletion not fully implemented");
-        Ok(())
-    }
+        let (low, high) = self.get_watermarks(topic, partition)?;
 
-    /// Close the mapper and release resources
-    pub fn close(&self) -> Result<()> {
-        info!("Closing Kafka mapper for: {}", self.connection.get_name());
+        let mut stats = SizeStats::new();
+        let start = (high - sample_size as i64).max(low);
+        if start >= high {
+            return Ok(stats.distribution());
+        }
 
-        // Drop admin and producer
-        // They will be automatically cleaned up when dropped
-        drop(&self.admin);
-        drop(&self.producer);
+        // Sizes must not be capped by the truncation limit
+        let consumer = self.create_consumer(BROWSE_CONSUMER_GROUP)?
+            .with_max_message_bytes(usize::MAX);
+        consumer.assign(topic, partition, start)?;
 
-        Ok(())
+        let timeout_ms = self.timeouts.request_timeout_ms.max(0) as u32;
+        while let Some(msg) = consumer.poll(timeout_ms)? {
+            let key_len = msg.key.as_ref().map_or(0, |k| k.len());
+            let value_len = msg.payload.as_ref().map_or(0, |p| p.len());
+            stats.add(key_len + value_len);
+
+            if msg.offset >= high - 1 {
+                break;
+            }
+        }
+
+        Ok(stats.distribution())
     }
-}
 
-/// Topic metadata
+    /// Produce a message to a topic. Without a partition the default partitioner picks one.
+    pub fn produce_message(
+        &self,
+        topic: &str,
+        key: Option<Vec<u8>>,
+        payload: Option<Vec<u8>>,
+        headers: &HashMap<String, String>,
+        partition: Option<i32>,
+    ) -> Result<()> {
+        debug!("Producing message to topic: {}", topic);
+
+        let producer = self.producer.as_ref()
+            .ok_or_else(|| anyhow!("Producer not initialized"))?;
+
+        let record = build_record(topic, key.as_deref(), payload.as_deref(), headers, partition, DeliveryTarget::log_only())?;
+        producer.send(record)
+            .map_err(|(e, _)| anyhow!("Failed to produce message: {}", e))?;
+
+        debug!("Message produced successfully to topic: {}", topic);
+        Ok(())
+    }
+
+    /// Produce a message with headers, optionally to a specific partition.
+    /// Waits while the producer queue is full, so it can be called in a loop;
+    /// its delivery report is added to `counts`.
+    pub fn produce_record(
+        &self,
+        topic: &str,
+        key: Option<&[u8]>,
+        payload: &[u8],
+        headers: &HashMap<String, String>,
+        partition: Option<i32>,
+        counts: &Arc<DeliveryCounts>,
+    ) -> Result<()> {
+        debug!("Producing record to topic: {}", topic);
+
+        let producer = self.producer.as_ref()
+            .ok_or_else(|| anyhow!("Producer not initialized"))?;
+
+        let record = build_record(topic, key, Some(payload), headers, partition, DeliveryTarget::counted(counts))?;
+        send_waiting_for_queue(producer, record)
+            .map_err(|e| anyhow!("Failed to produce record: {}", e))?;
+        // Serve delivery reports so they do not pile up during long imports
+        producer.poll(Duration::ZERO);
+
+        Ok(())
+    }
+
+    /// Queue every message and flush once, returning the delivery outcome of each by index.
+    /// One rejected message does not stop the rest of the batch.
+    pub fn produce_messages_batch(&self, topic: &str, messages: Vec<BatchMessage>) -> Result<Vec<BatchProduceResult>> {
+        info!("Producing batch of {} messages to topic: {}", messages.len(), topic);
+
+        let producer = self.producer.as_ref()
+            .ok_or_else(|| anyhow!("Producer not initialized"))?;
+
+        let results = send_batch(producer, topic, &messages, BATCH_FLUSH_TIMEOUT);
+        let failed = results.iter().filter(|r| r.error.is_some()).count();
+        if failed > 0 {
+            warn!("{} of {} messages to {} were rejected", failed, results.len(), topic);
+        }
+        Ok(results)
+    }
+
+    /// Wait until all queued messages are delivered
+    pub fn flush(&self) -> Result<()> {
+        let producer = self.producer.as_ref()
+            .ok_or_else(|| anyhow!("Producer not initialized"))?;
+
+        producer.flush(self.timeouts.request_timeout())
+            .map_err(|e| anyhow!("Failed to flush producer: {}", e))
+    }
+
+    /// Flush until no message is left in flight, as long as each flush delivers some.
+    ///
+    /// Returns how many messages were still in flight when a flush made no progress.
+    pub fn flush_all(&self) -> Result<usize> {
+        let producer = self.producer.as_ref()
+            .ok_or_else(|| anyhow!("Producer not initialized"))?;
+
+        let mut in_flight = producer.in_flight_count();
+        loop {
+            match producer.flush(self.timeouts.request_timeout()) {
+                Ok(()) => return Ok(0),
+                Err(KafkaError::Flush(RDKafkaErrorCode::OperationTimedOut)) => {}
+                Err(e) => return Err(anyhow!("Failed to flush producer: {}", e)),
+            }
+            let remaining = producer.in_flight_count();
+            if remaining >= in_flight {
+                warn!("Flush made no progress, {} messages still in flight", remaining);
+                return Ok(remaining.max(0) as usize);
+            }
+            in_flight = remaining;
+        }
+    }
+
+    /// Start a producer transaction (requires a transactional ID on the connection)
+    pub fn begin_transaction(&self) -> Result<()> {
+        if self.connection.transactional_id.is_none() {
+            return Err(anyhow!("No transactional ID configured for {}", self.connection.get_name()));
+        }
+
+        let producer = self.producer.as_ref()
+            .ok_or_else(|| anyhow!("Producer not initialized"))?;
+
+        producer.begin_transaction()
+            .map_err(|e| anyhow!("Failed to begin transaction: {}", e))
+    }
+
+    /// Commit the current transaction, flushing outstanding messages first
+    pub fn commit_transaction(&self) -> Result<()> {
+        let producer = self.producer.as_ref()
+            .ok_or_else(|| anyhow!("Producer not initialized"))?;
+
+        producer.commit_transaction(self.timeouts.request_timeout())
+            .map_err(|e| anyhow!("Failed to commit transaction: {}", e))
+    }
+
+    /// Abort the current transaction, discarding its messages
+    pub fn abort_transaction(&self) -> Result<()> {
+        let producer = self.producer.as_ref()
+            .ok_or_else(|| anyhow!("Producer not initialized"))?;
+
+        producer.abort_transaction(self.timeouts.request_timeout())
+            .map_err(|e| anyhow!("Failed to abort transaction: {}", e))
+    }
+
+    /// List consumer groups with their members
+    pub fn list_consumer_groups(&self) -> Result<Vec<ConsumerGroupInfo>> {
+        info!("Listing consumer groups");
+
+        let admin = self.admin.as_ref()
+            .ok_or_else(|| anyhow!("Admin client not initialized"))?;
+
+        let group_list = admin.inner()
+            .fetch_group_list(None, self.timeouts.request_timeout())
+            .map_err(|e| anyhow!("Failed to fetch consumer groups: {}", e))?;
+
+        let groups: Vec<ConsumerGroupInfo> = group_list
+            .groups()
+            .iter()
+            .map(ConsumerGroupInfo::from)
+            .collect();
+
+        info!("Found {} consumer groups", groups.len());
+        Ok(groups)
+    }
+
+    /// Describe one consumer group
+    pub fn get_consumer_group(&self, group_id: &str) -> Result<ConsumerGroupInfo> {
+        let admin = self.admin.as_ref()
+            .ok_or_else(|| anyhow!("Admin client not initialized"))?;
+
+        let group_list = admin.inner()
+            .fetch_group_list(Some(group_id), self.timeouts.request_timeout())
+            .map_err(|e| anyhow!("Failed to describe consumer group {}: {}", group_id, e))?;
+
+        group_list
+            .groups()
+            .iter()
+            .find(|g| g.name() == group_id)
+            .map(ConsumerGroupInfo::from)
+            .ok_or_else(|| anyhow!("Consumer group '{}' not found", group_id))
+    }
+
+    /// Commit, for every partition of `topic`, the first offset at or after `timestamp_ms`
+    /// (the end offset where no such message exists). The group must have no active members.
+    pub fn reset_group_offsets_to_timestamp(
+        &self,
+        group_id: &str,
+        topic: &str,
+        timestamp_ms: i64,
+    ) -> Result<Vec<(i32, i64)>> {
+        self.reset_group_offsets(group_id, topic, None, OffsetResetStrategy::ToTimestamp(timestamp_ms))
+    }
+
+    /// Commit offsets chosen by `strategy` for one partition of `topic`, or all of them,
+    /// returning the committed `(partition, offset)` pairs. The group must have no active members.
+    pub fn reset_group_offsets(
+        &self,
+        group_id: &str,
+        topic: &str,
+        partition: Option<i32>,
+        strategy: OffsetResetStrategy,
+    ) -> Result<Vec<(i32, i64)>> {
+        info!("Resetting offsets of group '{}' on {} ({:?})", group_id, topic, strategy);
+
+        self.get_consumer_group(group_id)?.ensure_inactive()?;
+
+        let mut partitions: Vec<i32> = self.get_topic_metadata(topic)?
+            .partitions
+            .iter()
+            .map(|p| p.id)
+            .collect();
+        if let Some(partition) = partition {
+            if !partitions.contains(&partition) {
+                return Err(anyhow!("Topic '{}' has no partition {}", topic, partition));
+            }
+            partitions = vec![partition];
+        }
+
+        let offsets = match strategy {
+            OffsetResetStrategy::ToTimestamp(timestamp_ms) => self.offsets_for_timestamp(topic, timestamp_ms)?
+                .into_iter()
+                .filter(|(p, _)| partitions.contains(p))
+                .collect(),
+            _ => {
+                partitions.sort_unstable();
+                partitions.into_iter()
+                    .map(|p| {
+                        let (low, high) = self.get_watermarks(topic, p)?;
+                        Ok((p, strategy.resolve(p, low, high)?))
+                    })
+                    .collect::<Result<Vec<_>>>()?
+            }
+        };
+
+        let consumer = self.create_consumer(group_id)?;
+        consumer.commit_with_offsets(topic, &offsets)?;
+
+        info!("Committed {} offsets for group '{}'", offsets.len(), group_id);
+        Ok(offsets)
+    }
+
+    /// Get the committed offsets of a consumer group across all topics
+    pub fn get_consumer_group_offsets(&self, group_id: &str) -> Result<Vec<ConsumerOffset>> {
+        debug!("Fetching committed offsets for group: {}", group_id);
+
+        let admin = self.admin.as_ref()
+            .ok_or_else(|| anyhow!("Admin client not initialized"))?;
+
+        let metadata = admin.inner()
+            .fetch_metadata(None, self.timeouts.request_timeout())
+            .map_err(|e| anyhow!("Failed to fetch metadata: {}", e))?;
+
+        let mut tpl = TopicPartitionList::new();
+        for topic in metadata.topics() {
+            for partition in topic.partitions() {
+                tpl.add_partition(topic.name(), partition.id());
+            }
+        }
+
+        let consumer = self.create_consumer(group_id)?;
+        let committed = consumer.consumer
+            .committed_offsets(tpl, self.timeouts.request_timeout())
+            .map_err(|e| anyhow!("Failed to fetch committed offsets for {}: {}", group_id, e))?;
+
+        Ok(committed
+            .elements()
+            .iter()
+            .filter_map(|elem| match elem.offset() {
+                Offset::Offset(offset) => Some(ConsumerOffset {
+                    group_id: group_id.to_string(),
+                    topic: elem.topic().to_string(),
+                    partition: elem.partition(),
+                    offset,
+                }),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Commit the offsets of `source_group` for `dest_group`, optionally limited to `topics`
+    pub fn copy_consumer_offsets(
+        &self,
+        source_group: &str,
+        dest_group: &str,
+        topics: Option<&[String]>,
+    ) -> Result<OffsetCopy> {
+        info!("Copying offsets from group '{}' to '{}'", source_group, dest_group);
+
+        let offsets = self.get_consumer_group_offsets(source_group)?;
+        let (offsets, mut errors) = select_offsets(offsets, topics);
+        if offsets.is_empty() {
+            return Ok(OffsetCopy { copied_partitions: 0, errors });
+        }
+
+        let mut tpl = TopicPartitionList::new();
+        for o in &offsets {
+            if let Err(e) = tpl.add_partition_offset(&o.topic, o.partition, Offset::Offset(o.offset)) {
+                errors.push(format!("{}-{}: {}", o.topic, o.partition, e));
+            }
+        }
+
+        let consumer = self.create_consumer(dest_group)?;
+        consumer.consumer.assign(&tpl)
+            .map_err(|e| anyhow!("Failed to assign partitions: {}", e))?;
+        consumer.consumer.commit(&tpl, rdkafka::consumer::CommitMode::Sync)
+            .map_err(|e| anyhow!("Failed to commit offsets for {}: {}", dest_group, e))?;
+
+        Ok(OffsetCopy { copied_partitions: tpl.count(), errors })
+    }
+
+    /// Get the lag of a consumer group for every partition it has committed offsets for
+    pub fn get_consumer_lag(&self, group_id: &str) -> Result<Vec<ConsumerLag>> {
+        debug!("Computing consumer lag for group: {}", group_id);
+
+        let offsets = self.get_consumer_group_offsets(group_id)?;
+        self.lags_for_offsets(offsets)
+    }
+
+    /// Compute lag for committed offsets against the current high watermarks
+    pub fn lags_for_offsets(&self, offsets: Vec<ConsumerOffset>) -> Result<Vec<ConsumerLag>> {
+        let admin = self.admin.as_ref()
+            .ok_or_else(|| anyhow!("Admin client not initialized"))?;
+
+        let mut lags = Vec::new();
+        for committed in offsets {
+            let (_, high) = admin.inner()
+                .fetch_watermarks(&committed.topic, committed.partition, self.timeouts.request_timeout())
+                .map_err(|e| anyhow!("Failed to fetch watermarks for {}-{}: {}",
+                                     committed.topic, committed.partition, e))?;
+
+            lags.push(ConsumerLag {
+                topic: committed.topic,
+                partition: committed.partition,
+                offset: committed.offset,
+                log_end_offset: high,
+                lag: compute_lag(committed.offset, high),
+            });
+        }
+
+        Ok(lags)
+    }
+
+    /// Fetch committed offsets of several groups in parallel
+    pub async fn snapshot_consumer_lag(self: &Arc<Self>, group_ids: &[&str]) -> Result<Vec<ConsumerOffset>> {
+        debug!("Snapshotting offsets for {} consumer groups", group_ids.len());
+
+        let mut join_set = JoinSet::new();
+        for group_id in group_ids {
+            let mapper = self.clone();
+            let group_id = group_id.to_string();
+            join_set.spawn_blocking(move || mapper.get_consumer_group_offsets(&group_id));
+        }
+
+        let mut offsets = Vec::new();
+        while let Some(result) = join_set.join_next().await {
+            let group_offsets = result.map_err(|e| anyhow!("Offset fetch task failed: {}", e))?;
+            match group_offsets {
+                Ok(group_offsets) => offsets.extend(group_offsets),
+                Err(e) => warn!("Skipping group in lag snapshot: {}", e),
+            }
+        }
+
+        Ok(offsets)
+    }
+
+    /// List consumer groups with committed offsets for `topic`.
+    ///
+    /// Only the first `MAX_GROUPS_PER_TOPIC_SCAN` groups are checked, with at most
+    /// `MAX_CONCURRENT_OFFSET_FETCHES` offset requests in flight.
+    pub async fn list_consumer_groups_for_topic(self: &Arc<Self>, topic: &str) -> Result<Vec<ConsumerGroupInfo>> {
+        debug!("Finding consumer groups for topic: {}", topic);
+
+        let mapper = self.clone();
+        let groups = tokio::task::spawn_blocking(move || mapper.list_consumer_groups())
+            .await
+            .map_err(|e| anyhow!("Group listing task failed: {}", e))??;
+
+        let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_OFFSET_FETCHES));
+        let mut join_set = JoinSet::new();
+        for group in groups.into_iter().take(MAX_GROUPS_PER_TOPIC_SCAN) {
+            let mapper = self.clone();
+            let semaphore = semaphore.clone();
+            let topic = topic.to_string();
+            join_set.spawn(async move {
+                let _permit = semaphore.acquire_owned().await?;
+                let group_id = group.group_id.clone();
+                let offsets = tokio::task::spawn_blocking(move || mapper.get_consumer_group_offsets(&group_id))
+                    .await??;
+                let consumes_topic = offsets.iter().any(|o| o.topic == topic);
+                Ok::<_, anyhow::Error>(consumes_topic.then_some(group))
+            });
+        }
+
+        let mut matching = Vec::new();
+        while let Some(result) = join_set.join_next().await {
+            match result.map_err(|e| anyhow!("Offset fetch task failed: {}", e))? {
+                Ok(Some(group)) => matching.push(group),
+                Ok(None) => {}
+                Err(e) => warn!("Skipping group while scanning topic {}: {}", topic, e),
+            }
+        }
+
+        matching.sort_by(|a, b| a.group_id.cmp(&b.group_id));
+        Ok(matching)
+    }
+
+    /// List topics that no consumer group has committed offsets for
+    pub fn get_topics_without_consumers(&self, exclude_internal: bool) -> Result<Vec<String>> {
+        info!("Finding topics without consumers for: {}", self.connection.get_name());
+
+        let topics = self.list_topics()?;
+
+        let mut consumed = HashSet::new();
+        for group in self.list_consumer_groups()? {
+            // A group that cannot be read should not hide the result for every other group
+            match self.get_consumer_group_offsets(&group.group_id) {
+                Ok(offsets) => consumed.extend(offsets.into_iter().map(|offset| offset.topic)),
+                Err(e) => warn!("Skipping consumer group '{}' while finding unconsumed topics: {}", group.group_id, e),
+            }
+        }
+
+        Ok(filter_unconsumed_topics(topics, &consumed, exclude_internal))
+    }
+
+    /// Set the SASL/OAUTHBEARER token used by the admin client and producer.
+    ///
+    /// Tokens are not refreshed by the clients themselves: call this again before
+    /// `expires_at_ms`, e.g. from `AppState::start_token_refresh`.
+    ///
+    /// The token is also set on every live consumer of this mapper and on consumers created later.
+    pub fn set_oauth_token(&self, token: &str, expires_at_ms: i64, principal_name: &str) -> Result<()> {
+        debug!("Setting OAuth token for: {}", self.connection.get_name());
+        if self.connection.sasl_mechanism != SaslMechanism::OAUTHBEARER {
+            return Err(anyhow!("{} does not use SASL OAUTHBEARER", self.connection.get_name()));
+        }
+
+        if let Some(admin) = self.admin.as_ref() {
+            set_client_oauth_token(admin.inner().native_ptr(), token, expires_at_ms, principal_name)?;
+        }
+        if let Some(producer) = self.producer.as_ref() {
+            set_client_oauth_token(producer.client().native_ptr(), token, expires_at_ms, principal_name)?;
+        }
+
+        let consumers: Vec<Arc<BaseConsumer>> = {
+            let mut consumers = self.oauth_consumers.lock().unwrap();
+            consumers.retain(|client| client.strong_count() > 0);
+            consumers.iter().filter_map(Weak::upgrade).collect()
+        };
+        for consumer in consumers {
+            set_client_oauth_token(consumer.client().native_ptr(), token, expires_at_ms, principal_name)?;
+        }
+
+        let token = OAuthAccessToken { token: token.to_string(), expires_at_ms };
+        *self.oauth_token.lock().unwrap() = Some((token, principal_name.to_string()));
+        Ok(())
+    }
+
+    /// Token configured on the connection for OAUTHBEARER, if any
+    fn static_oauth_token(&self) -> Option<OAuthAccessToken> {
+        if self.connection.sasl_mechanism != SaslMechanism::OAUTHBEARER {
+            return None;
+        }
+        self.connection.oauth_token.as_deref()
+            .filter(|token| !token.is_empty())
+            .map(OAuthAccessToken::from_static)
+    }
+
+    // ==================== ACL Management Methods ====================
+
+    /// List ACL bindings with optional filter
+    pub fn list_acls(&self, filter: Option<crate::acls::AclFilter>) -> Result<Vec<crate::acls::AclBinding>> {
+        info!("Listing ACLs for: {}", self.connection.get_name());
+
+        let admin = self.admin.as_ref()
+            .ok_or_else(|| anyhow!("Admin client not initialized"))?;
+
+        let spec = match filter {
+            Some(ref filter) => AclSpec::from_filter(filter)?,
+            None => AclSpec::match_all(),
+        };
+        let acls = acl_admin::describe_acls(admin.inner().native_ptr(), &spec, self.timeouts.request_timeout())?;
+
+        info!("Found {} ACLs", acls.len());
+        Ok(acls)
+    }
+
+    /// Create a new ACL
+    pub fn create_acl(&self, request: &crate::acls::CreateAclRequest) -> Result<()> {
+        info!("Creating ACL on: {}", self.connection.get_name());
+
+        let spec = AclSpec::from_request(request)?;
+        let admin = self.admin.as_ref()
+            .ok_or_else(|| anyhow!("Admin client not initialized"))?;
+
+        acl_admin::create_acls(admin.inner().native_ptr(), &[spec], self.timeouts.request_timeout())?;
+
+        info!("Created ACL for {} on {} '{}'", request.principal, request.resource_type, request.resource_name);
+        Ok(())
+    }
+
+    /// Delete an ACL. Deleting an ACL that does not exist only logs a warning.
+    #[allow(clippy::too_many_arguments)]
+    pub fn delete_acl(
+        &self,
+        principal: &str,
+        resource_type: &str,
+        resource_name: &str,
+        operation: &str,
+        permission_type: &str,
+        host: &str,
+        pattern_type: Option<&str>,
+    ) -> Result<()> {
+        info!("Deleting ACL on: {}", self.connection.get_name());
+
+        let filter = AclSpec::for_deletion(principal, resource_type, resource_name, operation, permission_type, host, pattern_type)?;
+        let admin = self.admin.as_ref()
+            .ok_or_else(|| anyhow!("Admin client not initialized"))?;
+
+        let deleted = acl_admin::delete_acls(admin.inner().native_ptr(), &[filter], self.timeouts.request_timeout())?;
+        if deleted == 0 {
+            warn!("No ACL matched {} {} on {} '{}'", principal, operation, resource_type, resource_name);
+        } else {
+            info!("Deleted {} ACLs", deleted);
+        }
+        Ok(())
+    }
+
+    /// Close the mapper, optionally delivering queued messages first.
+    ///
+    /// The admin client and producer are released when the last `Arc` of the mapper is
+    /// dropped, so callers remove it from the connection map once it is closed.
+    pub fn close(&self, flush_producer: bool) -> Result<()> {
+        info!("Closing Kafka mapper for: {}", self.connection.get_name());
+
+        if flush_producer && self.producer.is_some() {
+            self.flush()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Topic metadata
+#[derive(Debug, Clone)]
+pub struct TopicMetadata {
+    pub name: String,
+    pub partitions: Vec<PartitionMetadata>,
+    pub internal: bool,
+}
+
+/// Partition metadata
+#[derive(Debug, Clone)]
+pub struct PartitionMetadata {
+    pub id: i32,
+    pub leader: i32,
+    pub replicas: Vec<i32>,
+    pub isr: Vec<i32>,
+}
+
+/// Consumer group information
+#[derive(Debug, Clone)]
+pub struct ConsumerGroupInfo {
+    pub group_id: String,
+    pub state: String,
+    pub protocol_type: Option<String>,
+    /// Partition assignor chosen for the group, e.g. `range`
+    pub protocol: Option<String>,
+    pub members: Vec<ConsumerMember>,
+}
+
+impl From<&rdkafka::groups::GroupInfo> for ConsumerGroupInfo {
+    fn from(group: &rdkafka::groups::GroupInfo) -> Self {
+        Self {
+            group_id: group.name().to_string(),
+            state: group.state().to_string(),
+            protocol_type: Some(group.protocol_type().to_string()).filter(|p| !p.is_empty()),
+            protocol: Some(group.protocol().to_string()).filter(|p| !p.is_empty()),
+            members: group
+                .members()
+                .iter()
+                .map(|m| ConsumerMember {
+                    member_id: m.id().to_string(),
+                    client_id: m.client_id().to_string(),
+                    client_host: m.client_host().to_string(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl ConsumerGroupInfo {
+    /// Fail when the group has members or is rebalancing; the coordinator rejects offset
+    /// commits from outside an active group
+    pub fn ensure_inactive(&self) -> Result<()> {
+        let rebalancing = matches!(self.state.as_str(), "PreparingRebalance" | "CompletingRebalance");
+        if !self.members.is_empty() || rebalancing {
+            return Err(anyhow!(
+                "Consumer group '{}' is active ({}, {} members); stop its consumers before resetting offsets",
+                self.group_id, self.state, self.members.len()
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Where `reset_group_offsets` moves a consumer group
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum OffsetResetStrategy {
+    /// First available offset (low watermark)
+    Earliest,
+    /// End of the partition (high watermark)
+    Latest,
+    SpecificOffset(i64),
+    /// First message at or after a timestamp in milliseconds
+    ToTimestamp(i64),
+}
+
+impl OffsetResetStrategy {
+    /// Offset to commit for a partition with the given watermarks. Timestamps need a
+    /// broker lookup and are not resolved here.
+    pub fn resolve(&self, partition: i32, low: i64, high: i64) -> Result<i64> {
+        match *self {
+            Self::Earliest => Ok(low),
+            Self::Latest => Ok(high),
+            Self::SpecificOffset(offset) if (low..=high).contains(&offset) => Ok(offset),
+            Self::SpecificOffset(offset) => Err(anyhow!(
+                "Offset {} is outside the range {}..{} of partition {}", offset, low, high, partition
+            )),
+            Self::ToTimestamp(_) => Err(anyhow!("Timestamp offsets must be looked up on the broker")),
+        }
+    }
+}
+
+/// Consumer member information
+#[derive(Debug, Clone)]
+pub struct ConsumerMember {
+    pub member_id: String,
+    pub client_id: String,
+    pub client_host: String,
+}
+
+/// Committed offset of a consumer group for one partition
+#[derive(Debug, Clone)]
+pub struct ConsumerOffset {
+    pub group_id: String,
+    pub topic: String,
+    pub partition: i32,
+    pub offset: i64,
+}
+
+/// Outcome of copying committed offsets between consumer groups
+#[derive(Debug, Clone)]
+pub struct OffsetCopy {
+    pub copied_partitions: usize,
+    pub errors: Vec<String>,
+}
+
+/// Offsets on the requested topics, and an error for each requested topic without any
+fn select_offsets(
+    offsets: Vec<ConsumerOffset>,
+    topics: Option<&[String]>,
+) -> (Vec<ConsumerOffset>, Vec<String>) {
+    let Some(topics) = topics else {
+        return (offsets, Vec::new());
+    };
+
+    let selected: Vec<ConsumerOffset> = offsets
+        .into_iter()
+        .filter(|o| topics.contains(&o.topic))
+        .collect();
+    let errors = topics
+        .iter()
+        .filter(|t| !selected.iter().any(|o| &o.topic == *t))
+        .map(|t| format!("No committed offsets for topic '{}'", t))
+        .collect();
+    (selected, errors)
+}
+
+/// Consumer group lag for one partition
+#[derive(Debug, Clone)]
+pub struct ConsumerLag {
+    pub topic: String,
+    pub partition: i32,
+    pub offset: i64,
+    pub log_end_offset: i64,
+    pub lag: i64,
+}
+
+/// Messages between a committed offset and the high watermark. Never negative: the
+/// committed offset can briefly be ahead of a stale watermark.
+pub fn compute_lag(committed_offset: i64, high_watermark: i64) -> i64 {
+    (high_watermark - committed_offset).max(0)
+}
+
+/// Broker as advertised in the cluster metadata
+#[derive(Debug, Clone)]
+pub struct BrokerDetail {
+    pub id: i32,
+    pub host: String,
+    pub port: i32,
+}
+
+/// Cluster feature information
+#[derive(Debug, Clone)]
+pub struct ClusterFeatures {
+    pub kraft_enabled: bool,
+    /// Feature name -> (min version, max version)
+    pub supported_features: HashMap<String, (i16, i16)>,
+}
+
+/// KRaft brokers set `process.roles`; ZooKeeper-based brokers set `zookeeper.connect`
+/// instead, and brokers from Kafka 4.0 on no longer know that config at all
+fn is_kraft_broker(entries: &[ConfigEntry]) -> bool {
+    let value = |name: &str| entries.iter()
+        .find(|e| e.name == name)
+        .and_then(|e| e.value.as_deref())
+        .filter(|v| !v.trim().is_empty());
+    value("process.roles").is_some() || value("zookeeper.connect").is_none()
+}
+
+/// Retention-related properties of a topic, derived from its configs
+#[derive(Debug, Clone, PartialEq)]
+pub struct TopicFlags {
+    pub is_compacted: bool,
+    pub is_tiered_storage: bool,
+    pub has_delete_retention: bool,
+    /// `None` when retention is unlimited (-1) or unset
+    pub retention_ms: Option<i64>,
+}
+
+impl TopicFlags {
+    fn from_configs(configs: &HashMap<String, String>) -> Self {
+        let parse_ms = |name: &str| configs.get(name).and_then(|v| v.parse::<i64>().ok());
+
+        Self {
+            is_compacted: configs.get("cleanup.policy")
+                .map(|p| p.split(',').any(|policy| policy.trim() == "compact"))
+                .unwrap_or(false),
+            is_tiered_storage: configs.get("remote.storage.enable")
+                .map(|v| v == "true")
+                .unwrap_or(false),
+            has_delete_retention: parse_ms("delete.retention.ms").map(|ms| ms > 0).unwrap_or(false),
+            retention_ms: parse_ms("retention.ms").filter(|ms| *ms >= 0),
+        }
+    }
+}
+
+/// Config entry of a topic; sensitive values have no value
+#[derive(Debug, Clone)]
+pub struct TopicConfigInfo {
+    pub name: String,
+    pub value: Option<String>,
+    pub is_default: bool,
+    pub is_read_only: bool,
+}
+
+impl From<ConfigEntry> for TopicConfigInfo {
+    fn from(entry: ConfigEntry) -> Self {
+        Self {
+            name: entry.name,
+            value: entry.value,
+            is_default: entry.is_default,
+            is_read_only: entry.is_read_only,
+        }
+    }
+}
+
+/// Message of a `produce_messages_batch` call
+#[derive(Debug, Clone, Default)]
+pub struct BatchMessage {
+    pub key: Option<Vec<u8>>,
+    pub payload: Option<Vec<u8>>,
+    pub partition: Option<i32>,
+    pub headers: HashMap<String, String>,
+}
+
+/// Outcome of one message of a batch; `error` is `None` when it was delivered
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchProduceResult {
+    pub index: usize,
+    pub error: Option<String>,
+}
+
+/// Offset range of a partition
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartitionWatermark {
+    pub partition: i32,
+    pub low: i64,
+    pub high: i64,
+    /// Offsets between the watermarks; an upper bound on compacted or transactional topics
+    pub message_count: i64,
+}
+
+impl PartitionWatermark {
+    pub fn new(partition: i32, low: i64, high: i64) -> Self {
+        Self {
+            partition,
+            low,
+            high,
+            message_count: (high - low).max(0),
+        }
+    }
+}
+
+/// Config entry of a broker; sensitive values have no value
+#[derive(Debug)]
+pub struct BrokerConfigInfo {
+    pub name: String,
+    pub value: Option<String>,
+    pub is_sensitive: bool,
+    pub is_read_only: bool,
+    /// Where the value comes from
+    pub source: ConfigSource,
+}
+
+impl From<ConfigEntry> for BrokerConfigInfo {
+    fn from(entry: ConfigEntry) -> Self {
+        Self {
+            name: entry.name,
+            value: entry.value,
+            is_sensitive: entry.is_sensitive,
+            is_read_only: entry.is_read_only,
+            source: entry.source,
+        }
+    }
+}
+
+/// Name of a config source as used by Kafka's `ConfigEntry.ConfigSource`
+pub fn config_source_name(source: ConfigSource) -> &'static str {
+    match source {
+        ConfigSource::Unknown => "UNKNOWN",
+        ConfigSource::DynamicTopic => "DYNAMIC_TOPIC_CONFIG",
+        ConfigSource::DynamicBroker => "DYNAMIC_BROKER_CONFIG",
+        ConfigSource::DynamicDefaultBroker => "DYNAMIC_DEFAULT_BROKER_CONFIG",
+        ConfigSource::StaticBroker => "STATIC_BROKER_CONFIG",
+        ConfigSource::Default => "DEFAULT_CONFIG",
+    }
+}
+
+/// Topic with the config entries that are not at their default value
 #[derive(Debug, Clone)]
-pub struct TopicMetadata {
+pub struct TopicWithConfigs {
     pub name: String,
-    pub partitions: Vec<PartitionMetadata>,
-    pub internal: bool,
+    pub non_default_configs: HashMap<String, String>,
 }
 
-/// Partition metadata
-#[derive(Debug, Clone)]
-pub struct PartitionMetadata {
-    pub id: i32,
-    pub leader: i32,
-    pub replicas: Vec<i32>,
-    pub isr: Vec<i32>,
+/// Config entries that are explicitly set, by name
+fn non_default_configs(entries: &[ConfigEntry]) -> HashMap<String, String> {
+    entries
+        .iter()
+        .filter(|e| !e.is_default)
+        .filter_map(|e| e.value.clone().map(|v| (e.name.clone(), v)))
+        .collect()
 }
 
-/// Consumer group information
-#[derive(Debug, Clone)]
-pub struct ConsumerGroupInfo {
-    pub group_id: String,
-    pub state: String,
-    pub protocol_type: Option<String>,
-    pub members: Vec<ConsumerMember>,
+/// Config entries whose source is the hardcoded default, by name
+fn default_configs(entries: &[ConfigEntry]) -> HashMap<String, String> {
+    entries
+        .iter()
+        .filter(|e| e.source == ConfigSource::Default)
+        .filter_map(|e| e.value.clone().map(|v| (e.name.clone(), v)))
+        .collect()
 }
 
-/// Consumer member information
-#[derive(Debug, Clone)]
-pub struct ConsumerMember {
-    pub member_id: String,
-    pub client_id: String,
-    pub client_host: String,
+/// Delivery outcome per batch index, filled in by `DeliveryReportContext`
+#[derive(Default)]
+struct DeliveryReports {
+    errors: std::sync::Mutex<HashMap<usize, Option<String>>>,
+}
+
+/// Delivered and failed messages of a long-running produce, such as an import
+#[derive(Debug, Default)]
+pub struct DeliveryCounts {
+    delivered: AtomicUsize,
+    failed: AtomicUsize,
+}
+
+impl DeliveryCounts {
+    pub fn delivered(&self) -> usize {
+        self.delivered.load(Ordering::SeqCst)
+    }
+
+    pub fn failed(&self) -> usize {
+        self.failed.load(Ordering::SeqCst)
+    }
+
+    fn record(&self, delivered: bool) {
+        let count = if delivered { &self.delivered } else { &self.failed };
+        count.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// Where the delivery report of one produced message goes
+struct DeliveryTarget {
+    reports: Option<Arc<DeliveryReports>>,
+    index: usize,
+    counts: Option<Arc<DeliveryCounts>>,
+}
+
+impl DeliveryTarget {
+    /// Single messages are not waited for; failed deliveries are only logged
+    fn log_only() -> Box<Self> {
+        Box::new(Self { reports: None, index: 0, counts: None })
+    }
+
+    /// Messages of a long-running produce whose deliveries are counted, not waited for
+    fn counted(counts: &Arc<DeliveryCounts>) -> Box<Self> {
+        Box::new(Self { reports: None, index: 0, counts: Some(counts.clone()) })
+    }
+}
+
+/// Producer context that logs failed deliveries and records batch delivery reports
+struct DeliveryReportContext;
+
+impl ClientContext for DeliveryReportContext {}
+
+impl ProducerContext for DeliveryReportContext {
+    type DeliveryOpaque = Box<DeliveryTarget>;
+
+    fn delivery(&self, result: &DeliveryResult<'_>, target: Self::DeliveryOpaque) {
+        let error = result.as_ref().err().map(|(e, message)| {
+            warn!("Failed to deliver message to {}: {}", message.topic(), e);
+            e.to_string()
+        });
+        if let Some(counts) = target.counts {
+            counts.record(error.is_none());
+        }
+        if let Some(reports) = target.reports {
+            reports.errors.lock().unwrap().insert(target.index, error);
+        }
+    }
+}
+
+/// Queue each message, retrying while the producer queue is full, then wait up to
+/// `flush_timeout` for the delivery report of every queued message
+fn send_batch(
+    producer: &BaseProducer<DeliveryReportContext>,
+    topic: &str,
+    messages: &[BatchMessage],
+    flush_timeout: Duration,
+) -> Vec<BatchProduceResult> {
+    let reports = Arc::new(DeliveryReports::default());
+    let enqueue_errors: Vec<Option<String>> = messages
+        .iter()
+        .enumerate()
+        .map(|(index, message)| {
+            let target = Box::new(DeliveryTarget { reports: Some(reports.clone()), index, counts: None });
+            match build_record(
+                topic,
+                message.key.as_deref(),
+                message.payload.as_deref(),
+                &message.headers,
+                message.partition,
+                target,
+            ) {
+                Ok(record) => send_waiting_for_queue(producer, record).err().map(|e| e.to_string()),
+                Err(e) => Some(e.to_string()),
+            }
+        })
+        .collect();
+
+    if let Err(e) = producer.flush(flush_timeout) {
+        warn!("Failed to flush batch to {}: {}", topic, e);
+    }
+
+    let mut delivered = reports.errors.lock().unwrap();
+    enqueue_errors
+        .into_iter()
+        .enumerate()
+        .map(|(index, enqueue_error)| {
+            let error = enqueue_error.or_else(|| {
+                delivered.remove(&index).unwrap_or_else(|| {
+                    Some(format!("Delivery not confirmed within {:?}", flush_timeout))
+                })
+            });
+            BatchProduceResult { index, error }
+        })
+        .collect()
+}
+
+/// Send a record, serving delivery reports and retrying while the producer queue is full
+fn send_waiting_for_queue<'a>(
+    producer: &BaseProducer<DeliveryReportContext>,
+    mut record: BaseRecord<'a, [u8], [u8], Box<DeliveryTarget>>,
+) -> std::result::Result<(), KafkaError> {
+    loop {
+        match producer.send(record) {
+            Ok(()) => return Ok(()),
+            Err((KafkaError::MessageProduction(RDKafkaErrorCode::QueueFull), rejected)) => {
+                producer.poll(QUEUE_FULL_BACKOFF);
+                record = rejected;
+            }
+            Err((e, _)) => return Err(e),
+        }
+    }
+}
+
+/// Record to produce; `partition` must be a partition ID, not librdkafka's unassigned (-1)
+fn build_record<'a>(
+    topic: &'a str,
+    key: Option<&'a [u8]>,
+    payload: Option<&'a [u8]>,
+    headers: &HashMap<String, String>,
+    partition: Option<i32>,
+    target: Box<DeliveryTarget>,
+) -> Result<BaseRecord<'a, [u8], [u8], Box<DeliveryTarget>>> {
+    let mut record = BaseRecord::with_opaque_to(topic, target);
+    if let Some(payload) = payload {
+        record = record.payload(payload);
+    }
+    if let Some(key) = key {
+        record = record.key(key);
+    }
+    if let Some(partition) = partition {
+        if partition < 0 {
+            return Err(anyhow!("Invalid partition {} for topic {}", partition, topic));
+        }
+        record = record.partition(partition);
+    }
+    if !headers.is_empty() {
+        record = record.headers(owned_headers(headers));
+    }
+    Ok(record)
+}
+
+/// Record headers for produced messages, in name order
+fn owned_headers(headers: &HashMap<String, String>) -> OwnedHeaders {
+    let mut names: Vec<&String> = headers.keys().collect();
+    names.sort();
+    names.into_iter().fold(OwnedHeaders::new_with_capacity(headers.len()), |owned, name| {
+        owned.insert(Header { key: name, value: Some(headers[name].as_str()) })
+    })
+}
+
+/// Raw headers of a consumed message, in record order; null values become empty
+fn read_headers(headers: &impl Headers) -> Vec<(String, Vec<u8>)> {
+    headers
+        .iter()
+        .map(|header| (header.key.to_string(), header.value.unwrap_or_default().to_vec()))
+        .collect()
+}
+
+/// Header value as text; values that are not UTF-8 are base64 encoded
+fn header_value_string(value: &[u8]) -> String {
+    use base64::Engine;
+
+    match std::str::from_utf8(value) {
+        Ok(text) => text.to_string(),
+        Err(_) => base64::engine::general_purpose::STANDARD.encode(value),
+    }
+}
+
+/// Partition list for an `offsets_for_times` lookup: the offset field carries the timestamp
+fn timestamp_partition_list(topic: &str, partitions: &[i32], timestamp_ms: i64) -> Result<TopicPartitionList> {
+    if timestamp_ms < 0 {
+        return Err(anyhow!("Timestamp must not be negative: {}", timestamp_ms));
+    }
+
+    let mut tpl = TopicPartitionList::with_capacity(partitions.len());
+    for &partition in partitions {
+        tpl.add_partition_offset(topic, partition, Offset::Offset(timestamp_ms))
+            .map_err(|e| anyhow!("Invalid partition {}-{}: {}", topic, partition, e))?;
+    }
+    Ok(tpl)
+}
+
+/// Check that a new partition count grows the topic
+fn validate_partition_increase(topic: &str, current: usize, new_partition_count: i32) -> Result<()> {
+    if new_partition_count <= 0 || new_partition_count as usize <= current {
+        return Err(anyhow!(
+            "Topic '{}' has {} partitions; the new partition count must be greater than {} (got {})",
+            topic, current, current, new_partition_count
+        ));
+    }
+    Ok(())
+}
+
+/// Topics not present in `consumed`, sorted, optionally without internal (`__`) topics
+fn filter_unconsumed_topics(
+    topics: Vec<String>,
+    consumed: &HashSet<String>,
+    exclude_internal: bool,
+) -> Vec<String> {
+    let mut result: Vec<String> = topics
+        .into_iter()
+        .filter(|t| !consumed.contains(t))
+        .filter(|t| !(exclude_internal && t.starts_with("__")))
+        .collect();
+    result.sort();
+    result
+}
+
+/// Payload size above which messages are truncated, matching the `max.messages.bytes` default
+const DEFAULT_MAX_MESSAGE_BYTES: usize = 1048576;
+
+/// Replace payloads larger than `max_bytes` with a placeholder, returning whether it was truncated
+fn truncate_payload(payload: Option<Vec<u8>>, max_bytes: usize) -> (Option<Vec<u8>>, bool) {
+    match payload {
+        Some(p) if p.len() > max_bytes => {
+            (Some(format!("<message truncated: {} bytes>", p.len()).into_bytes()), true)
+        }
+        other => (other, false),
+    }
 }
 
 /// Consumer wrapper for message consumption
 pub struct ConsumerWrapper {
-    consumer: BaseConsumer,
+    /// Shared so the mapper can refresh the OAUTHBEARER token of a live consumer
+    consumer: Arc<BaseConsumer>,
+    /// Header decoders per topic (topics without one use UTF-8 strings)
+    header_decoders: HashMap<String, Box<dyn Decoder + Send + Sync>>,
+    /// Larger payloads are replaced with a placeholder
+    max_message_bytes: usize,
 }
 
 impl ConsumerWrapper {
-    fn new(consumer: BaseConsumer) -> Self {
-        Self { consumer }
+    fn new(consumer: BaseConsumer, header_decoders: HashMap<String, Box<dyn Decoder + Send + Sync>>) -> Self {
+        Self {
+            consumer: Arc::new(consumer),
+            header_decoders,
+            max_message_bytes: DEFAULT_MAX_MESSAGE_BYTES,
+        }
+    }
+
+    /// Set the payload size limit (from the `max.messages.bytes` setting)
+    pub fn with_max_message_bytes(mut self, max_message_bytes: usize) -> Self {
+        self.max_message_bytes = max_message_bytes;
+        self
+    }
+
+    /// Decode a header value with the topic's header decoder
+    fn decode_header(&self, topic: &str, value: &[u8]) -> String {
+        let result = match self.header_decoders.get(topic) {
+            Some(decoder) => decoder.decode(value),
+            None => Ok(header_value_string(value)),
+        };
+        result.unwrap_or_else(|e| format!("<Decode error: {}>", e))
     }
 
-    /// Subscribe to topics
+    /// Subscribe to topics, dropping a manual assignment left by `assign`
     pub fn subscribe(&self, topics: &[&str]) -> Result<()> {
+        if !self.is_subscribed()? {
+            self.consumer.unassign()
+                .map_err(|e| anyhow!("Failed to unassign partitions: {}", e))?;
+        }
         self.consumer.subscribe(topics)
             .map_err(|e| anyhow!("Failed to subscribe: {}", e))
     }
 
-    /// Assign specific partitions
+    fn is_subscribed(&self) -> Result<bool> {
+        let subscription = self.consumer.subscription()
+            .map_err(|e| anyhow!("Failed to get subscription: {}", e))?;
+        Ok(subscription.count() > 0)
+    }
+
+    /// Assign specific partitions, ending a subscription left by `subscribe`
     pub fn assign(&self, topic: &str, partition: i32, offset: i64) -> Result<()> {
+        if self.is_subscribed()? {
+            self.consumer.unsubscribe();
+        }
         let mut tpl = TopicPartitionList::new();
         tpl.add_partition_offset(topic, partition, rdkafka::Offset::Offset(offset))?;
 
@@ -511,13 +2084,30 @@ impl ConsumerWrapper {
     pub fn poll(&self, timeout_ms: u32) -> Result<Option<KafkaMessage>> {
         match self.consumer.poll(Duration::from_millis(timeout_ms as u64)) {
             Some(Ok(msg)) => {
+                let headers = msg.headers().map(read_headers).unwrap_or_default();
+                let decoded_headers = headers
+                    .iter()
+                    .map(|(name, value)| (name.clone(), self.decode_header(msg.topic(), value)))
+                    .collect();
+
+                let (payload, truncated) = truncate_payload(
+                    msg.payload().map(|p| p.to_vec()),
+                    self.max_message_bytes,
+                );
+                if truncated {
+                    warn!("Truncated oversized message at {}-{}@{}", msg.topic(), msg.partition(), msg.offset());
+                }
+
                 let kafka_msg = KafkaMessage {
                     topic: Some(msg.topic().to_string()),
                     partition: msg.partition(),
                     offset: msg.offset(),
                     key: msg.key().map(|k| k.to_vec()),
-                    payload: msg.payload().map(|p| p.to_vec()),
+                    payload,
                     timestamp: msg.timestamp().to_millis().unwrap_or(0),
+                    headers,
+                    decoded_headers,
+                    truncated,
                 };
                 Ok(Some(kafka_msg))
             }
@@ -529,6 +2119,20 @@ impl ConsumerWrapper {
         }
     }
 
+    /// Assign several partitions of a topic at the given offsets, ending a subscription
+    pub fn assign_partitions(&self, topic: &str, offsets: &[(i32, i64)]) -> Result<()> {
+        if self.is_subscribed()? {
+            self.consumer.unsubscribe();
+        }
+        let mut tpl = TopicPartitionList::with_capacity(offsets.len());
+        for &(partition, offset) in offsets {
+            tpl.add_partition_offset(topic, partition, rdkafka::Offset::Offset(offset))?;
+        }
+
+        self.consumer.assign(&tpl)
+            .map_err(|e| anyhow!("Failed to assign partitions: {}", e))
+    }
+
     /// Get consumer position (offsets)
     pub fn position(&self) -> Result<Vec<(String, i32, i64)>> {
         let tpl = self.consumer.position()
@@ -550,6 +2154,35 @@ impl ConsumerWrapper {
         Ok(result)
     }
 
+    /// Low and high watermarks of the given partitions
+    fn fetch_watermarks(&self, topic: &str, partitions: &[i32]) -> Result<Vec<(i32, i64, i64)>> {
+        partitions
+            .iter()
+            .map(|&partition| {
+                let (low, high) = self.consumer
+                    .fetch_watermarks(topic, partition, Duration::from_secs(10))
+                    .map_err(|e| anyhow!("Failed to fetch watermarks for {}-{}: {}", topic, partition, e))?;
+                Ok((partition, low, high))
+            })
+            .collect()
+    }
+
+    /// Offset of the first available message per partition
+    pub fn get_beginning_offsets(&self, topic: &str, partitions: &[i32]) -> Result<HashMap<i32, i64>> {
+        Ok(self.fetch_watermarks(topic, partitions)?
+            .into_iter()
+            .map(|(partition, low, _)| (partition, low))
+            .collect())
+    }
+
+    /// Offset the next produced message will get per partition
+    pub fn get_end_offsets(&self, topic: &str, partitions: &[i32]) -> Result<HashMap<i32, i64>> {
+        Ok(self.fetch_watermarks(topic, partitions)?
+            .into_iter()
+            .map(|(partition, _, high)| (partition, high))
+            .collect())
+    }
+
     /// Commit offsets
     pub fn commit(&self) -> Result<()> {
         let assignment = self.consumer.assignment()
@@ -558,6 +2191,20 @@ impl ConsumerWrapper {
             .map_err(|e| anyhow!("Failed to commit: {}", e))
     }
 
+    /// Commit the given `(partition, offset)` pairs of a topic for this consumer's group
+    pub fn commit_with_offsets(&self, topic: &str, offsets: &[(i32, i64)]) -> Result<()> {
+        let mut tpl = TopicPartitionList::with_capacity(offsets.len());
+        for &(partition, offset) in offsets {
+            tpl.add_partition_offset(topic, partition, Offset::Offset(offset))
+                .map_err(|e| anyhow!("Invalid offset {} for {}-{}: {}", offset, topic, partition, e))?;
+        }
+
+        self.consumer.assign(&tpl)
+            .map_err(|e| anyhow!("Failed to assign partitions: {}", e))?;
+        self.consumer.commit(&tpl, rdkafka::consumer::CommitMode::Sync)
+            .map_err(|e| anyhow!("Failed to commit offsets: {}", e))
+    }
+
     /// Seek to offset
     pub fn seek(&self, topic: &str, partition: i32, offset: i64) -> Result<()> {
         self.consumer.seek(
@@ -567,6 +2214,91 @@ impl ConsumerWrapper {
             Duration::from_secs(10)
         ).map_err(|e| anyhow!("Failed to seek: {}", e))
     }
+
+    /// Seek if the partition is already assigned, otherwise assign it at `offset`
+    pub fn seek_or_assign(&self, topic: &str, partition: i32, offset: i64) -> Result<()> {
+        if self.is_assigned(topic, partition)? {
+            self.seek(topic, partition, offset)
+        } else {
+            self.assign(topic, partition, offset)
+        }
+    }
+
+    /// Assign the partition at `offset` unless it is already assigned, in which case
+    /// consumption continues from the current position
+    pub fn ensure_assigned(&self, topic: &str, partition: i32, offset: i64) -> Result<()> {
+        if self.is_assigned(topic, partition)? {
+            Ok(())
+        } else {
+            self.assign(topic, partition, offset)
+        }
+    }
+
+    fn is_assigned(&self, topic: &str, partition: i32) -> Result<bool> {
+        let assignment = self.consumer.assignment()
+            .map_err(|e| anyhow!("Failed to get assignment: {}", e))?;
+        Ok(!self.is_subscribed()? && assignment.find_partition(topic, partition).is_some())
+    }
+}
+
+/// Hand an OAUTHBEARER token to a librdkafka client
+fn set_client_oauth_token(
+    client: *mut rdkafka::bindings::rd_kafka_t,
+    token: &str,
+    expires_at_ms: i64,
+    principal_name: &str,
+) -> Result<()> {
+    let token = CString::new(token)
+        .map_err(|e| anyhow!("Invalid OAuth token: {}", e))?;
+    let principal_name = CString::new(principal_name)
+        .map_err(|e| anyhow!("Invalid principal name: {}", e))?;
+
+    let mut err_buf = [0 as std::os::raw::c_char; 512];
+    let code = unsafe {
+        rdkafka::bindings::rd_kafka_oauthbearer_set_token(
+            client,
+            token.as_ptr(),
+            expires_at_ms,
+            principal_name.as_ptr(),
+            std::ptr::null_mut(),
+            0,
+            err_buf.as_mut_ptr(),
+            err_buf.len(),
+        )
+    };
+    if code != rdkafka::types::RDKafkaRespErr::RD_KAFKA_RESP_ERR_NO_ERROR {
+        let reason = unsafe { std::ffi::CStr::from_ptr(err_buf.as_ptr()) };
+        return Err(anyhow!("Failed to set OAuth token: {}", reason.to_string_lossy()));
+    }
+    Ok(())
+}
+
+/// librdkafka reads PEM files only, so point users at converting a Java keystore
+/// instead of letting the TLS handshake fail with an opaque error
+fn reject_java_keystore(location: &str, kind: &str) -> Result<()> {
+    // JKS and JCEKS magic numbers
+    const JAVA_KEYSTORE_MAGIC: [[u8; 4]; 2] = [[0xFE, 0xED, 0xFE, 0xED], [0xCE, 0xCE, 0xCE, 0xCE]];
+
+    let mut magic = [0u8; 4];
+    let is_java_keystore = std::fs::File::open(location)
+        .and_then(|mut file| std::io::Read::read_exact(&mut file, &mut magic))
+        .is_ok()
+        && JAVA_KEYSTORE_MAGIC.contains(&magic);
+    if is_java_keystore {
+        return Err(anyhow!(
+            "{} {} is a Java keystore; export it to a PEM file (for example with keytool -importkeystore \
+             -deststoretype PKCS12 followed by openssl pkcs12 -nodes) and select that file",
+            kind, location
+        ));
+    }
+    Ok(())
+}
+
+/// Principal reported with OAUTHBEARER tokens
+fn oauth_principal(connection: &ServerConnection) -> &str {
+    connection.sasl_callback.as_deref()
+        .filter(|principal| !principal.is_empty())
+        .unwrap_or(DEFAULT_OAUTH_PRINCIPAL)
 }
 
 /// Kafka message
@@ -578,12 +2310,212 @@ pub struct KafkaMessage {
     pub key: Option<Vec<u8>>,
     pub payload: Option<Vec<u8>>,
     pub timestamp: i64,
+    /// Raw header values
+    pub headers: Vec<(String, Vec<u8>)>,
+    /// Header values decoded with the topic's header decoder
+    pub decoded_headers: Vec<(String, String)>,
+    /// Was the payload replaced because it exceeded the size limit?
+    pub truncated: bool,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_compute_lag_never_negative() {
+        let values = [i64::MIN / 2, -1, 0, 1, 7, 1_000, i64::MAX / 2];
+        for &committed in &values {
+            for &high in &values {
+                let lag = compute_lag(committed, high);
+                assert!(lag >= 0, "lag {} for committed {} high {}", lag, committed, high);
+                if high >= committed {
+                    assert_eq!(lag, high - committed);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_sasl_client_config() {
+        let mut connection = ServerConnection::new(1, "sasl".to_string());
+        connection.broker_security_type = crate::config::BrokerSecurityType::SASL_SSL;
+        connection.sasl_callback = Some("alice".to_string());
+        connection.sasl_endpoint_token = Some("pw".to_string());
+
+        for (mechanism, expected) in [
+            (SaslMechanism::PLAIN, "PLAIN"),
+            (SaslMechanism::SCRAM_SHA_256, "SCRAM-SHA-256"),
+            (SaslMechanism::SCRAM_SHA_512, "SCRAM-SHA-512"),
+        ] {
+            connection.sasl_mechanism = mechanism;
+            let config = KafkaMapper::build_client_config("broker:9094", &connection, &KafkaClientTimeouts::default()).unwrap();
+            assert_eq!(config.get("security.protocol"), Some("sasl_ssl"));
+            assert_eq!(config.get("sasl.mechanism"), Some(expected));
+            assert_eq!(config.get("sasl.username"), Some("alice"));
+            assert_eq!(config.get("sasl.password"), Some("pw"));
+        }
+
+        connection.broker_security_type = crate::config::BrokerSecurityType::SASL_PLAINTEXT;
+        connection.jaas_config = Some(
+            r#"org.apache.kafka.common.security.plain.PlainLoginModule required username="svc" password="secret";"#
+                .to_string(),
+        );
+        let config = KafkaMapper::build_client_config("broker:9092", &connection, &KafkaClientTimeouts::default()).unwrap();
+        assert_eq!(config.get("security.protocol"), Some("sasl_plaintext"));
+        assert_eq!(config.get("sasl.username"), Some("svc"));
+        assert_eq!(config.get("sasl.password"), Some("secret"));
+        assert_eq!(config.get("ssl.ca.location"), None);
+    }
+
+    #[test]
+    fn test_gssapi_client_config() {
+        let mut connection = ServerConnection::new(1, "kerberized".to_string());
+        connection.broker_security_type = crate::config::BrokerSecurityType::SASL_PLAINTEXT;
+        connection.sasl_mechanism = SaslMechanism::GSSAPI;
+
+        let config = KafkaMapper::build_client_config("broker:9092", &connection, &KafkaClientTimeouts::default()).unwrap();
+        assert_eq!(config.get("sasl.mechanism"), Some("GSSAPI"));
+        assert_eq!(config.get("sasl.kerberos.service.name"), Some("kafka"));
+        assert_eq!(config.get("sasl.kerberos.keytab"), None);
+
+        connection.kerberos_service_name = Some("kafka-prod".to_string());
+        connection.kerberos_principal = Some("explorer@EXAMPLE.COM".to_string());
+        connection.kerberos_keytab = Some("/etc/security/kafka.keytab".to_string());
+        let config = KafkaMapper::build_client_config("broker:9092", &connection, &KafkaClientTimeouts::default()).unwrap();
+        assert_eq!(config.get("sasl.kerberos.service.name"), Some("kafka-prod"));
+        assert_eq!(config.get("sasl.kerberos.principal"), Some("explorer@EXAMPLE.COM"));
+        assert_eq!(config.get("sasl.kerberos.keytab"), Some("/etc/security/kafka.keytab"));
+    }
+
+    #[test]
+    fn test_oauthbearer_client_config() {
+        let mut connection = ServerConnection::new(1, "cloud".to_string());
+        connection.broker_security_type = crate::config::BrokerSecurityType::SASL_SSL;
+        connection.sasl_mechanism = SaslMechanism::OAUTHBEARER;
+
+        let config = KafkaMapper::build_client_config("broker:9094", &connection, &KafkaClientTimeouts::default()).unwrap();
+        assert_eq!(config.get("sasl.mechanism"), Some("OAUTHBEARER"));
+        assert_eq!(config.get("sasl.oauthbearer.config"), Some("principal=offset-explorer"));
+        assert_eq!(config.get("sasl.username"), None);
+
+        connection.sasl_callback = Some("svc-explorer".to_string());
+        let config = KafkaMapper::build_client_config("broker:9094", &connection, &KafkaClientTimeouts::default()).unwrap();
+        assert_eq!(config.get("sasl.oauthbearer.config"), Some("principal=svc-explorer"));
+    }
+
+    #[test]
+    fn test_ssl_client_config() {
+        let mut connection = ServerConnection::new(1, "secure".to_string());
+        connection.broker_security_type = crate::config::BrokerSecurityType::SSL;
+        connection.truststore_location = Some("/certs/ca.pem".to_string());
+        connection.keystore_location = Some("/certs/client.pem".to_string());
+        connection.keystore_privatekey = Some("secret".to_string());
+        connection.validate_ssl_endpoint_hostname = false;
+
+        let config = KafkaMapper::build_client_config("broker:9093", &connection, &KafkaClientTimeouts::default()).unwrap();
+        assert_eq!(config.get("security.protocol"), Some("ssl"));
+        assert_eq!(config.get("ssl.ca.location"), Some("/certs/ca.pem"));
+        assert_eq!(config.get("ssl.certificate.location"), Some("/certs/client.pem"));
+        assert_eq!(config.get("ssl.key.location"), Some("/certs/client.pem"));
+        assert_eq!(config.get("ssl.key.password"), Some("secret"));
+        assert_eq!(config.get("ssl.endpoint.identification.algorithm"), Some("none"));
+
+        connection.broker_security_type = crate::config::BrokerSecurityType::PLAINTEXT;
+        let config = KafkaMapper::build_client_config("broker:9092", &connection, &KafkaClientTimeouts::default()).unwrap();
+        assert_eq!(config.get("security.protocol"), Some("plaintext"));
+        assert_eq!(config.get("ssl.ca.location"), None);
+    }
+
+    #[test]
+    fn test_java_truststore_is_rejected() {
+        let path = std::env::temp_dir().join(format!("offset-explorer-truststore-{}.jks", std::process::id()));
+        std::fs::write(&path, [0xFE, 0xED, 0xFE, 0xED, 0, 0, 0, 2]).unwrap();
+
+        let mut connection = ServerConnection::new(1, "secure".to_string());
+        connection.broker_security_type = crate::config::BrokerSecurityType::SSL;
+        connection.truststore_location = Some(path.to_string_lossy().into_owned());
+        let result = KafkaMapper::build_client_config("broker:9093", &connection, &KafkaClientTimeouts::default());
+        let _ = std::fs::remove_file(&path);
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("Java keystore"), "{}", err);
+    }
+
+    #[test]
+    fn test_clients_can_be_created_for_each_security_type() {
+        let mut connection = ServerConnection::new(1, "security".to_string());
+        connection.sasl_callback = Some("alice".to_string());
+        connection.sasl_endpoint_token = Some("pw".to_string());
+
+        for &security_type in crate::config::BrokerSecurityType::all_variants() {
+            connection.broker_security_type = security_type;
+            let mechanisms: &[SaslMechanism] = if security_type.is_sasl() {
+                &[
+                    SaslMechanism::PLAIN,
+                    SaslMechanism::SCRAM_SHA_256,
+                    SaslMechanism::SCRAM_SHA_512,
+                    SaslMechanism::OAUTHBEARER,
+                ]
+            } else {
+                &[SaslMechanism::PLAIN]
+            };
+            for &mechanism in mechanisms {
+                connection.sasl_mechanism = mechanism;
+                let result = KafkaMapper::create_producer("127.0.0.1:1", &connection, &KafkaClientTimeouts::default());
+                assert!(result.is_ok(), "{:?} {:?}: {}", security_type, mechanism, result.err().unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_consumer_switches_between_assign_and_subscribe() {
+        let consumer: BaseConsumer = ClientConfig::new()
+            .set("bootstrap.servers", "127.0.0.1:1")
+            .set("group.id", "browse-test")
+            .create()
+            .unwrap();
+        let consumer = ConsumerWrapper::new(consumer, HashMap::new());
+
+        consumer.seek_or_assign("orders", 0, 5).unwrap();
+        assert!(consumer.is_assigned("orders", 0).unwrap());
+        consumer.ensure_assigned("orders", 0, 0).unwrap();
+        assert!(!consumer.is_assigned("orders", 1).unwrap());
+
+        consumer.subscribe(&["orders"]).unwrap();
+        assert!(consumer.is_subscribed().unwrap());
+        assert!(!consumer.is_assigned("orders", 0).unwrap());
+
+        consumer.ensure_assigned("orders", 1, 0).unwrap();
+        assert!(!consumer.is_subscribed().unwrap());
+        assert!(consumer.is_assigned("orders", 1).unwrap());
+        assert!(!consumer.is_assigned("orders", 0).unwrap());
+    }
+
+    #[test]
+    fn test_oauth_token_is_set_on_consumers() {
+        let mut connection = ServerConnection::new(1, "cloud".to_string());
+        connection.bootstrap_servers = "127.0.0.1:1".to_string();
+        connection.broker_security_type = crate::config::BrokerSecurityType::SASL_PLAINTEXT;
+        connection.sasl_mechanism = SaslMechanism::OAUTHBEARER;
+        let mapper = KafkaMapper::new(connection.clone(), KafkaClientTimeouts::default()).unwrap();
+
+        let consumer = mapper.create_consumer("browse").unwrap();
+        drop(mapper.create_consumer("dropped").unwrap());
+        let expires_at_ms = chrono::Utc::now().timestamp_millis() + 60_000;
+        mapper.set_oauth_token("token", expires_at_ms, "svc").unwrap();
+        assert_eq!(mapper.oauth_consumers.lock().unwrap().len(), 1);
+        assert_eq!(mapper.oauth_token.lock().unwrap().as_ref().unwrap().1, "svc");
+        drop(consumer);
+
+        connection.sasl_mechanism = SaslMechanism::PLAIN;
+        connection.sasl_callback = Some("alice".to_string());
+        connection.sasl_endpoint_token = Some("pw".to_string());
+        let mapper = KafkaMapper::new(connection, KafkaClientTimeouts::default()).unwrap();
+        assert!(mapper.set_oauth_token("token", expires_at_ms, "svc").is_err());
+        drop(mapper.create_consumer("browse").unwrap());
+        assert!(mapper.oauth_consumers.lock().unwrap().is_empty());
+    }
+
     #[test]
     fn test_metadata_structs() {
         let partition = PartitionMetadata {
@@ -602,8 +2534,328 @@ mod tests {
             group_id: "test-group".to_string(),
             state: "Stable".to_string(),
             protocol_type: Some("consumer".to_string()),
+            protocol: Some("range".to_string()),
             members: vec![],
         };
         assert_eq!(group.group_id, "test-group");
     }
+
+    #[test]
+    fn test_truncate_oversized_payload() {
+        let (payload, truncated) = truncate_payload(Some(vec![0u8; 2048]), 1024);
+        assert!(truncated);
+        assert_eq!(payload, Some(b"<message truncated: 2048 bytes>".to_vec()));
+
+        let (payload, truncated) = truncate_payload(Some(vec![1u8; 1024]), 1024);
+        assert!(!truncated);
+        assert_eq!(payload.map(|p| p.len()), Some(1024));
+
+        assert_eq!(truncate_payload(None, 0), (None, false));
+    }
+
+    #[test]
+    fn test_non_default_configs() {
+        let entry = |name: &str, value: &str, is_default| ConfigEntry {
+            name: name.to_string(),
+            value: Some(value.to_string()),
+            source: rdkafka::admin::ConfigSource::Default,
+            is_read_only: false,
+            is_default,
+            is_sensitive: false,
+        };
+
+        let configs = non_default_configs(&[
+            entry("retention.ms", "3600000", false),
+            entry("cleanup.policy", "delete", true),
+        ]);
+        assert_eq!(configs.len(), 1);
+        assert_eq!(configs.get("retention.ms").map(String::as_str), Some("3600000"));
+
+        let mut static_entry = entry("log.dirs", "/data", false);
+        static_entry.source = rdkafka::admin::ConfigSource::StaticBroker;
+        let defaults = default_configs(&[entry("num.io.threads", "8", true), static_entry]);
+        assert_eq!(defaults.len(), 1);
+        assert!(defaults.contains_key("num.io.threads"));
+    }
+
+    #[test]
+    fn test_is_kraft_broker() {
+        let entry = |name: &str, value: Option<&str>| ConfigEntry {
+            name: name.to_string(),
+            value: value.map(str::to_string),
+            source: rdkafka::admin::ConfigSource::StaticBroker,
+            is_read_only: true,
+            is_default: false,
+            is_sensitive: false,
+        };
+
+        assert!(is_kraft_broker(&[entry("process.roles", Some("broker,controller"))]));
+        assert!(is_kraft_broker(&[entry("log.dirs", Some("/data"))]));
+        assert!(!is_kraft_broker(&[
+            entry("process.roles", Some("")),
+            entry("zookeeper.connect", Some("zk:2181")),
+        ]));
+        assert!(!is_kraft_broker(&[
+            entry("process.roles", None),
+            entry("zookeeper.connect", Some("zk:2181/kafka")),
+        ]));
+    }
+
+    #[test]
+    fn test_partition_watermark_message_count() {
+        assert_eq!(PartitionWatermark::new(0, 100, 250).message_count, 150);
+        assert_eq!(PartitionWatermark::new(1, 0, 0).message_count, 0);
+        // librdkafka reports -1 for both watermarks when they are unknown
+        assert_eq!(PartitionWatermark::new(2, 5, -1).message_count, 0);
+    }
+
+    #[test]
+    fn test_timestamp_partition_list() {
+        let tpl = timestamp_partition_list("orders", &[0, 1, 2], 1_700_000_000_000).unwrap();
+        assert_eq!(tpl.count(), 3);
+        for (elem, partition) in tpl.elements().iter().zip(0..) {
+            assert_eq!(elem.topic(), "orders");
+            assert_eq!(elem.partition(), partition);
+            assert_eq!(elem.offset(), Offset::Offset(1_700_000_000_000));
+        }
+
+        assert_eq!(timestamp_partition_list("orders", &[], 0).unwrap().count(), 0);
+        assert!(timestamp_partition_list("orders", &[0], -1).is_err());
+    }
+
+    #[test]
+    fn test_build_record_partition() {
+        let headers = HashMap::new();
+        let record = build_record("orders", Some(b"k"), Some(b"v"), &headers, Some(3), DeliveryTarget::log_only()).unwrap();
+        assert_eq!(record.partition, Some(3));
+        assert_eq!(record.key, Some(&b"k"[..]));
+        assert_eq!(record.payload, Some(&b"v"[..]));
+        assert!(record.headers.is_none());
+
+        let record = build_record("orders", None, None, &headers, None, DeliveryTarget::log_only()).unwrap();
+        assert_eq!(record.partition, None);
+        assert_eq!(record.payload, None);
+
+        assert!(build_record("orders", None, None, &headers, Some(-1), DeliveryTarget::log_only()).is_err());
+    }
+
+    fn offline_producer() -> BaseProducer<DeliveryReportContext> {
+        ClientConfig::new()
+            .set("bootstrap.servers", "127.0.0.1:1")
+            .set("message.max.bytes", "1000")
+            .set("message.timeout.ms", "500")
+            .create_with_context(DeliveryReportContext)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_send_batch_unreachable_broker_fails_delivery() {
+        let producer = offline_producer();
+        let messages = vec![
+            BatchMessage { payload: Some(b"first".to_vec()), ..Default::default() },
+            BatchMessage {
+                key: Some(b"k".to_vec()),
+                payload: Some(b"second".to_vec()),
+                partition: Some(0),
+                headers: HashMap::from([("source".to_string(), "test".to_string())]),
+            },
+        ];
+
+        let results = send_batch(&producer, "orders", &messages, Duration::from_secs(10));
+        assert_eq!(results.len(), 2);
+        for result in &results {
+            let error = result.error.as_deref().unwrap();
+            assert!(error.contains("timed out"), "{}", error);
+        }
+    }
+
+    #[test]
+    fn test_counted_deliveries_record_failures() {
+        let producer = offline_producer();
+        let counts = Arc::new(DeliveryCounts::default());
+        for payload in [&b"first"[..], &b"second"[..]] {
+            let record = build_record("orders", None, Some(payload), &HashMap::new(), None, DeliveryTarget::counted(&counts)).unwrap();
+            send_waiting_for_queue(&producer, record).unwrap();
+        }
+
+        let _ = producer.flush(Duration::from_secs(10));
+        assert_eq!(counts.delivered(), 0);
+        assert_eq!(counts.failed(), 2);
+    }
+
+    #[test]
+    fn test_send_batch_partial_failure() {
+        let producer = offline_producer();
+        let messages = vec![
+            BatchMessage { payload: Some(b"ok".to_vec()), ..Default::default() },
+            BatchMessage { payload: Some(vec![b'x'; 2000]), ..Default::default() },
+            BatchMessage { payload: Some(b"bad partition".to_vec()), partition: Some(-5), ..Default::default() },
+            BatchMessage { payload: Some(b"also ok".to_vec()), ..Default::default() },
+        ];
+
+        let results = send_batch(&producer, "orders", &messages, Duration::from_secs(10));
+        assert!(results.iter().all(|r| r.error.is_some()));
+        assert!(results[1].error.as_deref().unwrap().contains("too large"));
+        assert!(results[2].error.as_deref().unwrap().contains("Invalid partition"));
+        assert!(results[3].error.as_deref().unwrap().contains("timed out"));
+    }
+
+    #[test]
+    fn test_consumer_group_ensure_inactive() {
+        let mut group = ConsumerGroupInfo {
+            group_id: "billing".to_string(),
+            state: "Empty".to_string(),
+            protocol_type: Some("consumer".to_string()),
+            protocol: None,
+            members: Vec::new(),
+        };
+        assert!(group.ensure_inactive().is_ok());
+
+        group.state = "CompletingRebalance".to_string();
+        assert!(group.ensure_inactive().is_err());
+
+        group.state = "Stable".to_string();
+        group.members.push(ConsumerMember {
+            member_id: "consumer-1".to_string(),
+            client_id: "billing-app".to_string(),
+            client_host: "/10.0.0.1".to_string(),
+        });
+        let err = group.ensure_inactive().unwrap_err().to_string();
+        assert!(err.contains("'billing' is active"), "{}", err);
+        assert!(err.contains("1 members"), "{}", err);
+    }
+
+    #[test]
+    fn test_offset_reset_strategy_resolve() {
+        assert_eq!(OffsetResetStrategy::Earliest.resolve(0, 10, 50).unwrap(), 10);
+        assert_eq!(OffsetResetStrategy::Latest.resolve(0, 10, 50).unwrap(), 50);
+        assert_eq!(OffsetResetStrategy::SpecificOffset(10).resolve(0, 10, 50).unwrap(), 10);
+        assert_eq!(OffsetResetStrategy::SpecificOffset(50).resolve(0, 10, 50).unwrap(), 50);
+
+        let err = OffsetResetStrategy::SpecificOffset(5).resolve(2, 10, 50).unwrap_err().to_string();
+        assert!(err.contains("partition 2"), "{}", err);
+        assert!(OffsetResetStrategy::SpecificOffset(51).resolve(0, 10, 50).is_err());
+        assert!(OffsetResetStrategy::ToTimestamp(0).resolve(0, 10, 50).is_err());
+    }
+
+    #[test]
+    fn test_offset_reset_strategy_json() {
+        let parse = |json| serde_json::from_value::<OffsetResetStrategy>(json).unwrap();
+        assert_eq!(parse(serde_json::json!({"type": "Earliest"})), OffsetResetStrategy::Earliest);
+        assert_eq!(
+            parse(serde_json::json!({"type": "SpecificOffset", "value": 42})),
+            OffsetResetStrategy::SpecificOffset(42)
+        );
+        assert_eq!(
+            parse(serde_json::json!({"type": "ToTimestamp", "value": 1_700_000_000_000i64})),
+            OffsetResetStrategy::ToTimestamp(1_700_000_000_000)
+        );
+    }
+
+    #[test]
+    fn test_header_round_trip() {
+        let produced = HashMap::from([
+            ("traceId".to_string(), "ABC-123".to_string()),
+            ("source".to_string(), "billing".to_string()),
+        ]);
+
+        let consumed = read_headers(&owned_headers(&produced));
+        assert_eq!(consumed, vec![
+            ("source".to_string(), b"billing".to_vec()),
+            ("traceId".to_string(), b"ABC-123".to_vec()),
+        ]);
+
+        let null_value = OwnedHeaders::new().insert(Header::<&str> { key: "empty", value: None });
+        assert_eq!(read_headers(&null_value), vec![("empty".to_string(), Vec::new())]);
+    }
+
+    #[test]
+    fn test_header_value_string() {
+        assert_eq!(header_value_string(b"billing"), "billing");
+        assert_eq!(header_value_string(&[0xff, 0x00, 0x10]), "/wAQ");
+        assert_eq!(header_value_string(b""), "");
+    }
+
+    #[test]
+    fn test_validate_partition_increase() {
+        assert!(validate_partition_increase("orders", 3, 6).is_ok());
+        assert!(validate_partition_increase("orders", 3, 4).is_ok());
+
+        let err = validate_partition_increase("orders", 3, 3).unwrap_err().to_string();
+        assert!(err.contains("has 3 partitions"), "{}", err);
+        assert!(validate_partition_increase("orders", 3, 2).is_err());
+        assert!(validate_partition_increase("orders", 3, -1).is_err());
+    }
+
+    #[test]
+    fn test_filter_unconsumed_topics() {
+        let topics = vec!["orders".to_string(), "__consumer_offsets".to_string(), "audit".to_string()];
+        let consumed: HashSet<String> = ["orders".to_string()].into_iter().collect();
+
+        assert_eq!(filter_unconsumed_topics(topics.clone(), &consumed, true), vec!["audit"]);
+        assert_eq!(
+            filter_unconsumed_topics(topics, &consumed, false),
+            vec!["__consumer_offsets", "audit"]
+        );
+    }
+
+    #[test]
+    fn test_topic_flags_from_configs() {
+        let configs: HashMap<String, String> = [
+            ("cleanup.policy", "compact,delete"),
+            ("remote.storage.enable", "true"),
+            ("delete.retention.ms", "86400000"),
+            ("retention.ms", "-1"),
+        ]
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+        let flags = TopicFlags::from_configs(&configs);
+        assert!(flags.is_compacted);
+        assert!(flags.is_tiered_storage);
+        assert!(flags.has_delete_retention);
+        assert_eq!(flags.retention_ms, None);
+
+        let flags = TopicFlags::from_configs(&HashMap::from([
+            ("cleanup.policy".to_string(), "delete".to_string()),
+            ("retention.ms".to_string(), "604800000".to_string()),
+        ]));
+        assert!(!flags.is_compacted);
+        assert!(!flags.is_tiered_storage);
+        assert_eq!(flags.retention_ms, Some(604800000));
+    }
+
+    #[test]
+    fn test_select_offsets() {
+        let offset = |topic: &str, partition| ConsumerOffset {
+            group_id: "old".to_string(),
+            topic: topic.to_string(),
+            partition,
+            offset: 10,
+        };
+        let offsets = vec![offset("orders", 0), offset("orders", 1), offset("audit", 0)];
+
+        let (all, errors) = select_offsets(offsets.clone(), None);
+        assert_eq!(all.len(), 3);
+        assert!(errors.is_empty());
+
+        let topics = vec!["orders".to_string(), "missing".to_string()];
+        let (selected, errors) = select_offsets(offsets, Some(&topics));
+        assert_eq!(selected.len(), 2);
+        assert_eq!(errors, vec!["No committed offsets for topic 'missing'"]);
+    }
+
+    #[test]
+    fn test_timeouts_from_settings() {
+        let mut settings = UserSettings::new();
+        settings.set("broker_read_timeout", crate::config::SettingValue::Integer(30000), true,
+                     crate::config::SettingDataType::Integer, true);
+        settings.set("auto_refresh_interval_ms", crate::config::SettingValue::Integer(60000), true,
+                     crate::config::SettingDataType::Integer, true);
+
+        let timeouts = KafkaClientTimeouts::from_settings(&settings);
+        assert_eq!(timeouts.request_timeout_ms, 30000);
+        assert_eq!(timeouts.metadata_max_age_ms, 30000);
+    }
 }
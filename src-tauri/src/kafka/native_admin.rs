@@ -0,0 +1,82 @@
+// Copyright 2024 Offset Explorer Rust Team
+// Licensed under Proprietary License
+//
+// Plumbing for librdkafka Admin API calls that rdkafka's AdminClient does not wrap
+
+use anyhow::{Result, anyhow};
+use rdkafka::bindings as rdsys;
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::time::Duration;
+
+/// Extra time to wait for the admin result after the broker request timed out
+const RESULT_POLL_MARGIN: Duration = Duration::from_secs(1);
+
+/// Submit an admin request and wait for its result event
+pub(crate) fn run_admin_op(
+    client: *mut rdsys::rd_kafka_t,
+    op: rdsys::rd_kafka_admin_op_t,
+    timeout: Duration,
+    submit: impl FnOnce(*const rdsys::rd_kafka_AdminOptions_t, *mut rdsys::rd_kafka_queue_t),
+) -> Result<NativeEvent> {
+    let timeout_ms = timeout.as_millis().min(i32::MAX as u128) as i32;
+    let queue = NativeQueue(unsafe { rdsys::rd_kafka_queue_new(client) });
+    let options = NativeOptions(unsafe { rdsys::rd_kafka_AdminOptions_new(client, op) });
+
+    let mut err_buf = [0 as c_char; 512];
+    let code = unsafe {
+        rdsys::rd_kafka_AdminOptions_set_request_timeout(options.0, timeout_ms, err_buf.as_mut_ptr(), err_buf.len())
+    };
+    if code != rdkafka::types::RDKafkaRespErr::RD_KAFKA_RESP_ERR_NO_ERROR {
+        return Err(anyhow!("Invalid admin request timeout: {}", unsafe { c_string(err_buf.as_ptr()) }));
+    }
+
+    submit(options.0, queue.0);
+
+    let poll_ms = (timeout + RESULT_POLL_MARGIN).as_millis().min(i32::MAX as u128) as i32;
+    let event = unsafe { rdsys::rd_kafka_queue_poll(queue.0, poll_ms) };
+    if event.is_null() {
+        return Err(anyhow!("Timed out waiting for admin request result"));
+    }
+    let event = NativeEvent(event);
+
+    let code = unsafe { rdsys::rd_kafka_event_error(event.0) };
+    if code != rdkafka::types::RDKafkaRespErr::RD_KAFKA_RESP_ERR_NO_ERROR {
+        return Err(anyhow!("Admin request failed: {}", unsafe {
+            c_string(rdsys::rd_kafka_event_error_string(event.0))
+        }));
+    }
+    Ok(event)
+}
+
+/// Copy a C string owned by librdkafka; null becomes an empty string
+pub(crate) unsafe fn c_string(ptr: *const c_char) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+    CStr::from_ptr(ptr).to_string_lossy().into_owned()
+}
+
+struct NativeQueue(*mut rdsys::rd_kafka_queue_t);
+
+impl Drop for NativeQueue {
+    fn drop(&mut self) {
+        unsafe { rdsys::rd_kafka_queue_destroy(self.0) }
+    }
+}
+
+struct NativeOptions(*mut rdsys::rd_kafka_AdminOptions_t);
+
+impl Drop for NativeOptions {
+    fn drop(&mut self) {
+        unsafe { rdsys::rd_kafka_AdminOptions_destroy(self.0) }
+    }
+}
+
+pub(crate) struct NativeEvent(pub(crate) *mut rdsys::rd_kafka_event_t);
+
+impl Drop for NativeEvent {
+    fn drop(&mut self) {
+        unsafe { rdsys::rd_kafka_event_destroy(self.0) }
+    }
+}
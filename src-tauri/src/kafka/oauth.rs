@@ -0,0 +1,124 @@
+// Copyright 2024 Offset Explorer Rust Team
+// Licensed under Proprietary License
+//
+// OAuth2 client credentials flow for SASL/OAUTHBEARER
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+/// Access token issued by an OAuth2 token endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct OAuthAccessToken {
+    pub token: String,
+    /// Expiry time in milliseconds since the Unix epoch
+    pub expires_at_ms: i64,
+}
+
+impl OAuthAccessToken {
+    /// Wrap a token configured by the user. JWTs expire at their `exp` claim,
+    /// opaque tokens are assumed to live for the default lifetime.
+    pub fn from_static(token: &str) -> Self {
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        Self {
+            token: token.to_string(),
+            expires_at_ms: jwt_expiry_ms(token)
+                .unwrap_or(now_ms + DEFAULT_TOKEN_LIFETIME_SECS * 1000),
+        }
+    }
+
+    /// Milliseconds until the token expires (negative if already expired)
+    pub fn remaining_ms(&self) -> i64 {
+        self.expires_at_ms - chrono::Utc::now().timestamp_millis()
+    }
+}
+
+/// Token endpoint response (RFC 6749 section 5.1)
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    /// Lifetime in seconds
+    expires_in: Option<i64>,
+}
+
+/// Lifetime assumed when the endpoint does not return `expires_in`
+const DEFAULT_TOKEN_LIFETIME_SECS: i64 = 3600;
+
+/// Request a token using the client credentials grant
+pub async fn fetch_token(
+    token_endpoint_url: &str,
+    client_id: &str,
+    client_secret: &str,
+) -> Result<OAuthAccessToken> {
+    debug!("Requesting OAuth token from {}", token_endpoint_url);
+
+    let response = reqwest::Client::new()
+        .post(token_endpoint_url)
+        .form(&[
+            ("grant_type", "client_credentials"),
+            ("client_id", client_id),
+            ("client_secret", client_secret),
+        ])
+        .send()
+        .await
+        .map_err(|e| anyhow!("Token request failed: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(anyhow!("Token endpoint returned {}: {}", status, body));
+    }
+
+    let body = response.text().await?;
+    parse_token_response(&body, chrono::Utc::now().timestamp_millis())
+}
+
+/// Expiry of a JWT from its `exp` claim (seconds since the epoch)
+fn jwt_expiry_ms(token: &str) -> Option<i64> {
+    use base64::Engine;
+
+    let payload = token.split('.').nth(1)?;
+    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(payload.trim_end_matches('='))
+        .ok()?;
+    let claims: serde_json::Value = serde_json::from_slice(&bytes).ok()?;
+    claims.get("exp")?.as_i64().map(|exp| exp * 1000)
+}
+
+/// Parse a token endpoint response received at `now_ms`
+fn parse_token_response(body: &str, now_ms: i64) -> Result<OAuthAccessToken> {
+    let response: TokenResponse = serde_json::from_str(body)
+        .map_err(|e| anyhow!("Invalid token response: {}", e))?;
+
+    let lifetime_secs = response.expires_in.unwrap_or(DEFAULT_TOKEN_LIFETIME_SECS);
+    Ok(OAuthAccessToken {
+        token: response.access_token,
+        expires_at_ms: now_ms + lifetime_secs * 1000,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_token_response() {
+        let body = r#"{"access_token":"abc","token_type":"Bearer","expires_in":300}"#;
+        let token = parse_token_response(body, 1_000).unwrap();
+        assert_eq!(token.token, "abc");
+        assert_eq!(token.expires_at_ms, 301_000);
+
+        let token = parse_token_response(r#"{"access_token":"abc"}"#, 0).unwrap();
+        assert_eq!(token.expires_at_ms, DEFAULT_TOKEN_LIFETIME_SECS * 1000);
+    }
+
+    #[test]
+    fn test_jwt_expiry() {
+        // {"alg":"none"}.{"sub":"explorer","exp":1700000000}.
+        let jwt = "eyJhbGciOiJub25lIn0.eyJzdWIiOiJleHBsb3JlciIsImV4cCI6MTcwMDAwMDAwMH0.";
+        assert_eq!(jwt_expiry_ms(jwt), Some(1_700_000_000_000));
+        assert_eq!(OAuthAccessToken::from_static(jwt).expires_at_ms, 1_700_000_000_000);
+        assert_eq!(jwt_expiry_ms("opaque-token"), None);
+        assert!(OAuthAccessToken::from_static("opaque-token").remaining_ms() > 0);
+    }
+}
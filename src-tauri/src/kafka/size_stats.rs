@@ -0,0 +1,202 @@
+// Copyright 2024 Offset Explorer Rust Team
+// Licensed under Proprietary License
+//
+// Streaming message size statistics
+
+/// Streaming quantile estimate using the P² algorithm (Jain & Chlamtac, 1985).
+///
+/// Keeps five markers instead of the observations, so memory use is constant.
+#[derive(Debug, Clone)]
+pub struct P2Quantile {
+    p: f64,
+    count: usize,
+    /// Marker heights
+    q: [f64; 5],
+    /// Actual marker positions (1-based)
+    n: [f64; 5],
+    /// Desired marker positions
+    np: [f64; 5],
+    /// Desired position increments per observation
+    dn: [f64; 5],
+}
+
+impl P2Quantile {
+    pub fn new(p: f64) -> Self {
+        Self {
+            p,
+            count: 0,
+            q: [0.0; 5],
+            n: [1.0, 2.0, 3.0, 4.0, 5.0],
+            np: [1.0, 1.0 + 2.0 * p, 1.0 + 4.0 * p, 3.0 + 2.0 * p, 5.0],
+            dn: [0.0, p / 2.0, p, (1.0 + p) / 2.0, 1.0],
+        }
+    }
+
+    pub fn add(&mut self, x: f64) {
+        if self.count < 5 {
+            self.q[self.count] = x;
+            self.count += 1;
+            if self.count == 5 {
+                self.q.sort_by(|a, b| a.total_cmp(b));
+            }
+            return;
+        }
+        self.count += 1;
+
+        // Cell containing x, widening the extreme markers if needed
+        let k = if x < self.q[0] {
+            self.q[0] = x;
+            0
+        } else if x >= self.q[4] {
+            self.q[4] = x;
+            3
+        } else {
+            (1..5).find(|&i| x < self.q[i]).unwrap() - 1
+        };
+
+        for i in k + 1..5 {
+            self.n[i] += 1.0;
+        }
+        for i in 0..5 {
+            self.np[i] += self.dn[i];
+        }
+
+        for i in 1..4 {
+            let d = self.np[i] - self.n[i];
+            if (d >= 1.0 && self.n[i + 1] - self.n[i] > 1.0)
+                || (d <= -1.0 && self.n[i - 1] - self.n[i] < -1.0)
+            {
+                let d = d.signum();
+                let parabolic = self.parabolic(i, d);
+                self.q[i] = if self.q[i - 1] < parabolic && parabolic < self.q[i + 1] {
+                    parabolic
+                } else {
+                    self.linear(i, d)
+                };
+                self.n[i] += d;
+            }
+        }
+    }
+
+    /// Current estimate (exact while fewer than five values were added)
+    pub fn value(&self) -> f64 {
+        if self.count >= 5 {
+            return self.q[2];
+        }
+        if self.count == 0 {
+            return 0.0;
+        }
+
+        let mut values = self.q[..self.count].to_vec();
+        values.sort_by(|a, b| a.total_cmp(b));
+        values[((self.count - 1) as f64 * self.p).round() as usize]
+    }
+
+    fn parabolic(&self, i: usize, d: f64) -> f64 {
+        let (q, n) = (&self.q, &self.n);
+        q[i] + d / (n[i + 1] - n[i - 1])
+            * ((n[i] - n[i - 1] + d) * (q[i + 1] - q[i]) / (n[i + 1] - n[i])
+                + (n[i + 1] - n[i] - d) * (q[i] - q[i - 1]) / (n[i] - n[i - 1]))
+    }
+
+    fn linear(&self, i: usize, d: f64) -> f64 {
+        let j = if d > 0.0 { i + 1 } else { i - 1 };
+        self.q[i] + d * (self.q[j] - self.q[i]) / (self.n[j] - self.n[i])
+    }
+}
+
+/// Percentiles and totals of message sizes (key + value bytes)
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageSizeDistribution {
+    pub p50_bytes: usize,
+    pub p90_bytes: usize,
+    pub p99_bytes: usize,
+    pub max_bytes: usize,
+    pub avg_bytes: f64,
+    pub sample_count: usize,
+}
+
+/// Accumulates message sizes in constant memory
+#[derive(Debug, Clone)]
+pub struct SizeStats {
+    p50: P2Quantile,
+    p90: P2Quantile,
+    p99: P2Quantile,
+    max: usize,
+    total: u64,
+    count: usize,
+}
+
+impl SizeStats {
+    pub fn new() -> Self {
+        Self {
+            p50: P2Quantile::new(0.5),
+            p90: P2Quantile::new(0.9),
+            p99: P2Quantile::new(0.99),
+            max: 0,
+            total: 0,
+            count: 0,
+        }
+    }
+
+    pub fn add(&mut self, size: usize) {
+        self.p50.add(size as f64);
+        self.p90.add(size as f64);
+        self.p99.add(size as f64);
+        self.max = self.max.max(size);
+        self.total += size as u64;
+        self.count += 1;
+    }
+
+    pub fn distribution(&self) -> MessageSizeDistribution {
+        MessageSizeDistribution {
+            p50_bytes: self.p50.value().round() as usize,
+            p90_bytes: self.p90.value().round() as usize,
+            p99_bytes: self.p99.value().round() as usize,
+            max_bytes: self.max,
+            avg_bytes: if self.count == 0 { 0.0 } else { self.total as f64 / self.count as f64 },
+            sample_count: self.count,
+        }
+    }
+}
+
+impl Default for SizeStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_p2_quantile_uniform() {
+        let mut median = P2Quantile::new(0.5);
+        let mut p90 = P2Quantile::new(0.9);
+        // Deterministic shuffle of 1..=1000
+        for i in 0..1000u64 {
+            let x = ((i * 7919) % 1000 + 1) as f64;
+            median.add(x);
+            p90.add(x);
+        }
+
+        assert!((median.value() - 500.0).abs() < 25.0, "median {}", median.value());
+        assert!((p90.value() - 900.0).abs() < 25.0, "p90 {}", p90.value());
+    }
+
+    #[test]
+    fn test_size_stats_small_sample() {
+        let mut stats = SizeStats::new();
+        assert_eq!(stats.distribution().sample_count, 0);
+
+        for size in [10, 30, 20] {
+            stats.add(size);
+        }
+        let dist = stats.distribution();
+        assert_eq!(dist.p50_bytes, 20);
+        assert_eq!(dist.max_bytes, 30);
+        assert_eq!(dist.avg_bytes, 20.0);
+        assert_eq!(dist.sample_count, 3);
+    }
+}
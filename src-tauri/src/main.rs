@@ -8,6 +8,7 @@ mod async_ops;
 mod config;
 mod decoders;
 mod kafka;
+mod message_import;
 mod models;
 mod schema_registry;
 mod tauri_commands;
@@ -16,8 +17,9 @@ mod zk_browser;
 mod zookeeper;
 
 use std::sync::Arc;
+use tauri::{Manager, WindowEvent};
 use tauri_commands::AppState;
-use tracing::info;
+use tracing::{info, warn};
 
 fn main() {
     // Initialize logging
@@ -29,50 +31,134 @@ fn main() {
     info!("Configuration directory: ~/.offsetexplorer3/");
 
     // Create application state
-    let app_state = Arc::new(AppState::new());
+    let app_state = Arc::new(AppState::load());
 
     // Run Tauri application
     tauri::Builder::default()
         .manage(app_state)
+        .on_window_event(|window, event| {
+            if let WindowEvent::Destroyed = event {
+                info!("Window destroyed, disconnecting all servers");
+                let state = window.state::<Arc<AppState>>();
+                let stopped = state.stop_zk_watches_for_window(window.label());
+                if stopped > 0 {
+                    info!("Stopped {} ZooKeeper watches", stopped);
+                }
+                let errors = state.disconnect_all();
+                if !errors.is_empty() {
+                    warn!("{} servers failed to disconnect cleanly", errors.len());
+                }
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             // Server management
             tauri_commands::get_server_connections,
+            tauri_commands::get_config_load_errors,
             tauri_commands::add_server_connection,
+            tauri_commands::test_server_connection,
             tauri_commands::update_server_connection,
             tauri_commands::remove_server_connection,
+            tauri_commands::export_connections_json,
+            tauri_commands::import_connections_json,
             tauri_commands::connect_to_server,
             tauri_commands::disconnect_from_server,
+            tauri_commands::start_token_refresh,
+            tauri_commands::get_security_types,
+            tauri_commands::get_sasl_mechanisms,
+            // ZooKeeper
+            tauri_commands::connect_zookeeper,
+            tauri_commands::disconnect_zookeeper,
+            tauri_commands::list_zk_connections,
+            tauri_commands::diff_zk_nodes,
+            tauri_commands::get_zk_children,
+            tauri_commands::get_zk_node,
+            tauri_commands::create_zk_node,
+            tauri_commands::update_zk_node,
+            tauri_commands::delete_zk_node,
+            tauri_commands::watch_zk_node,
             // Topic management
             tauri_commands::list_topics,
             tauri_commands::create_topic,
             tauri_commands::delete_topic,
+            tauri_commands::increase_topic_partitions,
+            tauri_commands::get_partition_watermarks,
+            tauri_commands::get_topic_watermarks,
+            tauri_commands::get_offsets_for_timestamp,
             tauri_commands::get_topic_metadata,
             tauri_commands::get_topic_partitions,
+            tauri_commands::get_replica_assignment,
+            tauri_commands::plan_partition_reassignment,
+            tauri_commands::get_topics_with_configs,
+            tauri_commands::get_topic_config,
+            tauri_commands::alter_topic_config,
+            tauri_commands::clone_topic_config,
+            tauri_commands::search_topics_all_servers,
             // Message operations
             tauri_commands::consume_messages,
+            tauri_commands::consume_messages_multi,
+            tauri_commands::preview_decode,
+            tauri_commands::seek_partition,
+            tauri_commands::get_message_size_distribution,
+            tauri_commands::get_message_throughput,
             tauri_commands::produce_message,
+            tauri_commands::produce_message_transactional,
+            tauri_commands::produce_messages_batch,
+            tauri_commands::estimate_import_time,
+            tauri_commands::import_messages,
+            tauri_commands::export_messages,
             // Consumer groups
             tauri_commands::list_consumer_groups,
+            tauri_commands::list_consumer_groups_for_topic,
             tauri_commands::get_consumer_group_details,
+            tauri_commands::get_topics_without_consumers,
+            tauri_commands::get_consumer_lag,
+            tauri_commands::get_lag_trend,
+            tauri_commands::diff_consumer_group_offsets_over_time,
+            tauri_commands::start_lag_tracking,
             tauri_commands::reset_consumer_offset,
+            tauri_commands::reset_consumer_offset_to_timestamp,
+            tauri_commands::reset_consumer_group_offsets,
+            tauri_commands::copy_consumer_offsets,
             // Tasks
             tauri_commands::get_task_progress,
+            tauri_commands::get_task_result,
             tauri_commands::cancel_task,
             tauri_commands::list_tasks,
+            tauri_commands::get_task_queue_status,
             // Brokers
             tauri_commands::list_brokers,
+            tauri_commands::get_default_broker_configs,
+            tauri_commands::get_broker_config,
+            tauri_commands::get_cluster_features,
+            tauri_commands::get_connection_topology,
             // ACLs
             tauri_commands::list_acls,
             tauri_commands::create_acl,
+            tauri_commands::create_acls_from_csv,
             tauri_commands::delete_acl,
             // Schema Registry
             tauri_commands::list_schema_subjects,
+            tauri_commands::list_subjects_with_version_counts,
             tauri_commands::get_schema,
             tauri_commands::get_latest_schema,
             tauri_commands::register_schema,
+            tauri_commands::lookup_schema,
             tauri_commands::test_compatibility,
+            tauri_commands::evolve_schema,
+            tauri_commands::delete_schema_subject,
+            tauri_commands::delete_schema_subject_version,
+            tauri_commands::get_schema_compatibility,
+            tauri_commands::set_schema_compatibility,
+            tauri_commands::get_subject_compatibility,
+            tauri_commands::set_subject_compatibility,
+            // Browser state
+            tauri_commands::save_browser_state,
+            tauri_commands::load_browser_state,
         ])
-        .setup(|_app| {
+        .setup(|app| {
+            if let Err(e) = app.state::<Arc<AppState>>().load_browser_state() {
+                warn!("Failed to load browser state: {}", e);
+            }
             info!("Tauri application initialized");
             Ok(())
         })
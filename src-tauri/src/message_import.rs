@@ -0,0 +1,244 @@
+// Copyright 2024 Offset Explorer Rust Team
+// Licensed under Proprietary License
+//
+// JSON lines message import with configurable field mapping, and the matching export
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use crate::kafka::mapper::KafkaMessage;
+
+/// Lines inspected for distinct partition values when estimating an import
+const PARTITION_SAMPLE_LINES: usize = 1000;
+
+/// Maps fields of each imported JSON object to parts of a Kafka message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldMapping {
+    /// Field holding the message key (no key is produced when unset)
+    #[serde(rename = "keyField")]
+    pub key_field: Option<String>,
+
+    /// Field holding the message value
+    #[serde(rename = "valueField")]
+    pub value_field: String,
+
+    /// Header name -> JSON field
+    #[serde(rename = "headerFields", default)]
+    pub header_fields: HashMap<String, String>,
+
+    /// Field holding the target partition (partitioner decides when unset)
+    #[serde(rename = "partitionField")]
+    pub partition_field: Option<String>,
+}
+
+/// Message extracted from a single JSON line
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedMessage {
+    pub key: Option<Vec<u8>>,
+    pub value: Vec<u8>,
+    pub headers: HashMap<String, String>,
+    pub partition: Option<i32>,
+}
+
+impl FieldMapping {
+    /// Extract a message from one JSON line
+    pub fn extract(&self, line: &str) -> Result<ImportedMessage> {
+        let json: Value = serde_json::from_str(line)
+            .map_err(|e| anyhow!("Invalid JSON: {}", e))?;
+
+        let key = match self.key_field {
+            Some(ref field) => Some(field_as_string(&json, field)?.into_bytes()),
+            None => None,
+        };
+        let value = field_as_string(&json, &self.value_field)?.into_bytes();
+
+        let mut headers = HashMap::new();
+        for (header_name, field) in &self.header_fields {
+            headers.insert(header_name.clone(), field_as_string(&json, field)?);
+        }
+
+        let partition = match self.partition_field {
+            Some(ref field) => {
+                let raw = field_as_string(&json, field)?;
+                Some(raw.parse::<i32>()
+                    .map_err(|_| anyhow!("Invalid partition in field '{}': {}", field, raw))?)
+            }
+            None => None,
+        };
+
+        Ok(ImportedMessage { key, value, headers, partition })
+    }
+}
+
+/// Size and expected duration of importing a JSON lines file
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportEstimate {
+    #[serde(rename = "fileSizeBytes")]
+    pub file_size_bytes: u64,
+    #[serde(rename = "messageCount")]
+    pub message_count: u64,
+    #[serde(rename = "estimatedSeconds")]
+    pub estimated_seconds: f64,
+    /// Distinct `partition` values in the first lines of the file
+    #[serde(rename = "estimatedPartitionCount")]
+    pub estimated_partition_count: usize,
+}
+
+/// Count the messages in a JSON lines file and estimate the import time at `rate` messages per second
+pub fn estimate_import(path: &Path, rate: u32) -> Result<ImportEstimate> {
+    let file = File::open(path)
+        .map_err(|e| anyhow!("Failed to open {}: {}", path.display(), e))?;
+    let file_size_bytes = file.metadata()
+        .map_err(|e| anyhow!("Failed to read metadata of {}: {}", path.display(), e))?
+        .len();
+
+    estimate_from_reader(BufReader::new(file), file_size_bytes, rate)
+}
+
+fn estimate_from_reader<R: BufRead>(reader: R, file_size_bytes: u64, rate: u32) -> Result<ImportEstimate> {
+    if rate == 0 {
+        return Err(anyhow!("Producer rate must be greater than zero"));
+    }
+
+    let mut message_count = 0u64;
+    let mut partitions = HashSet::new();
+    for line in reader.lines() {
+        let line = line.map_err(|e| anyhow!("Failed to read line: {}", e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if (message_count as usize) < PARTITION_SAMPLE_LINES {
+            if let Ok(json) = serde_json::from_str::<Value>(&line) {
+                if let Some(partition) = json.get("partition") {
+                    partitions.insert(partition.to_string());
+                }
+            }
+        }
+        message_count += 1;
+    }
+
+    Ok(ImportEstimate {
+        file_size_bytes,
+        message_count,
+        estimated_seconds: message_count as f64 / rate as f64,
+        estimated_partition_count: partitions.len(),
+    })
+}
+
+/// Read a field as text; strings are used as-is, other values as JSON
+fn field_as_string(json: &Value, field: &str) -> Result<String> {
+    match json.get(field) {
+        Some(Value::String(s)) => Ok(s.clone()),
+        Some(other) => Ok(other.to_string()),
+        None => Err(anyhow!("Missing field '{}'", field)),
+    }
+}
+
+/// One exported message as a JSON line.
+///
+/// Key and value are written as text; the line can be imported again with
+/// `key`, `value` and `partition` as the mapped fields.
+pub fn export_line(message: &KafkaMessage) -> String {
+    let text = |bytes: &Option<Vec<u8>>| bytes.as_deref().map(|b| String::from_utf8_lossy(b).into_owned());
+    let headers: serde_json::Map<String, Value> = message
+        .decoded_headers
+        .iter()
+        .map(|(name, value)| (name.clone(), Value::String(value.clone())))
+        .collect();
+
+    serde_json::json!({
+        "topic": message.topic,
+        "partition": message.partition,
+        "offset": message.offset,
+        "timestamp": message.timestamp,
+        "key": text(&message.key),
+        "value": text(&message.payload),
+        "headers": headers,
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_with_full_mapping() {
+        let mapping = FieldMapping {
+            key_field: Some("id".to_string()),
+            value_field: "payload".to_string(),
+            header_fields: HashMap::from([("source".to_string(), "origin".to_string())]),
+            partition_field: Some("p".to_string()),
+        };
+
+        let line = r#"{"id": 42, "payload": {"a": 1}, "origin": "batch", "p": 3}"#;
+        let message = mapping.extract(line).unwrap();
+
+        assert_eq!(message.key, Some(b"42".to_vec()));
+        assert_eq!(message.value, br#"{"a":1}"#.to_vec());
+        assert_eq!(message.headers.get("source").map(String::as_str), Some("batch"));
+        assert_eq!(message.partition, Some(3));
+    }
+
+    #[test]
+    fn test_estimate_from_reader() {
+        let lines = "{\"partition\": 0}\n{\"partition\": 1}\n\n{\"partition\": 1}\nnot json\n";
+        let estimate = estimate_from_reader(lines.as_bytes(), lines.len() as u64, 2).unwrap();
+
+        assert_eq!(estimate.message_count, 4);
+        assert_eq!(estimate.estimated_seconds, 2.0);
+        assert_eq!(estimate.estimated_partition_count, 2);
+        assert!(estimate_from_reader(lines.as_bytes(), 0, 0).is_err());
+    }
+
+    #[test]
+    fn test_extract_without_key() {
+        let mapping = FieldMapping {
+            key_field: None,
+            value_field: "value".to_string(),
+            header_fields: HashMap::new(),
+            partition_field: None,
+        };
+
+        let message = mapping.extract(r#"{"value": "hello"}"#).unwrap();
+        assert_eq!(message.key, None);
+        assert_eq!(message.value, b"hello".to_vec());
+        assert!(mapping.extract(r#"{"other": 1}"#).is_err());
+    }
+
+    #[test]
+    fn test_export_line_can_be_imported() {
+        let message = KafkaMessage {
+            topic: Some("orders".to_string()),
+            partition: 2,
+            offset: 17,
+            key: Some(b"order-1".to_vec()),
+            payload: Some(br#"{"total":5}"#.to_vec()),
+            timestamp: 1_700_000_000_000,
+            headers: vec![("source".to_string(), b"web".to_vec())],
+            decoded_headers: vec![("source".to_string(), "web".to_string())],
+            truncated: false,
+        };
+        let line = export_line(&message);
+
+        let json: Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(json["offset"], 17);
+        assert_eq!(json["headers"]["source"], "web");
+
+        let mapping = FieldMapping {
+            key_field: Some("key".to_string()),
+            value_field: "value".to_string(),
+            header_fields: HashMap::new(),
+            partition_field: Some("partition".to_string()),
+        };
+        let imported = mapping.extract(&line).unwrap();
+        assert_eq!(imported.key, message.key);
+        assert_eq!(Some(imported.value), message.payload);
+        assert_eq!(imported.partition, Some(2));
+    }
+}
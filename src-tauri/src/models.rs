@@ -7,6 +7,6 @@ pub mod consumer;
 pub mod broker;
 
 pub use topic::Topic;
-pub use partition::Partition;
+pub use partition::{Partition, PartitionAssignmentDisplay};
 pub use consumer::Consumer;
 pub use broker::Broker;
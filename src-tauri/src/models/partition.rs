@@ -4,6 +4,7 @@
 // Partition model
 
 use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
 
 /// Partition model
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,3 +23,53 @@ impl Partition {
         }
     }
 }
+
+/// Current and proposed replicas of a partition in a reassignment plan
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PartitionAssignmentDisplay {
+    pub partition: i32,
+    pub current_replicas: Vec<i32>,
+    pub proposed_replicas: Vec<i32>,
+}
+
+impl PartitionAssignmentDisplay {
+    /// Pair current and proposed assignments by partition; partitions missing from
+    /// `proposed` keep their current replicas
+    pub fn diff(current: &HashMap<i32, Vec<i32>>, proposed: &HashMap<i32, Vec<i32>>) -> Vec<Self> {
+        let mut partitions: Vec<i32> = current.keys().chain(proposed.keys()).copied().collect();
+        partitions.sort();
+        partitions.dedup();
+
+        partitions
+            .into_iter()
+            .map(|partition| {
+                let current_replicas = current.get(&partition).cloned().unwrap_or_default();
+                let proposed_replicas = proposed.get(&partition).cloned()
+                    .unwrap_or_else(|| current_replicas.clone());
+                Self { partition, current_replicas, proposed_replicas }
+            })
+            .collect()
+    }
+
+    pub fn is_changed(&self) -> bool {
+        self.current_replicas != self.proposed_replicas
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assignment_diff() {
+        let current = HashMap::from([(0, vec![1, 2]), (1, vec![2, 3])]);
+        let proposed = HashMap::from([(1, vec![3, 1])]);
+
+        let diff = PartitionAssignmentDisplay::diff(&current, &proposed);
+        assert_eq!(diff.len(), 2);
+        assert!(!diff[0].is_changed());
+        assert_eq!(diff[1].current_replicas, vec![2, 3]);
+        assert_eq!(diff[1].proposed_replicas, vec![3, 1]);
+        assert!(diff[1].is_changed());
+    }
+}
@@ -2,5 +2,7 @@
 // Licensed under Proprietary License
 
 pub mod client;
+pub mod normalize;
 
 pub use client::SchemaRegistryClient;
+pub use normalize::schemas_are_equivalent;
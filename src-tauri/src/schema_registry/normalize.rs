@@ -0,0 +1,52 @@
+// Copyright 2024 Offset Explorer Rust Team
+// Licensed under Proprietary License
+//
+// Avro schema normalization for duplicate detection
+
+use anyhow::{Result, anyhow};
+
+/// Parse an Avro schema and serialize it back as compact JSON with sorted keys
+pub fn normalize_avro_schema(schema_str: &str) -> Result<String> {
+    let schema = apache_avro::Schema::parse_str(schema_str)
+        .map_err(|e| anyhow!("Invalid Avro schema: {}", e))?;
+
+    // serde_json::Value keeps object keys in a BTreeMap, so this sorts them
+    let value = serde_json::to_value(&schema)
+        .map_err(|e| anyhow!("Failed to serialize Avro schema: {}", e))?;
+    Ok(value.to_string())
+}
+
+/// Whether two Avro schemas are identical after normalization
+pub fn schemas_are_equivalent(a: &str, b: &str) -> bool {
+    match (normalize_avro_schema(a), normalize_avro_schema(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reformatted_schemas_are_equivalent() {
+        let compact = r#"{"type":"record","name":"User","fields":[{"name":"id","type":"long"}]}"#;
+        let reformatted = r#"{
+            "name": "User",
+            "fields": [ { "type": "long", "name": "id" } ],
+            "type": "record"
+        }"#;
+
+        assert!(schemas_are_equivalent(compact, reformatted));
+        assert_eq!(normalize_avro_schema(compact).unwrap(), normalize_avro_schema(reformatted).unwrap());
+    }
+
+    #[test]
+    fn test_different_schemas_are_not_equivalent() {
+        let a = r#"{"type":"record","name":"User","fields":[{"name":"id","type":"long"}]}"#;
+        let b = r#"{"type":"record","name":"User","fields":[{"name":"id","type":"string"}]}"#;
+
+        assert!(!schemas_are_equivalent(a, b));
+        assert!(!schemas_are_equivalent(a, "not a schema"));
+    }
+}
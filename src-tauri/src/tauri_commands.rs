@@ -5,43 +5,184 @@
 // Bridges Rust backend with frontend UI
 
 use anyhow::{anyhow, Result};
+use rdkafka::admin::ConfigSource;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::atomic::AtomicBool;
 use std::sync::{Arc, Mutex};
-use tauri::State;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, State, Window};
 use tracing::{debug, info, warn};
 
-use crate::acls::{AclBinding, AclFilter, CreateAclRequest};
-use crate::async_ops::TaskManager;
-use crate::config::ServerConnection;
-use crate::kafka::mapper::{KafkaMapper, KafkaMessage};
+use crate::acls::{parse_acl_csv, AclBinding, AclFilter, BatchAclResult, CreateAclRequest};
+use crate::async_ops::{TaskManager, TaskSummary};
+use crate::config::{
+    BrokerSecurityType, BrowserState, ConfigPaths, ConnectionEnvironment, ConnectionSummary, Environment, SaslMechanism,
+    ServerConnection, ServerConnectionSettings,
+};
+use crate::kafka::config_admin::{self, ConfigAlterResult};
+use crate::kafka::mapper::{
+    config_source_name, BatchMessage, BROWSE_CONSUMER_GROUP, BatchProduceResult, BrokerConfigInfo, BrokerDetail, ClusterFeatures, ConsumerGroupInfo, ConsumerLag, ConsumerOffset,
+    ConsumerWrapper, DeliveryCounts, KafkaClientTimeouts, KafkaMapper, KafkaMessage, OffsetCopy, OffsetResetStrategy, PartitionWatermark,
+    TopicConfigInfo, TopicFlags, TopicWithConfigs,
+};
+use crate::kafka::oauth::{self, OAuthAccessToken};
+use crate::kafka::size_stats::MessageSizeDistribution;
+use crate::models::PartitionAssignmentDisplay;
+use crate::message_import::{export_line, FieldMapping, ImportEstimate};
 use crate::schema_registry::client::{SchemaInfo as SchemaInfoInternal, SchemaRegistryClient, SchemaType};
+use crate::ui_events::{AppEvent, EventBus};
+use crate::zk_browser::{
+    CreateZkNodeRequest, DeleteZkNodeRequest, UpdateZkNodeRequest, ZkConnectionRequest, ZkNode, ZkNodeDiff,
+    ZkWatchEvent,
+};
+use crate::zookeeper::client::{
+    decode_node_data, from_zk_acl, parse_create_mode, zookeeper_connect_string, ZkAuth,
+};
+use crate::zookeeper::ZooKeeperClient;
 
 // ==================== Application State ====================
 
+/// How long subject version counts are served from cache
+const SUBJECT_VERSION_COUNTS_TTL: Duration = Duration::from_secs(60);
+
+/// Delay before retrying a failed OAuth token request
+const TOKEN_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Number of lag samples kept per partition
+const LAG_HISTORY_LIMIT: usize = 60;
+
+/// Consumer groups snapshotted per lag tracking tick unless configured otherwise
+const DEFAULT_LAG_GROUPS_PER_TICK: usize = 10;
+
+/// Broker whose configs are used as the cluster defaults
+const DEFAULT_CONFIG_BROKER_ID: i32 = 0;
+
+/// Shown instead of the value of a sensitive config that has been set
+const SENSITIVE_CONFIG_MASK: &str = "******";
+
+/// Sliding window used to compute consume throughput
+const THROUGHPUT_WINDOW: Duration = Duration::from_secs(10);
+
+/// Consumed message counts and when they were recorded, oldest first
+type ConsumedWindow = VecDeque<(Instant, usize)>;
+
+/// Maximum number of topics consumed by one `consume_messages_multi` call
+const MAX_MULTI_CONSUME_TOPICS: usize = 10;
+
+/// Time allowed for each server to list its topics in a global search
+const TOPIC_SEARCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Time allowed for each component probed by `get_connection_topology`
+const TOPOLOGY_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Default time allowed for `test_server_connection` to fetch broker metadata
+const DEFAULT_CONNECTION_TEST_TIMEOUT_MS: u64 = 5000;
+
+/// Lines imported between two `MessagesImported` progress events
+const IMPORT_PROGRESS_INTERVAL: usize = 1000;
+
+/// Empty polls in a row after which an export stops waiting for the rest of its range
+const EXPORT_IDLE_POLLS: usize = 10;
+
+/// A filtered browse reads at most this many messages per requested message
+const FILTERED_SCAN_FACTOR: usize = 100;
+
+/// A filtered browse stops reading after this long and returns what it found
+const FILTERED_SCAN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Key for lag history: (server_id, group_id, topic, partition)
+type LagHistoryKey = (i64, String, String, i32);
+
+/// Consumer lag sample for trend graphs
+#[derive(Debug, Clone, Serialize)]
+pub struct LagTrendPoint {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub lag: i64,
+}
+
+/// Schema Registry client cached per registry URL
+pub struct SchemaRegistryEntry {
+    /// Shared client (keeps its schema cache between commands)
+    pub client: Arc<SchemaRegistryClient>,
+    /// Last fetched subject version counts and when they were fetched
+    pub subject_version_counts: Option<(Instant, Vec<(String, usize)>)>,
+}
+
 /// Global application state
 pub struct AppState {
     /// Connected Kafka mappers (server_id -> mapper)
     pub connections: Arc<Mutex<HashMap<i64, Arc<KafkaMapper>>>>,
-    /// Saved server configurations
-    pub server_configs: Arc<Mutex<Vec<ServerConnection>>>,
-    /// Next server ID
-    pub next_id: Arc<Mutex<i64>>,
+    /// Configuration paths and saved server connections; connection changes are written to disk
+    pub environment: Arc<Mutex<Environment>>,
     /// Task manager for background operations
     pub task_manager: Arc<TaskManager>,
     /// Flag indicating if shutdown is in progress
     pub is_shutting_down: Arc<AtomicBool>,
+    /// Schema Registry clients (registry URL -> client and cached results)
+    pub schema_registry_clients: Arc<Mutex<HashMap<String, SchemaRegistryEntry>>>,
+    /// Current OAuth tokens (server_id -> token)
+    pub oauth_tokens: Arc<Mutex<HashMap<i64, OAuthAccessToken>>>,
+    /// Application-wide event bus
+    pub event_bus: Arc<EventBus>,
+    /// Connected ZooKeeper sessions (server_id -> client)
+    pub zookeeper_connections: Arc<Mutex<HashMap<i64, Arc<ZooKeeperClient>>>>,
+    /// ZooKeeper session credentials, reused on reconnect (server_id -> auth)
+    pub zookeeper_auth: Arc<Mutex<HashMap<i64, ZkAuth>>>,
+    /// Recent consumer lag samples per partition
+    pub lag_history: Arc<Mutex<HashMap<LagHistoryKey, VecDeque<LagTrendPoint>>>>,
+    /// Broker default configs, cached for the lifetime of a connection (server_id -> configs)
+    pub broker_default_configs: Arc<Mutex<HashMap<i64, HashMap<String, String>>>>,
+    /// Recently consumed message counts per server, for throughput
+    pub consumed_messages: Arc<Mutex<HashMap<i64, ConsumedWindow>>>,
+    /// Consumers kept between browse operations ((server_id, group_id) -> consumer)
+    pub consumer_cache: Arc<Mutex<HashMap<ConsumerCacheKey, Arc<ConsumerWrapper>>>>,
+    /// Last browsed location, persisted to `browserstate.json`
+    pub browser_state: Arc<Mutex<BrowserState>>,
+    /// ZooKeeper watch relays to frontend windows ((window label, server_id, path) -> task)
+    pub zk_watch_relays: Arc<Mutex<ZkWatchRelays>>,
 }
 
+/// Server ID and consumer group of a cached consumer
+pub type ConsumerCacheKey = (i64, String);
+
+/// Window label, server ID and node path of a relayed ZooKeeper watch
+pub type ZkWatchKey = (String, i64, String);
+
+/// Tasks forwarding ZooKeeper watch events to a frontend window
+pub type ZkWatchRelays = HashMap<ZkWatchKey, tokio::task::JoinHandle<()>>;
+
 impl AppState {
+    /// State with empty configuration under the user's home directory; nothing is read from disk
     pub fn new() -> Self {
+        Self::with_environment(Environment::new(default_config_paths()))
+    }
+
+    /// State with the settings and server connections saved in the user's home directory;
+    /// files that fail to load are reported by `get_config_load_errors`
+    pub fn load() -> Self {
+        let mut environment = Environment::new(default_config_paths());
+        environment.load_settings();
+        Self::with_environment(environment)
+    }
+
+    pub fn with_environment(environment: Environment) -> Self {
         Self {
             connections: Arc::new(Mutex::new(HashMap::new())),
-            server_configs: Arc::new(Mutex::new(Vec::new())),
-            next_id: Arc::new(Mutex::new(1)),
+            environment: Arc::new(Mutex::new(environment)),
             task_manager: Arc::new(TaskManager::new()),
             is_shutting_down: Arc::new(AtomicBool::new(false)),
+            schema_registry_clients: Arc::new(Mutex::new(HashMap::new())),
+            oauth_tokens: Arc::new(Mutex::new(HashMap::new())),
+            event_bus: Arc::new(EventBus::new()),
+            zookeeper_connections: Arc::new(Mutex::new(HashMap::new())),
+            zookeeper_auth: Arc::new(Mutex::new(HashMap::new())),
+            lag_history: Arc::new(Mutex::new(HashMap::new())),
+            broker_default_configs: Arc::new(Mutex::new(HashMap::new())),
+            consumed_messages: Arc::new(Mutex::new(HashMap::new())),
+            consumer_cache: Arc::new(Mutex::new(HashMap::new())),
+            browser_state: Arc::new(Mutex::new(BrowserState::default())),
+            zk_watch_relays: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -51,42 +192,575 @@ impl AppState {
         info!("Added connection ID {}", id);
     }
 
+    /// Create a Kafka mapper from the saved config of a server and register it as connected
+    pub fn open_connection(&self, id: i64) -> Result<Arc<KafkaMapper>> {
+        let mapper = Arc::new(self.create_mapper(id)?);
+        self.add_connection(id, mapper.clone());
+        Ok(mapper)
+    }
+
+    fn create_mapper(&self, id: i64) -> Result<KafkaMapper> {
+        let config = self.find_server_config(id)?;
+        let timeouts = KafkaClientTimeouts::from_settings(&self.environment.lock().unwrap().settings);
+        KafkaMapper::new(config, timeouts)
+    }
+
+    /// Recreate the mapper of a connected server so it uses the saved config; returns
+    /// whether the server was connected.
+    ///
+    /// The new mapper replaces the old one before the old one is closed, so commands and
+    /// lag tracking keep a working connection. If the new mapper cannot be created the
+    /// server is disconnected.
+    pub fn reconnect_if_connected(&self, id: i64) -> Result<bool> {
+        let Some(previous) = self.get_connection(id) else {
+            return Ok(false);
+        };
+        info!(
+            "Reconnecting '{}' (server ID {}) with its updated configuration",
+            previous.connection().get_name(),
+            id
+        );
+
+        let mapper = match self.create_mapper(id) {
+            Ok(mapper) => Arc::new(mapper),
+            Err(e) => {
+                warn!("Failed to reconnect server ID {}, disconnecting: {}", id, e);
+                self.remove_connection(id);
+                close_replaced_mapper(id, &previous);
+                self.event_bus.publish(AppEvent::ServerConnectionDisconnected { id });
+                return Err(e);
+            }
+        };
+        self.connections.lock().unwrap().insert(id, mapper);
+        self.clear_connection_caches(id);
+        close_replaced_mapper(id, &previous);
+        Ok(true)
+    }
+
     pub fn remove_connection(&self, id: i64) {
         let mut conns = self.connections.lock().unwrap();
         conns.remove(&id);
+        if let Err(e) = self.task_manager.cancel_task(&lag_tracking_task_id(id)) {
+            warn!("Failed to stop lag tracking for server ID {}: {}", id, e);
+        }
+        if let Err(e) = self.task_manager.cancel_task(&token_refresh_task_id(id)) {
+            warn!("Failed to stop OAuth token refresh for server ID {}: {}", id, e);
+        }
+        self.oauth_tokens.lock().unwrap().remove(&id);
+        self.clear_connection_caches(id);
         info!("Removed connection ID {}", id);
     }
 
+    /// Forget what was cached from the current mapper of a server
+    fn clear_connection_caches(&self, id: i64) {
+        self.broker_default_configs.lock().unwrap().remove(&id);
+        self.consumer_cache.lock().unwrap().retain(|(server_id, _), _| *server_id != id);
+    }
+
+    /// Close every connection and cancel background tasks, returning the errors per server ID
+    pub fn disconnect_all(&self) -> Vec<(i64, anyhow::Error)> {
+        self.is_shutting_down.store(true, std::sync::atomic::Ordering::SeqCst);
+
+        let cancelled = self.task_manager.cancel_all();
+        info!("Cancelled {} background tasks", cancelled);
+
+        let ids: Vec<i64> = self.connections.lock().unwrap().keys().copied().collect();
+        let mut errors = Vec::new();
+        for id in ids {
+            if let Some(mapper) = self.get_connection(id) {
+                match mapper.close(true) {
+                    Ok(()) => info!("Disconnected from server ID {}", id),
+                    Err(e) => {
+                        warn!("Failed to disconnect from server ID {}: {}", id, e);
+                        errors.push((id, e));
+                    }
+                }
+            }
+            self.remove_connection(id);
+        }
+        errors
+    }
+
+    /// Get the cached consumer for a server and group, creating it on first use
+    pub fn get_or_create_consumer(&self, server_id: i64, group_id: &str) -> Result<Arc<ConsumerWrapper>> {
+        let mapper = self
+            .get_connection(server_id)
+            .ok_or_else(|| anyhow!("Not connected to server ID {}", server_id))?;
+
+        let mut cache = self.consumer_cache.lock().unwrap();
+        if let Some(consumer) = cache.get(&(server_id, group_id.to_string())) {
+            return Ok(consumer.clone());
+        }
+
+        let consumer = Arc::new(
+            mapper
+                .create_consumer(group_id)?
+                .with_max_message_bytes(self.max_message_bytes()),
+        );
+        cache.insert((server_id, group_id.to_string()), consumer.clone());
+        Ok(consumer)
+    }
+
     pub fn get_connection(&self, id: i64) -> Option<Arc<KafkaMapper>> {
         let conns = self.connections.lock().unwrap();
         conns.get(&id).cloned()
     }
 
-    pub fn add_server_config(&self, config: ServerConnection) -> i64 {
-        let id = {
-            let mut next = self.next_id.lock().unwrap();
-            let id = *next;
-            *next += 1;
-            id
-        };
-        let mut configs = self.server_configs.lock().unwrap();
-        let mut config = config;
-        config.id = id;
-        configs.push(config);
+    /// Save a new server config under a generated ID and write `connections.xml`
+    pub fn add_server_config(&self, mut config: ServerConnection) -> Result<i64> {
+        config.id = 0;
+        let registry_url = config.schema_registry_endpoint.clone();
+        let id = self.change_server_configs(|connections| connections.add_connection(config))?;
+        self.invalidate_schema_registry_client(registry_url.as_deref());
         info!("Added server config ID {}", id);
-        id
+        Ok(id)
+    }
+
+    /// Apply a change to a copy of the saved server configs and keep it only once
+    /// `connections.xml` has been written
+    fn change_server_configs<T>(&self, change: impl FnOnce(&mut ServerConnectionSettings) -> Result<T>) -> Result<T> {
+        let mut environment = self.environment.lock().unwrap();
+        let previous = environment.connection_settings.clone();
+        let result = change(&mut environment.connection_settings)
+            .and_then(|value| environment.save_connections().map(|()| value));
+        if result.is_err() {
+            environment.connection_settings = previous;
+        }
+        result
+    }
+
+    /// Saved config of a server
+    pub fn find_server_config(&self, id: i64) -> Result<ServerConnection> {
+        let environment = self.environment.lock().unwrap();
+        environment
+            .connection_settings
+            .find_connection(id)
+            .ok_or_else(|| anyhow!("Server ID {} not found in saved connections", id))
     }
 
     pub fn get_server_configs(&self) -> Vec<ServerConnection> {
-        let configs = self.server_configs.lock().unwrap();
-        configs.clone()
+        let environment = self.environment.lock().unwrap();
+        environment.connection_settings.get_connections().to_vec()
+    }
+
+    /// Replace the saved config with the same ID and write `connections.xml`
+    pub fn update_server_config(&self, config: ServerConnection) -> Result<()> {
+        let id = config.id;
+        let previous_url = self.find_server_config(id).ok().and_then(|c| c.schema_registry_endpoint);
+        let registry_url = config.schema_registry_endpoint.clone();
+        self.change_server_configs(|connections| connections.update_connection(config))?;
+        self.invalidate_schema_registry_client(previous_url.as_deref());
+        self.invalidate_schema_registry_client(registry_url.as_deref());
+        info!("Updated server config ID {}", id);
+        Ok(())
     }
 
-    pub fn remove_server_config(&self, id: i64) {
-        let mut configs = self.server_configs.lock().unwrap();
-        configs.retain(|c| c.id != id);
+    /// Remove a saved server config and write `connections.xml`
+    pub fn remove_server_config(&self, id: i64) -> Result<()> {
+        let registry_url = self.find_server_config(id).ok().and_then(|c| c.schema_registry_endpoint);
+        self.change_server_configs(|connections| connections.remove_connection(id))?;
+        self.invalidate_schema_registry_client(registry_url.as_deref());
         info!("Removed server config ID {}", id);
+        Ok(())
+    }
+
+    /// Drop the cached client of a registry whose connection settings changed, so the next
+    /// command builds one with the current credentials and TLS settings
+    fn invalidate_schema_registry_client(&self, registry_url: Option<&str>) {
+        let Some(url) = registry_url.map(|u| u.trim_end_matches('/')).filter(|u| !u.is_empty()) else {
+            return;
+        };
+        self.schema_registry_clients
+            .lock()
+            .unwrap()
+            .retain(|cached, _| cached.trim_end_matches('/') != url);
+    }
+
+    /// Record consumed messages for throughput tracking and notify listeners
+    pub fn record_consumed(&self, server_id: i64, topic: &str, count: usize) {
+        let now = Instant::now();
+        {
+            let mut consumed = self.consumed_messages.lock().unwrap();
+            let window = consumed.entry(server_id).or_default();
+            window.push_back((now, count));
+            while window.front().is_some_and(|(at, _)| now.duration_since(*at) > THROUGHPUT_WINDOW) {
+                window.pop_front();
+            }
+        }
+
+        self.event_bus.publish(AppEvent::MessageConsumed {
+            server_id,
+            topic: topic.to_string(),
+            count,
+        });
+    }
+
+    /// Messages per second consumed from a server over the last `THROUGHPUT_WINDOW`
+    pub fn get_consumed_messages_per_second(&self, server_id: i64) -> f64 {
+        let consumed = self.consumed_messages.lock().unwrap();
+        consumed
+            .get(&server_id)
+            .map(|window| throughput(window, Instant::now()))
+            .unwrap_or(0.0)
+    }
+
+    /// Payload size limit for consumed messages (`max.messages.bytes`)
+    pub fn max_message_bytes(&self) -> usize {
+        self.environment
+            .lock()
+            .unwrap()
+            .settings
+            .get_int("max.messages.bytes")
+            .unwrap_or(1048576)
+            .max(0) as usize
+    }
+
+    /// Restore the browser state saved by the previous session
+    pub fn load_browser_state(&self) -> Result<BrowserState> {
+        let loaded = BrowserState::load_from(&BrowserState::default_path()?)?;
+        *self.browser_state.lock().unwrap() = loaded.clone();
+        Ok(loaded)
+    }
+
+    pub fn save_browser_state(&self, browser_state: BrowserState) -> Result<()> {
+        browser_state.save_to(&BrowserState::default_path()?)?;
+        *self.browser_state.lock().unwrap() = browser_state;
+        Ok(())
+    }
+
+    /// Remember the topic/partition the user navigated to
+    pub fn record_navigation(&self, server_id: i64, topic: &str, partition: Option<i32>, offset: Option<i64>) {
+        let mut browser_state = self.browser_state.lock().unwrap().clone();
+        browser_state.navigate(server_id, topic, partition, offset);
+        if let Err(e) = self.save_browser_state(browser_state) {
+            warn!("Failed to save browser state: {}", e);
+        }
+    }
+
+    pub fn get_zookeeper_connection(&self, server_id: i64) -> Option<Arc<ZooKeeperClient>> {
+        let conns = self.zookeeper_connections.lock().unwrap();
+        conns.get(&server_id).cloned()
+    }
+
+    /// Stop relaying ZooKeeper watch events to a window, e.g. once it is closed
+    pub fn stop_zk_watches_for_window(&self, label: &str) -> usize {
+        self.stop_zk_watches(|(window, _, _)| window == label)
+    }
+
+    fn stop_zk_watches(&self, matches: impl Fn(&ZkWatchKey) -> bool) -> usize {
+        let mut relays = self.zk_watch_relays.lock().unwrap();
+        let keys: Vec<ZkWatchKey> = relays.keys().filter(|key| matches(key)).cloned().collect();
+        for key in &keys {
+            if let Some(relay) = relays.remove(key) {
+                relay.abort();
+            }
+        }
+        keys.len()
+    }
+
+    /// Append lag samples to the history, keeping the last `LAG_HISTORY_LIMIT` per partition
+    pub fn record_lag(&self, server_id: i64, group_id: &str, lags: &[ConsumerLag]) {
+        record_lag_points(&self.lag_history, server_id, group_id, lags);
+    }
+
+    pub fn get_lag_trend(
+        &self,
+        server_id: i64,
+        group_id: &str,
+        topic: &str,
+        partition: i32,
+    ) -> Vec<LagTrendPoint> {
+        let history = self.lag_history.lock().unwrap();
+        let key = (server_id, group_id.to_string(), topic.to_string(), partition);
+        history
+            .get(&key)
+            .map(|points| points.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// How long Schema Registry clients reuse fetched schemas (`schema_cache_ttl_secs`, 0 disables caching)
+    pub fn schema_cache_ttl(&self) -> Duration {
+        self.environment
+            .lock()
+            .unwrap()
+            .settings
+            .get_int("schema_cache_ttl_secs")
+            .map(|secs| Duration::from_secs(secs.max(0) as u64))
+            .unwrap_or(crate::schema_registry::client::DEFAULT_CACHE_TTL)
+    }
+
+    /// Schema Registry client for a single command, using the configured cache TTL and the
+    /// credentials and TLS settings of the saved connection with this registry, if any
+    pub fn new_schema_registry_client(&self, registry_url: String) -> Result<SchemaRegistryClient> {
+        let client = match self.find_schema_registry_connection(&registry_url) {
+            Some(conn) => SchemaRegistryClient::from_connection(&conn)?,
+            None => SchemaRegistryClient::new(registry_url)?,
+        };
+        Ok(client.with_cache_ttl(self.schema_cache_ttl()))
+    }
+
+    fn find_schema_registry_connection(&self, registry_url: &str) -> Option<ServerConnection> {
+        let url = registry_url.trim_end_matches('/');
+        self.environment.lock().unwrap()
+            .connection_settings
+            .get_connections()
+            .iter()
+            .find(|c| c.schema_registry_endpoint.as_deref().map(|e| e.trim_end_matches('/')) == Some(url))
+            .cloned()
+    }
+
+    pub fn get_schema_registry_client(
+        &self,
+        registry_url: &str,
+    ) -> Result<Arc<SchemaRegistryClient>> {
+        let mut clients = self.schema_registry_clients.lock().unwrap();
+        if let Some(entry) = clients.get(registry_url) {
+            return Ok(entry.client.clone());
+        }
+        let client = Arc::new(self.new_schema_registry_client(registry_url.to_string())?);
+        clients.insert(
+            registry_url.to_string(),
+            SchemaRegistryEntry {
+                client: client.clone(),
+                subject_version_counts: None,
+            },
+        );
+        Ok(client)
+    }
+
+    /// Schema Registry client of a saved connection, `None` when it has no registry.
+    /// A registry client that cannot be created is logged and treated as absent.
+    pub fn connection_schema_registry(&self, config: &ServerConnection) -> Option<Arc<SchemaRegistryClient>> {
+        let url = config.schema_registry_endpoint.as_deref().filter(|url| !url.trim().is_empty())?;
+        match self.get_schema_registry_client(url) {
+            Ok(client) => Some(client),
+            Err(e) => {
+                warn!("Schema Registry {} of {} is unavailable: {}", url, config.get_name(), e);
+                None
+            }
+        }
+    }
+
+    pub fn get_cached_subject_version_counts(&self, registry_url: &str) -> Option<Vec<(String, usize)>> {
+        let clients = self.schema_registry_clients.lock().unwrap();
+        match clients.get(registry_url)?.subject_version_counts {
+            Some((fetched_at, ref counts)) if fetched_at.elapsed() < SUBJECT_VERSION_COUNTS_TTL => {
+                Some(counts.clone())
+            }
+            _ => None,
+        }
+    }
+
+    /// Start a background task that keeps the OAuth token for a server fresh
+    pub fn start_token_refresh(
+        &self,
+        server_id: i64,
+        token_endpoint_url: String,
+        client_id: String,
+        client_secret: String,
+        refresh_before_expiry_secs: u64,
+    ) -> Result<()> {
+        let config = self.find_server_config(server_id)?;
+        if config.sasl_mechanism != SaslMechanism::OAUTHBEARER {
+            return Err(anyhow!("Server '{}' does not use SASL OAUTHBEARER", config.get_name()));
+        }
+
+        let task_id = token_refresh_task_id(server_id);
+        self.task_manager.cancel_task(&task_id)?;
+
+        let connections = self.connections.clone();
+        let oauth_tokens = self.oauth_tokens.clone();
+        let event_bus = self.event_bus.clone();
+
+        let task_name = format!("OAuth token refresh for server {}", server_id);
+        self.task_manager.spawn_periodic_task(task_id, task_name, move || async move {
+            loop {
+                let token = match oauth::fetch_token(&token_endpoint_url, &client_id, &client_secret)
+                    .await
+                {
+                    Ok(token) => token,
+                    Err(e) => {
+                        warn!("OAuth token refresh failed for server {}: {}", server_id, e);
+                        tokio::time::sleep(TOKEN_RETRY_DELAY).await;
+                        continue;
+                    }
+                };
+
+                let mapper = connections.lock().unwrap().get(&server_id).cloned();
+                if let Some(mapper) = mapper {
+                    if let Err(e) = mapper.set_oauth_token(&token.token, token.expires_at_ms, &client_id) {
+                        warn!("Failed to apply OAuth token for server {}: {}", server_id, e);
+                    }
+                }
+
+                let wait_ms = (token.remaining_ms() - refresh_before_expiry_secs as i64 * 1000).max(0);
+                oauth_tokens.lock().unwrap().insert(server_id, token);
+                event_bus.publish(AppEvent::TokenRefreshed { server_id });
+                info!("Refreshed OAuth token for server {}", server_id);
+
+                tokio::time::sleep(Duration::from_millis(wait_ms as u64).max(TOKEN_RETRY_DELAY)).await;
+            }
+        })
+    }
+
+    /// Periodically snapshot consumer group offsets and record lag history.
+    ///
+    /// Groups are processed in round-robin batches so large clusters are not
+    /// hit with offset fetches for every group at once.
+    pub fn start_lag_tracking(&self, server_id: i64) -> Result<()> {
+        let task_id = lag_tracking_task_id(server_id);
+        self.task_manager.cancel_task(&task_id)?;
+
+        let (interval_ms, groups_per_tick) = {
+            let environment = self.environment.lock().unwrap();
+            let settings = &environment.settings;
+            (
+                settings.get_int("auto_refresh_interval_ms").unwrap_or(30000).max(1000) as u64,
+                settings
+                    .get_int("lag_tracking_groups_per_tick")
+                    .map(|n| n.max(1) as usize)
+                    .unwrap_or(DEFAULT_LAG_GROUPS_PER_TICK),
+            )
+        };
+
+        let connections = self.connections.clone();
+        let lag_history = self.lag_history.clone();
+
+        let task_name = format!("Consumer lag tracking for server {}", server_id);
+        self.task_manager.spawn_periodic_task(task_id, task_name, move || async move {
+            let mut cursor = 0;
+            loop {
+                tokio::time::sleep(Duration::from_millis(interval_ms)).await;
+
+                let mapper = match connections.lock().unwrap().get(&server_id).cloned() {
+                    Some(mapper) => mapper,
+                    None => continue,
+                };
+
+                let list_mapper = mapper.clone();
+                let groups = match tokio::task::spawn_blocking(move || list_mapper.list_consumer_groups()).await {
+                    Ok(Ok(groups)) => groups.into_iter().map(|g| g.group_id).collect::<Vec<_>>(),
+                    Ok(Err(e)) => {
+                        warn!("Lag tracking failed to list groups for server {}: {}", server_id, e);
+                        continue;
+                    }
+                    Err(e) => {
+                        warn!("Lag tracking task failed for server {}: {}", server_id, e);
+                        continue;
+                    }
+                };
+
+                let batch = next_group_batch(&groups, &mut cursor, groups_per_tick);
+                let batch_refs: Vec<&str> = batch.iter().map(String::as_str).collect();
+                let offsets = match mapper.snapshot_consumer_lag(&batch_refs).await {
+                    Ok(offsets) => offsets,
+                    Err(e) => {
+                        warn!("Lag snapshot failed for server {}: {}", server_id, e);
+                        continue;
+                    }
+                };
+
+                let mut by_group: HashMap<String, Vec<ConsumerOffset>> = HashMap::new();
+                for offset in offsets {
+                    by_group.entry(offset.group_id.clone()).or_default().push(offset);
+                }
+
+                for (group_id, offsets) in by_group {
+                    let lag_mapper = mapper.clone();
+                    let lags = tokio::task::spawn_blocking(move || lag_mapper.lags_for_offsets(offsets)).await;
+                    match lags {
+                        Ok(Ok(lags)) => record_lag_points(&lag_history, server_id, &group_id, &lags),
+                        Ok(Err(e)) => warn!("Failed to compute lag for group '{}': {}", group_id, e),
+                        Err(e) => warn!("Lag computation task failed for group '{}': {}", group_id, e),
+                    }
+                }
+                debug!("Recorded lag for {} groups on server {}", batch.len(), server_id);
+            }
+        })
+    }
+
+    pub fn cache_subject_version_counts(&self, registry_url: &str, counts: Vec<(String, usize)>) {
+        let mut clients = self.schema_registry_clients.lock().unwrap();
+        if let Some(entry) = clients.get_mut(registry_url) {
+            entry.subject_version_counts = Some((Instant::now(), counts));
+        }
+    }
+
+    pub fn invalidate_subject_version_counts(&self, registry_url: &str) {
+        if let Some(entry) = self.schema_registry_clients.lock().unwrap().get_mut(registry_url) {
+            entry.subject_version_counts = None;
+        }
+    }
+}
+
+/// Task ID of the lag tracking of a server, so it can be stopped on disconnect
+fn lag_tracking_task_id(server_id: i64) -> String {
+    format!("lag-tracking-{}", server_id)
+}
+
+/// Task ID of the OAuth token refresh of a server, so it can be stopped on disconnect
+fn token_refresh_task_id(server_id: i64) -> String {
+    format!("token-refresh-{}", server_id)
+}
+
+/// Close a mapper that is no longer registered, delivering its queued messages first
+fn close_replaced_mapper(id: i64, mapper: &KafkaMapper) {
+    if let Err(e) = mapper.close(true) {
+        warn!("Failed to close the previous connection of server ID {}: {}", id, e);
+    }
+}
+
+/// Configuration paths under the user's home directory, or the temporary directory without one
+fn default_config_paths() -> ConfigPaths {
+    ConfigPaths::from_home_dir().unwrap_or_else(|e| {
+        warn!("{}, using the temporary directory for configuration", e);
+        ConfigPaths::new(std::env::temp_dir())
+    })
+}
+
+fn record_lag_points(
+    lag_history: &Mutex<HashMap<LagHistoryKey, VecDeque<LagTrendPoint>>>,
+    server_id: i64,
+    group_id: &str,
+    lags: &[ConsumerLag],
+) {
+    let timestamp = chrono::Utc::now();
+    let mut history = lag_history.lock().unwrap();
+    for lag in lags {
+        let key = (server_id, group_id.to_string(), lag.topic.clone(), lag.partition);
+        let points = history.entry(key).or_default();
+        points.push_back(LagTrendPoint { timestamp, lag: lag.lag });
+        while points.len() > LAG_HISTORY_LIMIT {
+            points.pop_front();
+        }
+    }
+}
+
+/// Messages per second within `THROUGHPUT_WINDOW` before `now`
+fn throughput(window: &ConsumedWindow, now: Instant) -> f64 {
+    let total: usize = window
+        .iter()
+        .filter(|(at, _)| now.duration_since(*at) <= THROUGHPUT_WINDOW)
+        .map(|(_, count)| count)
+        .sum();
+    total as f64 / THROUGHPUT_WINDOW.as_secs_f64()
+}
+
+/// Take the next `limit` groups starting at `cursor`, wrapping around the list
+fn next_group_batch(groups: &[String], cursor: &mut usize, limit: usize) -> Vec<String> {
+    if groups.is_empty() {
+        return Vec::new();
     }
+
+    let start = *cursor % groups.len();
+    let batch: Vec<String> = groups.iter()
+        .cycle()
+        .skip(start)
+        .take(limit.min(groups.len()))
+        .cloned()
+        .collect();
+    *cursor = (start + batch.len()) % groups.len();
+    batch
 }
 
 impl Default for AppState {
@@ -97,7 +771,7 @@ impl Default for AppState {
 
 // ==================== Type Definitions ====================
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Default, Deserialize, Serialize)]
 pub struct ServerConnectionRequest {
     pub name: String,
     #[serde(rename = "bootstrapServers")]
@@ -106,6 +780,131 @@ pub struct ServerConnectionRequest {
     pub security_type: Option<String>,
     pub zookeeper_hosts: Option<String>,
     pub zookeeper_chroot: Option<String>,
+    pub environment: Option<String>,
+
+    // SASL settings; unset fields keep their current value, empty strings clear them
+    #[serde(rename = "saslMechanism", default)]
+    pub sasl_mechanism: Option<String>,
+    #[serde(rename = "saslUsername", default)]
+    pub sasl_username: Option<String>,
+    #[serde(rename = "saslPassword", default)]
+    pub sasl_password: Option<String>,
+    #[serde(rename = "jaasConfig", default)]
+    pub jaas_config: Option<String>,
+    #[serde(rename = "kerberosServiceName", default)]
+    pub kerberos_service_name: Option<String>,
+    #[serde(rename = "kerberosPrincipal", default)]
+    pub kerberos_principal: Option<String>,
+    #[serde(rename = "kerberosKeytab", default)]
+    pub kerberos_keytab: Option<String>,
+    #[serde(rename = "oauthToken", default)]
+    pub oauth_token: Option<String>,
+    #[serde(rename = "oauthTokenEndpointUrl", default)]
+    pub oauth_token_endpoint_url: Option<String>,
+
+    // SSL settings
+    #[serde(rename = "truststoreLocation", default)]
+    pub truststore_location: Option<String>,
+    #[serde(rename = "truststorePassword", default)]
+    pub truststore_password: Option<String>,
+    #[serde(rename = "keystoreLocation", default)]
+    pub keystore_location: Option<String>,
+    #[serde(rename = "keystorePassword", default)]
+    pub keystore_password: Option<String>,
+    #[serde(rename = "keystorePrivateKey", default)]
+    pub keystore_private_key: Option<String>,
+    #[serde(rename = "validateSslEndpointHostname", default)]
+    pub validate_ssl_endpoint_hostname: Option<bool>,
+}
+
+impl ServerConnectionRequest {
+    /// Build the connection configuration described by this request
+    fn to_server_connection(&self, id: i64) -> Result<ServerConnection> {
+        let mut config = ServerConnection::new(id, self.name.clone());
+        self.apply_to(&mut config)?;
+        Ok(config)
+    }
+
+    /// Overwrite the fields of `config` that this request describes, keeping the rest
+    fn apply_to(&self, config: &mut ServerConnection) -> Result<()> {
+        config.name = self.name.clone();
+        if let Some(ref bootstrap_servers) = self.bootstrap_servers {
+            config.bootstrap_servers = bootstrap_servers.clone();
+            if !config.bootstrap_servers.is_empty() {
+                config.host = config.bootstrap_servers.split(',').next().unwrap_or("").split(':').next().unwrap_or("").to_string();
+            }
+        }
+        if let Some(ref security_type) = self.security_type {
+            config.broker_security_type = BrokerSecurityType::from_str(security_type)?;
+        }
+        if let Some(ref environment) = self.environment {
+            config.environment = Some(environment.as_str()).filter(|e| !e.is_empty()).map(ConnectionEnvironment::from_str);
+        }
+
+        if let Some(ref mechanism) = self.sasl_mechanism {
+            config.sasl_mechanism = SaslMechanism::from_str(mechanism)?;
+        }
+        update_optional(&mut config.sasl_callback, &self.sasl_username);
+        update_optional(&mut config.sasl_endpoint_token, &self.sasl_password);
+        update_optional(&mut config.jaas_config, &self.jaas_config);
+        update_optional(&mut config.kerberos_service_name, &self.kerberos_service_name);
+        update_optional(&mut config.kerberos_principal, &self.kerberos_principal);
+        update_optional(&mut config.kerberos_keytab, &self.kerberos_keytab);
+        update_optional(&mut config.oauth_token, &self.oauth_token);
+        update_optional(&mut config.oauth_token_endpoint_url, &self.oauth_token_endpoint_url);
+
+        update_optional(&mut config.truststore_location, &self.truststore_location);
+        update_optional(&mut config.truststore_password, &self.truststore_password);
+        update_optional(&mut config.keystore_location, &self.keystore_location);
+        update_optional(&mut config.keystore_password, &self.keystore_password);
+        update_optional(&mut config.keystore_privatekey, &self.keystore_private_key);
+        if let Some(validate) = self.validate_ssl_endpoint_hostname {
+            config.validate_ssl_endpoint_hostname = validate;
+        }
+        Ok(())
+    }
+}
+
+/// Replace `field` when the request sets `value`; an empty value clears it
+fn update_optional(field: &mut Option<String>, value: &Option<String>) {
+    if let Some(value) = value {
+        *field = Some(value.clone()).filter(|v| !v.is_empty());
+    }
+}
+
+/// Outcome of `test_server_connection`
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ConnectionTestResult {
+    pub success: bool,
+    #[serde(rename = "brokerCount")]
+    pub broker_count: usize,
+    #[serde(rename = "brokerIds")]
+    pub broker_ids: Vec<i32>,
+    #[serde(rename = "latencyMs")]
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+impl ConnectionTestResult {
+    fn new(brokers: Result<Vec<i32>>, latency: Duration) -> Self {
+        let latency_ms = latency.as_millis() as u64;
+        match brokers {
+            Ok(broker_ids) => Self {
+                success: true,
+                broker_count: broker_ids.len(),
+                broker_ids,
+                latency_ms,
+                error: None,
+            },
+            Err(e) => Self {
+                success: false,
+                broker_count: 0,
+                broker_ids: Vec::new(),
+                latency_ms,
+                error: Some(e.to_string()),
+            },
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -119,9 +918,217 @@ pub struct CreateTopicRequest {
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ProduceMessageRequest {
     pub topic: String,
+    /// Target partition; the default partitioner chooses one when absent
+    #[serde(default)]
+    pub partition: Option<i32>,
     pub key: Option<String>,
     pub value: Option<String>,
     pub headers: Option<HashMap<String, String>>,
+    /// Encoding of `key`: "utf8" (default), "base64" or "hex" (e.g. "deadbeef")
+    #[serde(rename = "keyEncoding")]
+    pub key_encoding: Option<String>,
+    /// Encoding of `value`: "utf8" (default), "base64" or "hex" (e.g. "deadbeef")
+    #[serde(rename = "valueEncoding")]
+    pub value_encoding: Option<String>,
+}
+
+/// Outcome of one message of `produce_messages_batch`
+#[derive(Debug, Serialize)]
+pub struct BatchProduceResultResponse {
+    pub index: usize,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+impl From<BatchProduceResult> for BatchProduceResultResponse {
+    fn from(result: BatchProduceResult) -> Self {
+        Self {
+            index: result.index,
+            success: result.error.is_none(),
+            error: result.error,
+        }
+    }
+}
+
+impl ProduceMessageRequest {
+    /// Decode the key and value into a batch message
+    fn into_batch_message(self) -> Result<BatchMessage> {
+        let key = self
+            .key
+            .map(|k| decode_with_encoding(k, self.key_encoding.as_deref()))
+            .transpose()
+            .map_err(|e| anyhow!("Invalid key: {}", e))?;
+        let payload = self
+            .value
+            .map(|v| decode_with_encoding(v, self.value_encoding.as_deref()))
+            .transpose()
+            .map_err(|e| anyhow!("Invalid value: {}", e))?;
+        Ok(BatchMessage {
+            key,
+            payload,
+            partition: self.partition,
+            headers: self.headers.unwrap_or_default(),
+        })
+    }
+}
+
+/// Decode a key or value string sent by the UI into raw bytes
+fn decode_with_encoding(data: String, encoding: Option<&str>) -> Result<Vec<u8>> {
+    use base64::Engine;
+
+    match encoding.unwrap_or("utf8") {
+        "utf8" => Ok(data.into_bytes()),
+        "base64" => base64::engine::general_purpose::STANDARD
+            .decode(data.trim())
+            .map_err(|e| anyhow!("Invalid base64: {}", e)),
+        "hex" => hex::decode(data.trim()).map_err(|e| anyhow!("Invalid hex: {}", e)),
+        other => Err(anyhow!("Unknown encoding: {}", other)),
+    }
+}
+
+/// Only keep messages whose headers match
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum HeaderFilter {
+    /// A header whose value matches a pattern
+    Pattern {
+        #[serde(rename = "headerName")]
+        header_name: String,
+        #[serde(rename = "headerValuePattern")]
+        header_value_pattern: String,
+        #[serde(rename = "caseSensitive")]
+        case_sensitive: bool,
+        /// Treat the pattern as a regex instead of a substring
+        #[serde(rename = "useRegex", default)]
+        use_regex: bool,
+    },
+    /// Every listed header, with exactly the given value
+    Exact {
+        #[serde(rename = "headerValues")]
+        header_values: HashMap<String, String>,
+    },
+}
+
+/// Compiled form of a `HeaderFilter`
+enum HeaderMatcher {
+    Pattern { header_name: String, pattern: HeaderPattern },
+    Exact(HashMap<String, String>),
+}
+
+enum HeaderPattern {
+    Substring { needle: String, case_sensitive: bool },
+    Regex(regex::Regex),
+}
+
+impl HeaderFilter {
+    fn compile(self) -> Result<HeaderMatcher> {
+        let (header_name, header_value_pattern, case_sensitive, use_regex) = match self {
+            HeaderFilter::Pattern { header_name, header_value_pattern, case_sensitive, use_regex } => {
+                (header_name, header_value_pattern, case_sensitive, use_regex)
+            }
+            HeaderFilter::Exact { header_values } => return Ok(HeaderMatcher::Exact(header_values)),
+        };
+
+        let pattern = if use_regex {
+            let regex = regex::RegexBuilder::new(&header_value_pattern)
+                .case_insensitive(!case_sensitive)
+                .build()
+                .map_err(|e| anyhow!("Invalid header pattern: {}", e))?;
+            HeaderPattern::Regex(regex)
+        } else if case_sensitive {
+            HeaderPattern::Substring {
+                needle: header_value_pattern,
+                case_sensitive: true,
+            }
+        } else {
+            HeaderPattern::Substring {
+                needle: header_value_pattern.to_lowercase(),
+                case_sensitive: false,
+            }
+        };
+
+        Ok(HeaderMatcher::Pattern { header_name, pattern })
+    }
+}
+
+impl HeaderMatcher {
+    /// Does any header named `header_name` have a matching UTF-8 value, or, for exact
+    /// filters, does every listed header have its value?
+    fn matches(&self, headers: &[(String, Vec<u8>)]) -> bool {
+        let (header_name, pattern) = match self {
+            HeaderMatcher::Pattern { header_name, pattern } => (header_name, pattern),
+            HeaderMatcher::Exact(expected) => return headers_contain_all(headers, expected),
+        };
+
+        headers
+            .iter()
+            .filter(|(name, _)| name == header_name)
+            .any(|(_, value)| {
+                let value = String::from_utf8_lossy(value);
+                match pattern {
+                    HeaderPattern::Substring { needle, case_sensitive: true } => value.contains(needle.as_str()),
+                    HeaderPattern::Substring { needle, case_sensitive: false } => {
+                        value.to_lowercase().contains(needle.as_str())
+                    }
+                    HeaderPattern::Regex(regex) => regex.is_match(&value),
+                }
+            })
+    }
+}
+
+/// Limits how many messages a browse reads when most of them may be filtered out
+struct ScanBudget {
+    deadline: Instant,
+    remaining: usize,
+}
+
+impl ScanBudget {
+    fn new(limit: usize) -> Self {
+        Self::with_timeout(limit, FILTERED_SCAN_TIMEOUT)
+    }
+
+    fn with_timeout(limit: usize, timeout: Duration) -> Self {
+        Self {
+            deadline: Instant::now() + timeout,
+            remaining: limit.max(1).saturating_mul(FILTERED_SCAN_FACTOR),
+        }
+    }
+
+    /// Count one more message read; false once the budget is spent
+    fn take(&mut self) -> bool {
+        if self.remaining == 0 || Instant::now() >= self.deadline {
+            return false;
+        }
+        self.remaining -= 1;
+        true
+    }
+}
+
+/// Does every expected header have a value equal to the expected one?
+/// Kafka allows repeated header names, so any header with the name may match.
+fn headers_contain_all(headers: &[(String, Vec<u8>)], expected: &HashMap<String, String>) -> bool {
+    expected.iter().all(|(name, expected_value)| {
+        headers
+            .iter()
+            .any(|(header, value)| header == name && value.as_slice() == expected_value.as_bytes())
+    })
+}
+
+/// A single message decoded with candidate decoders
+#[derive(Debug, Serialize)]
+pub struct DecodePreview {
+    pub offset: i64,
+    pub partition: i32,
+    #[serde(rename = "rawKeyHex")]
+    pub raw_key_hex: String,
+    #[serde(rename = "rawValueHex")]
+    pub raw_value_hex: String,
+    #[serde(rename = "decodedKey")]
+    pub decoded_key: String,
+    #[serde(rename = "decodedValue")]
+    pub decoded_value: String,
+    /// First decoding error, if any
+    pub error: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -132,6 +1139,14 @@ pub struct KafkaMessageResponse {
     pub key: Option<String>,
     pub value: Option<String>,
     pub timestamp: i64,
+    /// Header values by name, decoded with the topic's header decoder (base64 when binary).
+    /// A repeated header name keeps its last value.
+    pub headers: HashMap<String, String>,
+    /// Format used to decode the value
+    #[serde(rename = "detectedFormat")]
+    pub detected_format: String,
+    /// Was the value replaced because it exceeded `max.messages.bytes`?
+    pub truncated: bool,
 }
 
 impl From<KafkaMessage> for KafkaMessageResponse {
@@ -143,6 +1158,9 @@ impl From<KafkaMessage> for KafkaMessageResponse {
             key: msg.key.map(|k| String::from_utf8_lossy(&k).to_string()),
             value: msg.payload.map(|p| String::from_utf8_lossy(&p).to_string()),
             timestamp: msg.timestamp,
+            headers: msg.decoded_headers.into_iter().collect(),
+            detected_format: "String".to_string(),
+            truncated: msg.truncated,
         }
     }
 }
@@ -156,14 +1174,39 @@ pub struct TopicMetadataResponse {
     pub replication_factor: i32,
     pub internal: bool,
     pub partitions: Vec<PartitionInfo>,
+    /// Only populated when configs were requested
+    pub flags: Option<TopicFlagsResponse>,
 }
 
 #[derive(Debug, Serialize)]
-pub struct PartitionInfo {
-    pub id: i32,
-    pub leader: i32,
-    pub replicas: Vec<i32>,
-    pub isr: Vec<i32>,
+pub struct TopicFlagsResponse {
+    #[serde(rename = "isCompacted")]
+    pub is_compacted: bool,
+    #[serde(rename = "isTieredStorage")]
+    pub is_tiered_storage: bool,
+    #[serde(rename = "hasDeleteRetention")]
+    pub has_delete_retention: bool,
+    #[serde(rename = "retentionMs")]
+    pub retention_ms: Option<i64>,
+}
+
+impl From<TopicFlags> for TopicFlagsResponse {
+    fn from(flags: TopicFlags) -> Self {
+        Self {
+            is_compacted: flags.is_compacted,
+            is_tiered_storage: flags.is_tiered_storage,
+            has_delete_retention: flags.has_delete_retention,
+            retention_ms: flags.retention_ms,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct PartitionInfo {
+    pub id: i32,
+    pub leader: i32,
+    pub replicas: Vec<i32>,
+    pub isr: Vec<i32>,
 }
 
 #[derive(Debug, Serialize)]
@@ -173,9 +1216,510 @@ pub struct ConsumerGroupResponse {
     pub state: String,
     #[serde(rename = "protocolType")]
     pub protocol_type: Option<String>,
+    pub protocol: Option<String>,
     pub members: Vec<ConsumerMemberResponse>,
 }
 
+impl From<&ConsumerGroupInfo> for ConsumerGroupResponse {
+    fn from(g: &ConsumerGroupInfo) -> Self {
+        Self {
+            group_id: g.group_id.clone(),
+            state: g.state.clone(),
+            protocol_type: g.protocol_type.clone(),
+            protocol: g.protocol.clone(),
+            members: g
+                .members
+                .iter()
+                .map(|m| ConsumerMemberResponse {
+                    member_id: m.member_id.clone(),
+                    client_id: m.client_id.clone(),
+                    client_host: m.client_host.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct PartitionLagInfo {
+    pub topic: String,
+    pub partition: i32,
+    #[serde(rename = "committedOffset")]
+    pub committed_offset: i64,
+    #[serde(rename = "highWatermark")]
+    pub high_watermark: i64,
+    pub lag: i64,
+}
+
+impl From<ConsumerLag> for PartitionLagInfo {
+    fn from(lag: ConsumerLag) -> Self {
+        Self {
+            topic: lag.topic,
+            partition: lag.partition,
+            committed_offset: lag.offset,
+            high_watermark: lag.log_end_offset,
+            lag: lag.lag,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConsumerGroupDetailsResponse {
+    #[serde(flatten)]
+    pub group: ConsumerGroupResponse,
+    pub partitions: Vec<PartitionLagInfo>,
+    #[serde(rename = "totalLag")]
+    pub total_lag: i64,
+}
+
+impl ConsumerGroupDetailsResponse {
+    fn new(group: &ConsumerGroupInfo, lags: Vec<ConsumerLag>) -> Self {
+        let partitions: Vec<PartitionLagInfo> = lags.into_iter().map(PartitionLagInfo::from).collect();
+        Self {
+            group: ConsumerGroupResponse::from(group),
+            total_lag: partitions.iter().map(|p| p.lag).sum(),
+            partitions,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConsumerLagResponse {
+    pub topic: String,
+    pub partition: i32,
+    pub offset: i64,
+    #[serde(rename = "logEndOffset")]
+    pub log_end_offset: i64,
+    pub lag: i64,
+}
+
+impl From<ConsumerLag> for ConsumerLagResponse {
+    fn from(lag: ConsumerLag) -> Self {
+        Self {
+            topic: lag.topic,
+            partition: lag.partition,
+            offset: lag.offset,
+            log_end_offset: lag.log_end_offset,
+            lag: lag.lag,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct TopicWithConfigsResponse {
+    pub name: String,
+    #[serde(rename = "nonDefaultConfigs")]
+    pub non_default_configs: HashMap<String, String>,
+}
+
+/// Config entry of a topic, with sensitive values left empty
+#[derive(Debug, Serialize)]
+pub struct TopicConfigEntry {
+    pub key: String,
+    pub value: String,
+    #[serde(rename = "isDefault")]
+    pub is_default: bool,
+    #[serde(rename = "isReadOnly")]
+    pub is_read_only: bool,
+}
+
+impl From<TopicConfigInfo> for TopicConfigEntry {
+    fn from(entry: TopicConfigInfo) -> Self {
+        Self {
+            key: entry.name,
+            value: entry.value.unwrap_or_default(),
+            is_default: entry.is_default,
+            is_read_only: entry.is_read_only,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct TopicConfigResponse {
+    pub entries: Vec<TopicConfigEntry>,
+}
+
+impl From<Vec<TopicConfigInfo>> for TopicConfigResponse {
+    fn from(entries: Vec<TopicConfigInfo>) -> Self {
+        let mut entries: Vec<TopicConfigEntry> = entries.into_iter().map(TopicConfigEntry::from).collect();
+        entries.sort_by(|a, b| a.key.cmp(&b.key));
+        Self { entries }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct PartitionWatermarkResponse {
+    pub partition: i32,
+    pub low: i64,
+    pub high: i64,
+    #[serde(rename = "messageCount")]
+    pub message_count: i64,
+}
+
+impl From<PartitionWatermark> for PartitionWatermarkResponse {
+    fn from(watermark: PartitionWatermark) -> Self {
+        Self {
+            partition: watermark.partition,
+            low: watermark.low,
+            high: watermark.high,
+            message_count: watermark.message_count,
+        }
+    }
+}
+
+/// Background task with its progress, as listed by `list_tasks`
+#[derive(Debug, Serialize)]
+pub struct TaskSummaryResponse {
+    pub id: String,
+    pub name: String,
+    pub current: usize,
+    pub total: usize,
+    pub message: String,
+    #[serde(rename = "isComplete")]
+    pub is_complete: bool,
+    pub error: Option<String>,
+    #[serde(rename = "isCancelled")]
+    pub is_cancelled: bool,
+    #[serde(rename = "isFinished")]
+    pub is_finished: bool,
+}
+
+impl From<TaskSummary> for TaskSummaryResponse {
+    fn from(task: TaskSummary) -> Self {
+        Self {
+            id: task.id,
+            name: task.name,
+            current: task.progress.current,
+            total: task.progress.total,
+            message: task.progress.message,
+            is_complete: task.progress.is_complete,
+            error: task.progress.error,
+            is_cancelled: task.is_cancelled,
+            is_finished: task.is_finished,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct TaskQueueStatus {
+    pub running: usize,
+    pub queued: usize,
+    #[serde(rename = "maxConcurrent")]
+    pub max_concurrent: usize,
+}
+
+/// Offset of a partition, e.g. the result of a timestamp lookup
+#[derive(Debug, Serialize)]
+pub struct PartitionOffsetInfo {
+    pub partition: i32,
+    pub offset: i64,
+}
+
+/// Config entry of a broker. Sensitive values are masked, or empty when never set.
+#[derive(Debug, Serialize)]
+pub struct BrokerConfigEntry {
+    pub key: String,
+    pub value: Option<String>,
+    #[serde(rename = "isSensitive")]
+    pub is_sensitive: bool,
+    #[serde(rename = "isReadOnly")]
+    pub is_read_only: bool,
+    pub source: String,
+}
+
+impl From<BrokerConfigInfo> for BrokerConfigEntry {
+    fn from(entry: BrokerConfigInfo) -> Self {
+        let value = if !entry.is_sensitive {
+            entry.value
+        } else if entry.value.is_some() || entry.source != ConfigSource::Default {
+            Some(SENSITIVE_CONFIG_MASK.to_string())
+        } else {
+            None
+        };
+        Self {
+            key: entry.name,
+            value,
+            is_sensitive: entry.is_sensitive,
+            is_read_only: entry.is_read_only,
+            source: config_source_name(entry.source).to_string(),
+        }
+    }
+}
+
+/// Requested change to one topic config entry
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AlterTopicConfigEntry {
+    pub key: String,
+    /// Ignored for DELETE
+    pub value: Option<String>,
+    /// "SET", "DELETE", "APPEND" or "SUBTRACT"
+    pub operation: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AlterTopicConfigRequest {
+    pub topic: String,
+    pub entries: Vec<AlterTopicConfigEntry>,
+}
+
+/// Outcome of one requested config change
+#[derive(Debug, Serialize)]
+pub struct AlterTopicConfigResult {
+    pub key: String,
+    pub operation: String,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+impl From<ConfigAlterResult> for AlterTopicConfigResult {
+    fn from(result: ConfigAlterResult) -> Self {
+        Self {
+            success: result.is_success(),
+            key: result.name,
+            operation: result.operation,
+            error: result.error,
+        }
+    }
+}
+
+/// Per-entry outcome of `alter_topic_config`, in request order
+#[derive(Debug, Serialize)]
+pub struct AlterTopicConfigResponse {
+    pub success: bool,
+    pub results: Vec<AlterTopicConfigResult>,
+}
+
+impl AlterTopicConfigResponse {
+    /// Merge the results of the applied and the locally rejected entries back into request order
+    fn new(request: &AlterTopicConfigRequest, mut outcomes: Vec<ConfigAlterResult>) -> Self {
+        let mut results = Vec::with_capacity(outcomes.len());
+        for entry in &request.entries {
+            let key = entry.key.trim();
+            if let Some(index) = outcomes.iter().position(|o| o.name == key) {
+                results.push(AlterTopicConfigResult::from(outcomes.remove(index)));
+            }
+        }
+        results.extend(outcomes.into_iter().map(AlterTopicConfigResult::from));
+        Self {
+            success: results.iter().all(|r| r.success),
+            results,
+        }
+    }
+}
+
+impl From<TopicWithConfigs> for TopicWithConfigsResponse {
+    fn from(topic: TopicWithConfigs) -> Self {
+        Self {
+            name: topic.name,
+            non_default_configs: topic.non_default_configs,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CopyOffsetResult {
+    #[serde(rename = "copiedPartitions")]
+    pub copied_partitions: usize,
+    pub errors: Vec<String>,
+}
+
+impl From<OffsetCopy> for CopyOffsetResult {
+    fn from(copy: OffsetCopy) -> Self {
+        Self {
+            copied_partitions: copy.copied_partitions,
+            errors: copy.errors,
+        }
+    }
+}
+
+/// Saved connection with the details shown on its connection list card
+#[derive(Debug, Serialize)]
+pub struct ServerConnectionListItem {
+    #[serde(flatten)]
+    pub connection: ServerConnection,
+    #[serde(rename = "displayString")]
+    pub display_string: String,
+    pub summary: ConnectionSummary,
+}
+
+impl From<ServerConnection> for ServerConnectionListItem {
+    fn from(connection: ServerConnection) -> Self {
+        Self {
+            display_string: connection.to_display_string(),
+            summary: connection.get_connection_summary(),
+            connection,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct BrokerDetailResponse {
+    pub id: i32,
+    pub host: String,
+    pub port: i32,
+}
+
+impl From<BrokerDetail> for BrokerDetailResponse {
+    fn from(broker: BrokerDetail) -> Self {
+        Self {
+            id: broker.id,
+            host: broker.host,
+            port: broker.port,
+        }
+    }
+}
+
+/// Every network endpoint a connection uses, for debugging connectivity
+#[derive(Debug, Serialize)]
+pub struct ConnectionTopology {
+    #[serde(rename = "bootstrapServers")]
+    pub bootstrap_servers: Vec<String>,
+    /// Brokers from the cluster metadata (empty when not connected or unreachable)
+    #[serde(rename = "resolvedBrokers")]
+    pub resolved_brokers: Vec<BrokerDetailResponse>,
+    #[serde(rename = "zookeeperHosts")]
+    pub zookeeper_hosts: Option<Vec<String>>,
+    #[serde(rename = "schemaRegistryUrl")]
+    pub schema_registry_url: Option<String>,
+    #[serde(rename = "schemaRegistryReachable")]
+    pub schema_registry_reachable: bool,
+}
+
+/// Split a comma-separated host list, dropping empty entries
+fn split_hosts(hosts: &str) -> Vec<String> {
+    hosts
+        .split(',')
+        .map(|h| h.trim().to_string())
+        .filter(|h| !h.is_empty())
+        .collect()
+}
+
+#[derive(Debug, Serialize)]
+pub struct MessageSizeDistributionResponse {
+    #[serde(rename = "p50Bytes")]
+    pub p50_bytes: usize,
+    #[serde(rename = "p90Bytes")]
+    pub p90_bytes: usize,
+    #[serde(rename = "p99Bytes")]
+    pub p99_bytes: usize,
+    #[serde(rename = "maxBytes")]
+    pub max_bytes: usize,
+    #[serde(rename = "avgBytes")]
+    pub avg_bytes: f64,
+    #[serde(rename = "sampleCount")]
+    pub sample_count: usize,
+}
+
+impl From<MessageSizeDistribution> for MessageSizeDistributionResponse {
+    fn from(dist: MessageSizeDistribution) -> Self {
+        Self {
+            p50_bytes: dist.p50_bytes,
+            p90_bytes: dist.p90_bytes,
+            p99_bytes: dist.p99_bytes,
+            max_bytes: dist.max_bytes,
+            avg_bytes: dist.avg_bytes,
+            sample_count: dist.sample_count,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SchemaEvolutionResult {
+    pub compatible: bool,
+    #[serde(rename = "registeredId")]
+    pub registered_id: Option<i32>,
+    #[serde(rename = "compatibilityErrors")]
+    pub compatibility_errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsumerOffsetResponse {
+    #[serde(rename = "groupId")]
+    pub group_id: String,
+    pub topic: String,
+    pub partition: i32,
+    pub offset: i64,
+}
+
+impl From<ConsumerOffset> for ConsumerOffsetResponse {
+    fn from(offset: ConsumerOffset) -> Self {
+        Self {
+            group_id: offset.group_id,
+            topic: offset.topic,
+            partition: offset.partition,
+            offset: offset.offset,
+        }
+    }
+}
+
+/// Committed offsets of a group at the start and end of a measurement interval
+#[derive(Debug, Clone, Serialize)]
+pub struct OffsetDiff {
+    #[serde(rename = "beforeOffsets")]
+    pub before_offsets: Vec<ConsumerOffsetResponse>,
+    #[serde(rename = "afterOffsets")]
+    pub after_offsets: Vec<ConsumerOffsetResponse>,
+    #[serde(rename = "consumedPerPartition")]
+    pub consumed_per_partition: HashMap<i32, i64>,
+    #[serde(rename = "totalConsumed")]
+    pub total_consumed: i64,
+    #[serde(rename = "ratePerSecond")]
+    pub rate_per_second: f64,
+}
+
+impl OffsetDiff {
+    /// Compare two offset snapshots; partitions without a committed offset before count from zero
+    fn between(before: Vec<ConsumerOffset>, after: Vec<ConsumerOffset>, elapsed: Duration) -> Self {
+        let before_by_partition: HashMap<i32, i64> =
+            before.iter().map(|o| (o.partition, o.offset)).collect();
+        let consumed_per_partition: HashMap<i32, i64> = after
+            .iter()
+            .map(|o| {
+                let start = before_by_partition.get(&o.partition).copied().unwrap_or(0);
+                (o.partition, (o.offset - start).max(0))
+            })
+            .collect();
+        let total_consumed = consumed_per_partition.values().sum();
+        let secs = elapsed.as_secs_f64();
+
+        Self {
+            before_offsets: before.into_iter().map(ConsumerOffsetResponse::from).collect(),
+            after_offsets: after.into_iter().map(ConsumerOffsetResponse::from).collect(),
+            consumed_per_partition,
+            total_consumed,
+            rate_per_second: if secs > 0.0 { total_consumed as f64 / secs } else { 0.0 },
+        }
+    }
+}
+
+/// Topic matched by a search across all connected servers
+#[derive(Debug, Clone, Serialize)]
+pub struct TopicSearchResult {
+    #[serde(rename = "serverId")]
+    pub server_id: i64,
+    #[serde(rename = "serverName")]
+    pub server_name: String,
+    #[serde(rename = "topicName")]
+    pub topic_name: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ClusterFeaturesResponse {
+    #[serde(rename = "kraftEnabled")]
+    pub kraft_enabled: bool,
+    #[serde(rename = "supportedFeatures")]
+    pub supported_features: HashMap<String, (i16, i16)>,
+}
+
+impl From<ClusterFeatures> for ClusterFeaturesResponse {
+    fn from(features: ClusterFeatures) -> Self {
+        Self {
+            kraft_enabled: features.kraft_enabled,
+            supported_features: features.supported_features,
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct ConsumerMemberResponse {
     #[serde(rename = "memberId")]
@@ -213,41 +1757,109 @@ impl From<SchemaInfoInternal> for SchemaInfo {
 // --- Server Management ---
 
 #[tauri::command]
-pub fn get_server_connections(state: State<'_, Arc<AppState>>) -> Result<Vec<ServerConnection>, String> {
+pub fn get_server_connections(state: State<'_, Arc<AppState>>) -> Result<Vec<ServerConnectionListItem>, String> {
     info!("Getting all server connections");
-    Ok(state.get_server_configs())
+    Ok(state
+        .get_server_configs()
+        .into_iter()
+        .map(ServerConnectionListItem::from)
+        .collect())
+}
+
+/// Problems found while loading the configuration files at startup
+#[tauri::command]
+pub fn get_config_load_errors(state: State<'_, Arc<AppState>>) -> Result<Vec<String>, String> {
+    Ok(state.environment.lock().unwrap().load_errors.clone())
 }
 
 #[tauri::command]
 pub fn add_server_connection(request: ServerConnectionRequest, state: State<'_, Arc<AppState>>) -> Result<i64, String> {
     info!("Adding server connection: {}", request.name);
 
-    let mut config = ServerConnection::new(0, request.name.clone());
-    config.bootstrap_servers = request.bootstrap_servers.unwrap_or_default();
-    if !config.bootstrap_servers.is_empty() {
-        config.host = config.bootstrap_servers.split(',').next().unwrap_or("").split(':').next().unwrap_or("").to_string();
-    }
-
-    let id = state.add_server_config(config);
+    let config = request.to_server_connection(0).map_err(|e| e.to_string())?;
+    let id = state.add_server_config(config).map_err(|e| e.to_string())?;
     info!("Server added with ID: {}", id);
     Ok(id)
 }
 
+/// Check that a connection works before saving it; the connection is not kept
+#[tauri::command]
+pub fn test_server_connection(
+    request: ServerConnectionRequest,
+    timeout_ms: Option<u64>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<ConnectionTestResult, String> {
+    info!("Testing server connection: {}", request.name);
+
+    let config = request.to_server_connection(0).map_err(|e| e.to_string())?;
+    let timeouts = KafkaClientTimeouts {
+        request_timeout_ms: timeout_ms.unwrap_or(DEFAULT_CONNECTION_TEST_TIMEOUT_MS).min(i32::MAX as u64) as i32,
+        ..KafkaClientTimeouts::from_settings(&state.environment.lock().unwrap().settings)
+    };
+
+    let start = Instant::now();
+    let brokers = KafkaMapper::new(config, timeouts).and_then(|mapper| mapper.list_brokers());
+    let result = ConnectionTestResult::new(brokers, start.elapsed());
+
+    match result.error {
+        Some(ref e) => warn!("Connection test for '{}' failed: {}", request.name, e),
+        None => info!("Connection test for '{}' found {} brokers", request.name, result.broker_count),
+    }
+    Ok(result)
+}
+
 #[tauri::command]
 pub fn update_server_connection(id: i64, request: ServerConnectionRequest, state: State<'_, Arc<AppState>>) -> Result<(), String> {
     info!("Updating server connection ID {}: {}", id, request.name);
-    // TODO: Implement update
+
+    let mut config = state.find_server_config(id).map_err(|e| e.to_string())?;
+    request.apply_to(&mut config).map_err(|e| e.to_string())?;
+    state.update_server_config(config).map_err(|e| e.to_string())?;
+    state.reconnect_if_connected(id).map_err(|e| e.to_string())?;
     Ok(())
 }
 
 #[tauri::command]
 pub fn remove_server_connection(id: i64, state: State<'_, Arc<AppState>>) -> Result<(), String> {
     info!("Removing server connection ID {}", id);
-    state.remove_server_config(id);
+    state.remove_server_config(id).map_err(|e| e.to_string())?;
     state.remove_connection(id);
     Ok(())
 }
 
+/// Export all server connections to a JSON file, without passwords
+#[tauri::command]
+pub fn export_connections_json(file_path: String, state: State<'_, Arc<AppState>>) -> Result<(), String> {
+    info!("Exporting server connections to {}", file_path);
+
+    let mut settings = ServerConnectionSettings::new();
+    for config in state.get_server_configs() {
+        settings.add_connection(config).map_err(|e| e.to_string())?;
+    }
+
+    let json = settings.to_json().map_err(|e| e.to_string())?;
+    std::fs::write(&file_path, json).map_err(|e| format!("Failed to write {}: {}", file_path, e))
+}
+
+/// Import server connections from a JSON file, returning the new IDs
+#[tauri::command]
+pub fn import_connections_json(file_path: String, state: State<'_, Arc<AppState>>) -> Result<Vec<i64>, String> {
+    info!("Importing server connections from {}", file_path);
+
+    let json = std::fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read {}: {}", file_path, e))?;
+    let settings = ServerConnectionSettings::from_json(&json).map_err(|e| e.to_string())?;
+
+    let ids = settings
+        .get_connections()
+        .iter()
+        .map(|conn| state.add_server_config(conn.clone()))
+        .collect::<Result<Vec<i64>>>()
+        .map_err(|e| e.to_string())?;
+    info!("Imported {} server connections", ids.len());
+    Ok(ids)
+}
+
 #[tauri::command]
 pub fn connect_to_server(id: i64, state: State<'_, Arc<AppState>>) -> Result<(), String> {
     info!("Connecting to server ID {}", id);
@@ -256,9 +1868,7 @@ pub fn connect_to_server(id: i64, state: State<'_, Arc<AppState>>) -> Result<(),
         return Err(format!("Already connected to server ID {}", id));
     }
 
-    let conn = ServerConnection::new(id, format!("Server-{}", id));
-    let mapper = KafkaMapper::new(conn).map_err(|e| e.to_string())?;
-    state.add_connection(id, Arc::new(mapper));
+    state.open_connection(id).map_err(|e| e.to_string())?;
 
     info!("Successfully connected to server ID {}", id);
     Ok(())
@@ -269,7 +1879,7 @@ pub fn disconnect_from_server(id: i64, state: State<'_, Arc<AppState>>) -> Resul
     info!("Disconnecting from server ID {}", id);
 
     if let Some(mapper) = state.get_connection(id) {
-        mapper.close().map_err(|e| e.to_string())?;
+        mapper.close(true).map_err(|e| e.to_string())?;
         state.remove_connection(id);
         info!("Successfully disconnected from server ID {}", id);
     } else {
@@ -279,11 +1889,320 @@ pub fn disconnect_from_server(id: i64, state: State<'_, Arc<AppState>>) -> Resul
     Ok(())
 }
 
-// --- Topic Management ---
+#[tauri::command]
+pub fn get_security_types() -> Vec<String> {
+    BrokerSecurityType::all_variants()
+        .iter()
+        .map(|t| t.to_string())
+        .collect()
+}
 
 #[tauri::command]
-pub fn list_topics(server_id: i64, state: State<'_, Arc<AppState>>) -> Result<Vec<String>, String> {
-    info!("Listing topics for server ID {}", server_id);
+pub fn get_sasl_mechanisms() -> Vec<String> {
+    SaslMechanism::all_variants()
+        .iter()
+        .map(|m| m.to_string())
+        .collect()
+}
+
+#[tauri::command]
+pub fn start_token_refresh(
+    server_id: i64,
+    token_endpoint_url: String,
+    client_id: String,
+    client_secret: String,
+    refresh_before_expiry_secs: u64,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    info!("Starting OAuth token refresh for server ID {}", server_id);
+
+    state
+        .start_token_refresh(
+            server_id,
+            token_endpoint_url,
+            client_id,
+            client_secret,
+            refresh_before_expiry_secs,
+        )
+        .map_err(|e| format!("Failed to start token refresh: {}", e))
+}
+
+// --- ZooKeeper ---
+
+/// Open a ZooKeeper session for a server.
+///
+/// A `request` overrides the configured hosts, chroot and session timeout where set, and
+/// its authentication is kept for later reconnects.
+#[tauri::command]
+pub fn connect_zookeeper(
+    server_id: i64,
+    request: Option<ZkConnectionRequest>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    info!("Connecting to ZooKeeper for server ID {}", server_id);
+
+    if state.get_zookeeper_connection(server_id).is_some() {
+        return Err(format!("Already connected to ZooKeeper for server ID {}", server_id));
+    }
+
+    let config = state
+        .get_server_configs()
+        .into_iter()
+        .find(|c| c.id == server_id)
+        .ok_or_else(|| format!("Server ID {} not found", server_id))?;
+
+    let mut hosts = config.get_zookeeper_hosts();
+    let mut chroot = config.chroot.clone();
+    let mut session_timeout_ms = state
+        .environment
+        .lock()
+        .unwrap()
+        .settings
+        .get_int("zookeeper_timeout")
+        .unwrap_or(10000)
+        .max(0) as u64;
+    if let Some(request) = &request {
+        if !request.zk_hosts.trim().is_empty() {
+            hosts = request.zk_hosts.trim().to_string();
+        }
+        if let Some(request_chroot) = &request.chroot {
+            chroot = request_chroot.clone();
+        }
+        if let Some(timeout) = request.session_timeout {
+            session_timeout_ms = timeout as u64;
+        }
+        match ZkAuth::from_request(request).map_err(|e| e.to_string())? {
+            Some(auth) => state.zookeeper_auth.lock().unwrap().insert(server_id, auth),
+            None => state.zookeeper_auth.lock().unwrap().remove(&server_id),
+        };
+    } else if config.no_zookeeper_mode() {
+        return Err(format!("Server ID {} has no ZooKeeper host configured", server_id));
+    }
+    if hosts.is_empty() {
+        return Err(format!("Server ID {} has no ZooKeeper host configured", server_id));
+    }
+
+    let auth = state.zookeeper_auth.lock().unwrap().get(&server_id).cloned();
+    let client = crate::async_ops::background_runtime()
+        .block_on(ZooKeeperClient::connect_with_auth(
+            &zookeeper_connect_string(&hosts, &chroot),
+            Duration::from_millis(session_timeout_ms),
+            auth.as_ref(),
+        ))
+        .map_err(|e| e.to_string())?;
+
+    state
+        .zookeeper_connections
+        .lock()
+        .unwrap()
+        .insert(server_id, Arc::new(client));
+    state
+        .event_bus
+        .publish(AppEvent::ServerConnectionConnected { id: server_id });
+
+    info!("Successfully connected to ZooKeeper for server ID {}", server_id);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn disconnect_zookeeper(server_id: i64, state: State<'_, Arc<AppState>>) -> Result<(), String> {
+    info!("Disconnecting from ZooKeeper for server ID {}", server_id);
+
+    let client = state.zookeeper_connections.lock().unwrap().remove(&server_id);
+    state.stop_zk_watches(|(_, id, _)| *id == server_id);
+    if let Some(client) = client {
+        crate::async_ops::background_runtime()
+            .block_on(client.close())
+            .map_err(|e| e.to_string())?;
+        state
+            .event_bus
+            .publish(AppEvent::ServerConnectionDisconnected { id: server_id });
+    } else {
+        warn!("Server ID {} had no ZooKeeper session", server_id);
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn diff_zk_nodes(
+    server_id_a: i64,
+    path_a: String,
+    server_id_b: i64,
+    path_b: String,
+    include_stat: bool,
+    state: State<'_, Arc<AppState>>,
+) -> Result<ZkNodeDiff, String> {
+    info!("Comparing ZooKeeper node {}:{} with {}:{}", server_id_a, path_a, server_id_b, path_b);
+
+    let client_a = zookeeper_session(&state, server_id_a)?;
+    let client_b = zookeeper_session(&state, server_id_b)?;
+
+    crate::async_ops::background_runtime()
+        .block_on(client_a.compare_node(&path_a, &client_b, &path_b, include_stat))
+        .map_err(|e| e.to_string())
+}
+
+fn zookeeper_session(state: &AppState, server_id: i64) -> Result<Arc<ZooKeeperClient>, String> {
+    state
+        .get_zookeeper_connection(server_id)
+        .ok_or_else(|| format!("No ZooKeeper session for server ID {}", server_id))
+}
+
+#[tauri::command]
+pub fn get_zk_children(
+    server_id: i64,
+    path: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<ZkNode>, String> {
+    debug!("Listing ZooKeeper children of {} for server ID {}", path, server_id);
+
+    let client = zookeeper_session(&state, server_id)?;
+    crate::async_ops::background_runtime()
+        .block_on(client.list_child_nodes(&path))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_zk_node(server_id: i64, path: String, state: State<'_, Arc<AppState>>) -> Result<ZkNode, String> {
+    debug!("Reading ZooKeeper node {} for server ID {}", path, server_id);
+
+    let client = zookeeper_session(&state, server_id)?;
+    crate::async_ops::background_runtime()
+        .block_on(client.get_node(&path))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn create_zk_node(
+    server_id: i64,
+    request: CreateZkNodeRequest,
+    state: State<'_, Arc<AppState>>,
+) -> Result<String, String> {
+    info!("Creating ZooKeeper node {} for server ID {}", request.path, server_id);
+
+    request.validate().map_err(|e| e.to_string())?;
+    let mode = parse_create_mode(&request.create_mode).map_err(|e| e.to_string())?;
+    let data = decode_node_data(&request.data, request.is_binary).map_err(|e| e.to_string())?;
+    let acl = request
+        .acl
+        .iter()
+        .map(from_zk_acl)
+        .collect::<anyhow::Result<Vec<_>>>()
+        .map_err(|e| e.to_string())?;
+
+    let client = zookeeper_session(&state, server_id)?;
+    crate::async_ops::background_runtime()
+        .block_on(client.create_node(&request.path, &data, acl, mode))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn update_zk_node(
+    server_id: i64,
+    request: UpdateZkNodeRequest,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    info!("Updating ZooKeeper node {} for server ID {}", request.path, server_id);
+
+    let data = decode_node_data(&request.data, request.is_binary).map_err(|e| e.to_string())?;
+    let client = zookeeper_session(&state, server_id)?;
+    crate::async_ops::background_runtime()
+        .block_on(client.update_node(&request.path, &data, request.expected_version.unwrap_or(-1)))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_zk_node(
+    server_id: i64,
+    request: DeleteZkNodeRequest,
+    state: State<'_, Arc<AppState>>,
+) -> Result<usize, String> {
+    info!("Deleting ZooKeeper node {} for server ID {}", request.path, server_id);
+
+    let client = zookeeper_session(&state, server_id)?;
+    let runtime = crate::async_ops::background_runtime();
+    if request.recursive {
+        runtime.block_on(client.delete_recursive(&request.path))
+    } else {
+        runtime
+            .block_on(client.delete_node(&request.path, request.expected_version.unwrap_or(-1)))
+            .map(|()| 1)
+    }
+    .map_err(|e| e.to_string())
+}
+
+/// Frontend event carrying ZooKeeper watch notifications
+pub const ZK_WATCH_EVENT: &str = "zk-watch-event";
+
+/// ZooKeeper watch notification emitted to the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct ZkWatchEventResponse {
+    #[serde(rename = "serverId")]
+    pub server_id: i64,
+    #[serde(flatten)]
+    pub event: ZkWatchEvent,
+}
+
+/// Relay changes of a ZooKeeper node to the calling window as `zk-watch-event` events
+#[tauri::command]
+pub fn watch_zk_node(
+    window: Window,
+    server_id: i64,
+    path: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    info!("Watching ZooKeeper node {} for server ID {}", path, server_id);
+
+    let key = (window.label().to_string(), server_id, path.clone());
+    if state
+        .zk_watch_relays
+        .lock()
+        .unwrap()
+        .get(&key)
+        .is_some_and(|relay| !relay.is_finished())
+    {
+        return Ok(());
+    }
+
+    let client = zookeeper_session(&state, server_id)?;
+    let runtime = crate::async_ops::background_runtime();
+    let mut events = runtime
+        .block_on(client.watch_node(&path))
+        .map_err(|e| e.to_string())?;
+
+    let relay = runtime.spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(event) => {
+                    if let Err(e) = window.emit(ZK_WATCH_EVENT, ZkWatchEventResponse { server_id, event }) {
+                        warn!("Failed to emit ZooKeeper watch event: {}", e);
+                        break;
+                    }
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("ZooKeeper watch relay skipped {} events", skipped);
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+    state.zk_watch_relays.lock().unwrap().insert(key, relay);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_zk_connections(state: State<'_, Arc<AppState>>) -> Result<Vec<i64>, String> {
+    let mut ids: Vec<i64> = state.zookeeper_connections.lock().unwrap().keys().copied().collect();
+    ids.sort();
+    Ok(ids)
+}
+
+// --- Topic Management ---
+
+#[tauri::command]
+pub fn list_topics(server_id: i64, state: State<'_, Arc<AppState>>) -> Result<Vec<String>, String> {
+    info!("Listing topics for server ID {}", server_id);
 
     let mapper = state
         .get_connection(server_id)
@@ -320,10 +2239,84 @@ pub fn delete_topic(server_id: i64, topic_name: String, state: State<'_, Arc<App
     mapper.delete_topic(&topic_name).map_err(|e| e.to_string())
 }
 
+/// Low and high watermark offsets of one partition
+#[tauri::command]
+pub fn get_partition_watermarks(
+    server_id: i64,
+    topic: String,
+    partition: i32,
+    state: State<'_, Arc<AppState>>,
+) -> Result<PartitionWatermarkResponse, String> {
+    debug!("Getting watermarks of {}/{} on server ID {}", topic, partition, server_id);
+
+    let mapper = state
+        .get_connection(server_id)
+        .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
+
+    let (low, high) = mapper.get_watermarks(&topic, partition).map_err(|e| e.to_string())?;
+    Ok(PartitionWatermark::new(partition, low, high).into())
+}
+
+/// Low and high watermark offsets of every partition of a topic
+#[tauri::command]
+pub fn get_topic_watermarks(
+    server_id: i64,
+    topic: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<PartitionWatermarkResponse>, String> {
+    info!("Getting watermarks of topic '{}' on server ID {}", topic, server_id);
+
+    let mapper = state
+        .get_connection(server_id)
+        .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
+
+    let watermarks = mapper.get_all_watermarks(&topic).map_err(|e| e.to_string())?;
+    Ok(watermarks.into_iter().map(PartitionWatermarkResponse::from).collect())
+}
+
+/// Offset per partition of the first message at or after `timestamp_ms`, for seeking to a time
+#[tauri::command]
+pub fn get_offsets_for_timestamp(
+    server_id: i64,
+    topic: String,
+    timestamp_ms: i64,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<PartitionOffsetInfo>, String> {
+    info!("Getting offsets of topic '{}' at timestamp {} on server ID {}", topic, timestamp_ms, server_id);
+
+    let mapper = state
+        .get_connection(server_id)
+        .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
+
+    let offsets = mapper.offsets_for_timestamp(&topic, timestamp_ms).map_err(|e| e.to_string())?;
+    Ok(offsets
+        .into_iter()
+        .map(|(partition, offset)| PartitionOffsetInfo { partition, offset })
+        .collect())
+}
+
+/// Grow a topic to `new_count` partitions
+#[tauri::command]
+pub fn increase_topic_partitions(
+    server_id: i64,
+    topic: String,
+    new_count: i32,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    info!("Increasing partitions of topic '{}' to {} on server ID {}", topic, new_count, server_id);
+
+    let mapper = state
+        .get_connection(server_id)
+        .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
+
+    mapper.increase_partitions(&topic, new_count).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn get_topic_metadata(
     server_id: i64,
     topic_name: String,
+    include_configs: Option<bool>,
     state: State<'_, Arc<AppState>>,
 ) -> Result<TopicMetadataResponse, String> {
     info!("Getting metadata for topic '{}' on server ID {}", topic_name, server_id);
@@ -333,6 +2326,11 @@ pub fn get_topic_metadata(
         .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
 
     let metadata = mapper.get_topic_metadata(&topic_name).map_err(|e| e.to_string())?;
+    let flags = if include_configs.unwrap_or(false) {
+        Some(mapper.get_topic_flags(&topic_name).map_err(|e| e.to_string())?.into())
+    } else {
+        None
+    };
 
     Ok(TopicMetadataResponse {
         name: metadata.name,
@@ -353,6 +2351,7 @@ pub fn get_topic_metadata(
                 isr: p.isr.clone(),
             })
             .collect(),
+        flags,
     })
 }
 
@@ -382,367 +2381,2511 @@ pub fn get_topic_partitions(
         .collect())
 }
 
-// --- Message Operations ---
-
 #[tauri::command]
-pub fn consume_messages(
+pub fn get_replica_assignment(
     server_id: i64,
     topic: String,
-    partition: Option<i32>,
-    offset: Option<i64>,
-    limit: usize,
     state: State<'_, Arc<AppState>>,
-) -> Result<Vec<KafkaMessageResponse>, String> {
-    info!(
-        "Consuming messages from topic '{}' on server ID {} (limit: {})",
-        topic, server_id, limit
-    );
+) -> Result<HashMap<i32, Vec<i32>>, String> {
+    info!("Getting replica assignment of topic '{}' on server ID {}", topic, server_id);
 
     let mapper = state
         .get_connection(server_id)
         .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
 
-    let consumer = mapper.create_consumer("offset-explorer-temp").map_err(|e| e.to_string())?;
-
-    if let Some(p) = partition {
-        let start_offset = offset.unwrap_or(0);
-        consumer.assign(&topic, p, start_offset).map_err(|e| e.to_string())?;
-    } else {
-        consumer.subscribe(&[&topic]).map_err(|e| e.to_string())?;
-    }
-
-    let mut messages = Vec::new();
-    for _ in 0..limit {
-        match consumer.poll(1000).map_err(|e| e.to_string())? {
-            Some(msg) => {
-                messages.push(KafkaMessageResponse::from(msg));
-            }
-            None => break,
-        }
-    }
-
-    info!("Consumed {} messages", messages.len());
-    Ok(messages)
+    mapper.get_replica_assignment(&topic).map_err(|e| e.to_string())
 }
 
+/// Compare a proposed partition reassignment with the current replica assignment
 #[tauri::command]
-pub fn produce_message(
+pub fn plan_partition_reassignment(
     server_id: i64,
-    request: ProduceMessageRequest,
+    topic: String,
+    proposed: HashMap<i32, Vec<i32>>,
     state: State<'_, Arc<AppState>>,
-) -> Result<(), String> {
-    info!(
-        "Producing message to topic '{}' on server ID {}",
-        request.topic, server_id
-    );
+) -> Result<Vec<PartitionAssignmentDisplay>, String> {
+    info!("Planning reassignment of topic '{}' on server ID {}", topic, server_id);
 
     let mapper = state
         .get_connection(server_id)
         .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
 
-    let key = request.key.map(|k| k.into_bytes());
-    let value = request.value.map(|v| v.into_bytes());
+    let current = mapper.get_replica_assignment(&topic).map_err(|e| e.to_string())?;
+    if let Some(partition) = proposed.keys().find(|p| !current.contains_key(p)) {
+        return Err(format!("Topic '{}' has no partition {}", topic, partition));
+    }
 
-    mapper
-        .produce_message(&request.topic, key, value)
-        .map_err(|e| e.to_string())
+    let plan = PartitionAssignmentDisplay::diff(&current, &proposed);
+    info!(
+        "Reassignment plan moves {} of {} partitions",
+        plan.iter().filter(|p| p.is_changed()).count(),
+        plan.len()
+    );
+    Ok(plan)
 }
 
-// --- Consumer Groups ---
-
+/// List non-internal topics with their non-default config values
 #[tauri::command]
-pub fn list_consumer_groups(
+pub fn get_topics_with_configs(
     server_id: i64,
     state: State<'_, Arc<AppState>>,
-) -> Result<Vec<ConsumerGroupResponse>, String> {
-    info!("Listing consumer groups for server ID {}", server_id);
+) -> Result<Vec<TopicWithConfigsResponse>, String> {
+    info!("Getting topic configs on server ID {}", server_id);
 
     let mapper = state
         .get_connection(server_id)
         .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
 
-    let groups = mapper.list_consumer_groups().map_err(|e| e.to_string())?;
-
-    Ok(groups
-        .iter()
-        .map(|g| ConsumerGroupResponse {
-            group_id: g.group_id.clone(),
-            state: g.state.clone(),
-            protocol_type: g.protocol_type.clone(),
-            members: g
-                .members
-                .iter()
-                .map(|m| ConsumerMemberResponse {
-                    member_id: m.member_id.clone(),
-                    client_id: m.client_id.clone(),
-                    client_host: m.client_host.clone(),
-                })
-                .collect(),
-        })
-        .collect())
+    let topics = mapper.get_topics_with_configs().map_err(|e| e.to_string())?;
+    Ok(topics.into_iter().map(TopicWithConfigsResponse::from).collect())
 }
 
+/// Get every config entry of a topic (retention, cleanup policy, compression...)
 #[tauri::command]
-pub fn get_consumer_group_details(
+pub fn get_topic_config(
     server_id: i64,
-    group_id: String,
+    topic_name: String,
     state: State<'_, Arc<AppState>>,
-) -> Result<serde_json::Value, String> {
-    info!(
-        "Getting details for consumer group '{}' on server ID {}",
-        group_id, server_id
-    );
+) -> Result<TopicConfigResponse, String> {
+    info!("Getting config of topic '{}' on server ID {}", topic_name, server_id);
 
-    let _mapper = state
+    let mapper = state
         .get_connection(server_id)
         .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
 
-    Ok(serde_json::json!({
-        "groupId": group_id,
-        "state": "Stable",
-        "members": []
-    }))
+    let entries = mapper.get_topic_config_entries(&topic_name).map_err(|e| e.to_string())?;
+    Ok(entries.into())
 }
 
+/// Change config entries of a topic. Invalid entries are reported without being sent;
+/// the remaining ones are applied together.
 #[tauri::command]
-pub fn reset_consumer_offset(
+pub fn alter_topic_config(
     server_id: i64,
-    group_id: String,
-    topic: String,
-    partition: i32,
-    offset: i64,
+    request: AlterTopicConfigRequest,
     state: State<'_, Arc<AppState>>,
-) -> Result<(), String> {
-    info!(
-        "Resetting offset for group '{}' topic {} partition {} to {}",
-        group_id, topic, partition, offset
-    );
+) -> Result<AlterTopicConfigResponse, String> {
+    info!("Altering config of topic '{}' on server ID {}", request.topic, server_id);
 
     let mapper = state
         .get_connection(server_id)
         .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
 
-    let consumer = mapper.create_consumer(&group_id).map_err(|e| e.to_string())?;
-    consumer
-        .assign(&topic, partition, offset)
-        .map_err(|e| e.to_string())?;
-    consumer.commit().map_err(|e| e.to_string())?;
+    let (valid, mut outcomes) = config_admin::validate_alterations(
+        request.entries.iter().map(|e| (e.key.as_str(), e.operation.as_str(), e.value.as_deref())),
+    );
+    let applied = mapper.alter_topic_config_entries(&request.topic, &valid).map_err(|e| e.to_string())?;
+    outcomes.extend(applied);
 
-    info!("Offset reset successfully");
-    Ok(())
+    Ok(AlterTopicConfigResponse::new(&request, outcomes))
 }
 
-// --- Tasks ---
+/// Case-insensitive topic name matcher; plain patterns match as substrings
+fn topic_search_regex(pattern: &str, use_regex: bool) -> Result<regex::Regex> {
+    let pattern = if use_regex {
+        pattern.to_string()
+    } else {
+        regex::escape(pattern)
+    };
+    regex::RegexBuilder::new(&pattern)
+        .case_insensitive(true)
+        .build()
+        .map_err(|e| anyhow!("Invalid topic pattern: {}", e))
+}
 
+/// Search the topic lists of all connected servers in parallel
 #[tauri::command]
-pub fn get_task_progress(
-    task_id: String,
+pub fn search_topics_all_servers(
+    pattern: String,
+    use_regex: bool,
     state: State<'_, Arc<AppState>>,
-) -> Result<crate::async_ops::TaskProgress, String> {
-    debug!("Getting progress for task: {}", task_id);
+) -> Result<Vec<TopicSearchResult>, String> {
+    info!("Searching topics on all servers for '{}'", pattern);
 
-    let progress = state
-        .task_manager
-        .get_task_progress(&task_id)
-        .ok_or_else(|| format!("Task {} not found", task_id))?;
+    let regex = topic_search_regex(&pattern, use_regex).map_err(|e| e.to_string())?;
+    let mappers: Vec<(i64, Arc<KafkaMapper>)> = state
+        .connections
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(id, mapper)| (*id, mapper.clone()))
+        .collect();
+    let server_names: HashMap<i64, String> = state
+        .get_server_configs()
+        .into_iter()
+        .map(|c| (c.id, c.name))
+        .collect();
 
-    Ok(progress)
-}
+    let mut results = crate::async_ops::background_runtime().block_on(async move {
+        let mut tasks = tokio::task::JoinSet::new();
+        for (server_id, mapper) in mappers {
+            tasks.spawn(async move {
+                let listed = tokio::time::timeout(
+                    TOPIC_SEARCH_TIMEOUT,
+                    tokio::task::spawn_blocking(move || mapper.list_topics()),
+                )
+                .await;
+                (server_id, listed)
+            });
+        }
 
-#[tauri::command]
-pub fn cancel_task(task_id: String, state: State<'_, Arc<AppState>>) -> Result<(), String> {
-    info!("Cancelling task: {}", task_id);
-    state.task_manager.cancel_task(&task_id).map_err(|e| e.to_string())
+        let mut results = Vec::new();
+        while let Some(joined) = tasks.join_next().await {
+            let (server_id, listed) = match joined {
+                Ok(r) => r,
+                Err(e) => {
+                    warn!("Topic search task failed: {}", e);
+                    continue;
+                }
+            };
+            let topics = match listed {
+                Ok(Ok(Ok(topics))) => topics,
+                Ok(Ok(Err(e))) => {
+                    warn!("Failed to list topics on server ID {}: {}", server_id, e);
+                    continue;
+                }
+                Ok(Err(e)) => {
+                    warn!("Topic listing task for server ID {} failed: {}", server_id, e);
+                    continue;
+                }
+                Err(_) => {
+                    warn!("Listing topics on server ID {} timed out", server_id);
+                    continue;
+                }
+            };
+
+            let server_name = server_names.get(&server_id).cloned().unwrap_or_default();
+            results.extend(
+                topics
+                    .into_iter()
+                    .filter(|t| regex.is_match(t))
+                    .map(|topic_name| TopicSearchResult {
+                        server_id,
+                        server_name: server_name.clone(),
+                        topic_name,
+                    }),
+            );
+        }
+        results
+    });
+
+    results.sort_by(|a, b| (a.server_id, &a.topic_name).cmp(&(b.server_id, &b.topic_name)));
+    Ok(results)
 }
 
+/// Copy a topic's decoder config to other topics, returning the number updated
 #[tauri::command]
-pub fn list_tasks() -> Result<Vec<String>, String> {
-    debug!("Listing all tasks");
-    Ok(Vec::new())
+pub fn clone_topic_config(
+    server_id: i64,
+    source_topic: String,
+    dest_topics: Vec<String>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<usize, String> {
+    info!(
+        "Copying decoder config of '{}' to {} topics on server ID {}",
+        source_topic, dest_topics.len(), server_id
+    );
+
+    let mut config = state
+        .get_server_configs()
+        .into_iter()
+        .find(|c| c.id == server_id)
+        .ok_or_else(|| format!("Server ID {} not found", server_id))?;
+
+    let updated = config
+        .clone_topic_config(&source_topic, &dest_topics)
+        .map_err(|e| e.to_string())?;
+    state.update_server_config(config).map_err(|e| e.to_string())?;
+
+    Ok(updated)
 }
 
-// --- Brokers ---
+// --- Message Operations ---
 
 #[tauri::command]
-pub fn list_brokers(server_id: i64, state: State<'_, Arc<AppState>>) -> Result<Vec<i32>, String> {
-    info!("Listing brokers for server ID {}", server_id);
+pub fn consume_messages(
+    server_id: i64,
+    topic: String,
+    partition: Option<i32>,
+    offset: Option<i64>,
+    limit: usize,
+    header_filter: Option<HeaderFilter>,
+    json_path: Option<String>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<KafkaMessageResponse>, String> {
+    info!(
+        "Consuming messages from topic '{}' on server ID {} (limit: {})",
+        topic, server_id, limit
+    );
 
-    let mapper = state
-        .get_connection(server_id)
-        .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
+    // Shared with seek_partition, so a sought partition is read from its new position
+    let consumer = state
+        .get_or_create_consumer(server_id, BROWSE_CONSUMER_GROUP)
+        .map_err(|e| e.to_string())?;
 
-    mapper.list_brokers().map_err(|e| e.to_string())
+    let server_config = state.get_server_configs().into_iter().find(|c| c.id == server_id);
+    let auto_detect = server_config
+        .as_ref()
+        .and_then(|c| c.topic_configs.get(&topic))
+        .and_then(|c| c.message_decoder.as_deref())
+        == Some("auto");
+    let decoder_chain = server_config
+        .as_ref()
+        .and_then(|c| c.topic_configs.get(&topic))
+        .map(|c| c.build_decoder_chain())
+        .transpose()
+        .map_err(|e| e.to_string())?
+        .flatten();
+    let json_path_decoder = json_path
+        .or_else(|| {
+            server_config
+                .as_ref()
+                .and_then(|c| c.topic_configs.get(&topic))
+                .and_then(|c| c.json_path.clone())
+        })
+        .filter(|path| !path.trim().is_empty())
+        .map(|path| crate::decoders::JsonDecoder::with_path(&path))
+        .transpose()
+        .map_err(|e| e.to_string())?;
+    let schema_registry = server_config
+        .as_ref()
+        .and_then(|c| state.connection_schema_registry(c));
+
+    let header_matcher = header_filter
+        .map(HeaderFilter::compile)
+        .transpose()
+        .map_err(|e| e.to_string())?;
+
+    if let Some(p) = partition {
+        match offset {
+            Some(start_offset) => consumer.seek_or_assign(&topic, p, start_offset),
+            None => consumer.ensure_assigned(&topic, p, 0),
+        }
+        .map_err(|e| e.to_string())?;
+    } else {
+        consumer.subscribe(&[&topic]).map_err(|e| e.to_string())?;
+    }
+
+    let mut messages = Vec::new();
+    let mut budget = ScanBudget::new(limit);
+    while messages.len() < limit {
+        if !budget.take() {
+            info!("Stopped scanning '{}' before finding {} matching messages", topic, limit);
+            break;
+        }
+        match consumer.poll(1000).map_err(|e| e.to_string())? {
+            Some(msg) => {
+                if let Some(ref matcher) = header_matcher {
+                    if !matcher.matches(&msg.headers) {
+                        continue;
+                    }
+                }
+
+                let payload = msg.payload.clone();
+                let mut response = KafkaMessageResponse::from(msg);
+                if let (Some(chain), false, Some(payload)) = (&decoder_chain, response.truncated, &payload) {
+                    response.value = Some(chain.decode(payload).unwrap_or_else(|e| e.to_string()));
+                } else if let (true, false, Some(payload)) = (auto_detect, response.truncated, payload) {
+                    let (value, format) = crate::async_ops::background_runtime()
+                        .block_on(crate::decoders::auto_detect(&payload, schema_registry.clone()))
+                        .map_err(|e| e.to_string())?;
+                    response.value = Some(value);
+                    response.detected_format = format.to_string();
+                }
+                if let (Some(decoder), Some(value)) = (&json_path_decoder, &response.value) {
+                    // Values that are not JSON are shown unchanged
+                    if let Ok(extracted) = decoder.decode(value.as_bytes()) {
+                        response.value = Some(extracted);
+                    }
+                }
+                messages.push(response);
+            }
+            None => break,
+        }
+    }
+
+    info!("Consumed {} messages", messages.len());
+    state.record_consumed(server_id, &topic, messages.len());
+    state.record_navigation(server_id, &topic, partition, offset);
+    Ok(messages)
 }
 
-// --- ACLs ---
+/// Consume from several topics with one consumer, returning messages ordered by timestamp
+#[tauri::command]
+pub fn consume_messages_multi(
+    server_id: i64,
+    topics: Vec<String>,
+    limit_per_topic: usize,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<KafkaMessageResponse>, String> {
+    info!(
+        "Consuming from {} topics on server ID {} (limit per topic: {})",
+        topics.len(), server_id, limit_per_topic
+    );
+
+    if topics.is_empty() {
+        return Err("At least one topic is required".to_string());
+    }
+    if topics.len() > MAX_MULTI_CONSUME_TOPICS {
+        return Err(format!("At most {} topics can be consumed at once", MAX_MULTI_CONSUME_TOPICS));
+    }
+
+    let consumer = state
+        .get_or_create_consumer(server_id, BROWSE_CONSUMER_GROUP)
+        .map_err(|e| e.to_string())?;
+
+    let topic_refs: Vec<&str> = topics.iter().map(String::as_str).collect();
+    consumer.subscribe(&topic_refs).map_err(|e| e.to_string())?;
+
+    let mut counts: HashMap<String, usize> = topics.iter().map(|t| (t.clone(), 0)).collect();
+    let mut messages = Vec::new();
+    // Messages of topics that already reached their limit are read and dropped
+    let mut budget = ScanBudget::new(limit_per_topic.saturating_mul(topics.len()));
+    while counts.values().any(|&count| count < limit_per_topic) {
+        if !budget.take() {
+            info!("Stopped consuming before every topic reached {} messages", limit_per_topic);
+            break;
+        }
+        let msg = match consumer.poll(1000).map_err(|e| e.to_string())? {
+            Some(msg) => msg,
+            None => break,
+        };
+
+        let count = match msg.topic.as_ref().and_then(|t| counts.get_mut(t)) {
+            Some(count) => count,
+            None => continue,
+        };
+        if *count < limit_per_topic {
+            *count += 1;
+            messages.push(KafkaMessageResponse::from(msg));
+        }
+    }
+
+    messages.sort_by_key(|m| m.timestamp);
+    info!("Consumed {} messages from {} topics", messages.len(), topics.len());
+    Ok(messages)
+}
 
 #[tauri::command]
-pub fn list_acls(
+pub fn produce_message(
     server_id: i64,
-    filter: Option<AclFilter>,
+    request: ProduceMessageRequest,
     state: State<'_, Arc<AppState>>,
-) -> Result<Vec<AclBinding>, String> {
-    info!("Listing ACLs for server ID {}", server_id);
+) -> Result<(), String> {
+    info!(
+        "Producing message to topic '{}' on server ID {}",
+        request.topic, server_id
+    );
 
     let mapper = state
         .get_connection(server_id)
         .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
 
-    mapper.list_acls(filter).map_err(|e| e.to_string())
+    let is_production = state
+        .get_server_configs()
+        .iter()
+        .any(|c| c.id == server_id && c.is_production());
+    let warn_enabled = state
+        .environment
+        .lock()
+        .unwrap()
+        .settings
+        .get_bool("warn_on_produce_in_production")
+        .unwrap_or(true);
+    if is_production && warn_enabled {
+        warn!("Producing to production server ID {}", server_id);
+        state.event_bus.publish(AppEvent::ProductionWarning {
+            server_id,
+            topic: request.topic.clone(),
+        });
+    }
+
+    let key = request
+        .key
+        .map(|k| decode_with_encoding(k, request.key_encoding.as_deref()))
+        .transpose()
+        .map_err(|e| format!("Invalid key: {}", e))?;
+    let value = request
+        .value
+        .map(|v| decode_with_encoding(v, request.value_encoding.as_deref()))
+        .transpose()
+        .map_err(|e| format!("Invalid value: {}", e))?;
+
+    mapper
+        .produce_message(&request.topic, key, value, &request.headers.unwrap_or_default(), request.partition)
+        .map_err(|e| e.to_string())
 }
 
+/// Produce many messages to one topic with a single flush. Messages that cannot be
+/// decoded or queued are reported by index; the others are still sent.
 #[tauri::command]
-pub fn create_acl(
+pub fn produce_messages_batch(
     server_id: i64,
-    request: CreateAclRequest,
+    requests: Vec<ProduceMessageRequest>,
     state: State<'_, Arc<AppState>>,
-) -> Result<(), String> {
-    info!("Creating ACL on server ID {}", server_id);
+) -> Result<Vec<BatchProduceResultResponse>, String> {
+    info!("Producing batch of {} messages on server ID {}", requests.len(), server_id);
 
-    request.validate().map_err(|e| e.to_string())?;
+    let topic = match requests.first() {
+        Some(request) => request.topic.clone(),
+        None => return Ok(Vec::new()),
+    };
+    if let Some(other) = requests.iter().find(|r| r.topic != topic) {
+        return Err(format!(
+            "All messages of a batch must go to one topic (found '{}' and '{}')",
+            topic, other.topic
+        ));
+    }
 
     let mapper = state
         .get_connection(server_id)
         .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
 
-    mapper.create_acl(&request).map_err(|e| e.to_string())
+    let (messages, indices, decode_failures) = split_batch_requests(requests);
+    let mut results: Vec<BatchProduceResult> = mapper
+        .produce_messages_batch(&topic, messages)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|result| BatchProduceResult { index: indices[result.index], ..result })
+        .collect();
+    results.extend(decode_failures);
+    results.sort_by_key(|r| r.index);
+
+    Ok(results.into_iter().map(BatchProduceResultResponse::from).collect())
+}
+
+/// Decode batch requests, returning the messages with their request indices and a failed
+/// result for every request that could not be decoded
+fn split_batch_requests(
+    requests: Vec<ProduceMessageRequest>,
+) -> (Vec<BatchMessage>, Vec<usize>, Vec<BatchProduceResult>) {
+    let mut messages = Vec::with_capacity(requests.len());
+    let mut indices = Vec::with_capacity(requests.len());
+    let mut failures = Vec::new();
+    for (index, request) in requests.into_iter().enumerate() {
+        match request.into_batch_message() {
+            Ok(message) => {
+                messages.push(message);
+                indices.push(index);
+            }
+            Err(e) => failures.push(BatchProduceResult { index, error: Some(e.to_string()) }),
+        }
+    }
+    (messages, indices, failures)
 }
 
+/// Produce several messages atomically in one transaction
 #[tauri::command]
-pub fn delete_acl(
+pub fn produce_message_transactional(
     server_id: i64,
-    principal: String,
-    resource_type: String,
-    resource_name: String,
-    operation: String,
-    permission_type: String,
-    host: String,
+    requests: Vec<ProduceMessageRequest>,
     state: State<'_, Arc<AppState>>,
 ) -> Result<(), String> {
-    info!("Deleting ACL on server ID {}", server_id);
+    info!(
+        "Producing {} messages in a transaction on server ID {}",
+        requests.len(), server_id
+    );
 
     let mapper = state
         .get_connection(server_id)
         .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
 
-    mapper
-        .delete_acl(
-            &principal,
-            &resource_type,
-            &resource_name,
-            &operation,
-            &permission_type,
-            &host,
-        )
-        .map_err(|e| e.to_string())
-}
+    // Decode everything up front so a bad request never leaves a transaction open
+    let mut records = Vec::with_capacity(requests.len());
+    for request in requests {
+        let key = request
+            .key
+            .map(|k| decode_with_encoding(k, request.key_encoding.as_deref()))
+            .transpose()
+            .map_err(|e| format!("Invalid key: {}", e))?;
+        let value = request
+            .value
+            .map(|v| decode_with_encoding(v, request.value_encoding.as_deref()))
+            .transpose()
+            .map_err(|e| format!("Invalid value: {}", e))?;
+        records.push((request.topic, key, value, request.headers.unwrap_or_default(), request.partition));
+    }
 
-// --- Schema Registry ---
+    mapper.begin_transaction().map_err(|e| e.to_string())?;
+    for (topic, key, value, headers, partition) in records {
+        if let Err(e) = mapper.produce_message(&topic, key, value, &headers, partition) {
+            if let Err(abort_err) = mapper.abort_transaction() {
+                warn!("Failed to abort transaction: {}", abort_err);
+            }
+            return Err(e.to_string());
+        }
+    }
+    mapper.commit_transaction().map_err(|e| e.to_string())
+}
 
+/// Estimate how long importing a JSON lines file takes at the given producer rate
 #[tauri::command]
-pub fn list_schema_subjects(registry_url: String) -> Result<Vec<String>, String> {
-    info!("Listing Schema Registry subjects");
-
-    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("Failed to create runtime: {}", e))?;
-
-    let client =
-        SchemaRegistryClient::new(registry_url).map_err(|e| format!("Failed to create client: {}", e))?;
+pub fn estimate_import_time(
+    file_path: String,
+    producer_rate_msgs_per_sec: u32,
+) -> Result<ImportEstimate, String> {
+    info!(
+        "Estimating import time of '{}' at {} msgs/s",
+        file_path, producer_rate_msgs_per_sec
+    );
 
-    rt.block_on(async { client.get_subjects().await })
-        .map_err(|e| format!("Failed to list subjects: {}", e))
+    crate::message_import::estimate_import(std::path::Path::new(&file_path), producer_rate_msgs_per_sec)
+        .map_err(|e| e.to_string())
 }
 
+/// Import messages from a JSON lines file in the background, returning the task ID.
+///
+/// The task result has the delivered (`count`) and `failed` messages, and how many were
+/// still in flight when flushing stopped making progress (`unflushed`).
 #[tauri::command]
-pub fn get_schema(
-    registry_url: String,
-    subject: String,
-    version: i32,
-) -> Result<SchemaInfo, String> {
-    info!("Getting schema {} version {}", subject, version);
+pub fn import_messages(
+    server_id: i64,
+    topic: String,
+    file_path: String,
+    mapping: FieldMapping,
+    state: State<'_, Arc<AppState>>,
+) -> Result<String, String> {
+    info!(
+        "Importing messages from '{}' into topic '{}' on server ID {}",
+        file_path, topic, server_id
+    );
 
-    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("Failed to create runtime: {}", e))?;
+    let mapper = state
+        .get_connection(server_id)
+        .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
 
-    let client =
-        SchemaRegistryClient::new(registry_url).map_err(|e| format!("Failed to create client: {}", e))?;
+    let task_id = format!("import-{}-{}", topic, chrono::Utc::now().timestamp_millis());
+    let task_name = format!("Import messages into {}", topic);
+    let event_bus = state.event_bus.clone();
+    let progress_task_id = task_id.clone();
 
-    rt.block_on(async { client.get_schema(&subject, version).await })
-        .map(|info| info.into())
-        .map_err(|e| format!("Failed to get schema: {}", e))
+    state
+        .task_manager
+        .spawn_task_with_handle(task_id.clone(), task_name, move |handle| async move {
+            let file = std::fs::File::open(&file_path)
+                .map_err(|e| anyhow!("Failed to read {}: {}", file_path, e))?;
+            let progress_topic = topic.clone();
+            let publish_progress = move |count| {
+                event_bus.publish(AppEvent::MessagesImported {
+                    server_id,
+                    topic: progress_topic.clone(),
+                    task_id: progress_task_id.clone(),
+                    count,
+                });
+            };
+
+            let (queued, unflushed, counts, topic) = tokio::task::spawn_blocking(move || {
+                let counts = Arc::new(DeliveryCounts::default());
+                let mut queued = 0;
+                for (line_no, line) in std::io::BufRead::lines(std::io::BufReader::new(file)).enumerate() {
+                    if handle.is_cancelled() {
+                        return Err(anyhow!("Import cancelled after {} messages", queued));
+                    }
+                    let line = line.map_err(|e| anyhow!("Failed to read {}: {}", file_path, e))?;
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    let message = mapping
+                        .extract(&line)
+                        .map_err(|e| anyhow!("Line {}: {}", line_no + 1, e))?;
+                    mapper.produce_record(
+                        &topic,
+                        message.key.as_deref(),
+                        &message.value,
+                        &message.headers,
+                        message.partition,
+                        &counts,
+                    )?;
+                    queued += 1;
+                    if queued % IMPORT_PROGRESS_INTERVAL == 0 {
+                        publish_progress(queued);
+                    }
+                }
+                let unflushed = mapper.flush_all()?;
+                publish_progress(queued);
+                Ok::<_, anyhow::Error>((queued, unflushed, counts, topic))
+            })
+            .await
+            .map_err(|e| anyhow!("Import task failed: {}", e))??;
+
+            let (imported, failed) = (counts.delivered(), counts.failed());
+            if failed > 0 || unflushed > 0 {
+                warn!(
+                    "Import into '{}': {} of {} messages failed, {} not delivered before the flush stalled",
+                    topic, failed, queued, unflushed
+                );
+            }
+            info!("Imported {} messages into topic '{}'", imported, topic);
+            Ok(serde_json::json!({ "count": imported, "failed": failed, "unflushed": unflushed }))
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(task_id)
 }
 
+/// Export messages of a topic to a JSON lines file in the background, returning the task ID.
+///
+/// Reads `partitions` (all when unset) from their beginning up to the end offsets at the time
+/// the export starts; the task's progress total is the number of messages in that range.
 #[tauri::command]
-pub fn get_latest_schema(registry_url: String, subject: String) -> Result<SchemaInfo, String> {
-    info!("Getting latest schema for {}", subject);
+pub fn export_messages(
+    server_id: i64,
+    topic: String,
+    partitions: Option<Vec<i32>>,
+    file_path: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<String, String> {
+    info!(
+        "Exporting messages from topic '{}' on server ID {} to '{}'",
+        topic, server_id, file_path
+    );
 
-    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("Failed to create runtime: {}", e))?;
+    let mapper = state
+        .get_connection(server_id)
+        .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
 
-    let client =
-        SchemaRegistryClient::new(registry_url).map_err(|e| format!("Failed to create client: {}", e))?;
+    let task_id = format!("export-{}-{}", topic, chrono::Utc::now().timestamp_millis());
+    let task_name = format!("Export messages from {}", topic);
 
-    rt.block_on(async { client.get_latest_schema(&subject).await })
-        .map(|info| info.into())
-        .map_err(|e| format!("Failed to get schema: {}", e))
-}
+    state
+        .task_manager
+        .spawn_task_with_handle(task_id.clone(), task_name, move |handle| async move {
+            let (count, topic) = tokio::task::spawn_blocking(move || {
+                let partitions = match partitions {
+                    Some(partitions) => partitions,
+                    None => mapper.get_topic_metadata(&topic)?.partitions.iter().map(|p| p.id).collect(),
+                };
+                // A consumer of its own, so the export leaves the browse position alone;
+                // payloads are exported in full
+                let consumer = mapper
+                    .create_consumer(BROWSE_CONSUMER_GROUP)?
+                    .with_max_message_bytes(usize::MAX);
+                let beginning = consumer.get_beginning_offsets(&topic, &partitions)?;
+                let end = consumer.get_end_offsets(&topic, &partitions)?;
+                handle.set_total(offset_range_total(&beginning, &end));
 
-#[tauri::command]
-pub fn register_schema(
-    registry_url: String,
-    subject: String,
-    schema: String,
-    schema_type: String,
-) -> Result<i32, String> {
-    info!("Registering schema for {}", subject);
+                let file = std::fs::File::create(&file_path)
+                    .map_err(|e| anyhow!("Failed to create {}: {}", file_path, e))?;
+                let mut writer = std::io::BufWriter::new(file);
+                let assignment: Vec<(i32, i64)> = beginning
+                    .iter()
+                    .filter(|(partition, &low)| end.get(partition).is_some_and(|&high| high > low))
+                    .map(|(&partition, &low)| (partition, low))
+                    .collect();
 
-    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("Failed to create runtime: {}", e))?;
+                let mut count = 0;
+                if !assignment.is_empty() {
+                    consumer.assign_partitions(&topic, &assignment)?;
+                    let mut idle_polls = 0;
+                    while !export_finished(&consumer.position()?, &assignment, &end) {
+                        if handle.is_cancelled() {
+                            return Err(anyhow!("Export cancelled after {} messages", count));
+                        }
+                        let Some(message) = consumer.poll(1000)? else {
+                            idle_polls += 1;
+                            if idle_polls >= EXPORT_IDLE_POLLS {
+                                warn!("Export of '{}' stopped after {} idle polls", topic, idle_polls);
+                                break;
+                            }
+                            continue;
+                        };
+                        idle_polls = 0;
+                        if end.get(&message.partition).is_none_or(|&high| message.offset >= high) {
+                            continue;
+                        }
+                        std::io::Write::write_all(&mut writer, format!("{}\n", export_line(&message)).as_bytes())
+                            .map_err(|e| anyhow!("Failed to write {}: {}", file_path, e))?;
+                        count += 1;
+                        handle.set_current(count);
+                    }
+                }
+                std::io::Write::flush(&mut writer)
+                    .map_err(|e| anyhow!("Failed to write {}: {}", file_path, e))?;
+                Ok::<_, anyhow::Error>((count, topic))
+            })
+            .await
+            .map_err(|e| anyhow!("Export task failed: {}", e))??;
+
+            info!("Exported {} messages from topic '{}'", count, topic);
+            Ok(serde_json::json!({ "count": count }))
+        })
+        .map_err(|e| e.to_string())?;
 
-    let client =
-        SchemaRegistryClient::new(registry_url).map_err(|e| format!("Failed to create client: {}", e))?;
+    Ok(task_id)
+}
 
-    let schema_type_enum = match schema_type.as_str() {
-        "AVRO" => SchemaType::AVRO,
-        "PROTOBUF" => SchemaType::PROTOBUF,
-        "JSON" => SchemaType::JSON,
-        _ => return Err(format!("Invalid schema type: {}", schema_type)),
-    };
+/// Messages between the beginning and end offset of each partition
+fn offset_range_total(beginning: &HashMap<i32, i64>, end: &HashMap<i32, i64>) -> usize {
+    end.iter()
+        .map(|(partition, &high)| {
+            let low = beginning.get(partition).copied().unwrap_or(high);
+            (high - low).max(0) as usize
+        })
+        .sum()
+}
 
-    rt.block_on(async { client.register_schema(&subject, &schema, schema_type_enum).await })
-        .map_err(|e| format!("Failed to register schema: {}", e))
+/// Has the consumer position of every assigned partition reached its end offset?
+fn export_finished(positions: &[(String, i32, i64)], assignment: &[(i32, i64)], end: &HashMap<i32, i64>) -> bool {
+    assignment.iter().all(|(partition, _)| {
+        positions
+            .iter()
+            .any(|(_, p, offset)| p == partition && end.get(partition).is_some_and(|&high| *offset >= high))
+    })
 }
 
+/// Decode one message with the given decoders without saving them to the topic config
 #[tauri::command]
-pub fn test_compatibility(
-    registry_url: String,
-    subject: String,
-    schema: String,
-    schema_type: String,
-) -> Result<bool, String> {
-    info!("Testing compatibility for {}", subject);
+#[allow(clippy::too_many_arguments)]
+pub fn preview_decode(
+    server_id: i64,
+    topic: String,
+    partition: i32,
+    offset: i64,
+    key_decoder_name: String,
+    value_decoder_name: String,
+    schema: Option<String>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<DecodePreview, String> {
+    info!(
+        "Previewing decode of {}/{} at offset {} on server ID {}",
+        topic, partition, offset, server_id
+    );
 
-    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("Failed to create runtime: {}", e))?;
+    let mapper = state
+        .get_connection(server_id)
+        .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
+
+    let schema_registry = state
+        .get_server_configs()
+        .into_iter()
+        .find(|c| c.id == server_id)
+        .and_then(|config| state.connection_schema_registry(&config));
+    let key_decoder = crate::decoders::decoder_by_name(&key_decoder_name, None, schema_registry.clone())
+        .map_err(|e| e.to_string())?;
+    let value_decoder = crate::decoders::decoder_by_name(&value_decoder_name, schema, schema_registry)
+        .map_err(|e| e.to_string())?;
 
-    let client =
-        SchemaRegistryClient::new(registry_url).map_err(|e| format!("Failed to create client: {}", e))?;
+    let msg = mapper
+        .get_message_at_offset(&topic, partition, offset)
+        .map_err(|e| e.to_string())?;
+    let key = msg.key.unwrap_or_default();
+    let value = msg.payload.unwrap_or_default();
 
-    let schema_type_enum = match schema_type.as_str() {
-        "AVRO" => SchemaType::AVRO,
-        "PROTOBUF" => SchemaType::PROTOBUF,
-        "JSON" => SchemaType::JSON,
-        _ => return Err(format!("Invalid schema type: {}", schema_type)),
+    let mut error = None;
+    let mut decode = |decoder: &dyn crate::decoders::Decoder, data: &[u8], what: &str| {
+        decoder.decode(data).unwrap_or_else(|e| {
+            let message = format!("{} decoding with {} failed: {}", what, decoder.name(), e);
+            error.get_or_insert(message.clone());
+            format!("<Decode error: {}>", e)
+        })
     };
+    let decoded_key = decode(key_decoder.as_ref(), &key, "Key");
+    let decoded_value = decode(value_decoder.as_ref(), &value, "Value");
 
-    rt.block_on(async { client.check_compatibility(&subject, &schema, schema_type_enum).await })
-        .map_err(|e| format!("Failed to check compatibility: {}", e))
+    Ok(DecodePreview {
+        offset: msg.offset,
+        partition: msg.partition,
+        raw_key_hex: hex::encode(&key),
+        raw_value_hex: hex::encode(&value),
+        decoded_key,
+        decoded_value,
+        error,
+    })
 }
 
-// ==================== Tests ====================
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
+/// Move the browse consumer of a partition to `offset` for the next browse operation
+#[tauri::command]
+pub fn seek_partition(
+    server_id: i64,
+    topic: String,
+    partition: i32,
+    offset: i64,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    info!(
+        "Seeking {}/{} to offset {} on server ID {}",
+        topic, partition, offset, server_id
+    );
+
+    let consumer = state
+        .get_or_create_consumer(server_id, BROWSE_CONSUMER_GROUP)
+        .map_err(|e| e.to_string())?;
+    consumer
+        .seek_or_assign(&topic, partition, offset)
+        .map_err(|e| e.to_string())
+}
+
+/// Size percentiles of the most recent messages in a partition, to help size `max.message.bytes`
+#[tauri::command]
+pub fn get_message_size_distribution(
+    server_id: i64,
+    topic: String,
+    partition: i32,
+    sample_size: usize,
+    state: State<'_, Arc<AppState>>,
+) -> Result<MessageSizeDistributionResponse, String> {
+    info!(
+        "Sampling {} message sizes from {}/{} on server ID {}",
+        sample_size, topic, partition, server_id
+    );
+
+    let mapper = state
+        .get_connection(server_id)
+        .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
+
+    mapper
+        .sample_message_sizes(&topic, partition, sample_size)
+        .map(MessageSizeDistributionResponse::from)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_message_throughput(server_id: i64, state: State<'_, Arc<AppState>>) -> Result<f64, String> {
+    debug!("Getting message throughput for server ID {}", server_id);
+    Ok(state.get_consumed_messages_per_second(server_id))
+}
+
+// --- Consumer Groups ---
+
+#[tauri::command]
+pub fn list_consumer_groups(
+    server_id: i64,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<ConsumerGroupResponse>, String> {
+    info!("Listing consumer groups for server ID {}", server_id);
+
+    let mapper = state
+        .get_connection(server_id)
+        .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
+
+    let groups = mapper.list_consumer_groups().map_err(|e| e.to_string())?;
+
+    Ok(groups.iter().map(ConsumerGroupResponse::from).collect())
+}
+
+/// List consumer groups with committed offsets for a topic (first 100 groups only)
+#[tauri::command]
+pub fn list_consumer_groups_for_topic(
+    server_id: i64,
+    topic_name: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<ConsumerGroupResponse>, String> {
+    info!("Listing consumer groups of topic '{}' on server ID {}", topic_name, server_id);
+
+    let mapper = state
+        .get_connection(server_id)
+        .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
+
+    let groups = crate::async_ops::background_runtime()
+        .block_on(mapper.list_consumer_groups_for_topic(&topic_name))
+        .map_err(|e| e.to_string())?;
+
+    Ok(groups.iter().map(ConsumerGroupResponse::from).collect())
+}
+
+/// Get per-partition lag of a consumer group and record it for trend graphs
+#[tauri::command]
+pub fn get_consumer_lag(
+    server_id: i64,
+    group_id: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<ConsumerLagResponse>, String> {
+    debug!("Getting consumer lag for group '{}' on server ID {}", group_id, server_id);
+
+    let mapper = state
+        .get_connection(server_id)
+        .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
+
+    let lags = mapper.get_consumer_lag(&group_id).map_err(|e| e.to_string())?;
+    state.record_lag(server_id, &group_id, &lags);
+
+    Ok(lags.into_iter().map(ConsumerLagResponse::from).collect())
+}
+
+/// Measure how many messages a consumer group processes on a topic over an interval.
+///
+/// Runs as a background task so the measurement can be cancelled from the task list;
+/// returns the task ID, and the `OffsetDiff` is available from `get_task_result`.
+#[tauri::command]
+pub fn diff_consumer_group_offsets_over_time(
+    server_id: i64,
+    group_id: String,
+    topic: String,
+    interval_seconds: u64,
+    state: State<'_, Arc<AppState>>,
+) -> Result<String, String> {
+    let mapper = state
+        .get_connection(server_id)
+        .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
+
+    let max_interval = state
+        .environment
+        .lock()
+        .unwrap()
+        .settings
+        .get_int("offset_diff_max_interval_secs")
+        .unwrap_or(60)
+        .max(1) as u64;
+    let interval = Duration::from_secs(interval_seconds.clamp(1, max_interval));
+    info!(
+        "Measuring consumption of group '{}' on topic '{}' over {:?}",
+        group_id, topic, interval
+    );
+
+    let task_id = format!("offset-diff-{}-{}-{}", server_id, group_id, chrono::Utc::now().timestamp_millis());
+    let task_name = format!("Measure consumption of {} on {}", group_id, topic);
+
+    state
+        .task_manager
+        .spawn_task(task_id.clone(), task_name, move || async move {
+            let fetch = |mapper: Arc<KafkaMapper>, group_id: String, topic: String| async move {
+                tokio::task::spawn_blocking(move || {
+                    mapper.get_consumer_group_offsets(&group_id).map(|offsets| {
+                        offsets.into_iter().filter(|o| o.topic == topic).collect::<Vec<_>>()
+                    })
+                })
+                .await
+                .map_err(|e| anyhow!("Offset fetch task failed: {}", e))?
+            };
+
+            let before = fetch(mapper.clone(), group_id.clone(), topic.clone()).await?;
+            let started = Instant::now();
+            tokio::time::sleep(interval).await;
+            let after = fetch(mapper, group_id, topic).await?;
+            Ok(serde_json::to_value(OffsetDiff::between(before, after, started.elapsed()))?)
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(task_id)
+}
+
+/// Start periodic lag tracking for all consumer groups of a server
+#[tauri::command]
+pub fn start_lag_tracking(server_id: i64, state: State<'_, Arc<AppState>>) -> Result<(), String> {
+    info!("Starting consumer lag tracking for server ID {}", server_id);
+
+    state
+        .get_connection(server_id)
+        .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
+
+    state.start_lag_tracking(server_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_lag_trend(
+    server_id: i64,
+    group_id: String,
+    topic: String,
+    partition: i32,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<LagTrendPoint>, String> {
+    Ok(state.get_lag_trend(server_id, &group_id, &topic, partition))
+}
+
+#[tauri::command]
+pub fn get_topics_without_consumers(
+    server_id: i64,
+    exclude_internal: bool,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<String>, String> {
+    info!("Listing topics without consumers for server ID {}", server_id);
+
+    let mapper = state
+        .get_connection(server_id)
+        .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
+
+    mapper
+        .get_topics_without_consumers(exclude_internal)
+        .map_err(|e| e.to_string())
+}
+
+/// Group state, members and per-partition lag of a consumer group
+#[tauri::command]
+pub fn get_consumer_group_details(
+    server_id: i64,
+    group_id: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<ConsumerGroupDetailsResponse, String> {
+    info!(
+        "Getting details for consumer group '{}' on server ID {}",
+        group_id, server_id
+    );
+
+    let mapper = state
+        .get_connection(server_id)
+        .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
+
+    let group = mapper
+        .list_consumer_groups()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .find(|g| g.group_id == group_id)
+        .ok_or_else(|| format!("Consumer group '{}' not found", group_id))?;
+    let lags = mapper.get_consumer_lag(&group_id).map_err(|e| e.to_string())?;
+    state.record_lag(server_id, &group_id, &lags);
+
+    Ok(ConsumerGroupDetailsResponse::new(&group, lags))
+}
+
+#[tauri::command]
+pub fn reset_consumer_offset(
+    server_id: i64,
+    group_id: String,
+    topic: String,
+    partition: i32,
+    offset: i64,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    info!(
+        "Resetting offset for group '{}' topic {} partition {} to {}",
+        group_id, topic, partition, offset
+    );
+
+    let mapper = state
+        .get_connection(server_id)
+        .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
+
+    let consumer = mapper.create_consumer(&group_id).map_err(|e| e.to_string())?;
+    consumer
+        .assign(&topic, partition, offset)
+        .map_err(|e| e.to_string())?;
+    consumer.commit().map_err(|e| e.to_string())?;
+
+    info!("Offset reset successfully");
+    Ok(())
+}
+
+/// Move a consumer group on every partition of a topic to the first message at or after
+/// `timestamp_ms`, returning the committed offsets
+#[tauri::command]
+pub fn reset_consumer_offset_to_timestamp(
+    server_id: i64,
+    group_id: String,
+    topic: String,
+    timestamp_ms: i64,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<PartitionOffsetInfo>, String> {
+    info!(
+        "Resetting offsets of group '{}' on topic {} to timestamp {}",
+        group_id, topic, timestamp_ms
+    );
+
+    let mapper = state
+        .get_connection(server_id)
+        .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
+
+    let offsets = mapper
+        .reset_group_offsets_to_timestamp(&group_id, &topic, timestamp_ms)
+        .map_err(|e| e.to_string())?;
+    Ok(offsets
+        .into_iter()
+        .map(|(partition, offset)| PartitionOffsetInfo { partition, offset })
+        .collect())
+}
+
+/// Move a consumer group to the earliest, latest, a specific or a timestamp's offset on one
+/// partition of a topic, or all of them, returning the committed offsets
+#[tauri::command]
+pub fn reset_consumer_group_offsets(
+    server_id: i64,
+    group_id: String,
+    topic: String,
+    partition: Option<i32>,
+    strategy: OffsetResetStrategy,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<PartitionOffsetInfo>, String> {
+    info!(
+        "Resetting offsets of group '{}' on topic {} with {:?}",
+        group_id, topic, strategy
+    );
+
+    let mapper = state
+        .get_connection(server_id)
+        .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
+
+    let offsets = mapper
+        .reset_group_offsets(&group_id, &topic, partition, strategy)
+        .map_err(|e| e.to_string())?;
+    Ok(offsets
+        .into_iter()
+        .map(|(partition, offset)| PartitionOffsetInfo { partition, offset })
+        .collect())
+}
+
+/// Start `dest_group` where `source_group` left off, e.g. for blue-green deployments
+#[tauri::command]
+pub fn copy_consumer_offsets(
+    server_id: i64,
+    source_group: String,
+    dest_group: String,
+    topics: Option<Vec<String>>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<CopyOffsetResult, String> {
+    info!(
+        "Copying offsets from group '{}' to '{}' on server ID {}",
+        source_group, dest_group, server_id
+    );
+
+    if source_group == dest_group {
+        return Err("Source and destination groups must differ".to_string());
+    }
+
+    let mapper = state
+        .get_connection(server_id)
+        .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
+
+    mapper
+        .copy_consumer_offsets(&source_group, &dest_group, topics.as_deref())
+        .map(CopyOffsetResult::from)
+        .map_err(|e| e.to_string())
+}
+
+// --- Tasks ---
+
+#[tauri::command]
+pub fn get_task_progress(
+    task_id: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<crate::async_ops::TaskProgress, String> {
+    debug!("Getting progress for task: {}", task_id);
+
+    let progress = state
+        .task_manager
+        .get_task_progress(&task_id)
+        .ok_or_else(|| format!("Task {} not found", task_id))?;
+
+    Ok(progress)
+}
+
+/// Value a completed background task finished with, e.g. the number of imported messages
+#[tauri::command]
+pub fn get_task_result(
+    task_id: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<serde_json::Value, String> {
+    debug!("Getting result for task: {}", task_id);
+
+    match state.task_manager.get_task_result(&task_id) {
+        Some(result) => result.map_err(|e| e.to_string()),
+        None => match state.task_manager.get_task_progress(&task_id) {
+            Some(progress) if !progress.is_complete => Err(format!("Task {} is still running", task_id)),
+            Some(_) => Err(format!("Task {} has no result", task_id)),
+            None => Err(format!("Task {} not found", task_id)),
+        },
+    }
+}
+
+#[tauri::command]
+pub fn cancel_task(task_id: String, state: State<'_, Arc<AppState>>) -> Result<(), String> {
+    info!("Cancelling task: {}", task_id);
+    state.task_manager.cancel_task(&task_id).map_err(|e| e.to_string())
+}
+
+/// How many background tasks are running and waiting for a free slot
+#[tauri::command]
+pub fn get_task_queue_status(state: State<'_, Arc<AppState>>) -> Result<TaskQueueStatus, String> {
+    let manager = &state.task_manager;
+    Ok(TaskQueueStatus {
+        running: manager.running_count(),
+        queued: manager.queue_depth(),
+        max_concurrent: manager.max_concurrent(),
+    })
+}
+
+#[tauri::command]
+pub fn list_tasks(state: State<'_, Arc<AppState>>) -> Result<Vec<TaskSummaryResponse>, String> {
+    debug!("Listing all tasks");
+    Ok(state.task_manager.list_tasks().into_iter().map(TaskSummaryResponse::from).collect())
+}
+
+// --- Brokers ---
+
+/// Show bootstrap servers, advertised brokers, ZooKeeper hosts and the Schema Registry of a connection.
+///
+/// Each component is probed separately; failures and timeouts leave that part empty.
+#[tauri::command]
+pub fn get_connection_topology(server_id: i64, state: State<'_, Arc<AppState>>) -> Result<ConnectionTopology, String> {
+    info!("Getting connection topology for server ID {}", server_id);
+
+    let config = state
+        .get_server_configs()
+        .into_iter()
+        .find(|c| c.id == server_id)
+        .ok_or_else(|| format!("Server ID {} not found", server_id))?;
+    let mapper = state.get_connection(server_id);
+    let schema_registry_url = config.schema_registry_endpoint.clone().filter(|u| !u.is_empty());
+    let schema_registry = state.connection_schema_registry(&config);
+
+    // A live ZooKeeper session knows the hosts it actually connected to
+    let zookeeper_hosts = match state.get_zookeeper_connection(server_id) {
+        Some(client) => {
+            let connect_string = client.get_connect_string();
+            let hosts = connect_string.split('/').next().unwrap_or(connect_string);
+            Some(split_hosts(hosts))
+        }
+        None if !config.no_zookeeper_mode() => Some(split_hosts(&config.get_zookeeper_hosts())),
+        None => None,
+    };
+
+    let (resolved_brokers, schema_registry_reachable) = crate::async_ops::background_runtime().block_on(async {
+        let brokers = async {
+            let Some(mapper) = mapper else {
+                return Vec::new();
+            };
+            let described = tokio::time::timeout(
+                TOPOLOGY_PROBE_TIMEOUT,
+                tokio::task::spawn_blocking(move || mapper.describe_cluster()),
+            )
+            .await;
+            match described {
+                Ok(Ok(Ok(brokers))) => brokers,
+                Ok(Ok(Err(e))) => {
+                    warn!("Failed to describe cluster for server ID {}: {}", server_id, e);
+                    Vec::new()
+                }
+                Ok(Err(e)) => {
+                    warn!("Describe cluster task for server ID {} failed: {}", server_id, e);
+                    Vec::new()
+                }
+                Err(_) => {
+                    warn!("Describing cluster for server ID {} timed out", server_id);
+                    Vec::new()
+                }
+            }
+        };
+        let reachable = async {
+            let Some(registry) = schema_registry else {
+                return false;
+            };
+            match tokio::time::timeout(TOPOLOGY_PROBE_TIMEOUT, registry.ping()).await {
+                Ok(Ok(())) => true,
+                Ok(Err(e)) => {
+                    warn!("Schema Registry for server ID {} is unreachable: {}", server_id, e);
+                    false
+                }
+                Err(_) => {
+                    warn!("Schema Registry ping for server ID {} timed out", server_id);
+                    false
+                }
+            }
+        };
+        tokio::join!(brokers, reachable)
+    });
+
+    Ok(ConnectionTopology {
+        bootstrap_servers: split_hosts(&config.get_bootstrap_servers()),
+        resolved_brokers: resolved_brokers.into_iter().map(BrokerDetailResponse::from).collect(),
+        zookeeper_hosts,
+        schema_registry_url,
+        schema_registry_reachable,
+    })
+}
+
+#[tauri::command]
+pub fn list_brokers(server_id: i64, state: State<'_, Arc<AppState>>) -> Result<Vec<i32>, String> {
+    info!("Listing brokers for server ID {}", server_id);
+
+    let mapper = state
+        .get_connection(server_id)
+        .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
+
+    mapper.list_brokers().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_cluster_features(
+    server_id: i64,
+    state: State<'_, Arc<AppState>>,
+) -> Result<ClusterFeaturesResponse, String> {
+    info!("Getting cluster features for server ID {}", server_id);
+
+    let mapper = state
+        .get_connection(server_id)
+        .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
+
+    let features = mapper.describe_features().map_err(|e| e.to_string())?;
+    Ok(ClusterFeaturesResponse::from(features))
+}
+
+/// Get every config entry of a broker, dynamic and static, sorted by key
+#[tauri::command]
+pub fn get_broker_config(
+    server_id: i64,
+    broker_id: i32,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<BrokerConfigEntry>, String> {
+    info!("Getting config of broker {} on server ID {}", broker_id, server_id);
+
+    let mapper = state
+        .get_connection(server_id)
+        .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
+
+    let mut entries: Vec<BrokerConfigEntry> = mapper
+        .get_broker_config_entries(broker_id)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(BrokerConfigEntry::from)
+        .collect();
+    entries.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(entries)
+}
+
+/// Get broker config values that are at their default, cached per connection
+#[tauri::command]
+pub fn get_default_broker_configs(
+    server_id: i64,
+    state: State<'_, Arc<AppState>>,
+) -> Result<HashMap<String, String>, String> {
+    if let Some(configs) = state.broker_default_configs.lock().unwrap().get(&server_id) {
+        debug!("Using cached broker default configs for server ID {}", server_id);
+        return Ok(configs.clone());
+    }
+
+    info!("Getting broker default configs for server ID {}", server_id);
+
+    let mapper = state
+        .get_connection(server_id)
+        .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
+
+    let configs = mapper
+        .get_default_broker_configs(DEFAULT_CONFIG_BROKER_ID)
+        .map_err(|e| e.to_string())?;
+    state
+        .broker_default_configs
+        .lock()
+        .unwrap()
+        .insert(server_id, configs.clone());
+
+    Ok(configs)
+}
+
+// --- ACLs ---
+
+#[tauri::command]
+pub fn list_acls(
+    server_id: i64,
+    filter: Option<AclFilter>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<AclBinding>, String> {
+    info!("Listing ACLs for server ID {}", server_id);
+
+    let mapper = state
+        .get_connection(server_id)
+        .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
+
+    mapper.list_acls(filter).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn create_acl(
+    server_id: i64,
+    request: CreateAclRequest,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    info!("Creating ACL on server ID {}", server_id);
+
+    request.validate().map_err(|e| e.to_string())?;
+
+    let mapper = state
+        .get_connection(server_id)
+        .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
+
+    mapper.create_acl(&request).map_err(|e| e.to_string())
+}
+
+/// Create ACLs from CSV lines, collecting per-line failures
+#[tauri::command]
+pub fn create_acls_from_csv(
+    server_id: i64,
+    csv_content: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<BatchAclResult, String> {
+    info!("Creating ACLs from CSV on server ID {}", server_id);
+
+    let mapper = state
+        .get_connection(server_id)
+        .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
+
+    let mut result = BatchAclResult {
+        created: 0,
+        failed: Vec::new(),
+    };
+    for (line_no, request) in parse_acl_csv(&csv_content) {
+        match request.and_then(|request| mapper.create_acl(&request)) {
+            Ok(()) => result.created += 1,
+            Err(e) => result.failed.push((line_no, e.to_string())),
+        }
+    }
+
+    info!("Created {} ACLs, {} failed", result.created, result.failed.len());
+    Ok(result)
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn delete_acl(
+    server_id: i64,
+    principal: String,
+    resource_type: String,
+    resource_name: String,
+    operation: String,
+    permission_type: String,
+    host: String,
+    pattern_type: Option<String>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), String> {
+    info!("Deleting ACL on server ID {}", server_id);
+
+    let mapper = state
+        .get_connection(server_id)
+        .ok_or_else(|| format!("Not connected to server ID {}", server_id))?;
+
+    mapper
+        .delete_acl(
+            &principal,
+            &resource_type,
+            &resource_name,
+            &operation,
+            &permission_type,
+            &host,
+            pattern_type.as_deref(),
+        )
+        .map_err(|e| e.to_string())
+}
+
+// --- Schema Registry ---
+
+#[tauri::command]
+pub fn list_schema_subjects(
+    registry_url: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<String>, String> {
+    info!("Listing Schema Registry subjects");
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("Failed to create runtime: {}", e))?;
+
+    let client = state
+        .new_schema_registry_client(registry_url)
+        .map_err(|e| format!("Failed to create client: {}", e))?;
+
+    rt.block_on(async { client.get_subjects().await })
+        .map_err(|e| format!("Failed to list subjects: {}", e))
+}
+
+#[tauri::command]
+pub fn list_subjects_with_version_counts(
+    registry_url: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<(String, usize)>, String> {
+    info!("Listing Schema Registry subjects with version counts");
+
+    if let Some(counts) = state.get_cached_subject_version_counts(&registry_url) {
+        debug!("Returning cached version counts for {}", registry_url);
+        return Ok(counts);
+    }
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("Failed to create runtime: {}", e))?;
+
+    let client = state
+        .get_schema_registry_client(&registry_url)
+        .map_err(|e| format!("Failed to create client: {}", e))?;
+
+    let counts = rt
+        .block_on(async { client.get_subject_version_counts().await })
+        .map_err(|e| format!("Failed to list subject versions: {}", e))?;
+
+    state.cache_subject_version_counts(&registry_url, counts.clone());
+    Ok(counts)
+}
+
+#[tauri::command]
+pub fn get_schema(
+    registry_url: String,
+    subject: String,
+    version: i32,
+    state: State<'_, Arc<AppState>>,
+) -> Result<SchemaInfo, String> {
+    info!("Getting schema {} version {}", subject, version);
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("Failed to create runtime: {}", e))?;
+
+    let client = state
+        .new_schema_registry_client(registry_url)
+        .map_err(|e| format!("Failed to create client: {}", e))?;
+
+    rt.block_on(async { client.get_schema(&subject, version).await })
+        .map(|info| info.into())
+        .map_err(|e| format!("Failed to get schema: {}", e))
+}
+
+#[tauri::command]
+pub fn get_latest_schema(
+    registry_url: String,
+    subject: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<SchemaInfo, String> {
+    info!("Getting latest schema for {}", subject);
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("Failed to create runtime: {}", e))?;
+
+    let client = state
+        .new_schema_registry_client(registry_url)
+        .map_err(|e| format!("Failed to create client: {}", e))?;
+
+    rt.block_on(async { client.get_latest_schema(&subject).await })
+        .map(|info| info.into())
+        .map_err(|e| format!("Failed to get schema: {}", e))
+}
+
+/// Register a schema, returning its ID. With `skip_if_registered` an identical schema already
+/// registered under the subject is returned instead of being registered again.
+#[tauri::command]
+pub fn register_schema(
+    registry_url: String,
+    subject: String,
+    schema: String,
+    schema_type: String,
+    skip_if_registered: Option<bool>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<i32, String> {
+    info!("Registering schema for {}", subject);
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("Failed to create runtime: {}", e))?;
+
+    let client = state
+        .new_schema_registry_client(registry_url)
+        .map_err(|e| format!("Failed to create client: {}", e))?;
+
+    let schema_type_enum = parse_schema_type(&schema_type)?;
+
+    rt.block_on(async {
+        if skip_if_registered.unwrap_or(false) {
+            let existing = client
+                .find_schema(&subject, &schema, schema_type_enum.clone())
+                .await
+                .map_err(|e| format!("Failed to look up schema: {}", e))?;
+            if let Some(existing) = existing {
+                info!("Schema already registered for {} as version {}", subject, existing.version);
+                return Ok(existing.id);
+            }
+        }
+        client
+            .register_schema(&subject, &schema, schema_type_enum)
+            .await
+            .map_err(|e| format!("Failed to register schema: {}", e))
+    })
+}
+
+/// Version of `subject` with exactly this schema; fails if it is not registered
+#[tauri::command]
+pub fn lookup_schema(
+    registry_url: String,
+    subject: String,
+    schema: String,
+    schema_type: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<SchemaInfo, String> {
+    info!("Looking up schema in {}", subject);
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("Failed to create runtime: {}", e))?;
+
+    let client = state
+        .new_schema_registry_client(registry_url)
+        .map_err(|e| format!("Failed to create client: {}", e))?;
+
+    let schema_type_enum = parse_schema_type(&schema_type)?;
+
+    rt.block_on(async { client.lookup_schema(&subject, &schema, schema_type_enum).await })
+        .map(|info| info.into())
+        .map_err(|e| format!("Failed to look up schema: {}", e))
+}
+
+#[tauri::command]
+pub fn test_compatibility(
+    registry_url: String,
+    subject: String,
+    schema: String,
+    schema_type: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<bool, String> {
+    info!("Testing compatibility for {}", subject);
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("Failed to create runtime: {}", e))?;
+
+    let client = state
+        .new_schema_registry_client(registry_url)
+        .map_err(|e| format!("Failed to create client: {}", e))?;
+
+    let schema_type_enum = parse_schema_type(&schema_type)?;
+
+    rt.block_on(async { client.check_compatibility(&subject, &schema, schema_type_enum).await })
+        .map_err(|e| format!("Failed to check compatibility: {}", e))
+}
+
+/// Register a new schema version, optionally refusing incompatible schemas
+#[tauri::command]
+pub fn evolve_schema(
+    registry_url: String,
+    subject: String,
+    schema: String,
+    schema_type: String,
+    check_compatibility_first: bool,
+    state: State<'_, Arc<AppState>>,
+) -> Result<SchemaEvolutionResult, String> {
+    info!("Evolving schema for {}", subject);
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("Failed to create runtime: {}", e))?;
+
+    let client = state
+        .new_schema_registry_client(registry_url)
+        .map_err(|e| format!("Failed to create client: {}", e))?;
+
+    let schema_type_enum = parse_schema_type(&schema_type)?;
+
+    rt.block_on(async {
+        if check_compatibility_first {
+            let result = client
+                .check_compatibility_verbose(&subject, &schema, schema_type_enum.clone())
+                .await
+                .map_err(|e| format!("Failed to check compatibility: {}", e))?;
+            if !result.is_compatible {
+                info!("Schema for {} is incompatible, not registering", subject);
+                return Ok(SchemaEvolutionResult {
+                    compatible: false,
+                    registered_id: None,
+                    compatibility_errors: result.messages,
+                });
+            }
+        }
+
+        let id = client
+            .register_schema(&subject, &schema, schema_type_enum)
+            .await
+            .map_err(|e| format!("Failed to register schema: {}", e))?;
+        Ok(SchemaEvolutionResult {
+            compatible: true,
+            registered_id: Some(id),
+            compatibility_errors: Vec::new(),
+        })
+    })
+}
+
+/// Delete a subject, soft-deleting unless `permanent`; returns the deleted versions
+#[tauri::command]
+pub fn delete_schema_subject(
+    registry_url: String,
+    subject: String,
+    permanent: Option<bool>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<i32>, String> {
+    info!("Deleting schema subject {}", subject);
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("Failed to create runtime: {}", e))?;
+
+    let client = state
+        .get_schema_registry_client(&registry_url)
+        .map_err(|e| format!("Failed to create client: {}", e))?;
+
+    let versions = rt
+        .block_on(async { client.delete_subject(&subject, permanent.unwrap_or(false)).await })
+        .map_err(|e| format!("Failed to delete subject: {}", e))?;
+
+    state.invalidate_subject_version_counts(&registry_url);
+    Ok(versions)
+}
+
+/// Delete one version of a subject, soft-deleting unless `permanent`; returns the deleted version
+#[tauri::command]
+pub fn delete_schema_subject_version(
+    registry_url: String,
+    subject: String,
+    version: i32,
+    permanent: Option<bool>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<i32, String> {
+    info!("Deleting schema {} version {}", subject, version);
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("Failed to create runtime: {}", e))?;
+
+    let client = state
+        .get_schema_registry_client(&registry_url)
+        .map_err(|e| format!("Failed to create client: {}", e))?;
+
+    let deleted = rt
+        .block_on(async { client.delete_subject_version(&subject, version, permanent.unwrap_or(false)).await })
+        .map_err(|e| format!("Failed to delete schema version: {}", e))?;
+
+    state.invalidate_subject_version_counts(&registry_url);
+    Ok(deleted)
+}
+
+/// Registry-wide compatibility level, e.g. "BACKWARD"
+#[tauri::command]
+pub fn get_schema_compatibility(
+    registry_url: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<String, String> {
+    info!("Getting global schema compatibility level");
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("Failed to create runtime: {}", e))?;
+
+    let client = state
+        .new_schema_registry_client(registry_url)
+        .map_err(|e| format!("Failed to create client: {}", e))?;
+
+    rt.block_on(async { client.get_global_config().await })
+        .map_err(|e| format!("Failed to get compatibility level: {}", e))
+}
+
+#[tauri::command]
+pub fn set_schema_compatibility(
+    registry_url: String,
+    compatibility: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<String, String> {
+    info!("Setting global schema compatibility level to {}", compatibility);
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("Failed to create runtime: {}", e))?;
+
+    let client = state
+        .new_schema_registry_client(registry_url)
+        .map_err(|e| format!("Failed to create client: {}", e))?;
+
+    rt.block_on(async { client.set_global_config(&compatibility).await })
+        .map_err(|e| format!("Failed to set compatibility level: {}", e))
+}
+
+/// Compatibility level of a subject, or the global level if the subject has none
+#[tauri::command]
+pub fn get_subject_compatibility(
+    registry_url: String,
+    subject: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<String, String> {
+    info!("Getting compatibility level for {}", subject);
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("Failed to create runtime: {}", e))?;
+
+    let client = state
+        .new_schema_registry_client(registry_url)
+        .map_err(|e| format!("Failed to create client: {}", e))?;
+
+    rt.block_on(async { client.get_subject_config(&subject).await })
+        .map_err(|e| format!("Failed to get compatibility level: {}", e))
+}
+
+#[tauri::command]
+pub fn set_subject_compatibility(
+    registry_url: String,
+    subject: String,
+    compatibility: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<String, String> {
+    info!("Setting compatibility level of {} to {}", subject, compatibility);
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("Failed to create runtime: {}", e))?;
+
+    let client = state
+        .new_schema_registry_client(registry_url)
+        .map_err(|e| format!("Failed to create client: {}", e))?;
+
+    rt.block_on(async { client.set_subject_config(&subject, &compatibility).await })
+        .map_err(|e| format!("Failed to set compatibility level: {}", e))
+}
+
+/// Parse a schema type name as sent by the frontend
+fn parse_schema_type(schema_type: &str) -> Result<SchemaType, String> {
+    match schema_type {
+        "AVRO" => Ok(SchemaType::AVRO),
+        "PROTOBUF" => Ok(SchemaType::PROTOBUF),
+        "JSON" => Ok(SchemaType::JSON),
+        _ => Err(format!("Invalid schema type: {}", schema_type)),
+    }
+}
+
+// --- Browser State ---
+
+#[tauri::command]
+pub fn save_browser_state(state: BrowserState, app_state: State<'_, Arc<AppState>>) -> Result<(), String> {
+    debug!("Saving browser state");
+    app_state.save_browser_state(state).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn load_browser_state(app_state: State<'_, Arc<AppState>>) -> Result<BrowserState, String> {
+    debug!("Loading browser state");
+    Ok(app_state.browser_state.lock().unwrap().clone())
+}
+
+// ==================== Tests ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::UserSettings;
+
     #[test]
     fn test_app_state_new() {
         let state = AppState::new();
         assert!(state.get_connection(1).is_none());
     }
+
+    #[test]
+    fn test_server_config_changes_are_saved() {
+        let home = std::env::temp_dir().join(format!("offset-explorer-state-{}", std::process::id()));
+        let paths = ConfigPaths::new(home.clone());
+        let state = AppState::with_environment(Environment::new(paths.clone()));
+
+        let mut config = ServerConnection::new(0, "local".to_string());
+        config.bootstrap_servers = "localhost:9092".to_string();
+        let id = state.add_server_config(config).unwrap();
+        let xml = std::fs::read_to_string(&paths.connections_file).unwrap();
+        assert!(xml.contains("local"));
+        assert!(xml.contains("localhost:9092"));
+        assert!(state.add_server_config(ServerConnection::new(0, "LOCAL".to_string())).is_err());
+
+        let request = ServerConnectionRequest {
+            name: "local".to_string(),
+            bootstrap_servers: Some("localhost:9093".to_string()),
+            ..Default::default()
+        };
+        let mut config = state.get_server_configs().remove(0);
+        request.apply_to(&mut config).unwrap();
+        state.update_server_config(config).unwrap();
+        let reloaded = ServerConnectionSettings::load_from_file(&paths.connections_file, &UserSettings::new()).unwrap();
+        assert_eq!(reloaded.find_connection(id).unwrap().bootstrap_servers, "localhost:9093");
+
+        let mut renamed = state.find_server_config(id).unwrap();
+        renamed.name = "other".to_string();
+        state.add_server_config(renamed.clone()).unwrap();
+        let err = state.update_server_config(renamed).unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+        assert_eq!(state.find_server_config(id).unwrap().name, "local");
+
+        state.environment.lock().unwrap().read_only = true;
+        assert!(state.add_server_config(ServerConnection::new(0, "unsaved".to_string())).is_err());
+        assert_eq!(state.get_server_configs().len(), 2);
+        state.environment.lock().unwrap().read_only = false;
+
+        state.remove_server_config(id).unwrap();
+        let xml = std::fs::read_to_string(&paths.connections_file).unwrap();
+        let _ = std::fs::remove_dir_all(&home);
+        assert!(!xml.contains("\"local\""));
+        assert_eq!(state.get_server_configs().len(), 1);
+        assert!(state.remove_server_config(id).is_err());
+    }
+
+    #[test]
+    fn test_connect_uses_saved_config() {
+        let home = std::env::temp_dir().join(format!("offset-explorer-connect-{}", std::process::id()));
+        let state = AppState::with_environment(Environment::new(ConfigPaths::new(home.clone())));
+
+        let mut config = ServerConnection::new(0, "sasl".to_string());
+        config.bootstrap_servers = "127.0.0.1:1".to_string();
+        config.broker_security_type = BrokerSecurityType::SASL_PLAINTEXT;
+        config.sasl_mechanism = SaslMechanism::PLAIN;
+        config.jaas_config = Some(
+            r#"org.apache.kafka.common.security.plain.PlainLoginModule required username="svc" password="pw";"#
+                .to_string(),
+        );
+        let id = state.add_server_config(config).unwrap();
+
+        let mapper = state.open_connection(id).unwrap();
+        assert_eq!(mapper.connection().id, id);
+        assert_eq!(mapper.connection().get_name(), "sasl");
+        assert_eq!(mapper.connection().broker_security_type, BrokerSecurityType::SASL_PLAINTEXT);
+        assert_eq!(mapper.connection().sasl_mechanism, SaslMechanism::PLAIN);
+        assert!(state.get_connection(id).is_some());
+
+        let mut updated = state.find_server_config(id).unwrap();
+        updated.bootstrap_servers = "127.0.0.1:2".to_string();
+        state.update_server_config(updated).unwrap();
+        assert!(state.reconnect_if_connected(id).unwrap());
+        let reconnected = state.get_connection(id).unwrap();
+        assert_eq!(reconnected.connection().bootstrap_servers, "127.0.0.1:2");
+        assert_eq!(reconnected.connection().sasl_mechanism, SaslMechanism::PLAIN);
+
+        state.remove_connection(id);
+        assert!(!state.reconnect_if_connected(id).unwrap());
+        let _ = std::fs::remove_dir_all(&home);
+        let err = state.open_connection(id + 1).err().unwrap();
+        assert!(err.to_string().contains("not found"));
+    }
+
+    #[test]
+    fn test_connection_test_result() {
+        let ok = ConnectionTestResult::new(Ok(vec![1, 2, 3]), Duration::from_millis(42));
+        assert!(ok.success);
+        assert_eq!(ok.broker_count, 3);
+        assert_eq!(ok.error, None);
+        let json = serde_json::to_value(&ok).unwrap();
+        assert_eq!(json["brokerIds"], serde_json::json!([1, 2, 3]));
+        assert_eq!(json["latencyMs"], 42);
+
+        let failed = ConnectionTestResult::new(Err(anyhow!("Failed to fetch metadata")), Duration::from_secs(5));
+        assert!(!failed.success);
+        assert_eq!(failed.broker_count, 0);
+        assert!(failed.broker_ids.is_empty());
+        assert_eq!(failed.latency_ms, 5000);
+        assert_eq!(failed.error.as_deref(), Some("Failed to fetch metadata"));
+    }
+
+    #[test]
+    fn test_topic_config_response() {
+        let entry = |name: &str, value: Option<&str>, is_default| TopicConfigInfo {
+            name: name.to_string(),
+            value: value.map(str::to_string),
+            is_default,
+            is_read_only: false,
+        };
+        let response = TopicConfigResponse::from(vec![
+            entry("retention.ms", Some("604800000"), true),
+            entry("cleanup.policy", Some("compact"), false),
+            entry("sasl.jaas.config", None, true),
+        ]);
+
+        let keys: Vec<&str> = response.entries.iter().map(|e| e.key.as_str()).collect();
+        assert_eq!(keys, ["cleanup.policy", "retention.ms", "sasl.jaas.config"]);
+        assert!(!response.entries[0].is_default);
+        assert_eq!(response.entries[2].value, "");
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["entries"][0]["isReadOnly"], false);
+    }
+
+    #[test]
+    fn test_broker_config_entry_masks_sensitive_values() {
+        let entry = |name: &str, value: Option<&str>, is_sensitive, source| BrokerConfigInfo {
+            name: name.to_string(),
+            value: value.map(str::to_string),
+            is_sensitive,
+            is_read_only: false,
+            source,
+        };
+
+        let plain = BrokerConfigEntry::from(entry("num.io.threads", Some("8"), false, ConfigSource::StaticBroker));
+        assert_eq!(plain.value.as_deref(), Some("8"));
+
+        let leaked = BrokerConfigEntry::from(entry("ssl.key.password", Some("secret"), true, ConfigSource::StaticBroker));
+        assert_eq!(leaked.value.as_deref(), Some(SENSITIVE_CONFIG_MASK));
+
+        let set = BrokerConfigEntry::from(entry("ssl.keystore.password", None, true, ConfigSource::DynamicBroker));
+        assert_eq!(set.value.as_deref(), Some(SENSITIVE_CONFIG_MASK));
+        assert!(set.is_sensitive);
+
+        let unset = BrokerConfigEntry::from(entry("ssl.truststore.password", None, true, ConfigSource::Default));
+        assert_eq!(unset.value, None);
+
+        let json = serde_json::to_value(&set).unwrap();
+        assert_eq!(json["isSensitive"], true);
+        assert_eq!(json["source"], "DYNAMIC_BROKER_CONFIG");
+    }
+
+    #[test]
+    fn test_alter_topic_config_response_order() {
+        let request: AlterTopicConfigRequest = serde_json::from_value(serde_json::json!({
+            "topic": "orders",
+            "entries": [
+                {"key": "retention.ms", "value": "1000", "operation": "SET"},
+                {"key": "segment.ms", "value": null, "operation": "RESET"},
+                {"key": "cleanup.policy", "value": "compact", "operation": "APPEND"},
+            ],
+        })).unwrap();
+        assert_eq!(request.entries[1].value, None);
+
+        let (valid, mut outcomes) = config_admin::validate_alterations(
+            request.entries.iter().map(|e| (e.key.as_str(), e.operation.as_str(), e.value.as_deref())),
+        );
+        assert_eq!(valid.len(), 2);
+        outcomes.extend(valid.iter().map(|a| a.result(None)));
+
+        let response = AlterTopicConfigResponse::new(&request, outcomes);
+        assert!(!response.success);
+        let keys: Vec<&str> = response.results.iter().map(|r| r.key.as_str()).collect();
+        assert_eq!(keys, ["retention.ms", "segment.ms", "cleanup.policy"]);
+        assert!(response.results[0].success);
+        assert!(!response.results[1].success);
+        assert!(response.results[1].error.as_deref().unwrap().contains("RESET"));
+        assert_eq!(response.results[2].operation, "APPEND");
+    }
+
+    #[test]
+    fn test_consumer_group_response_mapping() {
+        use crate::kafka::mapper::ConsumerMember;
+
+        let group = ConsumerGroupInfo {
+            group_id: "billing".to_string(),
+            state: "Stable".to_string(),
+            protocol_type: Some("consumer".to_string()),
+            protocol: Some("cooperative-sticky".to_string()),
+            members: vec![ConsumerMember {
+                member_id: "consumer-1-abc".to_string(),
+                client_id: "consumer-1".to_string(),
+                client_host: "/10.0.0.5".to_string(),
+            }],
+        };
+
+        let json = serde_json::to_value(ConsumerGroupResponse::from(&group)).unwrap();
+        assert_eq!(json["groupId"], "billing");
+        assert_eq!(json["state"], "Stable");
+        assert_eq!(json["protocolType"], "consumer");
+        assert_eq!(json["protocol"], "cooperative-sticky");
+        assert_eq!(json["members"][0]["clientHost"], "/10.0.0.5");
+    }
+
+    #[test]
+    fn test_offset_diff_between() {
+        let offset = |partition, offset| ConsumerOffset {
+            group_id: "billing".to_string(),
+            topic: "orders".to_string(),
+            partition,
+            offset,
+        };
+        let before = vec![offset(0, 100), offset(1, 50)];
+        let after = vec![offset(0, 160), offset(1, 50), offset(2, 20)];
+
+        let diff = OffsetDiff::between(before, after, Duration::from_secs(10));
+        assert_eq!(diff.consumed_per_partition[&0], 60);
+        assert_eq!(diff.consumed_per_partition[&1], 0);
+        assert_eq!(diff.consumed_per_partition[&2], 20);
+        assert_eq!(diff.total_consumed, 80);
+        assert_eq!(diff.rate_per_second, 8.0);
+    }
+
+    #[test]
+    fn test_lag_history_is_bounded() {
+        let state = AppState::new();
+        let lag = |value| ConsumerLag {
+            topic: "orders".to_string(),
+            partition: 0,
+            offset: 0,
+            log_end_offset: value,
+            lag: value,
+        };
+
+        for i in 0..(LAG_HISTORY_LIMIT as i64 + 5) {
+            state.record_lag(1, "billing", &[lag(i)]);
+        }
+
+        let trend = state.get_lag_trend(1, "billing", "orders", 0);
+        assert_eq!(trend.len(), LAG_HISTORY_LIMIT);
+        assert_eq!(trend[0].lag, 5);
+        assert!(state.get_lag_trend(1, "billing", "orders", 1).is_empty());
+    }
+
+    #[test]
+    fn test_decode_with_encoding() {
+        assert_eq!(decode_with_encoding("hi".to_string(), None).unwrap(), b"hi".to_vec());
+        assert_eq!(decode_with_encoding("deadbeef".to_string(), Some("hex")).unwrap(), vec![0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(decode_with_encoding("aGk=".to_string(), Some("base64")).unwrap(), b"hi".to_vec());
+        assert!(decode_with_encoding("xyz".to_string(), Some("hex")).is_err());
+        assert!(decode_with_encoding("hi".to_string(), Some("utf16")).is_err());
+    }
+
+    #[test]
+    fn test_split_hosts() {
+        assert_eq!(split_hosts("zk1:2181, zk2:2181,,"), vec!["zk1:2181", "zk2:2181"]);
+        assert!(split_hosts("").is_empty());
+    }
+
+    #[test]
+    fn test_topic_search_regex() {
+        let plain = topic_search_regex("Orders.v1", false).unwrap();
+        assert!(plain.is_match("prod.orders.v1"));
+        assert!(!plain.is_match("prod.ordersXv1"));
+
+        let regex = topic_search_regex("^orders-\\d+$", true).unwrap();
+        assert!(regex.is_match("ORDERS-42"));
+        assert!(!regex.is_match("orders-x"));
+        assert!(topic_search_regex("(", true).is_err());
+    }
+
+    #[test]
+    fn test_header_filter() {
+        let headers = vec![("traceId".to_string(), b"ABC-123".to_vec())];
+        let filter = |pattern: &str, case_sensitive, use_regex| HeaderFilter::Pattern {
+            header_name: "traceId".to_string(),
+            header_value_pattern: pattern.to_string(),
+            case_sensitive,
+            use_regex,
+        }
+        .compile()
+        .unwrap();
+
+        assert!(filter("ABC", true, false).matches(&headers));
+        assert!(!filter("abc", true, false).matches(&headers));
+        assert!(filter("abc", false, false).matches(&headers));
+        assert!(filter("^abc-\\d+$", false, true).matches(&headers));
+        assert!(!filter("^\\d+$", true, true).matches(&headers));
+        assert!(!filter("ABC", true, false).matches(&[("other".to_string(), b"ABC".to_vec())]));
+
+        let exact: HeaderFilter = serde_json::from_value(serde_json::json!({
+            "headerValues": { "traceId": "ABC-123" }
+        })).unwrap();
+        assert!(exact.clone().compile().unwrap().matches(&headers));
+        assert!(!exact.compile().unwrap().matches(&[("traceId".to_string(), b"ABC".to_vec())]));
+
+        let pattern: HeaderFilter = serde_json::from_value(serde_json::json!({
+            "headerName": "traceId", "headerValuePattern": "abc", "caseSensitive": false
+        })).unwrap();
+        assert!(pattern.compile().unwrap().matches(&headers));
+    }
+
+    #[test]
+    fn test_produce_request_partition() {
+        let request: ProduceMessageRequest = serde_json::from_value(serde_json::json!({
+            "topic": "orders",
+            "partition": 2,
+            "key": "deadbeef",
+            "value": "{}",
+            "headers": null,
+            "keyEncoding": "hex",
+            "valueEncoding": null,
+        })).unwrap();
+        assert_eq!(request.partition, Some(2));
+        assert_eq!(
+            decode_with_encoding(request.key.unwrap(), request.key_encoding.as_deref()).unwrap(),
+            vec![0xde, 0xad, 0xbe, 0xef]
+        );
+
+        let request: ProduceMessageRequest = serde_json::from_value(serde_json::json!({
+            "topic": "orders", "key": null, "value": null, "headers": null,
+            "keyEncoding": null, "valueEncoding": null,
+        })).unwrap();
+        assert_eq!(request.partition, None);
+    }
+
+    #[test]
+    fn test_split_batch_requests() {
+        let request = |key: &str, key_encoding: &str| ProduceMessageRequest {
+            topic: "orders".to_string(),
+            partition: None,
+            key: Some(key.to_string()),
+            value: Some("{}".to_string()),
+            headers: None,
+            key_encoding: Some(key_encoding.to_string()),
+            value_encoding: None,
+        };
+
+        let (messages, indices, failures) = split_batch_requests(vec![
+            request("a", "utf8"),
+            request("zz", "hex"),
+            request("0a0b", "hex"),
+        ]);
+        assert_eq!(indices, vec![0, 2]);
+        assert_eq!(messages[1].key, Some(vec![0x0a, 0x0b]));
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].index, 1);
+        assert!(failures[0].error.as_deref().unwrap().starts_with("Invalid key"));
+
+        let response = BatchProduceResultResponse::from(failures[0].clone());
+        assert!(!response.success);
+        assert!(BatchProduceResultResponse::from(BatchProduceResult { index: 0, error: None }).success);
+    }
+
+    #[test]
+    fn test_message_response_headers() {
+        let msg = KafkaMessage {
+            topic: Some("orders".to_string()),
+            partition: 0,
+            offset: 7,
+            key: None,
+            payload: Some(b"{}".to_vec()),
+            timestamp: 0,
+            headers: vec![("traceId".to_string(), b"ABC-123".to_vec())],
+            decoded_headers: vec![
+                ("traceId".to_string(), "ABC-123".to_string()),
+                ("checksum".to_string(), "/wAQ".to_string()),
+            ],
+            truncated: false,
+        };
+
+        let response = KafkaMessageResponse::from(msg);
+        assert_eq!(response.headers.len(), 2);
+        assert_eq!(response.headers["traceId"], "ABC-123");
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["headers"]["checksum"], "/wAQ");
+    }
+
+    #[test]
+    fn test_headers_contain_all() {
+        let headers = vec![
+            ("traceId".to_string(), b"ABC-123".to_vec()),
+            ("source".to_string(), b"billing".to_vec()),
+            ("source".to_string(), b"orders".to_vec()),
+        ];
+        let expected = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+        };
+
+        assert!(headers_contain_all(&headers, &HashMap::new()));
+        assert!(headers_contain_all(&headers, &expected(&[("traceId", "ABC-123")])));
+        assert!(headers_contain_all(&headers, &expected(&[("traceId", "ABC-123"), ("source", "orders")])));
+        assert!(!headers_contain_all(&headers, &expected(&[("traceId", "abc-123")])));
+        assert!(!headers_contain_all(&headers, &expected(&[("traceId", "ABC-123"), ("tenant", "acme")])));
+        assert!(!headers_contain_all(&headers, &expected(&[("traceId", "ABC")])));
+    }
+
+    #[test]
+    fn test_task_summary_response() {
+        let mut progress = crate::async_ops::TaskProgress::new(10);
+        progress.current = 4;
+        let summary = TaskSummary {
+            id: "export-1".to_string(),
+            name: "Export".to_string(),
+            progress,
+            is_cancelled: true,
+            is_finished: true,
+        };
+
+        let json = serde_json::to_value(TaskSummaryResponse::from(summary)).unwrap();
+        assert_eq!(json["id"], "export-1");
+        assert_eq!(json["current"], 4);
+        assert_eq!(json["isComplete"], false);
+        assert_eq!(json["isCancelled"], true);
+        assert_eq!(json["isFinished"], true);
+    }
+
+    #[test]
+    fn test_consumed_throughput() {
+        let state = AppState::new();
+        assert_eq!(state.get_consumed_messages_per_second(1), 0.0);
+
+        state.record_consumed(1, "orders", 30);
+        state.record_consumed(1, "orders", 20);
+        assert_eq!(state.get_consumed_messages_per_second(1), 5.0);
+        assert_eq!(state.get_consumed_messages_per_second(2), 0.0);
+
+        let now = Instant::now();
+        let window = VecDeque::from([(now, 10)]);
+        assert_eq!(throughput(&window, now + THROUGHPUT_WINDOW + Duration::from_secs(1)), 0.0);
+    }
+
+    #[test]
+    fn test_next_group_batch_round_robin() {
+        let groups: Vec<String> = ["a", "b", "c"].iter().map(|g| g.to_string()).collect();
+        let mut cursor = 0;
+
+        assert_eq!(next_group_batch(&groups, &mut cursor, 2), vec!["a", "b"]);
+        assert_eq!(next_group_batch(&groups, &mut cursor, 2), vec!["c", "a"]);
+        assert_eq!(next_group_batch(&groups, &mut cursor, 10), vec!["b", "c", "a"]);
+        assert!(next_group_batch(&[], &mut cursor, 2).is_empty());
+    }
+
+    #[test]
+    fn test_subject_version_counts_cache() {
+        let state = AppState::new();
+        let url = "http://localhost:8081";
+        state.get_schema_registry_client(url).unwrap();
+        assert!(state.get_cached_subject_version_counts(url).is_none());
+
+        state.cache_subject_version_counts(url, vec![("orders-value".to_string(), 3)]);
+        assert_eq!(
+            state.get_cached_subject_version_counts(url),
+            Some(vec![("orders-value".to_string(), 3)])
+        );
+
+        state.invalidate_subject_version_counts(url);
+        assert!(state.get_cached_subject_version_counts(url).is_none());
+    }
+
+    #[test]
+    fn test_schema_cache_ttl_setting() {
+        use crate::config::{SettingDataType, SettingValue};
+
+        let state = AppState::new();
+        assert_eq!(state.schema_cache_ttl(), crate::schema_registry::client::DEFAULT_CACHE_TTL);
+
+        state.environment.lock().unwrap().settings.set(
+            "schema_cache_ttl_secs",
+            SettingValue::Integer(0),
+            true,
+            SettingDataType::Integer,
+            true,
+        );
+        assert_eq!(state.schema_cache_ttl(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_scan_budget() {
+        let mut budget = ScanBudget::new(2);
+        for _ in 0..2 * FILTERED_SCAN_FACTOR {
+            assert!(budget.take());
+        }
+        assert!(!budget.take());
+
+        let mut expired = ScanBudget::with_timeout(2, Duration::ZERO);
+        assert!(!expired.take());
+    }
+
+    #[test]
+    fn test_remove_connection_stops_lag_tracking() {
+        let state = AppState::new();
+        state.start_lag_tracking(7).unwrap();
+        state.remove_connection(7);
+
+        let task = state
+            .task_manager
+            .list_tasks()
+            .into_iter()
+            .find(|t| t.id == lag_tracking_task_id(7))
+            .unwrap();
+        assert!(task.is_cancelled);
+    }
+
+    #[test]
+    fn test_remove_connection_stops_token_refresh() {
+        let home = std::env::temp_dir().join(format!("offset-explorer-token-refresh-{}", std::process::id()));
+        let state = AppState::with_environment(Environment::new(ConfigPaths::new(home.clone())));
+        let mut config = ServerConnection::new(0, "oauth".to_string());
+        config.bootstrap_servers = "127.0.0.1:1".to_string();
+        config.sasl_mechanism = SaslMechanism::OAUTHBEARER;
+        let id = state.add_server_config(config).unwrap();
+        let _ = std::fs::remove_dir_all(&home);
+
+        state
+            .start_token_refresh(id, "http://127.0.0.1:1/token".to_string(), "client".to_string(), "secret".to_string(), 60)
+            .unwrap();
+        state.remove_connection(id);
+
+        let task = state
+            .task_manager
+            .list_tasks()
+            .into_iter()
+            .find(|t| t.id == token_refresh_task_id(id))
+            .unwrap();
+        assert!(task.is_cancelled);
+    }
+
+    #[test]
+    fn test_update_request_keeps_unset_fields() {
+        let request: ServerConnectionRequest = serde_json::from_value(serde_json::json!({
+            "name": "prod",
+            "bootstrapServers": "kafka-1:9092,kafka-2:9092",
+            "environment": "production",
+        }))
+        .unwrap();
+        let mut config = request.to_server_connection(0).unwrap();
+        let environment = config.environment.clone();
+        assert!(environment.is_some());
+
+        let rename = ServerConnectionRequest { name: "prod-eu".to_string(), ..Default::default() };
+        rename.apply_to(&mut config).unwrap();
+        assert_eq!(config.name, "prod-eu");
+        assert_eq!(config.bootstrap_servers, "kafka-1:9092,kafka-2:9092");
+        assert_eq!(config.host, "kafka-1");
+        assert_eq!(config.environment, environment);
+
+        let clear = ServerConnectionRequest {
+            name: "prod-eu".to_string(),
+            environment: Some(String::new()),
+            ..Default::default()
+        };
+        clear.apply_to(&mut config).unwrap();
+        assert_eq!(config.environment, None);
+    }
+
+    #[test]
+    fn test_server_connection_request_security_fields() {
+        let request: ServerConnectionRequest = serde_json::from_value(serde_json::json!({
+            "name": "secure",
+            "bootstrapServers": "127.0.0.1:1",
+            "securityType": "SASL_PLAINTEXT",
+            "saslMechanism": "SCRAM-SHA-512",
+            "saslUsername": "alice",
+            "saslPassword": "pw",
+            "validateSslEndpointHostname": false,
+        }))
+        .unwrap();
+        let mut config = request.to_server_connection(0).unwrap();
+        assert_eq!(config.sasl_mechanism, SaslMechanism::SCRAM_SHA_512);
+        assert_eq!(config.sasl_credentials(), Some(("alice".to_string(), "pw".to_string())));
+        assert!(!config.validate_ssl_endpoint_hostname);
+        KafkaMapper::new(config.clone(), KafkaClientTimeouts::default()).unwrap();
+
+        let update = ServerConnectionRequest {
+            name: "secure".to_string(),
+            bootstrap_servers: Some("127.0.0.1:1".to_string()),
+            sasl_password: Some(String::new()),
+            truststore_location: Some("/certs/ca.pem".to_string()),
+            ..Default::default()
+        };
+        update.apply_to(&mut config).unwrap();
+        assert_eq!(config.sasl_callback.as_deref(), Some("alice"));
+        assert_eq!(config.sasl_endpoint_token, None);
+        assert_eq!(config.truststore_location.as_deref(), Some("/certs/ca.pem"));
+
+        let invalid = ServerConnectionRequest {
+            sasl_mechanism: Some("NTLM".to_string()),
+            ..Default::default()
+        };
+        assert!(invalid.to_server_connection(0).is_err());
+    }
+
+    #[test]
+    fn test_server_config_changes_drop_cached_registry_client() {
+        let home = std::env::temp_dir().join(format!("offset-explorer-registry-{}", std::process::id()));
+        let state = AppState::with_environment(Environment::new(ConfigPaths::new(home.clone())));
+
+        let mut config = ServerConnection::new(0, "registry".to_string());
+        config.schema_registry_endpoint = Some("http://127.0.0.1:1/".to_string());
+        let id = state.add_server_config(config).unwrap();
+        let config = state.find_server_config(id).unwrap();
+
+        let cached = state.connection_schema_registry(&config).unwrap();
+        assert!(Arc::ptr_eq(&cached, &state.connection_schema_registry(&config).unwrap()));
+
+        state.update_server_config(config.clone()).unwrap();
+        assert!(state.schema_registry_clients.lock().unwrap().is_empty());
+
+        state.get_schema_registry_client("http://127.0.0.1:1").unwrap();
+        state.remove_server_config(id).unwrap();
+        let _ = std::fs::remove_dir_all(&home);
+        assert!(state.schema_registry_clients.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_reconnect_swaps_mapper_and_keeps_lag_tracking() {
+        let home = std::env::temp_dir().join(format!("offset-explorer-reconnect-{}", std::process::id()));
+        let state = AppState::with_environment(Environment::new(ConfigPaths::new(home.clone())));
+        let mut config = ServerConnection::new(0, "swap".to_string());
+        config.bootstrap_servers = "127.0.0.1:1".to_string();
+        let id = state.add_server_config(config).unwrap();
+
+        let previous = state.open_connection(id).unwrap();
+        state.start_lag_tracking(id).unwrap();
+        assert!(state.reconnect_if_connected(id).unwrap());
+        assert!(!Arc::ptr_eq(&previous, &state.get_connection(id).unwrap()));
+        let lag_task = |state: &AppState| {
+            state.task_manager.list_tasks().into_iter().find(|t| t.id == lag_tracking_task_id(id)).unwrap()
+        };
+        assert!(!lag_task(&state).is_cancelled);
+
+        let truststore = home.join("truststore.jks");
+        std::fs::write(&truststore, [0xFE, 0xED, 0xFE, 0xED, 0, 0, 0, 2]).unwrap();
+        let mut broken = state.find_server_config(id).unwrap();
+        broken.broker_security_type = BrokerSecurityType::SSL;
+        broken.truststore_location = Some(truststore.to_string_lossy().into_owned());
+        state.update_server_config(broken).unwrap();
+
+        let mut events = state.event_bus.subscribe();
+        let result = state.reconnect_if_connected(id);
+        let _ = std::fs::remove_dir_all(&home);
+        assert!(result.is_err());
+        assert!(state.get_connection(id).is_none());
+        assert!(lag_task(&state).is_cancelled);
+        assert!(matches!(events.try_recv(), Ok(AppEvent::ServerConnectionDisconnected { id: disconnected }) if disconnected == id));
+    }
+
+    #[test]
+    fn test_export_range_and_completion() {
+        let beginning = HashMap::from([(0, 10), (1, 0), (2, 5)]);
+        let end = HashMap::from([(0, 25), (1, 0), (2, 7)]);
+        assert_eq!(offset_range_total(&beginning, &end), 17);
+
+        let assignment = [(0, 10), (2, 5)];
+        let at = |p0: i64, p2: i64| vec![("orders".to_string(), 0, p0), ("orders".to_string(), 2, p2)];
+        assert!(!export_finished(&[], &assignment, &end));
+        assert!(!export_finished(&at(24, 7), &assignment, &end));
+        assert!(export_finished(&at(25, 7), &assignment, &end));
+    }
 }
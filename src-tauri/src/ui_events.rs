@@ -17,6 +17,11 @@ pub enum AppEvent {
     TopicRemoved { name: String },
     ConsumerAdded { group_id: String },
     ConsumerRemoved { group_id: String },
+    TokenRefreshed { server_id: i64 },
+    ProductionWarning { server_id: i64, topic: String },
+    MessageConsumed { server_id: i64, topic: String, count: usize },
+    /// Progress of an `import_messages` task: messages queued so far
+    MessagesImported { server_id: i64, topic: String, task_id: String, count: usize },
 }
 
 /// Event bus for application-wide events
@@ -15,6 +15,10 @@ pub struct ZkNode {
     /// Node data (base64 encoded if binary)
     pub data: String,
 
+    /// Whether `data` is base64 encoded
+    #[serde(rename = "isBinary")]
+    pub is_binary: bool,
+
     /// Is this a directory?
     #[serde(rename = "isDirectory")]
     pub is_directory: bool,
@@ -205,6 +209,7 @@ pub struct DeleteZkNodeRequest {
     pub expected_version: Option<i32>,
 
     /// Recursive delete?
+    #[serde(default)]
     pub recursive: bool,
 }
 
@@ -218,6 +223,106 @@ pub struct ZkChildren {
     pub children: Vec<String>,
 }
 
+/// Kind of change reported by a ZooKeeper watch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ZkEventType {
+    Created,
+    Deleted,
+    DataChanged,
+    ChildrenChanged,
+}
+
+/// Change to a watched ZooKeeper node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZkWatchEvent {
+    /// Watched node path
+    pub path: String,
+
+    /// Kind of change
+    #[serde(rename = "eventType")]
+    pub event_type: ZkEventType,
+}
+
+/// Differences between two ZooKeeper nodes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZkNodeDiff {
+    /// Is the node data identical?
+    #[serde(rename = "dataEqual")]
+    pub data_equal: bool,
+
+    /// Differing stat fields (empty unless stats were compared)
+    #[serde(rename = "statDiff")]
+    pub stat_diff: Vec<String>,
+
+    /// Children only present under the first node
+    #[serde(rename = "childOnlyInA")]
+    pub child_only_in_a: Vec<String>,
+
+    /// Children only present under the second node
+    #[serde(rename = "childOnlyInB")]
+    pub child_only_in_b: Vec<String>,
+
+    /// Children present under both nodes
+    #[serde(rename = "commonChildren")]
+    pub common_children: Vec<String>,
+}
+
+impl ZkNodeDiff {
+    /// Compare node data and children, and stats when both are given
+    pub fn compute(
+        data_a: &[u8],
+        data_b: &[u8],
+        children_a: &[String],
+        children_b: &[String],
+        stats: Option<(&ZkStat, &ZkStat)>,
+    ) -> Self {
+        let mut child_only_in_a: Vec<String> = children_a.iter()
+            .filter(|c| !children_b.contains(c))
+            .cloned()
+            .collect();
+        let mut child_only_in_b: Vec<String> = children_b.iter()
+            .filter(|c| !children_a.contains(c))
+            .cloned()
+            .collect();
+        let mut common_children: Vec<String> = children_a.iter()
+            .filter(|c| children_b.contains(c))
+            .cloned()
+            .collect();
+        child_only_in_a.sort();
+        child_only_in_b.sort();
+        common_children.sort();
+
+        let stat_diff = match stats {
+            Some((a, b)) => Self::diff_stat(a, b),
+            None => Vec::new(),
+        };
+
+        Self {
+            data_equal: data_a == data_b,
+            stat_diff,
+            child_only_in_a,
+            child_only_in_b,
+            common_children,
+        }
+    }
+
+    /// Compare stat fields that are meaningful across clusters (not zxids or timestamps)
+    fn diff_stat(a: &ZkStat, b: &ZkStat) -> Vec<String> {
+        let fields = [
+            ("version", a.version as i64, b.version as i64),
+            ("cversion", a.cversion as i64, b.cversion as i64),
+            ("aversion", a.aversion as i64, b.aversion as i64),
+            ("dataLength", a.data_length as i64, b.data_length as i64),
+            ("numChildren", a.num_children as i64, b.num_children as i64),
+        ];
+
+        fields.iter()
+            .filter(|(_, a, b)| a != b)
+            .map(|(name, a, b)| format!("{}: {} != {}", name, a, b))
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -244,4 +349,32 @@ mod tests {
 
         assert!(invalid_request.validate().is_err());
     }
+
+    #[test]
+    fn test_node_diff() {
+        let children_a = vec!["brokers".to_string(), "config".to_string()];
+        let children_b = vec!["config".to_string(), "controller".to_string()];
+
+        let diff = ZkNodeDiff::compute(b"x", b"x", &children_a, &children_b, None);
+        assert!(diff.data_equal);
+        assert!(diff.stat_diff.is_empty());
+        assert_eq!(diff.child_only_in_a, vec!["brokers"]);
+        assert_eq!(diff.child_only_in_b, vec!["controller"]);
+        assert_eq!(diff.common_children, vec!["config"]);
+
+        let stat = |version| ZkStat {
+            data_length: 1,
+            version,
+            cversion: 0,
+            aversion: 0,
+            ephemeral_owner: "0".to_string(),
+            ctime: 0,
+            mtime: 0,
+            pzxid: 0,
+            num_children: 2,
+        };
+        let diff = ZkNodeDiff::compute(b"x", b"y", &[], &[], Some((&stat(1), &stat(2))));
+        assert!(!diff.data_equal);
+        assert_eq!(diff.stat_diff, vec!["version: 1 != 2"]);
+    }
 }
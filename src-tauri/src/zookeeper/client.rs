@@ -3,16 +3,780 @@
 //
 // ZooKeeper client wrapper
 
-use anyhow::Result;
+use anyhow::{Result, anyhow};
+use base64::Engine;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc, watch};
+use tracing::{debug, info, warn};
+use zookeeper_async::{
+    Acl, CreateMode, Permission, Stat, WatchedEvent, WatchedEventType, Watcher, ZkError, ZooKeeper,
+};
+
+use crate::zk_browser::{ZkAcl, ZkConnectionRequest, ZkEventType, ZkNode, ZkNodeDiff, ZkStat, ZkWatchEvent};
+
+/// Buffered watch events per watched path before slow receivers lag
+const WATCH_CHANNEL_CAPACITY: usize = 64;
+
+/// Logs session state changes
+struct SessionWatcher;
+
+impl Watcher for SessionWatcher {
+    fn handle(&self, event: WatchedEvent) {
+        debug!("ZooKeeper session event: {:?}", event);
+    }
+}
 
 /// ZooKeeper client
-/// This will be expanded with full implementation
 pub struct ZooKeeperClient {
-    // Client fields will be added
+    client: Arc<ZooKeeper>,
+    /// Hosts with the chroot suffix, e.g. `zk1:2181,zk2:2181/kafka`
+    connect_string: String,
+    /// Chroot applied to every path, `None` for the root namespace
+    chroot: Option<String>,
+    /// Active watches (path -> event sender)
+    watches: Arc<Mutex<HashMap<String, broadcast::Sender<ZkWatchEvent>>>>,
+    /// Set on close so watch tasks stop waiting for events that will never fire
+    shutdown: watch::Sender<bool>,
+}
+
+/// Credentials added to a ZooKeeper session once it is established
+#[derive(Clone, PartialEq, Eq)]
+pub struct ZkAuth {
+    /// `digest` or `ip`
+    pub scheme: String,
+    /// `user:password` for digest, an optional address for ip
+    pub auth: String,
+}
+
+impl fmt::Debug for ZkAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ZkAuth")
+            .field("scheme", &self.scheme)
+            .field("auth", &"******")
+            .finish()
+    }
+}
+
+impl ZkAuth {
+    /// Auth configured on a connection request, `None` when it has no scheme
+    pub fn from_request(request: &ZkConnectionRequest) -> Result<Option<Self>> {
+        Self::parse(request.auth_scheme.as_deref(), request.auth.as_deref())
+    }
+
+    pub fn parse(scheme: Option<&str>, auth: Option<&str>) -> Result<Option<Self>> {
+        let scheme = scheme.map(|s| s.trim().to_lowercase()).unwrap_or_default();
+        let auth = auth.map(str::trim).unwrap_or_default();
+        if scheme.is_empty() {
+            if !auth.is_empty() {
+                return Err(anyhow!("ZooKeeper authentication requires a scheme"));
+            }
+            return Ok(None);
+        }
+
+        match scheme.as_str() {
+            "digest" => {
+                let (user, _) = auth
+                    .split_once(':')
+                    .ok_or_else(|| anyhow!("Digest authentication must be user:password"))?;
+                if user.is_empty() {
+                    return Err(anyhow!("Digest authentication requires a user name"));
+                }
+            }
+            "ip" => {}
+            "sasl" => return Err(anyhow!("SASL authentication is not supported for ZooKeeper, use digest")),
+            other => return Err(anyhow!("Unsupported ZooKeeper authentication scheme: {}", other)),
+        }
+
+        Ok(Some(Self {
+            scheme,
+            auth: auth.to_string(),
+        }))
+    }
+}
+
+/// Which one-shot ZooKeeper watch fired
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WatchSource {
+    Data,
+    Children,
 }
 
+type WatchEvents = mpsc::UnboundedSender<(WatchSource, WatchedEvent)>;
+
 impl ZooKeeperClient {
-    pub fn new() -> Result<Self> {
-        Ok(Self {})
+    /// Open a session to `host:port[,host:port...][/chroot]` and add `auth` to it
+    /// before it is used.
+    ///
+    /// The chroot is applied client-side by `effective_path`, so the session itself
+    /// is opened on the root namespace.
+    pub async fn connect_with_auth(hosts: &str, timeout: Duration, auth: Option<&ZkAuth>) -> Result<Self> {
+        let (servers, chroot) = split_connect_string(hosts);
+        let connect_string = zookeeper_connect_string(servers, chroot.as_deref().unwrap_or("/"));
+        info!("Connecting to ZooKeeper: {}", connect_string);
+
+        let client = ZooKeeper::connect(servers, timeout, SessionWatcher)
+            .await
+            .map_err(|e| anyhow!("Failed to connect to ZooKeeper {}: {:?}", connect_string, e))?;
+
+        if let Some(auth) = auth {
+            debug!("Adding {} authentication to ZooKeeper session", auth.scheme);
+            if let Err(e) = client.add_auth(&auth.scheme, auth.auth.as_bytes().to_vec()).await {
+                let _ = client.close().await;
+                return Err(anyhow!("ZooKeeper {} authentication failed: {:?}", auth.scheme, e));
+            }
+        }
+
+        Ok(Self {
+            client: Arc::new(client),
+            connect_string,
+            chroot,
+            watches: Arc::new(Mutex::new(HashMap::new())),
+            shutdown: watch::channel(false).0,
+        })
+    }
+
+    pub fn get_connect_string(&self) -> &str {
+        &self.connect_string
+    }
+
+    /// Prefix an absolute path with the chroot
+    pub fn effective_path(&self, path: &str) -> String {
+        chroot_path(self.chroot.as_deref(), path)
+    }
+
+    /// Get node data and stat
+    pub async fn get_data(&self, path: &str) -> Result<(Vec<u8>, ZkStat)> {
+        let path = self.effective_path(path);
+        let (data, stat) = self.client
+            .get_data(&path, false)
+            .await
+            .map_err(|e| anyhow!("Failed to get data for {}: {:?}", path, e))?;
+        Ok((data, to_zk_stat(&stat)))
+    }
+
+    /// Get a node with its data, ACL and stat
+    pub async fn get_node(&self, path: &str) -> Result<ZkNode> {
+        let full_path = self.effective_path(path);
+        let (data, stat) = self.client
+            .get_data(&full_path, false)
+            .await
+            .map_err(|e| anyhow!("Failed to get data for {}: {:?}", full_path, e))?;
+        let (acl, _) = self.client
+            .get_acl(&full_path)
+            .await
+            .map_err(|e| anyhow!("Failed to get ACL for {}: {:?}", full_path, e))?;
+
+        let (data, is_binary) = encode_node_data(&data);
+        Ok(ZkNode {
+            data,
+            is_binary,
+            acl: acl.iter().map(to_zk_acl).collect(),
+            ..node_from_stat(path, &stat)
+        })
+    }
+
+    /// List child node names
+    pub async fn list_children(&self, path: &str) -> Result<Vec<String>> {
+        let path = self.effective_path(path);
+        self.client
+            .get_children(&path, false)
+            .await
+            .map_err(|e| anyhow!("Failed to list children of {}: {:?}", path, e))
+    }
+
+    /// List the children of `path` sorted by name, with stat only.
+    ///
+    /// Data and ACL are left empty; load them for a single node with `get_node`.
+    /// The stat lookups run concurrently, and children deleted after the listing
+    /// are skipped.
+    pub async fn list_child_nodes(&self, path: &str) -> Result<Vec<ZkNode>> {
+        let mut names = self.list_children(path).await?;
+        names.sort();
+
+        let mut lookups = tokio::task::JoinSet::new();
+        for (index, name) in names.iter().enumerate() {
+            let client = Arc::clone(&self.client);
+            let node_path = child_path(path, name);
+            let full_path = self.effective_path(&node_path);
+            lookups.spawn(async move {
+                let stat = client
+                    .exists(&full_path, false)
+                    .await
+                    .map_err(|e| anyhow!("Failed to stat {}: {:?}", full_path, e))?;
+                Ok::<_, anyhow::Error>((index, stat.map(|stat| node_from_stat(&node_path, &stat))))
+            });
+        }
+
+        let mut nodes = Vec::with_capacity(names.len());
+        while let Some(joined) = lookups.join_next().await {
+            if let (index, Some(node)) = joined.map_err(|e| anyhow!("Stat lookup failed: {}", e))?? {
+                nodes.push((index, node));
+            }
+        }
+        nodes.sort_by_key(|(index, _)| *index);
+        Ok(nodes.into_iter().map(|(_, node)| node).collect())
+    }
+
+    /// Create a node, returning the created path (outside the chroot).
+    ///
+    /// An empty `acl` creates the node world-readable and writable.
+    pub async fn create_node(&self, path: &str, data: &[u8], acl: Vec<Acl>, mode: CreateMode) -> Result<String> {
+        let full_path = self.effective_path(path);
+        let acl = if acl.is_empty() {
+            vec![Acl::new(Permission::ALL, "world", "anyone")]
+        } else {
+            acl
+        };
+        let created = self.client
+            .create(&full_path, data.to_vec(), acl, mode)
+            .await
+            .map_err(|e| anyhow!("Failed to create {}: {:?}", full_path, e))?;
+        Ok(strip_chroot(self.chroot.as_deref(), &created))
+    }
+
+    /// Replace node data if the node is at `version`; a negative version matches any
+    pub async fn update_node(&self, path: &str, data: &[u8], version: i32) -> Result<()> {
+        let path = self.effective_path(path);
+        self.client
+            .set_data(&path, data.to_vec(), expected_version(version))
+            .await
+            .map_err(|e| anyhow!("Failed to set data for {}: {:?}", path, e))?;
+        Ok(())
+    }
+
+    /// Delete a node if it is at `version`; a negative version matches any
+    pub async fn delete_node(&self, path: &str, version: i32) -> Result<()> {
+        check_deletable(path)?;
+        let path = self.effective_path(path);
+        self.client
+            .delete(&path, expected_version(version))
+            .await
+            .map_err(|e| anyhow!("Failed to delete {}: {:?}", path, e))
+    }
+
+    /// Delete a node and everything below it, deepest nodes first, returning the number
+    /// of deleted nodes
+    pub async fn delete_recursive(&self, path: &str) -> Result<usize> {
+        check_deletable(path)?;
+        let mut pending = vec![path.to_string()];
+        let mut ordered = Vec::new();
+        while let Some(node) = pending.pop() {
+            for child in self.list_children(&node).await? {
+                pending.push(child_path(&node, &child));
+            }
+            ordered.push(node);
+        }
+
+        for node in ordered.iter().rev() {
+            self.delete_node(node, -1).await?;
+        }
+        info!("Deleted {} ZooKeeper nodes under {}", ordered.len(), path);
+        Ok(ordered.len())
+    }
+
+    /// Subscribe to changes of a node and its children.
+    ///
+    /// ZooKeeper watches fire once, so a background task re-registers them after every
+    /// event until the last receiver is dropped or the session closes. Watching a path
+    /// that does not exist yet reports its creation.
+    pub async fn watch_node(&self, path: &str) -> Result<broadcast::Receiver<ZkWatchEvent>> {
+        if let Some(sender) = self.watches.lock().unwrap().get(path) {
+            return Ok(sender.subscribe());
+        }
+
+        let full_path = self.effective_path(path);
+        let (events_tx, events_rx) = mpsc::unbounded_channel();
+        arm_data_watch(&self.client, &full_path, &events_tx).await?;
+        let child_armed = arm_child_watch(&self.client, &full_path, &events_tx).await?;
+
+        let (sender, receiver) = broadcast::channel(WATCH_CHANNEL_CAPACITY);
+        {
+            let mut watches = self.watches.lock().unwrap();
+            if let Some(existing) = watches.get(path) {
+                // Lost a race with another caller; its watch task already covers the path
+                return Ok(existing.subscribe());
+            }
+            watches.insert(path.to_string(), sender.clone());
+        }
+
+        debug!("Watching ZooKeeper node {}", full_path);
+        tokio::spawn(run_watch(
+            self.client.clone(),
+            self.watches.clone(),
+            path.to_string(),
+            full_path,
+            sender,
+            events_tx,
+            events_rx,
+            child_armed,
+            self.shutdown.subscribe(),
+        ));
+        Ok(receiver)
+    }
+
+    /// Compare a node with a node on another (or the same) connection
+    pub async fn compare_node(
+        &self,
+        path_a: &str,
+        client_b: &ZooKeeperClient,
+        path_b: &str,
+        include_stat: bool,
+    ) -> Result<ZkNodeDiff> {
+        debug!("Comparing {} with {}", path_a, path_b);
+
+        let (data_a, stat_a) = self.get_data(path_a).await?;
+        let (data_b, stat_b) = client_b.get_data(path_b).await?;
+        let children_a = self.list_children(path_a).await?;
+        let children_b = client_b.list_children(path_b).await?;
+
+        let stats = if include_stat { Some((&stat_a, &stat_b)) } else { None };
+        Ok(ZkNodeDiff::compute(&data_a, &data_b, &children_a, &children_b, stats))
+    }
+
+    /// Close the session
+    pub async fn close(&self) -> Result<()> {
+        info!("Closing ZooKeeper session: {}", self.connect_string);
+        self.watches.lock().unwrap().clear();
+        let _ = self.shutdown.send(true);
+        self.client
+            .close()
+            .await
+            .map_err(|e| anyhow!("Failed to close ZooKeeper session: {:?}", e))
+    }
+}
+
+/// Relay watch events for `path` and re-arm the watches that fired
+#[allow(clippy::too_many_arguments)]
+async fn run_watch(
+    client: Arc<ZooKeeper>,
+    watches: Arc<Mutex<HashMap<String, broadcast::Sender<ZkWatchEvent>>>>,
+    path: String,
+    full_path: String,
+    sender: broadcast::Sender<ZkWatchEvent>,
+    events_tx: WatchEvents,
+    mut events_rx: mpsc::UnboundedReceiver<(WatchSource, WatchedEvent)>,
+    mut child_armed: bool,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    loop {
+        let (source, event) = tokio::select! {
+            Some(received) = events_rx.recv() => received,
+            _ = shutdown.changed() => break,
+        };
+        if source == WatchSource::Children {
+            child_armed = false;
+        }
+        // A deleted node fires both watches; report it once
+        let duplicate = source == WatchSource::Children && event.event_type == WatchedEventType::NodeDeleted;
+        if let Some(event_type) = to_event_type(event.event_type).filter(|_| !duplicate) {
+            let _ = sender.send(ZkWatchEvent { path: path.clone(), event_type });
+        }
+
+        {
+            let mut watches = watches.lock().unwrap();
+            if sender.receiver_count() == 0 {
+                watches.remove(&path);
+                debug!("Stopped watching ZooKeeper node {}: no receivers", full_path);
+                return;
+            }
+        }
+
+        let rearmed = async {
+            if source == WatchSource::Data {
+                arm_data_watch(&client, &full_path, &events_tx).await?;
+            }
+            if !child_armed {
+                child_armed = arm_child_watch(&client, &full_path, &events_tx).await?;
+            }
+            Ok::<_, anyhow::Error>(())
+        };
+        if let Err(e) = rearmed.await {
+            warn!("Stopped watching ZooKeeper node {}: {}", full_path, e);
+            break;
+        }
+    }
+    watches.lock().unwrap().remove(&path);
+}
+
+async fn arm_data_watch(client: &ZooKeeper, path: &str, events: &WatchEvents) -> Result<()> {
+    let events = events.clone();
+    client
+        .exists_w(path, move |event| {
+            let _ = events.send((WatchSource::Data, event));
+        })
+        .await
+        .map_err(|e| anyhow!("Failed to watch {}: {:?}", path, e))?;
+    Ok(())
+}
+
+/// Watch the children of `path`; returns false when the node does not exist yet
+async fn arm_child_watch(client: &ZooKeeper, path: &str, events: &WatchEvents) -> Result<bool> {
+    let events = events.clone();
+    match client
+        .get_children_w(path, move |event| {
+            let _ = events.send((WatchSource::Children, event));
+        })
+        .await
+    {
+        Ok(_) => Ok(true),
+        Err(ZkError::NoNode) => Ok(false),
+        Err(e) => Err(anyhow!("Failed to watch children of {}: {:?}", path, e)),
+    }
+}
+
+fn to_event_type(event_type: WatchedEventType) -> Option<ZkEventType> {
+    match event_type {
+        WatchedEventType::NodeCreated => Some(ZkEventType::Created),
+        WatchedEventType::NodeDeleted => Some(ZkEventType::Deleted),
+        WatchedEventType::NodeDataChanged => Some(ZkEventType::DataChanged),
+        WatchedEventType::NodeChildrenChanged => Some(ZkEventType::ChildrenChanged),
+        _ => None,
+    }
+}
+
+/// Reject deleting the root or ZooKeeper's own `/zookeeper` subtree
+fn check_deletable(path: &str) -> Result<()> {
+    let path = path.trim_end_matches('/');
+    if path.is_empty() {
+        return Err(anyhow!("Cannot delete the root node"));
+    }
+    if path == "/zookeeper" || path.starts_with("/zookeeper/") {
+        return Err(anyhow!("Cannot delete system node {}", path));
+    }
+    Ok(())
+}
+
+/// Join hosts and a chroot into a connect string, e.g. `zk1:2181,zk2:2181/kafka`
+pub fn zookeeper_connect_string(hosts: &str, chroot: &str) -> String {
+    match normalize_chroot(chroot) {
+        Some(chroot) => format!("{}{}", hosts, chroot),
+        None => hosts.to_string(),
+    }
+}
+
+/// Path of the child `name` under `parent`
+pub fn child_path(parent: &str, name: &str) -> String {
+    if parent.ends_with('/') {
+        format!("{}{}", parent, name)
+    } else {
+        format!("{}/{}", parent, name)
+    }
+}
+
+/// Map a request create mode name to a ZooKeeper create mode
+pub fn parse_create_mode(mode: &str) -> Result<CreateMode> {
+    match mode.to_lowercase().as_str() {
+        "persistent" => Ok(CreateMode::Persistent),
+        "ephemeral" => Ok(CreateMode::Ephemeral),
+        "sequential" | "persistent_sequential" => Ok(CreateMode::PersistentSequential),
+        "ephemeral_sequential" => Ok(CreateMode::EphemeralSequential),
+        "container" => Ok(CreateMode::Container),
+        _ => Err(anyhow!("Invalid create mode: {}", mode)),
+    }
+}
+
+/// Convert a request ACL entry; permissions use the zkCli letters `cdrwa`
+pub fn from_zk_acl(acl: &ZkAcl) -> Result<Acl> {
+    Ok(Acl::new(parse_permissions(&acl.permissions)?, &acl.scheme, &acl.id))
+}
+
+fn to_zk_acl(acl: &Acl) -> ZkAcl {
+    ZkAcl {
+        scheme: acl.scheme.clone(),
+        id: acl.id.clone(),
+        permissions: format_permissions(acl.perms),
+        host: String::new(),
+    }
+}
+
+const PERMISSION_LETTERS: [(char, Permission); 5] = [
+    ('c', Permission::CREATE),
+    ('d', Permission::DELETE),
+    ('r', Permission::READ),
+    ('w', Permission::WRITE),
+    ('a', Permission::ADMIN),
+];
+
+fn format_permissions(perms: Permission) -> String {
+    PERMISSION_LETTERS.iter()
+        .filter(|(_, perm)| perms.can(*perm))
+        .map(|(letter, _)| *letter)
+        .collect()
+}
+
+fn parse_permissions(perms: &str) -> Result<Permission> {
+    if perms.eq_ignore_ascii_case("all") {
+        return Ok(Permission::ALL);
+    }
+    let mut result = Permission::from_raw(0);
+    for c in perms.chars() {
+        let (_, perm) = PERMISSION_LETTERS.iter()
+            .find(|(letter, _)| *letter == c.to_ascii_lowercase())
+            .ok_or_else(|| anyhow!("Invalid ACL permission '{}' in {}", c, perms))?;
+        result = result | *perm;
+    }
+    Ok(result)
+}
+
+/// Decode request data, base64 when `is_binary`
+pub fn decode_node_data(data: &str, is_binary: bool) -> Result<Vec<u8>> {
+    if is_binary {
+        base64::engine::general_purpose::STANDARD
+            .decode(data)
+            .map_err(|e| anyhow!("Invalid base64 node data: {}", e))
+    } else {
+        Ok(data.as_bytes().to_vec())
+    }
+}
+
+/// Node data as UTF-8 text, or base64 when it is not valid UTF-8
+/// Node data as text, or base64 when it is not UTF-8; the flag is true for base64
+fn encode_node_data(data: &[u8]) -> (String, bool) {
+    match std::str::from_utf8(data) {
+        Ok(text) => (text.to_string(), false),
+        Err(_) => (base64::engine::general_purpose::STANDARD.encode(data), true),
+    }
+}
+
+/// A node with its stat filled in and no data or ACL
+fn node_from_stat(path: &str, stat: &Stat) -> ZkNode {
+    ZkNode {
+        path: path.to_string(),
+        data: String::new(),
+        is_binary: false,
+        is_directory: stat.num_children > 0,
+        child_count: stat.num_children.max(0) as usize,
+        creation_time: stat.ctime,
+        modification_time: stat.mtime,
+        version: stat.version,
+        acl: Vec::new(),
+        stat: to_zk_stat(stat),
+    }
+}
+
+fn expected_version(version: i32) -> Option<i32> {
+    if version < 0 { None } else { Some(version) }
+}
+
+fn to_zk_stat(stat: &Stat) -> ZkStat {
+    ZkStat {
+        data_length: stat.data_length.max(0) as usize,
+        version: stat.version,
+        cversion: stat.cversion,
+        aversion: stat.aversion,
+        ephemeral_owner: format!("0x{:x}", stat.ephemeral_owner),
+        ctime: stat.ctime,
+        mtime: stat.mtime,
+        pzxid: stat.pzxid,
+        num_children: stat.num_children,
+    }
+}
+
+/// Normalize a chroot to `/name` form, or `None` for the root namespace
+fn normalize_chroot(chroot: &str) -> Option<String> {
+    let trimmed = chroot.trim().trim_end_matches('/');
+    if trimmed.is_empty() {
+        return None;
+    }
+    if trimmed.starts_with('/') {
+        Some(trimmed.to_string())
+    } else {
+        Some(format!("/{}", trimmed))
+    }
+}
+
+/// Split `hosts[/chroot]` into the host list and the normalized chroot
+fn split_connect_string(connect_string: &str) -> (&str, Option<String>) {
+    match connect_string.find('/') {
+        Some(idx) => (&connect_string[..idx], normalize_chroot(&connect_string[idx..])),
+        None => (connect_string, None),
+    }
+}
+
+/// Strip `chroot` from a server-side path
+fn strip_chroot(chroot: Option<&str>, path: &str) -> String {
+    match chroot.and_then(|chroot| path.strip_prefix(chroot)) {
+        Some("") => "/".to_string(),
+        Some(rest) if rest.starts_with('/') => rest.to_string(),
+        _ => path.to_string(),
+    }
+}
+
+/// Prefix absolute `path` with `chroot`; relative paths are returned unchanged
+fn chroot_path(chroot: Option<&str>, path: &str) -> String {
+    match chroot {
+        Some(chroot) if path.starts_with('/') => {
+            if path == "/" {
+                chroot.to_string()
+            } else {
+                format!("{}{}", chroot, path)
+            }
+        }
+        _ => path.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_chroot() {
+        assert_eq!(normalize_chroot("/"), None);
+        assert_eq!(normalize_chroot(""), None);
+        assert_eq!(normalize_chroot("/kafka/"), Some("/kafka".to_string()));
+        assert_eq!(normalize_chroot("kafka"), Some("/kafka".to_string()));
+    }
+
+    #[test]
+    fn test_chroot_path() {
+        assert_eq!(chroot_path(Some("/kafka"), "/brokers/ids"), "/kafka/brokers/ids");
+        assert_eq!(chroot_path(Some("/kafka"), "/"), "/kafka");
+        assert_eq!(chroot_path(None, "/brokers"), "/brokers");
+        assert_eq!(chroot_path(Some("/kafka"), "relative"), "relative");
+    }
+
+    #[test]
+    fn test_split_connect_string() {
+        assert_eq!(split_connect_string("zk1:2181,zk2:2181"), ("zk1:2181,zk2:2181", None));
+        assert_eq!(
+            split_connect_string("zk1:2181,zk2:2181/kafka/"),
+            ("zk1:2181,zk2:2181", Some("/kafka".to_string()))
+        );
+        assert_eq!(split_connect_string("zk1:2181/"), ("zk1:2181", None));
+        assert_eq!(zookeeper_connect_string("zk1:2181", "kafka"), "zk1:2181/kafka");
+        assert_eq!(zookeeper_connect_string("zk1:2181", "/"), "zk1:2181");
+        // The configured chroot survives the trip through connect_zookeeper
+        assert_eq!(
+            split_connect_string(&zookeeper_connect_string("zk1:2181,zk2:2181", "/kafka")),
+            ("zk1:2181,zk2:2181", Some("/kafka".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_strip_chroot() {
+        assert_eq!(strip_chroot(Some("/kafka"), "/kafka/seq0000000001"), "/seq0000000001");
+        assert_eq!(strip_chroot(Some("/kafka"), "/kafka"), "/");
+        assert_eq!(strip_chroot(Some("/kafka"), "/kafka2/a"), "/kafka2/a");
+        assert_eq!(strip_chroot(None, "/a"), "/a");
+        assert_eq!(child_path("/", "brokers"), "/brokers");
+        assert_eq!(child_path("/brokers", "ids"), "/brokers/ids");
+    }
+
+    #[test]
+    fn test_watch_event_type() {
+        assert_eq!(to_event_type(WatchedEventType::NodeCreated), Some(ZkEventType::Created));
+        assert_eq!(
+            to_event_type(WatchedEventType::NodeChildrenChanged),
+            Some(ZkEventType::ChildrenChanged)
+        );
+        assert_eq!(to_event_type(WatchedEventType::None), None);
+        assert_eq!(to_event_type(WatchedEventType::DataWatchRemoved), None);
+    }
+
+    #[test]
+    fn test_auth_parsing() {
+        assert_eq!(ZkAuth::parse(None, None).unwrap(), None);
+        assert_eq!(ZkAuth::parse(Some(" "), Some("")).unwrap(), None);
+        assert!(ZkAuth::parse(None, Some("admin:secret")).is_err());
+
+        let digest = ZkAuth::parse(Some("Digest"), Some("admin:secret")).unwrap().unwrap();
+        assert_eq!(digest.scheme, "digest");
+        assert_eq!(digest.auth, "admin:secret");
+        assert!(!format!("{:?}", digest).contains("secret"));
+        assert!(ZkAuth::parse(Some("digest"), Some("admin")).is_err());
+        assert!(ZkAuth::parse(Some("digest"), Some(":secret")).is_err());
+
+        let ip = ZkAuth::parse(Some("ip"), None).unwrap().unwrap();
+        assert_eq!(ip.auth, "");
+        assert!(ZkAuth::parse(Some("sasl"), Some("admin")).is_err());
+        assert!(ZkAuth::parse(Some("kerberos"), Some("admin")).is_err());
+    }
+
+    #[test]
+    fn test_auth_from_request() {
+        let request = ZkConnectionRequest {
+            name: "local".to_string(),
+            zk_hosts: "localhost:2181".to_string(),
+            chroot: None,
+            session_timeout: None,
+            auth_scheme: Some("digest".to_string()),
+            auth: Some("kafka:kafka-secret".to_string()),
+        };
+        let auth = ZkAuth::from_request(&request).unwrap().unwrap();
+        assert_eq!(auth.scheme, "digest");
+        assert_eq!(auth.auth, "kafka:kafka-secret");
+    }
+
+    #[test]
+    fn test_check_deletable() {
+        assert!(check_deletable("/").is_err());
+        assert!(check_deletable("/zookeeper").is_err());
+        assert!(check_deletable("/zookeeper/").is_err());
+        assert!(check_deletable("/zookeeper/quota").is_err());
+        assert!(check_deletable("/zookeeper-backup").is_ok());
+        assert!(check_deletable("/brokers/ids/1").is_ok());
+    }
+
+    #[test]
+    fn test_create_mode() {
+        assert_eq!(parse_create_mode("persistent").unwrap(), CreateMode::Persistent);
+        assert_eq!(parse_create_mode("sequential").unwrap(), CreateMode::PersistentSequential);
+        assert_eq!(parse_create_mode("Container").unwrap(), CreateMode::Container);
+        assert!(parse_create_mode("forever").is_err());
+    }
+
+    #[test]
+    fn test_acl_permissions() {
+        let acl = ZkAcl {
+            scheme: "digest".to_string(),
+            id: "admin:hash".to_string(),
+            permissions: "rwa".to_string(),
+            host: String::new(),
+        };
+        let converted = from_zk_acl(&acl).unwrap();
+        assert_eq!(converted.perms, Permission::READ | Permission::WRITE | Permission::ADMIN);
+        assert_eq!(to_zk_acl(&converted).permissions, "rwa");
+
+        assert_eq!(format_permissions(Permission::ALL), "cdrwa");
+        assert_eq!(parse_permissions("ALL").unwrap(), Permission::ALL);
+        assert!(parse_permissions("rx").is_err());
+    }
+
+    #[test]
+    fn test_node_data_encoding() {
+        assert_eq!(decode_node_data("hello", false).unwrap(), b"hello");
+        assert_eq!(decode_node_data("AP8=", true).unwrap(), vec![0x00, 0xff]);
+        assert!(decode_node_data("not base64!", true).is_err());
+        assert_eq!(encode_node_data(b"{\"a\":1}"), ("{\"a\":1}".to_string(), false));
+        assert_eq!(encode_node_data(&[0x00, 0xff]), ("AP8=".to_string(), true));
+    }
+
+    #[test]
+    fn test_node_from_stat_has_no_data() {
+        let stat = Stat {
+            czxid: 1,
+            mzxid: 2,
+            ctime: 10,
+            mtime: 20,
+            version: 3,
+            cversion: 4,
+            aversion: 0,
+            ephemeral_owner: 0,
+            data_length: 128,
+            num_children: 2,
+            pzxid: 5,
+        };
+        let node = node_from_stat("/brokers", &stat);
+        assert_eq!(node.path, "/brokers");
+        assert!(node.data.is_empty());
+        assert!(!node.is_binary);
+        assert!(node.acl.is_empty());
+        assert!(node.is_directory);
+        assert_eq!(node.child_count, 2);
+        assert_eq!(node.stat.data_length, 128);
     }
 }
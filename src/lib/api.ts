@@ -14,6 +14,24 @@ export interface ServerConnectionRequest {
   securityType?: string;
   zookeeperHosts?: string;
   zookeeperChroot?: string;
+  environment?: string;
+  // SASL settings; omitted fields keep their saved value, empty strings clear them
+  saslMechanism?: string;
+  saslUsername?: string;
+  saslPassword?: string;
+  jaasConfig?: string;
+  kerberosServiceName?: string;
+  kerberosPrincipal?: string;
+  kerberosKeytab?: string;
+  oauthToken?: string;
+  oauthTokenEndpointUrl?: string;
+  // SSL settings
+  truststoreLocation?: string;
+  truststorePassword?: string;
+  keystoreLocation?: string;
+  keystorePassword?: string;
+  keystorePrivateKey?: string;
+  validateSslEndpointHostname?: boolean;
 }
 
 /// Topic creation request
@@ -26,9 +44,12 @@ export interface CreateTopicRequest {
 /// Message production request
 export interface ProduceMessageRequest {
   topic: string;
+  partition?: number;
   key?: string;
   value?: string;
   headers?: Record<string, string>;
+  keyEncoding?: 'utf8' | 'base64' | 'hex';
+  valueEncoding?: 'utf8' | 'base64' | 'hex';
 }
 
 /// Topic metadata response
@@ -71,8 +92,26 @@ export interface KafkaMessageResponse {
   key?: string;
   value?: string;
   timestamp: number;
+  headers: Record<string, string>;
+  detectedFormat: string;
+  truncated: boolean;
 }
 
+/// Only keep messages with a header whose value matches a pattern
+export interface HeaderPatternFilter {
+  headerName: string;
+  headerValuePattern: string;
+  caseSensitive: boolean;
+  useRegex?: boolean;
+}
+
+/// Only keep messages where every listed header has exactly the given value
+export interface HeaderValuesFilter {
+  headerValues: Record<string, string>;
+}
+
+export type HeaderFilter = HeaderPatternFilter | HeaderValuesFilter;
+
 /// Error response
 export interface ErrorResponse {
   error: string;
@@ -88,6 +127,11 @@ export const getServerConnections = async (): Promise<ServerConnectionSettings[]
   return await invoke<ServerConnectionSettings[]>('get_server_connections');
 };
 
+/** Problems found while loading the configuration files at startup */
+export const getConfigLoadErrors = async (): Promise<string[]> => {
+  return await invoke<string[]>('get_config_load_errors');
+};
+
 export const addServerConnection = async (request: ServerConnectionRequest): Promise<number> => {
   return await invoke<number>('add_server_connection', { request });
 };
@@ -139,14 +183,18 @@ export const consumeMessages = async (
   topic: string,
   limit: number,
   partition?: number,
-  offset?: number
+  offset?: number,
+  headerFilter?: HeaderFilter,
+  jsonPath?: string
 ): Promise<KafkaMessageResponse[]> => {
   return await invoke<KafkaMessageResponse[]>('consume_messages', {
     serverId,
     topic,
     partition,
     offset,
-    limit
+    limit,
+    headerFilter,
+    jsonPath
   });
 };
 
@@ -188,12 +236,20 @@ export const getTaskProgress = async (taskId: string): Promise<TaskProgress> =>
   return await invoke<TaskProgress>('get_task_progress', { taskId });
 };
 
+export const getTaskResult = async <T = unknown>(taskId: string): Promise<T> => {
+  return await invoke<T>('get_task_result', { taskId });
+};
+
 export const cancelTask = async (taskId: string): Promise<void> => {
   return await invoke<void>('cancel_task', { taskId });
 };
 
-export const listTasks = async (): Promise<string[]> => {
-  return await invoke<string[]>('list_tasks');
+export const getTaskQueueStatus = async (): Promise<TaskQueueStatus> => {
+  return await invoke<TaskQueueStatus>('get_task_queue_status');
+};
+
+export const listTasks = async (): Promise<TaskSummary[]> => {
+  return await invoke<TaskSummary[]>('list_tasks');
 };
 
 /**
@@ -202,6 +258,7 @@ export const listTasks = async (): Promise<string[]> => {
 export interface ZkNode {
   path: string;
   data: string;
+  isBinary: boolean;
   isDirectory: boolean;
   childCount: number;
   creationTime: number;
@@ -251,6 +308,32 @@ export interface DeleteZkNodeRequest {
   expectedVersion?: number;
 }
 
+export interface ZkWatchEvent {
+  serverId: number;
+  path: string;
+  eventType: 'Created' | 'Deleted' | 'DataChanged' | 'ChildrenChanged';
+}
+
+/// Tauri event emitted for every change of a watched ZooKeeper node
+export const ZK_WATCH_EVENT = 'zk-watch-event';
+
+export interface ZkConnectionRequest {
+  name: string;
+  zkHosts: string;
+  chroot?: string;
+  sessionTimeout?: number;
+  authScheme?: 'digest' | 'ip';
+  auth?: string;
+}
+
+export const connectZookeeper = async (serverId: number, request?: ZkConnectionRequest): Promise<void> => {
+  return await invoke<void>('connect_zookeeper', { serverId, request });
+};
+
+export const disconnectZookeeper = async (serverId: number): Promise<void> => {
+  return await invoke<void>('disconnect_zookeeper', { serverId });
+};
+
 export const getZkChildren = async (serverId: number, path: string): Promise<ZkNode[]> => {
   return await invoke<ZkNode[]>('get_zk_children', { serverId, path });
 };
@@ -259,16 +342,20 @@ export const getZkNode = async (serverId: number, path: string): Promise<ZkNode>
   return await invoke<ZkNode>('get_zk_node', { serverId, path });
 };
 
-export const createZkNode = async (serverId: number, request: CreateZkNodeRequest): Promise<void> => {
-  return await invoke<void>('create_zk_node', { serverId, request });
+export const createZkNode = async (serverId: number, request: CreateZkNodeRequest): Promise<string> => {
+  return await invoke<string>('create_zk_node', { serverId, request });
 };
 
 export const updateZkNode = async (serverId: number, request: UpdateZkNodeRequest): Promise<void> => {
   return await invoke<void>('update_zk_node', { serverId, request });
 };
 
-export const deleteZkNode = async (serverId: number, request: DeleteZkNodeRequest): Promise<void> => {
-  return await invoke<void>('delete_zk_node', { serverId, request });
+export const deleteZkNode = async (serverId: number, request: DeleteZkNodeRequest): Promise<number> => {
+  return await invoke<number>('delete_zk_node', { serverId, request });
+};
+
+export const watchZkNode = async (serverId: number, path: string): Promise<void> => {
+  return await invoke<void>('watch_zk_node', { serverId, path });
 };
 
 /**
@@ -282,6 +369,7 @@ export interface AclBinding {
   permissionType: string;
   host?: string;
   wildcard?: boolean;
+  patternType?: string;
 }
 
 export interface CreateAclRequest {
@@ -314,7 +402,9 @@ export const deleteAcl = async (
   resourceType: string,
   resourceName: string,
   operation: string,
-  permissionType: string
+  permissionType: string,
+  host: string = '',
+  patternType?: string
 ): Promise<void> => {
   return await invoke<void>('delete_acl', {
     serverId,
@@ -322,7 +412,9 @@ export const deleteAcl = async (
     resourceType,
     resourceName,
     operation,
-    permissionType
+    permissionType,
+    host,
+    patternType
   });
 };
 
@@ -337,3 +429,14 @@ export type TaskProgress = {
   isComplete: boolean;
   error?: string;
 };
+export type TaskQueueStatus = {
+  running: number;
+  queued: number;
+  maxConcurrent: number;
+};
+export type TaskSummary = TaskProgress & {
+  id: string;
+  name: string;
+  isCancelled: boolean;
+  isFinished: boolean;
+};
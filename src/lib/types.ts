@@ -56,6 +56,7 @@ export interface ConsumerMember {
 export interface ZkNode {
   path: string;
   data: string;
+  isBinary: boolean;
   isDirectory: boolean;
   childCount: number;
   creationTime: number;